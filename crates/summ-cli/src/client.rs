@@ -1,8 +1,37 @@
 use anyhow::{Context, Result};
-use summ_common::{Request, Response};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use summ_common::{ErrorCode, Request, Response, Scope, ScopedRequest};
+use summ_common::ipc::{self, WireFormat};
 use tokio::net::UnixStream;
 
+/// Retries used when neither `--retries` nor `SUMM_RETRIES` is given
+const DEFAULT_RETRIES: u32 = 2;
+
+/// Delay before the first retry; doubled for every further attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// Retry count set from the command line (`--retries`)
+static RETRIES: OnceLock<u32> = OnceLock::new();
+
+/// Wire format set from the command line (`--wire-format`)
+static WIRE_FORMAT: OnceLock<WireFormat> = OnceLock::new();
+
+/// Namespaces requests apply to (`--namespace`, `--all-namespaces`)
+static SCOPE: OnceLock<Scope> = OnceLock::new();
+
+/// Version of this client, compared with the daemon's when a request fails
+const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Daemon version learned from this invocation's responses
+static DAEMON_VERSION: OnceLock<String> = OnceLock::new();
+
+/// Whether the daemon answered any request of this invocation with an error
+static SAW_ERROR: AtomicBool = AtomicBool::new(false);
+
 /// Get the default socket path for the daemon
 pub fn socket_path() -> std::path::PathBuf {
     dirs::home_dir()
@@ -10,56 +39,459 @@ pub fn socket_path() -> std::path::PathBuf {
         .join(".summ-daemon/daemon.sock")
 }
 
+/// Override the retry count for this process (from `--retries`)
+pub fn set_retries(retries: u32) {
+    let _ = RETRIES.set(retries);
+}
+
+/// Override the wire format for this process (from `--wire-format`)
+pub fn set_wire_format(format: WireFormat) {
+    let _ = WIRE_FORMAT.set(format);
+}
+
+/// Set the namespaces every request of this process applies to
+pub fn set_scope(scope: Scope) {
+    let _ = SCOPE.set(scope);
+}
+
+/// Scope of this process's requests: the default namespace unless set
+pub fn scope() -> Scope {
+    SCOPE.get().cloned().unwrap_or_default()
+}
+
+/// Wire format: `--wire-format`, then `SUMM_WIRE_FORMAT`, then JSON
+fn wire_format() -> WireFormat {
+    WIRE_FORMAT.get().copied().unwrap_or_else(|| {
+        std::env::var("SUMM_WIRE_FORMAT")
+            .ok()
+            .and_then(|v| WireFormat::parse(&v))
+            .unwrap_or_default()
+    })
+}
+
+/// Retry count: `--retries`, then `SUMM_RETRIES`, then the default
+fn retries() -> u32 {
+    RETRIES.get().copied().unwrap_or_else(|| {
+        std::env::var("SUMM_RETRIES")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_RETRIES)
+    })
+}
+
+/// How far a failed attempt got, which decides whether it may be retried
+#[derive(Debug)]
+enum Failure {
+    /// Nothing reached the daemon (connect or write failed); always safe to retry
+    NotSent(anyhow::Error),
+    /// The request was written but no complete response came back
+    NoResponse(anyhow::Error),
+    /// What came back is not a summ response frame; never retried
+    Foreign(anyhow::Error),
+    /// Any other error (e.g. the request cannot be encoded); never retried
+    Fatal(anyhow::Error),
+}
+
 /// Send a request to the daemon and receive the response
 /// Uses length-prefixed framing: [4 bytes length][JSON payload]
 pub async fn send_request(request: Request) -> Result<Response> {
-    let socket = socket_path();
-    let mut stream = UnixStream::connect(&socket)
-        .await
-        .context(format!("Failed to connect to daemon at {:?}", socket))?;
+    send_request_in(request, &scope()).await
+}
 
-    // Serialize request to JSON
-    let json_bytes = serde_json::to_vec(&request)
-        .context("Failed to serialize request")?;
+/// Send a request scoped to `scope` rather than this process's namespaces
+pub async fn send_request_in(request: Request, scope: &Scope) -> Result<Response> {
+    let daemon_status = matches!(request, Request::DaemonStatus);
+    let response = send_request_to(&socket_path(), request, scope, retries(), wire_format()).await?;
+    remember_daemon_version(&response, daemon_status);
+    Ok(response)
+}
 
-    // Write length prefix (big-endian u32)
-    let len = json_bytes.len() as u32;
-    stream.write_all(&len.to_be_bytes())
-        .await
-        .context("Failed to write request length")?;
+/// Send several requests in one round trip (`Request::Batch`). Returns one
+/// response per request, in order; with `stop_on_error` the requests after
+/// the first failure come back as `Response::Skipped`.
+pub async fn batch(requests: Vec<Request>, stop_on_error: bool) -> Result<Vec<Response>> {
+    let daemon_status: Vec<bool> = requests
+        .iter()
+        .map(|request| matches!(request, Request::DaemonStatus))
+        .collect();
+    let response = send_request(Request::Batch { requests, stop_on_error }).await?;
+    let responses = unpack_batch(response, daemon_status.len())?;
+    for (response, daemon_status) in responses.iter().zip(daemon_status) {
+        remember_daemon_version(response, daemon_status);
+    }
+    Ok(responses)
+}
+
+/// The responses of a batch of `count` requests
+fn unpack_batch(response: Response, count: usize) -> Result<Vec<Response>> {
+    match response {
+        Response::Batch { responses } if responses.len() == count => Ok(responses),
+        Response::Batch { responses } => anyhow::bail!(
+            "Daemon answered a batch of {} requests with {} responses",
+            count,
+            responses.len()
+        ),
+        Response::Error { code, message, .. } => anyhow::bail!("{}: {}", code, message),
+        Response::Success { .. } | Response::Skipped => {
+            anyhow::bail!("Daemon did not answer the batch with a batch response")
+        }
+    }
+}
+
+/// Note the daemon version an error or DaemonStatus response carries, for
+/// `version_drift_note`
+fn remember_daemon_version(response: &Response, daemon_status: bool) {
+    let version = match response {
+        Response::Error { daemon_version, .. } => {
+            SAW_ERROR.store(true, Ordering::Relaxed);
+            daemon_version.clone()
+        }
+        Response::Success { data } if daemon_status => data["version"].as_str().map(str::to_string),
+        // The requests of a batch are looked at one by one in `batch`
+        Response::Success { .. } | Response::Batch { .. } | Response::Skipped => None,
+    };
+    if let Some(version) = version {
+        let _ = DAEMON_VERSION.set(version);
+    }
+}
+
+/// One-line notice for a failed command when the daemon answered an error
+/// and runs a different version than this client. The version comes from
+/// the responses already received; daemons too old to put it on their
+/// errors are asked once with DaemonStatus.
+pub async fn version_drift_note() -> Option<String> {
+    if !SAW_ERROR.load(Ordering::Relaxed) {
+        return None;
+    }
+    let daemon = match DAEMON_VERSION.get() {
+        Some(version) => version.clone(),
+        None => match send_request_to(&socket_path(), Request::DaemonStatus, &scope(), 0, wire_format()).await {
+            Ok(Response::Success { data }) => data["version"].as_str()?.to_string(),
+            _ => return None,
+        },
+    };
+    drift_note(&daemon, CLIENT_VERSION)
+}
+
+fn drift_note(daemon: &str, client: &str) -> Option<String> {
+    (daemon != client).then(|| {
+        format!(
+            "note: daemon is v{}, client is v{}; consider restarting it \
+             (`summ daemon stop && summ daemon start`)",
+            daemon, client
+        )
+    })
+}
+
+/// Send a request to the daemon at `socket`, retrying transient failures with backoff.
+///
+/// Connection failures are always retried. A request that was fully written is only
+/// resent when it is idempotent, so a Start or Inject is never executed twice.
+/// A request the daemon refused because it is still recovering (`E015`) was not
+/// applied, so it is resent too.
+async fn send_request_to(
+    socket: &Path,
+    request: Request,
+    scope: &Scope,
+    retries: u32,
+    format: WireFormat,
+) -> Result<Response> {
+    let mut delay = RETRY_BASE_DELAY;
+    let mut attempt = 0;
+
+    loop {
+        let err = match try_send(socket, &request, scope, format).await {
+            Ok(Response::Error { code, .. })
+                if code == ErrorCode::E015.code() && attempt < retries =>
+            {
+                attempt += 1;
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                continue;
+            }
+            Ok(response) => return Ok(response),
+            Err(Failure::Fatal(e)) => return Err(e),
+            Err(Failure::Foreign(e)) => return Err(foreign_error(socket, e)),
+            Err(Failure::NoResponse(e)) if !request.is_idempotent() => {
+                return Err(e.context(
+                    "Daemon running but not responding; the request may have been applied",
+                ));
+            }
+            Err(Failure::NotSent(e)) | Err(Failure::NoResponse(e)) => e,
+        };
 
-    // Write JSON payload
-    stream.write_all(&json_bytes)
+        if attempt >= retries {
+            return Err(unreachable_error(socket, err));
+        }
+        attempt += 1;
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+}
+
+/// Final error once retries are exhausted: no socket (the daemon never
+/// started), a socket nobody listens on (left by a daemon that died), or a
+/// daemon that accepts connections but does not answer
+fn unreachable_error(socket: &Path, err: anyhow::Error) -> anyhow::Error {
+    let connect_error = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .map(std::io::Error::kind);
+    if connect_error == Some(ErrorKind::ConnectionRefused) {
+        err.context(format!(
+            "Stale socket at {}: the daemon that created it is gone (it may have crashed). \
+             Run `summ daemon start` to clean it up and start a new one",
+            socket.display()
+        ))
+    } else if connect_error == Some(ErrorKind::NotFound) || !socket.exists() {
+        err.context(format!(
+            "Daemon not running (no socket at {}). Start it with: summ daemon start",
+            socket.display()
+        ))
+    } else {
+        err.context(format!(
+            "Daemon running but not responding at {}",
+            socket.display()
+        ))
+    }
+}
+
+/// Error for an answer that is not a summ response
+fn foreign_error(socket: &Path, err: anyhow::Error) -> anyhow::Error {
+    err.context(format!(
+        "The process listening at {} does not speak the summ protocol \
+         (another program, or a summ daemon too different from this client)",
+        socket.display()
+    ))
+}
+
+async fn try_send(
+    socket: &Path,
+    request: &Request,
+    scope: &Scope,
+    format: WireFormat,
+) -> std::result::Result<Response, Failure> {
+    let mut stream = UnixStream::connect(socket)
         .await
-        .context("Failed to write request payload")?;
+        .context(format!("Failed to connect to daemon at {:?}", socket))
+        .map_err(Failure::NotSent)?;
+
+    let payload = ipc::encode(&ScopedRequest { request, scope }, format).map_err(Failure::Fatal)?;
 
-    // Read response length
-    let mut len_buf = [0u8; 4];
-    stream.read_exact(&mut len_buf)
+    // The daemon always reads compressed requests; large injections benefit most
+    ipc::write_frame(&mut stream, &payload, Some(format), true)
         .await
-        .context("Failed to read response length")?;
-    let resp_len = u32::from_be_bytes(len_buf) as usize;
+        .context("Failed to write request")
+        .map_err(Failure::NotSent)?;
 
-    // Read response payload
-    let mut resp_buf = vec![0u8; resp_len];
-    stream.read_exact(&mut resp_buf)
+    let (payload, frame) = ipc::read_frame(&mut stream)
         .await
-        .context("Failed to read response payload")?;
+        .map_err(|e| read_failure(e, "Failed to read response"))?;
 
-    // Deserialize response
-    let response: Response = serde_json::from_slice(&resp_buf)
-        .context("Failed to deserialize response")?;
+    ipc::decode(&payload, frame.format.unwrap_or_default()).map_err(Failure::Foreign)
+}
 
-    Ok(response)
+/// The connection dropping before a full response is transient, and a frame
+/// that cannot be one of ours (absurd length, bad compression) is foreign;
+/// other read errors are fatal
+fn read_failure(err: std::io::Error, what: &'static str) -> Failure {
+    let kind = err.kind();
+    let err = anyhow::Error::new(err).context(what);
+    match kind {
+        ErrorKind::UnexpectedEof | ErrorKind::ConnectionReset | ErrorKind::BrokenPipe => Failure::NoResponse(err),
+        ErrorKind::InvalidData => Failure::Foreign(err),
+        _ => Failure::Fatal(err),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    #[test]
+    fn test_drift_note() {
+        assert_eq!(drift_note("0.4.2", "0.4.2"), None);
+        assert_eq!(
+            drift_note("0.1.0", "0.4.2").unwrap(),
+            "note: daemon is v0.1.0, client is v0.4.2; consider restarting it \
+             (`summ daemon stop && summ daemon start`)"
+        );
+    }
+
+    #[test]
+    fn test_unpack_batch() {
+        let ok = || Response::success(serde_json::json!({}));
+        let responses = unpack_batch(Response::Batch { responses: vec![ok(), Response::Skipped] }, 2).unwrap();
+        assert!(matches!(responses[..], [Response::Success { .. }, Response::Skipped]));
+
+        let err = unpack_batch(Response::Batch { responses: vec![ok()] }, 2).unwrap_err();
+        assert_eq!(err.to_string(), "Daemon answered a batch of 2 requests with 1 responses");
+        let refused = Response::Error {
+            code: "E010".to_string(),
+            message: "Invalid request: unknown variant `Batch`".to_string(),
+            daemon_version: None,
+            retry_after_seconds: None,
+        };
+        assert!(unpack_batch(refused, 2).unwrap_err().to_string().starts_with("E010: "));
+    }
 
     #[test]
     fn test_socket_path() {
         let path = socket_path();
         assert!(path.ends_with(".summ-daemon/daemon.sock"));
     }
+
+    #[tokio::test]
+    async fn test_missing_socket_reports_daemon_not_running() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("daemon.sock");
+
+        let err = send_request_to(&socket, Request::DaemonStatus, &Scope::default(), 1, WireFormat::Json)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("Daemon not running"));
+    }
+
+    #[tokio::test]
+    async fn test_stale_socket_is_reported_as_such() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("daemon.sock");
+        // A daemon that died leaves its socket file behind
+        drop(std::os::unix::net::UnixListener::bind(&socket).unwrap());
+
+        let err = send_request_to(&socket, Request::DaemonStatus, &Scope::default(), 1, WireFormat::Json)
+            .await
+            .unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("Stale socket"), "{}", message);
+        assert!(message.contains("summ daemon start"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_foreign_listener_is_not_retried() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("daemon.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        // Something that is not summ: an HTTP server, then a peer whose frame
+        // holds no summ response
+        let server = tokio::spawn(async move {
+            let answers: [&[u8]; 2] = [b"HTTP/1.1 400 Bad Request\r\n\r\n", b"\0\0\0\x05hello"];
+            for answer in answers {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                stream.write_all(answer).await.unwrap();
+            }
+            // A third connection would mean a retry
+            tokio::time::timeout(Duration::from_millis(500), listener.accept())
+                .await
+                .is_err()
+        });
+
+        for _ in 0..2 {
+            let err = send_request_to(&socket, Request::DaemonStatus, &Scope::default(), 2, WireFormat::Json)
+                .await
+                .unwrap_err();
+            let message = format!("{:#}", err);
+            assert!(message.contains("does not speak the summ protocol"), "{}", message);
+        }
+        assert!(server.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_daemon_is_retried_for_idempotent_requests() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("daemon.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        // Accept and immediately drop every connection
+        let server = tokio::spawn(async move {
+            let mut accepted = 0;
+            while let Ok((stream, _)) = listener.accept().await {
+                drop(stream);
+                accepted += 1;
+                if accepted == 3 {
+                    break;
+                }
+            }
+            accepted
+        });
+
+        let err = send_request_to(&socket, Request::DaemonStatus, &Scope::default(), 2, WireFormat::Json)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("Daemon running but not responding"));
+        assert_eq!(server.await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_recovering_daemon_is_retried() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("daemon.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        // Refuse the first attempt as still recovering, answer the second
+        let server = tokio::spawn(async move {
+            for attempt in 0..2 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let (request, _): (Request, _) = ipc::read_message(&mut stream).await.unwrap();
+                assert!(matches!(request, Request::Stop { .. }));
+                let response = if attempt == 0 {
+                    Response::Error {
+                        code: "E015".to_string(),
+                        message: "Daemon is recovering sessions (0 of 10 loaded)".to_string(),
+                        daemon_version: None,
+                        retry_after_seconds: None,
+                    }
+                } else {
+                    Response::success(serde_json::json!({ "status": "stopped" }))
+                };
+                ipc::write_message(&mut stream, &response, None, false).await.unwrap();
+            }
+        });
+
+        let request = Request::Stop {
+            session_id: "session_abc".to_string(),
+            override_protection: false,
+            stop_observers: false,
+        };
+        match send_request_to(&socket, request, &Scope::default(), 2, WireFormat::Json).await.unwrap() {
+            Response::Success { data } => assert_eq!(data["status"], "stopped"),
+            other => panic!("Expected Success response, got {:?}", other),
+        }
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_request_is_not_resent() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("daemon.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        // Read the full request, then hang up without answering
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut len_buf = [0u8; 4];
+            stream.read_exact(&mut len_buf).await.unwrap();
+            let mut buf = vec![0u8; u32::from_be_bytes(len_buf) as usize];
+            stream.read_exact(&mut buf).await.unwrap();
+            drop(stream);
+
+            // A second connection would mean the request was resent
+            tokio::time::timeout(Duration::from_millis(500), listener.accept())
+                .await
+                .is_err()
+        });
+
+        let request = Request::Stop {
+            session_id: "session_abc".to_string(),
+            override_protection: false,
+            stop_observers: false,
+        };
+        let err = send_request_to(&socket, request, &Scope::default(), 2, WireFormat::Json).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("may have been applied"));
+        assert!(server.await.unwrap());
+    }
 }
@@ -0,0 +1,61 @@
+// summ-cli/src/commands/confirm.rs
+// Interactive confirmation for destructive commands
+use anyhow::Result;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Ask the user to confirm an operation affecting `items`.
+///
+/// `--yes` skips the prompt. Without it the prompt requires an interactive
+/// terminal; non-interactive callers are refused rather than silently confirmed.
+pub fn confirm(action: &str, items: &[String], yes: bool) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+
+    if !(io::stdin().is_terminal() && io::stdout().is_terminal()) {
+        anyhow::bail!("Refusing to {} without --yes in non-interactive mode", action);
+    }
+
+    println!("This will {} {} session(s):", action, items.len());
+    for item in items {
+        println!("  {}", item);
+    }
+    print!("Continue? Type 'y' or the number of sessions ({}): ", items.len());
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    if !is_confirmed(&answer, items.len()) {
+        anyhow::bail!("Aborted");
+    }
+    Ok(())
+}
+
+/// Accept "y"/"yes" or the exact number of affected sessions
+fn is_confirmed(answer: &str, count: usize) -> bool {
+    let answer = answer.trim();
+    answer.eq_ignore_ascii_case("y")
+        || answer.eq_ignore_ascii_case("yes")
+        || answer.parse::<usize>() == Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_confirmed() {
+        assert!(is_confirmed("y\n", 3));
+        assert!(is_confirmed("YES", 3));
+        assert!(is_confirmed(" 3 ", 3));
+        assert!(!is_confirmed("2", 3));
+        assert!(!is_confirmed("", 3));
+        assert!(!is_confirmed("no", 3));
+    }
+
+    #[test]
+    fn test_confirm_with_yes_skips_prompt() {
+        assert!(confirm("stop", &["session_a".to_string()], true).is_ok());
+    }
+}
@@ -0,0 +1,242 @@
+// summ-cli/src/commands/files.rs
+// Browse and fetch the files of a session's workspace (`summ ls`, `summ get-file`)
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::{expect_success, print_json};
+use crate::client::send_request;
+use crate::timestamps::{self, TimeStyle};
+use summ_common::{FileChunk, FileKind, FileListing, Request};
+
+/// Arguments for the `ls` command
+#[derive(Debug, Args)]
+pub struct LsArgs {
+    /// Session ID whose workspace to list
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Directory of the workspace to list (default: its root)
+    #[clap(value_name = "SUBDIR")]
+    pub subdir: Option<PathBuf>,
+
+    /// Levels of directories to descend into
+    #[clap(long, default_value_t = 1, value_name = "N")]
+    pub depth: u32,
+
+    /// Print the listing as JSON instead of a table
+    #[clap(long)]
+    pub json: bool,
+}
+
+/// Arguments for the `get-file` command
+#[derive(Debug, Args)]
+pub struct GetFileArgs {
+    /// Session ID to fetch the file from
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// File to fetch, relative to the workspace
+    #[clap(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// Where to write the file; `-` for stdout (default: its name in the
+    /// current directory)
+    #[clap(short, long, value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+
+    /// Replace a local file that already exists at the output path
+    #[clap(long)]
+    pub overwrite: bool,
+}
+
+pub async fn cmd_ls(args: LsArgs) -> Result<()> {
+    let data = expect_success(
+        send_request(Request::ListFiles {
+            session_id: args.session_id.clone(),
+            subpath: args.subdir,
+            depth: Some(args.depth),
+        })
+        .await?,
+    )?;
+    if args.json {
+        return print_json(&data);
+    }
+    let listing: FileListing = serde_json::from_value(data).context("Malformed ListFiles response")?;
+    println!("{}", render_listing(&listing, timestamps::time_style()));
+    Ok(())
+}
+
+/// One line per entry: size, modification time, and path, with `/` after
+/// directories and `@` after symlinks
+fn render_listing(listing: &FileListing, style: TimeStyle) -> String {
+    if listing.entries.is_empty() {
+        return "No files.".to_string();
+    }
+    let mut lines: Vec<String> = listing
+        .entries
+        .iter()
+        .map(|entry| {
+            let (size, suffix) = match entry.kind {
+                FileKind::File => (entry.size.to_string(), ""),
+                FileKind::Directory => ("-".to_string(), "/"),
+                FileKind::Symlink => ("-".to_string(), "@"),
+            };
+            let modified = entry
+                .modified
+                .map(|modified| timestamps::clock(modified, style))
+                .unwrap_or_default();
+            format!("{:>10}  {:<14}  {}{}", size, modified, entry.path.display(), suffix)
+        })
+        .collect();
+    if listing.truncated {
+        lines.push(format!("(listing stopped after {} entries)", listing.entries.len()));
+    }
+    lines.join("\n")
+}
+
+pub async fn cmd_get_file(args: GetFileArgs) -> Result<()> {
+    let output = match args.output {
+        Some(output) => output,
+        None => default_output(&args.path)?,
+    };
+    let to_stdout = output == Path::new("-");
+    if !to_stdout && !args.overwrite && output.symlink_metadata().is_ok() {
+        bail!("{} already exists; pass --overwrite to replace it", output.display());
+    }
+
+    let mut sink: Box<dyn Write> = if to_stdout {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(File::create(&output).with_context(|| format!("Failed to create {}", output.display()))?)
+    };
+    let fetched = fetch(&args.session_id, &args.path, &mut sink).await;
+    drop(sink);
+    let bytes = match fetched {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            if !to_stdout {
+                let _ = fs::remove_file(&output);
+            }
+            return Err(e);
+        }
+    };
+    if to_stdout {
+        return Ok(());
+    }
+    print_json(&serde_json::json!({
+        "session_id": args.session_id,
+        "path": args.path,
+        "output": output,
+        "bytes": bytes
+    }))
+}
+
+/// Copy a workspace file into `sink` chunk by chunk, and return its size
+async fn fetch(session_id: &str, path: &Path, sink: &mut dyn Write) -> Result<u64> {
+    let mut first: Option<FileChunk> = None;
+    let mut offset = 0;
+    loop {
+        let data = expect_success(
+            send_request(Request::GetFile {
+                session_id: session_id.to_string(),
+                path: path.to_path_buf(),
+                offset,
+            })
+            .await?,
+        )?;
+        let chunk: FileChunk = serde_json::from_value(data).context("Malformed GetFile response")?;
+        if let Some(first) = &first {
+            check_unchanged(first, &chunk)?;
+        }
+        sink.write_all(&chunk.data).context("Failed to write the file")?;
+        offset += chunk.data.len() as u64;
+        if chunk.done {
+            sink.flush().context("Failed to write the file")?;
+            return Ok(offset);
+        }
+        if chunk.data.is_empty() {
+            bail!("{} ended early at {} bytes", path.display(), offset);
+        }
+        first.get_or_insert(chunk).data.clear();
+    }
+}
+
+/// Refuse a chunk of a file that changed since its first chunk was read
+fn check_unchanged(first: &FileChunk, chunk: &FileChunk) -> Result<()> {
+    if chunk.size != first.size || chunk.modified != first.modified {
+        bail!("{} changed while it was being fetched; try again", chunk.path.display());
+    }
+    Ok(())
+}
+
+/// The workspace file's name, in the current directory
+fn default_output(path: &Path) -> Result<PathBuf> {
+    path.file_name()
+        .map(PathBuf::from)
+        .with_context(|| format!("{} names no file; pass -o", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use summ_common::FileEntry;
+
+    #[test]
+    fn test_render_listing() {
+        let modified = Utc.with_ymd_and_hms(2026, 10, 16, 14, 3, 27).unwrap();
+        let entry = |path: &str, kind, size| FileEntry {
+            path: PathBuf::from(path),
+            kind,
+            size,
+            modified: Some(modified),
+        };
+        let listing = FileListing {
+            entries: vec![
+                entry("out", FileKind::Directory, 0),
+                entry("out/report.md", FileKind::File, 2048),
+                entry("link", FileKind::Symlink, 12),
+            ],
+            truncated: true,
+        };
+        assert_eq!(
+            render_listing(&listing, TimeStyle::Utc),
+            [
+                "         -  10-16 14:03:27Z  out/",
+                "      2048  10-16 14:03:27Z  out/report.md",
+                "         -  10-16 14:03:27Z  link@",
+                "(listing stopped after 3 entries)",
+            ]
+            .join("\n")
+        );
+        let empty = FileListing {
+            entries: Vec::new(),
+            truncated: false,
+        };
+        assert_eq!(render_listing(&empty, TimeStyle::Utc), "No files.");
+    }
+
+    #[test]
+    fn test_check_unchanged() {
+        let chunk = |size, seconds| FileChunk {
+            path: PathBuf::from("report.md"),
+            offset: 0,
+            data: Vec::new(),
+            size,
+            modified: Utc.timestamp_opt(seconds, 0).single(),
+            done: false,
+        };
+        assert!(check_unchanged(&chunk(10, 100), &chunk(10, 100)).is_ok());
+        assert!(check_unchanged(&chunk(10, 100), &chunk(12, 100)).is_err());
+        assert!(check_unchanged(&chunk(10, 100), &chunk(10, 101)).is_err());
+    }
+
+    #[test]
+    fn test_default_output() {
+        assert_eq!(default_output(Path::new("out/report.md")).unwrap(), PathBuf::from("report.md"));
+        assert!(default_output(Path::new("..")).is_err());
+    }
+}
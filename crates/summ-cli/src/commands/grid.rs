@@ -0,0 +1,247 @@
+// summ-cli/src/commands/grid.rs
+// Tile several sessions in one local tmux window (`summ attach --grid`)
+use anyhow::{Context, Result};
+use std::process::Command;
+
+use super::{expect_success, AttachArgs};
+use crate::client::send_request;
+use summ_common::{Namespace, Request, SessionStatus};
+
+/// Most sessions shown in one grid; more panes become too small to read
+const MAX_GRID_PANES: usize = 6;
+
+/// Prefix of grid sessions. It differs from the daemon's `summ-` prefix so
+/// the daemon never mistakes a grid for one of its sessions.
+const GRID_SESSION_PREFIX: &str = "summgrid-";
+
+/// A session shown in the grid
+#[derive(Debug, PartialEq, Eq)]
+struct GridPane {
+    session_id: String,
+    /// tmux session the pane attaches to
+    tmux_session: String,
+    /// Pane title: the session name if it has one
+    title: String,
+}
+
+/// Sessions from a List response that still have a tmux session to attach to
+fn live_panes(sessions: &[serde_json::Value]) -> Vec<GridPane> {
+    sessions
+        .iter()
+        .filter(|session| {
+            matches!(
+                serde_json::from_value(session["status"].clone()),
+                Ok(SessionStatus::Running | SessionStatus::Idle | SessionStatus::Paused)
+            )
+        })
+        .filter_map(|session| {
+            let session_id = session["session_id"].as_str()?.to_string();
+            // Daemons without namespaces send none: the default one
+            let namespace: Namespace = serde_json::from_value(session["namespace"].clone()).unwrap_or_default();
+            let tmux_session = namespace.tmux_session(&session_id);
+            let title = match session["name"].as_str() {
+                Some(name) if !name.is_empty() => format!("{} ({})", name, session_id),
+                _ => session_id.clone(),
+            };
+            Some(GridPane { session_id, tmux_session, title })
+        })
+        .collect()
+}
+
+/// Command run in a grid pane. It attaches a nested client to the session,
+/// so closing the pane only detaches that client. `$TMUX` is unset because
+/// tmux refuses to attach from inside one of its own panes otherwise.
+///
+/// `ignore-size` does not help while the grid's clients are the only ones
+/// attached, so the session's window follows the pane size. Once the client
+/// exits (HUP is ignored so this also runs when the grid is killed), the
+/// window gets its original `size` back and follows its clients again.
+/// tmux before 3.2 lacks `-f`, so the plain attach is the fallback.
+fn pane_command(tmux_session: &str, writable: bool, size: Option<(u32, u32)>) -> String {
+    let (flags, fallback) = if writable {
+        ("ignore-size", "")
+    } else {
+        ("read-only,ignore-size", " -r")
+    };
+    let target = tmux_session;
+    let attach = "env -u TMUX tmux attach-session";
+    let mut command = format!(
+        "trap '' HUP; {attach} -f {flags} -t {target} || {attach}{fallback} -t {target}",
+        attach = attach,
+        flags = flags,
+        fallback = fallback,
+        target = target
+    );
+    if let Some((width, height)) = size {
+        command.push_str(&format!(
+            "; tmux resize-window -t {target} -x {width} -y {height}; \
+             tmux set-option -wu -t {target} window-size",
+            target = target,
+            width = width,
+            height = height
+        ));
+    }
+    command
+}
+
+/// Current size of a tmux session's window
+fn window_size(tmux_session: &str) -> Option<(u32, u32)> {
+    let output = Command::new("tmux")
+        .args([
+            "display-message",
+            "-p",
+            "-t",
+            tmux_session,
+            "#{window_width} #{window_height}",
+        ])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (width, height) = text.trim().split_once(' ')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+fn tmux(args: &[&str]) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(args)
+        .output()
+        .context("Failed to run tmux")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "tmux {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Build a detached grid session with one pane per session
+fn build_grid(grid: &str, panes: &[GridPane], writable: bool) -> Result<()> {
+    let command = |pane: &GridPane| {
+        pane_command(&pane.tmux_session, writable, window_size(&pane.tmux_session))
+    };
+    tmux(&["new-session", "-d", "-s", grid, &command(&panes[0])])?;
+    // The grid goes away once the user detaches or closes the last pane.
+    // Setting destroy-unattached now would kill the still-detached grid.
+    let destroy = format!("set-option -t {} destroy-unattached on", grid);
+    tmux(&["set-hook", "-t", grid, "client-attached", &destroy])?;
+    tmux(&["set-option", "-w", "-t", grid, "pane-border-status", "top"])?;
+    tmux(&["set-option", "-w", "-t", grid, "pane-border-format", " #{pane_title} "])?;
+    for pane in &panes[1..] {
+        tmux(&["split-window", "-t", grid, &command(pane)])?;
+        // Re-tile after each split so the window never runs out of room
+        tmux(&["select-layout", "-t", grid, "tiled"])?;
+    }
+    for (index, pane) in panes.iter().enumerate() {
+        tmux(&["select-pane", "-t", &format!("{}:0.{}", grid, index), "-T", &pane.title])?;
+    }
+    tmux(&["select-pane", "-t", &format!("{}:0.0", grid)])
+}
+
+pub async fn cmd_attach_grid(args: AttachArgs) -> Result<()> {
+    let created_by_uid = args.mine.then(summ_common::user::current_uid);
+    let data = expect_success(
+        send_request(Request::List {
+            status_filter: args.status,
+            created_by_uid,
+            include_archived: false,
+        })
+        .await?,
+    )?;
+    let mut panes = live_panes(data.as_array().map(Vec::as_slice).unwrap_or_default());
+
+    match panes.len() {
+        0 => anyhow::bail!("No running, idle, or paused sessions match"),
+        1 => return Err(super::exec_attach(&panes[0].tmux_session, !args.writable)),
+        count if count > MAX_GRID_PANES => {
+            eprintln!(
+                "{} sessions match; showing the first {} (narrow them with --status or --mine)",
+                count, MAX_GRID_PANES
+            );
+            panes.truncate(MAX_GRID_PANES);
+        }
+        _ => {}
+    }
+
+    let grid = format!("{}{}", GRID_SESSION_PREFIX, std::process::id());
+    if let Err(e) = build_grid(&grid, &panes, args.writable) {
+        let _ = tmux(&["kill-session", "-t", &grid]);
+        return Err(e);
+    }
+
+    // Inside tmux, switch this client over instead of nesting another one
+    if std::env::var_os("TMUX").is_some() {
+        return tmux(&["switch-client", "-t", &grid]);
+    }
+    let status = Command::new("tmux")
+        .args(["attach-session", "-t", &grid])
+        .status()
+        .context("Failed to run tmux")?;
+    if !status.success() {
+        anyhow::bail!("Failed to attach to the grid session {}", grid);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_live_panes_skip_sessions_without_tmux() {
+        let sessions = [
+            json!({"session_id": "session_a", "status": "running", "name": "api"}),
+            json!({"session_id": "session_b", "status": "stopped"}),
+            json!({"session_id": "session_c", "status": "idle", "name": ""}),
+            json!({"session_id": "session_d", "status": "starting"}),
+            json!({"session_id": "session_e", "status": "paused", "namespace": "work"}),
+            json!({"session_id": "session_f", "status": "failed"}),
+        ];
+        let panes = live_panes(&sessions);
+        assert_eq!(
+            panes,
+            [
+                GridPane {
+                    session_id: "session_a".into(),
+                    tmux_session: "summ-session_a".into(),
+                    title: "api (session_a)".into(),
+                },
+                GridPane {
+                    session_id: "session_c".into(),
+                    tmux_session: "summ-session_c".into(),
+                    title: "session_c".into(),
+                },
+                GridPane {
+                    session_id: "session_e".into(),
+                    tmux_session: "summ-work-session_e".into(),
+                    title: "session_e".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pane_command_is_read_only_by_default() {
+        assert_eq!(
+            pane_command("summ-session_a", false, None),
+            "trap '' HUP; env -u TMUX tmux attach-session -f read-only,ignore-size -t summ-session_a \
+             || env -u TMUX tmux attach-session -r -t summ-session_a"
+        );
+        assert_eq!(
+            pane_command("summ-session_a", true, None),
+            "trap '' HUP; env -u TMUX tmux attach-session -f ignore-size -t summ-session_a \
+             || env -u TMUX tmux attach-session -t summ-session_a"
+        );
+    }
+
+    #[test]
+    fn test_pane_command_restores_window_size() {
+        let command = pane_command("summ-session_a", false, Some((200, 50)));
+        assert!(command.ends_with(
+            "; tmux resize-window -t summ-session_a -x 200 -y 50; \
+             tmux set-option -wu -t summ-session_a window-size"
+        ));
+    }
+}
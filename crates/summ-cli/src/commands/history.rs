@@ -0,0 +1,226 @@
+// summ-cli/src/commands/history.rs
+// List the messages injected into a session, and recall one to send again
+use anyhow::{Context, Result};
+use clap::Args;
+use std::fs;
+use std::io::Write;
+use std::process::Command;
+
+use super::open::editor_command;
+use super::{expect_success, print_json};
+use crate::client::send_request;
+use crate::timestamps::{self, TimeStyle};
+use summ_common::{InjectionHistory, InjectionRecord, Request};
+
+/// Arguments for the `inject-history` command
+#[derive(Debug, Args)]
+pub struct InjectHistoryArgs {
+    /// Session ID to show
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Show the latest N messages
+    #[clap(short = 'n', long = "limit", value_name = "N", default_value_t = 20)]
+    pub limit: usize,
+
+    /// Print the messages as JSON instead of a list
+    #[clap(long)]
+    pub json: bool,
+}
+
+pub async fn cmd_inject_history(args: InjectHistoryArgs) -> Result<()> {
+    let data = fetch(&args.session_id, args.limit).await?;
+    if args.json {
+        return print_json(&data);
+    }
+    let history: InjectionHistory =
+        serde_json::from_value(data).context("Malformed InjectionHistory response")?;
+    println!("{}", render_history(&history, timestamps::time_style()));
+    Ok(())
+}
+
+async fn fetch(session_id: &str, limit: usize) -> Result<serde_json::Value> {
+    expect_success(
+        send_request(Request::InjectionHistory {
+            session_id: session_id.to_string(),
+            limit: Some(limit),
+        })
+        .await?,
+    )
+}
+
+/// One line per message, latest first: index, clock time, first line, and
+/// what was lost when it was kept
+fn render_history(history: &InjectionHistory, style: TimeStyle) -> String {
+    if history.entries.is_empty() {
+        return if history.recording {
+            format!("No messages injected into session {} yet.", history.session_id)
+        } else {
+            format!(
+                "Session {} keeps no injection history (started with --no-inject-history).",
+                history.session_id
+            )
+        };
+    }
+    history
+        .entries
+        .iter()
+        .map(|entry| {
+            let record = &entry.record;
+            let mut lines = record.message.lines();
+            let mut text = lines.next().unwrap_or_default().to_string();
+            let more = lines.count();
+            if more > 0 {
+                text.push_str(&format!(" (+{} line{})", more, if more == 1 { "" } else { "s" }));
+            }
+            for (lost, label) in [(record.redacted, "redacted"), (record.truncated, "truncated")] {
+                if lost {
+                    text.push_str(&format!(" [{}]", label));
+                }
+            }
+            format!("{:>4}  {}  {}", entry.index, timestamps::clock(record.at, style), text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Entry `index` (1 is the latest) of the session's history
+pub(super) async fn recall(session_id: &str, index: usize) -> Result<InjectionRecord> {
+    if index == 0 {
+        anyhow::bail!("History entries are numbered from 1, the latest message");
+    }
+    let history: InjectionHistory = serde_json::from_value(fetch(session_id, index).await?)
+        .context("Malformed InjectionHistory response")?;
+    if !history.recording {
+        anyhow::bail!("Session {} keeps no injection history", session_id);
+    }
+    let count = history.entries.len();
+    history
+        .entries
+        .into_iter()
+        .find(|entry| entry.index == index)
+        .map(|entry| entry.record)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Session {} has {} message{} in its history; see `summ inject-history {}`",
+                session_id,
+                count,
+                if count == 1 { "" } else { "s" },
+                session_id
+            )
+        })
+}
+
+/// The message of entry `index`, when it was kept whole and can be sent as is
+pub(super) async fn resend_message(session_id: &str, index: usize) -> Result<String> {
+    let record = recall(session_id, index).await?;
+    if !record.is_complete() {
+        anyhow::bail!(
+            "Entry {} of session {} was {} when it was kept; restore it with `--edit-resend {}`",
+            index,
+            session_id,
+            if record.redacted { "redacted" } else { "truncated" },
+            index
+        );
+    }
+    Ok(record.message)
+}
+
+/// Let the user edit `text` in $VISUAL/$EDITOR and return the result; an
+/// emptied message cancels the injection. The text goes to a fresh file only
+/// its owner can read, as it may hold secrets.
+pub(super) fn edit_message(text: &str) -> Result<String> {
+    let mut file = tempfile::Builder::new()
+        .prefix("summ-inject-")
+        .suffix(".txt")
+        .tempfile()
+        .context("Failed to create a file to edit the message in")?;
+    file.write_all(text.as_bytes())
+        .and_then(|()| file.flush())
+        .with_context(|| format!("Failed to write {}", file.path().display()))?;
+
+    let editor = editor_command();
+    let status = Command::new(&editor[0])
+        .args(&editor[1..])
+        .arg(file.path())
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to run editor {}: {}", editor[0], e))?;
+    if !status.success() {
+        anyhow::bail!("Editor {} exited with {}; nothing sent", editor[0], status);
+    }
+
+    // Editors end the file with a newline that would submit an empty line
+    let edited = fs::read_to_string(file.path())
+        .with_context(|| format!("Failed to read {}", file.path().display()))?;
+    let message = edited.trim_end_matches(['\n', '\r']);
+    if message.trim().is_empty() {
+        anyhow::bail!("Empty message; nothing sent");
+    }
+    Ok(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use summ_common::InjectionEntry;
+
+    fn entry(index: usize, minute: u32, message: &str, redacted: bool, truncated: bool) -> InjectionEntry {
+        InjectionEntry {
+            index,
+            record: InjectionRecord {
+                at: format!("2026-10-16T12:{:02}:00Z", minute).parse().unwrap(),
+                message: message.to_string(),
+                redacted,
+                truncated,
+            },
+        }
+    }
+
+    #[test]
+    fn test_render_history() {
+        let history = InjectionHistory {
+            session_id: "abc123".to_string(),
+            recording: true,
+            entries: vec![
+                entry(1, 5, "Commit it", false, false),
+                entry(2, 3, "Use the staging key sk-•••redacted•••", true, false),
+                entry(3, 1, "Fix the tests:\n- unit\n- integration", false, true),
+            ],
+        };
+        assert_eq!(
+            render_history(&history, TimeStyle::Utc),
+            [
+                "   1  10-16 12:05:00Z  Commit it",
+                "   2  10-16 12:03:00Z  Use the staging key sk-•••redacted••• [redacted]",
+                "   3  10-16 12:01:00Z  Fix the tests: (+2 lines) [truncated]",
+            ]
+            .join("\n")
+        );
+
+        let empty = InjectionHistory {
+            entries: Vec::new(),
+            ..history
+        };
+        assert_eq!(
+            render_history(&empty, TimeStyle::Utc),
+            "No messages injected into session abc123 yet."
+        );
+        let off = InjectionHistory { recording: false, ..empty };
+        assert_eq!(
+            render_history(&off, TimeStyle::Utc),
+            "Session abc123 keeps no injection history (started with --no-inject-history)."
+        );
+    }
+
+    #[test]
+    fn test_edit_message() {
+        // No other test reads $VISUAL
+        std::env::set_var("VISUAL", "sed -i s/draft/final/");
+        assert_eq!(edit_message("The draft plan\n").unwrap(), "The final plan");
+        std::env::set_var("VISUAL", "sed -i d");
+        assert_eq!(edit_message("Anything").unwrap_err().to_string(), "Empty message; nothing sent");
+        std::env::set_var("VISUAL", "false");
+        assert!(edit_message("Anything").unwrap_err().to_string().starts_with("Editor false exited"));
+        std::env::remove_var("VISUAL");
+    }
+}
@@ -0,0 +1,176 @@
+// summ-cli/src/commands/hooks.rs
+// Show the hook settings summ would deploy into a workspace, without writing anything
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use similar::TextDiff;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use summ_common::hook_settings::{self, CLAUDE_SETTINGS_FILE};
+use summ_common::{DaemonConfig, HookKind};
+
+/// Stands in for the ID a session gets when it starts
+const SESSION_ID_PLACEHOLDER: &str = "<session-id>";
+
+/// Arguments for the `hooks` command
+#[derive(Debug, Args)]
+pub struct HooksArgs {
+    #[clap(subcommand)]
+    pub command: HooksCommand,
+}
+
+/// Hook subcommands
+#[derive(Debug, Subcommand)]
+pub enum HooksCommand {
+    /// Print the hook settings a session would get, as a diff against the
+    /// init source's own settings file when it has one
+    Preview(PreviewArgs),
+}
+
+/// Arguments for `hooks preview`
+#[derive(Debug, Args)]
+pub struct PreviewArgs {
+    /// CLI command the session would run
+    #[clap(long)]
+    pub cli: String,
+
+    /// Initialization source the workspace would start from
+    #[clap(long)]
+    pub init: Option<String>,
+
+    /// Working subdirectory, as for `summ start --cwd`
+    #[clap(long, value_name = "SUBPATH", value_parser = super::parse_cwd)]
+    pub cwd: Option<PathBuf>,
+
+    /// Preview hooks deployed into the `--cwd` directory, as for `summ start --hooks-in-cwd`
+    #[clap(long, requires = "cwd")]
+    pub hooks_in_cwd: bool,
+}
+
+pub async fn cmd_hooks(args: HooksArgs) -> Result<()> {
+    match args.command {
+        HooksCommand::Preview(args) => cmd_preview(args),
+    }
+}
+
+fn cmd_preview(args: PreviewArgs) -> Result<()> {
+    let kind = hook_settings::hook_kind(&args.cli);
+    if kind != HookKind::Claude {
+        println!("'{}' gets no hooks; summ would write nothing into the workspace.", args.cli);
+        return Ok(());
+    }
+
+    // Where the settings file goes, relative to the workspace
+    let hooks_dir = match (&args.cwd, args.hooks_in_cwd) {
+        (Some(cwd), true) => cwd.clone(),
+        _ => PathBuf::new(),
+    };
+    let existing = match &args.init {
+        Some(init) => {
+            let init = super::resolve_init_path(init, &std::env::current_dir()?)?;
+            existing_settings(&init, &hooks_dir)?
+        }
+        None => None,
+    };
+
+    // Not `DaemonConfig::load`, which creates the daemon's directories
+    let config = DaemonConfig::load_from(&DaemonConfig::config_path())?;
+    let runtime_dir = config.sessions_dir.join(SESSION_ID_PLACEHOLDER).join("runtime");
+    let settings = hook_settings::claude_settings(existing.as_deref(), SESSION_ID_PLACEHOLDER, &runtime_dir);
+    let rendered = serde_json::to_string_pretty(&settings)?;
+
+    let file = hooks_dir.join(CLAUDE_SETTINGS_FILE);
+    match &existing {
+        Some(existing) => print!("{}", settings_diff(existing, &rendered, &file)),
+        None => println!("Would create {}:\n{}", file.display(), rendered),
+    }
+    println!("\nHook commands ({} stands for the new session's ID):", SESSION_ID_PLACEHOLDER);
+    for (event, command) in hook_settings::hook_commands(SESSION_ID_PLACEHOLDER, &runtime_dir) {
+        println!("  {:<17} {}", event, command);
+    }
+    Ok(())
+}
+
+/// The settings file an init source brings along, if it has one. Only
+/// directories are looked into; an archive is previewed as if it had none.
+fn existing_settings(init: &Path, hooks_dir: &Path) -> Result<Option<String>> {
+    if !init.is_dir() {
+        eprintln!(
+            "Note: {} is an archive; a {} inside it is not inspected",
+            init.display(),
+            CLAUDE_SETTINGS_FILE
+        );
+        return Ok(None);
+    }
+    let path = hook_settings::claude_settings_path(&init.join(hooks_dir));
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Unified diff from the init source's settings file to the deployed one
+fn settings_diff(existing: &str, deployed: &str, file: &Path) -> String {
+    let deployed = format!("{}\n", deployed);
+    if existing == deployed {
+        return format!("{} would be left unchanged\n", file.display());
+    }
+    TextDiff::from_lines(existing, deployed.as_str())
+        .unified_diff()
+        .header(
+            &format!("{} (init source)", file.display()),
+            &format!("{} (deployed)", file.display()),
+        )
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_existing_settings() {
+        let init = TempDir::new().unwrap();
+        assert_eq!(existing_settings(init.path(), Path::new("")).unwrap(), None);
+
+        let claude_dir = init.path().join("services/api/.claude");
+        fs::create_dir_all(&claude_dir).unwrap();
+        fs::write(claude_dir.join("settings.local.json"), "{}").unwrap();
+        assert_eq!(existing_settings(init.path(), Path::new("")).unwrap(), None);
+        assert_eq!(
+            existing_settings(init.path(), Path::new("services/api")).unwrap().as_deref(),
+            Some("{}")
+        );
+
+        let archive = init.path().join("repo.tar.gz");
+        fs::write(&archive, "").unwrap();
+        assert_eq!(existing_settings(&archive, Path::new("")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_settings_diff() {
+        let file = Path::new(".claude/settings.local.json");
+        let existing = "{\n  \"permissions\": {}\n}\n";
+        let deployed = "{\n  \"hooks\": {},\n  \"permissions\": {}\n}";
+        assert_eq!(
+            settings_diff(existing, deployed, file),
+            [
+                "--- .claude/settings.local.json (init source)",
+                "+++ .claude/settings.local.json (deployed)",
+                "@@ -1,3 +1,4 @@",
+                " {",
+                "+  \"hooks\": {},",
+                "   \"permissions\": {}",
+                " }",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            settings_diff(existing, existing.trim_end(), file),
+            ".claude/settings.local.json would be left unchanged\n"
+        );
+    }
+}
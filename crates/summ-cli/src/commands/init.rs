@@ -0,0 +1,314 @@
+// summ-cli/src/commands/init.rs
+// First-run bootstrap: directories, config, hook script, tmux check, service unit
+use anyhow::{Context, Result};
+use clap::Args;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use summ_common::assets::{self, HOOK_ASSETS_VERSION, SUMM_HOOK_SCRIPT, SYSTEMD_UNIT};
+use summ_common::tmux::{parse_tmux_version, MIN_TMUX_VERSION};
+use summ_common::DaemonConfig;
+
+/// Arguments for the `init` command
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    /// Also install and enable the systemd user service
+    #[clap(long)]
+    pub with_service: bool,
+
+    /// Rewrite managed files (hook script, service unit) even if present, and
+    /// add new settings to an existing config without changing the user's
+    #[clap(long)]
+    pub force: bool,
+}
+
+/// Outcome of a single bootstrap step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Created,
+    AlreadyPresent,
+    Rewritten,
+    Ok,
+    Warning,
+}
+
+impl Outcome {
+    fn label(self) -> &'static str {
+        match self {
+            Outcome::Created => "created",
+            Outcome::AlreadyPresent => "already present",
+            Outcome::Rewritten => "rewritten",
+            Outcome::Ok => "ok",
+            Outcome::Warning => "warning",
+        }
+    }
+}
+
+/// Report a step outcome on one line
+fn report(outcome: Outcome, item: &str, detail: impl AsRef<str>) {
+    println!("[{}] {}: {}", outcome.label(), item, detail.as_ref());
+}
+
+pub async fn cmd_init(args: InitArgs) -> Result<()> {
+    let base_dir = DaemonConfig::base_dir();
+    let config_path = DaemonConfig::config_path();
+
+    // Load any existing config so directories follow the user's settings
+    let config = DaemonConfig::load_from(&config_path)?;
+
+    for (item, dir) in [
+        ("base directory", base_dir.clone()),
+        ("sessions directory", config.sessions_dir.clone()),
+        ("logs directory", config.logs_dir.clone()),
+    ] {
+        let outcome = ensure_dir(&dir)?;
+        report(outcome, item, dir.display().to_string());
+    }
+
+    let outcome = if args.force && config_path.exists() {
+        update_config(&config_path)?
+    } else {
+        write_managed(&config_path, &default_config_json()?, false)?
+    };
+    report(outcome, "config", config_path.display().to_string());
+
+    // Where the daemon installs and refreshes it, which follows sessions_dir
+    let hook_path = assets::hook_script_path(&config.data_dir());
+    let current = assets::installed_hook_version(&hook_path) == Some(HOOK_ASSETS_VERSION);
+    let outcome = if current && !args.force {
+        Outcome::AlreadyPresent
+    } else {
+        let existed = hook_path.exists();
+        assets::write_executable(&hook_path, SUMM_HOOK_SCRIPT)?;
+        if existed {
+            Outcome::Rewritten
+        } else {
+            Outcome::Created
+        }
+    };
+    report(outcome, "hook script", hook_path.display().to_string());
+
+    let tmux_ok = check_tmux();
+
+    if args.with_service {
+        install_service(args.force)?;
+    }
+
+    println!();
+    println!("Next steps:");
+    if !tmux_ok {
+        println!(
+            "  - Install tmux {}.{} or later",
+            MIN_TMUX_VERSION.0, MIN_TMUX_VERSION.1
+        );
+    }
+    if args.with_service {
+        println!("  - Start the service: systemctl --user start summ-daemon");
+    } else {
+        println!("  - Start the daemon: summ daemon start");
+    }
+    println!("  - Create a session: summ start --cli claude --init <path>");
+
+    Ok(())
+}
+
+/// Create a directory if needed
+fn ensure_dir(dir: &Path) -> Result<Outcome> {
+    if dir.is_dir() {
+        return Ok(Outcome::AlreadyPresent);
+    }
+    fs::create_dir_all(dir).context(format!("Failed to create directory: {}", dir.display()))?;
+    Ok(Outcome::Created)
+}
+
+/// Write a managed file unless it exists (or `force` is set)
+fn write_managed(path: &Path, content: &str, force: bool) -> Result<Outcome> {
+    let existed = path.exists();
+    if existed && !force {
+        return Ok(Outcome::AlreadyPresent);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::write(path, content).context(format!("Failed to write {}", path.display()))?;
+    Ok(if existed {
+        Outcome::Rewritten
+    } else {
+        Outcome::Created
+    })
+}
+
+/// Default config.json content with an explanatory comment key
+fn default_config_json() -> Result<String> {
+    let mut value = serde_json::to_value(DaemonConfig::default())?;
+    value["_comment"] = serde_json::json!(
+        "SUMM Daemon configuration. Remove a key to use its default value; unknown keys are ignored."
+    );
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// Add the settings an existing config.json lacks, at their default values.
+/// The user's settings, and keys the daemon does not know, are kept as they are.
+fn update_config(path: &Path) -> Result<Outcome> {
+    let content = fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+    match merge_defaults(&content)? {
+        Some(merged) => {
+            fs::write(path, merged).context(format!("Failed to write {}", path.display()))?;
+            Ok(Outcome::Rewritten)
+        }
+        None => Ok(Outcome::AlreadyPresent),
+    }
+}
+
+/// `content` with the default of every setting it lacks, or `None` when it
+/// has them all
+fn merge_defaults(content: &str) -> Result<Option<String>> {
+    let mut existing: serde_json::Value = serde_json::from_str(content).context("Config is not valid JSON")?;
+    let fields = existing.as_object_mut().context("Config is not a JSON object")?;
+    let serde_json::Value::Object(defaults) = serde_json::to_value(DaemonConfig::default())? else {
+        anyhow::bail!("Default config is not a JSON object");
+    };
+    let mut added = false;
+    for (key, value) in defaults {
+        if !fields.contains_key(&key) {
+            fields.insert(key, value);
+            added = true;
+        }
+    }
+    if !added {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string_pretty(&existing)?))
+}
+
+/// Run the tmux version check and report it; returns whether tmux is usable
+fn check_tmux() -> bool {
+    let output = match Command::new("tmux").arg("-V").output() {
+        Ok(output) => output,
+        Err(_) => {
+            report(Outcome::Warning, "tmux", "not found in PATH");
+            return false;
+        }
+    };
+
+    let version_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    match parse_tmux_version(&version_str) {
+        Some(version) if version >= MIN_TMUX_VERSION => {
+            report(Outcome::Ok, "tmux", &version_str);
+            true
+        }
+        Some(_) => {
+            report(
+                Outcome::Warning,
+                "tmux",
+                format!(
+                    "{} is below minimum required {}.{}",
+                    version_str, MIN_TMUX_VERSION.0, MIN_TMUX_VERSION.1
+                ),
+            );
+            false
+        }
+        None => {
+            report(Outcome::Warning, "tmux", format!("unrecognized version: {}", version_str));
+            false
+        }
+    }
+}
+
+/// Install the systemd user unit, pointing ExecStart at the local daemon binary
+fn install_service(force: bool) -> Result<()> {
+    let unit_dir = dirs::config_dir()
+        .context("Cannot determine config directory")?
+        .join("systemd/user");
+    let unit_path = unit_dir.join("summ-daemon.service");
+
+    let unit = match daemon_binary() {
+        Some(bin) => SYSTEMD_UNIT.replace(
+            "ExecStart=%h/.cargo/bin/summ-daemon",
+            &format!("ExecStart={}", bin.display()),
+        ),
+        None => SYSTEMD_UNIT.to_string(),
+    };
+
+    let outcome = write_managed(&unit_path, &unit, force)?;
+    report(outcome, "service unit", unit_path.display().to_string());
+
+    for args in [&["daemon-reload"][..], &["enable", "summ-daemon.service"][..]] {
+        let status = Command::new("systemctl").arg("--user").args(args).status();
+        match status {
+            Ok(s) if s.success() => {
+                report(Outcome::Ok, "systemctl", args.join(" "));
+            }
+            _ => {
+                report(
+                    Outcome::Warning,
+                    "systemctl",
+                    format!("`systemctl --user {}` failed", args.join(" ")),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Locate the summ-daemon binary next to the current executable
+fn daemon_binary() -> Option<PathBuf> {
+    let bin = std::env::current_exe().ok()?.parent()?.join("summ-daemon");
+    bin.exists().then_some(bin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_managed_is_idempotent() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+
+        assert_eq!(write_managed(&path, "one", false).unwrap(), Outcome::Created);
+        assert_eq!(write_managed(&path, "two", false).unwrap(), Outcome::AlreadyPresent);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one");
+
+        assert_eq!(write_managed(&path, "two", true).unwrap(), Outcome::Rewritten);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two");
+    }
+
+    #[test]
+    fn test_merge_defaults_keeps_user_settings() {
+        let existing = r#"{"cleanup_retention_hours": 72, "_comment": "mine", "custom": true}"#;
+        let merged = merge_defaults(existing).unwrap().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(value["cleanup_retention_hours"], 72);
+        assert_eq!(value["_comment"], "mine");
+        assert_eq!(value["custom"], true);
+        assert_eq!(value["inject_rate_limit"], DaemonConfig::default().inject_rate_limit);
+
+        // Nothing to add: left alone
+        assert_eq!(merge_defaults(&merged).unwrap(), None);
+        assert!(merge_defaults("[]").is_err());
+    }
+
+    #[test]
+    fn test_update_config_never_loses_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        fs::write(&path, r#"{"cleanup_retention_hours": 72}"#).unwrap();
+
+        assert_eq!(update_config(&path).unwrap(), Outcome::Rewritten);
+        assert_eq!(DaemonConfig::load_from(&path).unwrap().cleanup_retention_hours, 72);
+        assert_eq!(update_config(&path).unwrap(), Outcome::AlreadyPresent);
+    }
+
+    #[test]
+    fn test_default_config_json_round_trips() {
+        let json = default_config_json().unwrap();
+        assert!(json.contains("_comment"));
+        let config: DaemonConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config.cleanup_retention_hours, 24);
+    }
+}
@@ -1,27 +1,104 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-
-use crate::client::{send_request, socket_path};
-use summ_common::{Request, Response, SessionStatus};
+use std::time::Duration;
+
+use crate::client::{self, send_request, socket_path};
+use crate::style;
+use crate::timestamps::{self, TimeStyle};
+use summ_common::{
+    CreationTimings, HealthReport, HealthStatus, HookInfo, HookRun, HookState, LifecycleEvent, Namespace, NamespaceSummary,
+    PromptAnswer, Request, Response, SessionInfo, SessionStatus, StartTrigger, DEFAULT_NAMESPACE,
+};
+
+mod confirm;
+mod files;
+mod grid;
+mod history;
+mod hooks;
+mod init;
+mod open;
+mod prompt;
+mod secret;
+mod selftest;
+mod send_file;
+mod templates;
+mod timeline;
+mod wait;
+pub use files::{GetFileArgs, LsArgs};
+pub use hooks::HooksArgs;
+pub use init::InitArgs;
+pub use open::OpenArgs;
+pub use prompt::PromptStatusArgs;
+pub use secret::SecretArgs;
+pub use send_file::SendFileArgs;
+pub use templates::PromptsArgs;
 
 /// SUMM CLI subcommands
 #[derive(Debug, Subcommand)]
 pub enum Commands {
+    /// Bootstrap ~/.summ-daemon (directories, config, hook script, tmux check)
+    Init(InitArgs),
     /// Start a new session
     Start(StartArgs),
-    /// Stop a running session
+    /// Start a throwaway session in an empty workspace, removed once it stops
+    Scratch(ScratchArgs),
+    /// Stop a running session, or all sessions with --all
     Stop(StopArgs),
+    /// Protect a session from stop sweeps (or remove protection with --off)
+    Protect(ProtectArgs),
+    /// Attach a free-text note to a session (or remove it with --clear)
+    Note(NoteArgs),
     /// List all sessions
     List(ListArgs),
     /// Query detailed session status
     Status(StatusArgs),
+    /// Show a session's events, injections, and status changes in time order
+    Timeline(timeline::TimelineArgs),
+    /// List namespaces that have sessions, with their counts
+    Namespaces,
+    /// Wait until a session reaches a status (default: running)
+    Wait(wait::WaitArgs),
     /// Attach to a session terminal (Unix only)
     Attach(AttachArgs),
+    /// Print a session's directory, workspace, runtime, or log path
+    Path(PathArgs),
+    /// Open a session's workspace (or log) in $VISUAL/$EDITOR or the desktop opener
+    Open(OpenArgs),
     /// Inject a message into a running session
     Inject(InjectArgs),
+    /// List the messages injected into a session, latest first
+    InjectHistory(history::InjectHistoryArgs),
+    /// Copy a local file into a session's workspace, optionally injecting a message about it
+    SendFile(SendFileArgs),
+    /// List the files of a session's workspace
+    Ls(LsArgs),
+    /// Copy a file out of a session's workspace
+    GetFile(GetFileArgs),
+    /// Preview the hook settings a session would get, without writing anything
+    Hooks(HooksArgs),
+    /// Manage inject templates (~/.summ-daemon/prompts)
+    Prompts(PromptsArgs),
+    /// Manage named secrets for `start --secret` (~/.summ-daemon/secrets.json)
+    Secret(SecretArgs),
+    /// Send raw key presses (e.g. C-c, Escape, Enter) to a session
+    Keys(KeysArgs),
+    /// Answer the permission or confirmation prompt a session is waiting on
+    Answer(AnswerArgs),
+    /// Send a signal (INT, TERM, HUP, QUIT) to a session's foreground process
+    Signal(SignalArgs),
+    /// Pause a session (SIGSTOP its foreground process group)
+    Pause(PauseArgs),
+    /// Resume a paused session
+    Resume(ResumeArgs),
+    /// Print session counts for a shell prompt (prints nothing if the daemon is down)
+    PromptStatus(PromptStatusArgs),
+    /// Delete logs and session directories left behind by sessions that no longer exist
+    Gc(GcArgs),
+    /// Print the JSON Schema of the daemon protocol
+    Schema,
     /// Daemon management commands
     Daemon(DaemonArgs),
 }
@@ -29,12 +106,36 @@ pub enum Commands {
 impl Commands {
     pub async fn execute(self) -> Result<()> {
         match self {
+            Commands::Init(args) => init::cmd_init(args).await,
             Commands::Start(args) => cmd_start(args).await,
+            Commands::Scratch(args) => cmd_scratch(args).await,
             Commands::Stop(args) => cmd_stop(args).await,
+            Commands::Protect(args) => cmd_protect(args).await,
+            Commands::Note(args) => cmd_note(args).await,
             Commands::List(args) => cmd_list(args).await,
             Commands::Status(args) => cmd_status(args).await,
+            Commands::Timeline(args) => timeline::cmd_timeline(args).await,
+            Commands::Namespaces => cmd_namespaces().await,
+            Commands::Wait(args) => wait::cmd_wait(args).await,
             Commands::Attach(args) => cmd_attach(args).await,
+            Commands::Path(args) => cmd_path(args).await,
+            Commands::Open(args) => open::cmd_open(args).await,
             Commands::Inject(args) => cmd_inject(args).await,
+            Commands::InjectHistory(args) => history::cmd_inject_history(args).await,
+            Commands::SendFile(args) => send_file::cmd_send_file(args).await,
+            Commands::Ls(args) => files::cmd_ls(args).await,
+            Commands::GetFile(args) => files::cmd_get_file(args).await,
+            Commands::Hooks(args) => hooks::cmd_hooks(args).await,
+            Commands::Prompts(args) => templates::cmd_prompts(args).await,
+            Commands::Secret(args) => secret::cmd_secret(args).await,
+            Commands::Keys(args) => cmd_keys(args).await,
+            Commands::Answer(args) => cmd_answer(args).await,
+            Commands::Signal(args) => cmd_signal(args).await,
+            Commands::Pause(args) => cmd_pause(args).await,
+            Commands::Resume(args) => cmd_resume(args).await,
+            Commands::PromptStatus(args) => prompt::cmd_prompt_status(args).await,
+            Commands::Gc(args) => cmd_gc(args).await,
+            Commands::Schema => cmd_schema(),
             Commands::Daemon(args) => cmd_daemon(args).await,
         }
     }
@@ -48,28 +149,199 @@ pub struct StartArgs {
     pub cli: String,
 
     /// Initialization source path (directory, .zip, or .tar.gz)
-    #[clap(long)]
-    pub init: String,
+    #[clap(long, required_unless_present_any = ["init_from_session", "share_workspace"])]
+    pub init: Option<String>,
+
+    /// Initialize from a copy of another session's workspace instead of `--init`
+    #[clap(long, value_name = "SESSION_ID", conflicts_with = "init")]
+    pub init_from_session: Option<String>,
+
+    /// Hold the start until another session reaches a status, e.g.
+    /// `session_ab12cd34:idle` (the status defaults to idle); prints the ID
+    /// of the pending session
+    #[clap(long, value_name = "SESSION_ID[:STATUS]", value_parser = parse_trigger, conflicts_with = "timeout")]
+    pub after: Option<StartTrigger>,
 
     /// Optional custom name for the session
     #[clap(long)]
     pub name: Option<String>,
+
+    /// Protect the session from `summ stop` and `summ stop --all`
+    #[clap(long)]
+    pub protect: bool,
+
+    /// Give up if the session is not ready within this many seconds (the daemon cleans up)
+    #[clap(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+
+    /// Print the session ID immediately and set the session up in the background
+    #[clap(long)]
+    pub background: bool,
+
+    /// Start even if the init source is over the daemon's `max_init_size_mb`
+    #[clap(long)]
+    pub allow_large_init: bool,
+
+    /// When the session stops, commit its git workspace to `summ/<session_id>`
+    /// (`commit`, the default) or write the changes to `runtime/final.patch` (`patch`)
+    #[clap(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "commit")]
+    pub git_checkpoint: Option<summ_common::CheckpointMode>,
+
+    /// Export a secret stored with `summ secret set` into the session's
+    /// environment (repeatable); the daemon reads the value itself
+    #[clap(long = "secret", value_name = "NAME", value_parser = secret::parse_secret_name)]
+    pub secrets: Vec<String>,
+
+    /// Start the CLI in this directory of the workspace (e.g. `services/api`)
+    /// instead of its root; it must exist once the workspace is initialized
+    #[clap(long, value_name = "SUBPATH", value_parser = parse_cwd)]
+    pub cwd: Option<PathBuf>,
+
+    /// Deploy the CLI's hooks into the `--cwd` directory instead of the
+    /// workspace root
+    #[clap(long, requires = "cwd")]
+    pub hooks_in_cwd: bool,
+
+    /// Start in another session's workspace instead of a copy of an init
+    /// source (needs `--observer`)
+    #[clap(long, value_name = "SESSION_ID", requires = "observer", conflicts_with_all = ["init", "init_from_session"])]
+    pub share_workspace: Option<String>,
+
+    /// Start as an observer of the `--share-workspace` session: nothing is
+    /// copied, no hooks are deployed, and files cannot be sent to it
+    #[clap(long, requires = "share_workspace")]
+    pub observer: bool,
+
+    /// Alert once the CLI has been busy on one task this many minutes,
+    /// overriding the config's `busy_alert_minutes` (0 never alerts)
+    #[clap(long, value_name = "MINUTES")]
+    pub busy_alert_minutes: Option<u64>,
+
+    /// Injections per minute the session accepts before refusing more,
+    /// overriding the config's `inject_rate_limit` (0 removes the limit)
+    #[clap(long, value_name = "PER_MINUTE")]
+    pub inject_rate_limit: Option<u32>,
+
+    /// Keep no history of the messages injected into the session
+    #[clap(long)]
+    pub no_inject_history: bool,
+}
+
+/// Arguments for the `scratch` command
+#[derive(Debug, Args)]
+pub struct ScratchArgs {
+    /// CLI command to execute
+    #[clap(long)]
+    pub cli: String,
+
+    /// Optional custom name for the session (default: `scratch-<n>`)
+    #[clap(long)]
+    pub name: Option<String>,
+
+    /// Stop the session once its CLI has been idle this long, e.g. `30m` or `2h`
+    #[clap(long, value_name = "DURATION", value_parser = summ_common::parse_duration, default_value = "30m")]
+    pub idle_timeout: Duration,
+
+    /// Print the session ID immediately and set the session up in the background
+    #[clap(long)]
+    pub background: bool,
 }
 
 /// Arguments for the `stop` command
 #[derive(Debug, Args)]
 pub struct StopArgs {
     /// Session ID to stop
+    #[clap(value_name = "SESSION_ID", required_unless_present = "all")]
+    pub session_id: Option<String>,
+
+    /// Stop every running session (protected sessions are skipped)
+    #[clap(long, conflicts_with = "session_id")]
+    pub all: bool,
+
+    /// Stop protected sessions too
+    #[clap(long)]
+    pub yes_really: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(short, long)]
+    pub yes: bool,
+
+    /// Also stop the observer sessions sharing its workspace
+    #[clap(long, conflicts_with = "all")]
+    pub observers: bool,
+}
+
+/// Arguments for the `gc` command
+#[derive(Debug, Args)]
+pub struct GcArgs {
+    /// Only report what would be deleted
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments for the `protect` command
+#[derive(Debug, Args)]
+pub struct ProtectArgs {
+    /// Session ID to protect
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Remove the protection instead
+    #[clap(long)]
+    pub off: bool,
+}
+
+/// Arguments for the `note` command
+#[derive(Debug, Args)]
+pub struct NoteArgs {
+    /// Session ID to annotate
     #[clap(value_name = "SESSION_ID")]
     pub session_id: String,
+
+    /// Note text (control characters are stripped, long notes are truncated)
+    #[clap(value_name = "TEXT", required_unless_present = "clear", conflicts_with = "clear")]
+    pub text: Option<String>,
+
+    /// Remove the note instead
+    #[clap(long)]
+    pub clear: bool,
 }
 
 /// Arguments for the `list` command
 #[derive(Debug, Args)]
 pub struct ListArgs {
-    /// Optional status filter (running/idle/stopped)
-    #[clap(long, value_name = "STATUS")]
-    pub status: Option<String>,
+    /// Only list sessions in this status
+    #[clap(long, value_name = "STATUS", value_parser = StatusParser)]
+    pub status: Option<SessionStatus>,
+
+    /// Only list starts waiting for another session (`summ start --after`)
+    #[clap(long, conflicts_with = "status")]
+    pub pending: bool,
+
+    /// Only list sessions started by the current user
+    #[clap(long)]
+    pub mine: bool,
+
+    /// Show additional columns (creator)
+    #[clap(long)]
+    pub wide: bool,
+
+    /// Print only the number of sessions in each status
+    #[clap(long, conflicts_with_all = ["status", "pending", "mine", "wide"])]
+    pub counts_only: bool,
+
+    /// List sessions the CLI cannot fully read instead of failing (for debugging)
+    #[clap(long)]
+    pub lenient: bool,
+
+    /// Print only the session IDs, one per line (nothing for no sessions)
+    #[clap(long, conflicts_with_all = ["wide", "counts_only"])]
+    pub ids: bool,
+
+    /// Also list stopped sessions archived out of the daemon's memory
+    /// (`archive_after_hours`), read back from disk
+    #[clap(long, conflicts_with = "counts_only")]
+    pub archived: bool,
 }
 
 /// Arguments for the `status` command
@@ -78,14 +350,60 @@ pub struct StatusArgs {
     /// Session ID to query
     #[clap(value_name = "SESSION_ID")]
     pub session_id: String,
+
+    /// Print only this field of the status, e.g. `pid` or `hooks.state`
+    #[clap(long, value_name = "PATH")]
+    pub field: Option<String>,
+
+    /// Print the environment the session was created under: daemon, tmux
+    /// and CLI versions, host, and environment variables
+    #[clap(long, conflicts_with = "field")]
+    pub env: bool,
 }
 
 /// Arguments for the `attach` command
 #[derive(Debug, Args)]
 pub struct AttachArgs {
     /// Session ID to attach to
+    #[clap(value_name = "SESSION_ID", required_unless_present = "grid", conflicts_with = "grid")]
+    pub session_id: Option<String>,
+
+    /// Show the matching live sessions side by side in one tmux window
+    #[clap(long)]
+    pub grid: bool,
+
+    /// With --grid: only sessions in this status (running/idle/paused)
+    #[clap(long, value_name = "STATUS", requires = "grid", value_parser = StatusParser)]
+    pub status: Option<SessionStatus>,
+
+    /// With --grid: only sessions started by the current user
+    #[clap(long, requires = "grid")]
+    pub mine: bool,
+
+    /// With --grid: let keystrokes reach the sessions (read-only by default)
+    #[clap(long, requires = "grid")]
+    pub writable: bool,
+}
+
+/// Arguments for the `path` command
+#[derive(Debug, Args)]
+#[clap(group(clap::ArgGroup::new("kind").args(["workspace", "log", "runtime"])))]
+pub struct PathArgs {
+    /// Session ID to look up
     #[clap(value_name = "SESSION_ID")]
     pub session_id: String,
+
+    /// Print the workspace directory (where the CLI runs)
+    #[clap(long)]
+    pub workspace: bool,
+
+    /// Print the terminal log file
+    #[clap(long)]
+    pub log: bool,
+
+    /// Print the runtime directory (hook status files)
+    #[clap(long)]
+    pub runtime: bool,
 }
 
 /// Arguments for the `inject` command
@@ -102,6 +420,109 @@ pub struct InjectArgs {
     /// Read message from file
     #[clap(long, value_name = "FILE")]
     pub file: Option<String>,
+
+    /// Render a template from ~/.summ-daemon/prompts/<NAME>.txt
+    #[clap(long, value_name = "NAME", conflicts_with_all = ["message", "file"])]
+    pub template: Option<String>,
+
+    /// Template variable (repeatable)
+    #[clap(long = "var", value_name = "KEY=VALUE", value_parser = templates::parse_var, requires = "template")]
+    pub vars: Vec<(String, String)>,
+
+    /// Send entry N of the session's history again (`summ inject-history`)
+    #[clap(long, value_name = "N", conflicts_with_all = ["message", "file", "template"])]
+    pub resend: Option<usize>,
+
+    /// Send the latest message again (same as `--resend 1`)
+    #[clap(long, conflicts_with_all = ["message", "file", "template", "resend"])]
+    pub last: bool,
+
+    /// Edit entry N of the session's history in $VISUAL/$EDITOR, then send it
+    #[clap(long, value_name = "N", conflicts_with_all = ["message", "file", "template", "resend", "last"])]
+    pub edit_resend: Option<usize>,
+
+    /// Simulate typing with a delay (milliseconds) between characters
+    #[clap(long, value_name = "MS")]
+    pub typing_delay: Option<u64>,
+
+    /// Delay (milliseconds) between lines of the message
+    #[clap(long, value_name = "MS")]
+    pub line_delay: Option<u64>,
+
+    /// Wrap the message in bracketed paste escape sequences
+    #[clap(long)]
+    pub bracketed_paste: bool,
+}
+
+/// Arguments for the `keys` command
+#[derive(Debug, Args)]
+pub struct KeysArgs {
+    /// Session ID to send keys to
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// tmux key names, sent in order (e.g. C-c, Escape, Enter, Up)
+    #[clap(value_name = "KEY", required = true)]
+    pub keys: Vec<String>,
+}
+
+/// Arguments for the `answer` command
+#[derive(Debug, Args)]
+pub struct AnswerArgs {
+    /// Session ID to answer
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// `yes`, `no`, or text to type and submit
+    #[clap(value_name = "ANSWER", required_unless_present = "option")]
+    pub answer: Option<String>,
+
+    /// Choose a numbered option instead (counting from 1)
+    #[clap(long, value_name = "N", conflicts_with = "answer")]
+    pub option: Option<u32>,
+
+    /// Send the answer even if the session is not waiting for input
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl AnswerArgs {
+    fn prompt_answer(&self) -> PromptAnswer {
+        match (self.option, self.answer.as_deref()) {
+            (Some(n), _) => PromptAnswer::Option(n),
+            (None, Some("yes" | "y")) => PromptAnswer::Yes,
+            (None, Some("no" | "n")) => PromptAnswer::No,
+            (None, text) => PromptAnswer::Text(text.unwrap_or_default().to_string()),
+        }
+    }
+}
+
+/// Arguments for the `signal` command
+#[derive(Debug, Args)]
+pub struct SignalArgs {
+    /// Session ID to signal
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Signal name (INT, TERM, HUP, QUIT)
+    #[clap(value_name = "SIGNAL")]
+    pub signal: String,
+}
+
+/// Arguments for the `pause` command
+#[derive(Debug, Args)]
+pub struct PauseArgs {
+    /// Session ID to pause
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+}
+
+/// Arguments for the `resume` command
+#[derive(Debug, Args)]
+pub struct ResumeArgs {
+    /// Session ID to resume
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
 }
 
 /// Arguments for the `daemon` command
@@ -124,133 +545,571 @@ pub enum DaemonSubcommand {
     Stop,
     /// Check daemon status
     Status,
+    /// Check daemon health (ok, degraded, or failing); exits non-zero when failing
+    Health,
+    /// Refresh hook assets for the daemon and all live sessions
+    SyncHooks {
+        /// Rewrite hook assets even if they are already current
+        #[clap(long)]
+        force: bool,
+    },
+    /// Run a lifecycle hook from the daemon config once, with a made-up
+    /// session, and print its exit code and output
+    TestHook {
+        /// Name of the hook in `lifecycle_hooks`
+        name: String,
+        /// Event to run it for (default: its first event)
+        #[clap(long, value_parser = parse_lifecycle_event)]
+        event: Option<LifecycleEvent>,
+    },
+    /// Check the installation end to end: start a throwaway bash session,
+    /// inject a command, see its output and a status report, then stop and
+    /// remove it, printing PASS or FAIL per stage
+    Selftest {
+        /// How long each stage may take, e.g. `15s` or `1m`
+        #[clap(long, value_name = "DURATION", value_parser = summ_common::parse_duration, default_value = "15s")]
+        timeout: Duration,
+        /// Leave the session in place when a stage fails, for debugging
+        #[clap(long)]
+        keep: bool,
+    },
 }
 
 // Command implementations
 
+/// `summ start` explains where the time went when creation takes at least this long
+const SLOW_START_MS: u64 = 3000;
+
+/// Resolve `--init` on the client, where it was typed: expand `~` and `$VAR`
+/// once, make relative paths absolute against `cwd`, and resolve symlinks.
+/// The daemon has its own working directory, so it only ever gets the result.
+fn resolve_init_path(raw: &str, cwd: &Path) -> Result<PathBuf> {
+    let expanded = shellexpand::full(raw)
+        .map_err(|e| anyhow::anyhow!("Failed to expand init path {}: {}", raw, e))?;
+    let path = cwd.join(expanded.as_ref());
+    fs::canonicalize(&path).map_err(|e| {
+        let resolved = if path.as_os_str() == raw {
+            String::new()
+        } else {
+            format!(" (resolved to {})", path.display())
+        };
+        anyhow::anyhow!(
+            "{}: Initialization source not found: {}{}: {}",
+            summ_common::ErrorCode::E001.code(),
+            raw,
+            resolved,
+            e
+        )
+    })
+}
+
+/// Parse `--event` of `daemon test-hook`
+fn parse_lifecycle_event(text: &str) -> Result<LifecycleEvent, String> {
+    serde_json::from_value(serde_json::Value::String(text.to_string())).map_err(|_| {
+        "expected created, running, idle, stopped, failed, removed, busy_alert or throttled".to_string()
+    })
+}
+
+/// Parse `--after SESSION_ID[:STATUS]`
+fn parse_trigger(text: &str) -> Result<StartTrigger, String> {
+    let (session_id, status) = text.split_once(':').unwrap_or((text, "idle"));
+    let status = status.parse().map_err(|e: summ_common::DaemonError| e.message)?;
+    Ok(StartTrigger {
+        session_id: session_id.to_string(),
+        status,
+    })
+}
+
+/// Parse `--cwd`, a directory below the workspace
+fn parse_cwd(text: &str) -> Result<PathBuf, String> {
+    summ_common::validate_workdir_subpath(Path::new(text)).map_err(|e| e.message)
+}
+
 pub async fn cmd_start(args: StartArgs) -> Result<()> {
-    // Expand path with shell expansion (e.g., ~, $HOME)
-    let init_path = shellexpand::full(&args.init)
-        .map_err(|e| anyhow::anyhow!("Failed to expand init path: {}", e))?;
-    let init_path = PathBuf::from(init_path.as_ref());
+    let init_path = match &args.init {
+        Some(init) => resolve_init_path(init, &std::env::current_dir()?)?,
+        None => PathBuf::new(),
+    };
+    // Like --background, a pending start has nothing to show but its ID yet
+    let id_only = args.background || args.after.is_some();
 
     let req = Request::Start {
         cli: args.cli,
         init: init_path,
+        init_from_session: args.init_from_session,
+        after: args.after,
         name: args.name,
+        protected: args.protect,
+        deadline_ms: args.timeout.map(|secs| secs.saturating_mul(1000)),
+        background: args.background,
+        allow_large_init: args.allow_large_init,
+        git_checkpoint: args.git_checkpoint,
+        secrets: args.secrets,
+        workdir_subpath: args.cwd,
+        hooks_in_subpath: args.hooks_in_cwd,
+        ephemeral: false,
+        idle_timeout_seconds: None,
+        observer_of: args.share_workspace,
+        busy_alert_minutes: args.busy_alert_minutes,
+        inject_rate_limit: args.inject_rate_limit,
+        no_inject_history: args.no_inject_history,
     };
 
-    let resp = send_request(req).await?;
+    let data = expect_success(send_request(req).await?)?;
+    if id_only {
+        // Just the ID, for `id=$(summ start --background ...)` and `summ wait`
+        println!("{}", data["session_id"].as_str().unwrap_or_default());
+        return Ok(());
+    }
+    print_json(&data)?;
+    let timings: Option<CreationTimings> =
+        serde_json::from_value(data["creation_timings"].clone()).unwrap_or(None);
+    if let Some(timings) = timings.filter(|t| t.total_ms >= SLOW_START_MS) {
+        eprintln!(
+            "Started in {:.1}s: {}",
+            timings.total_ms as f64 / 1000.0,
+            timings.summary()
+        );
+    }
+    Ok(())
+}
 
-    match resp {
-        Response::Success { data } => {
-            println!("{}", serde_json::to_string_pretty(&data)?);
-            Ok(())
-        }
-        Response::Error { code, message } => {
-            anyhow::bail!("{}: {}", code, message);
-        }
+/// `summ start` with the scratch preset: an empty workspace, an idle timeout,
+/// and removal of everything once the session stops
+pub async fn cmd_scratch(args: ScratchArgs) -> Result<()> {
+    let req = Request::Start {
+        cli: args.cli,
+        init: PathBuf::new(),
+        init_from_session: None,
+        after: None,
+        name: args.name,
+        protected: false,
+        deadline_ms: None,
+        background: args.background,
+        allow_large_init: false,
+        git_checkpoint: None,
+        secrets: Vec::new(),
+        workdir_subpath: None,
+        hooks_in_subpath: false,
+        ephemeral: true,
+        idle_timeout_seconds: Some(args.idle_timeout.as_secs()),
+        observer_of: None,
+        busy_alert_minutes: None,
+        inject_rate_limit: None,
+        no_inject_history: false,
+    };
+
+    let data = expect_success(send_request(req).await?)?;
+    if args.background {
+        println!("{}", data["session_id"].as_str().unwrap_or_default());
+        return Ok(());
     }
+    print_json(&data)
 }
 
 pub async fn cmd_stop(args: StopArgs) -> Result<()> {
-    let req = Request::Stop {
-        session_id: args.session_id,
+    let req = match args.session_id {
+        Some(session_id) if !args.all => {
+            // Stopping a session in the middle of a task loses its work, so confirm first
+            let preview = expect_success(
+                send_request(Request::Status {
+                    session_id: session_id.clone(),
+                })
+                .await?,
+            )?;
+            if preview["status"] == "running" {
+                confirm::confirm("stop busy", &[describe_session(&preview)], args.yes)?;
+            }
+
+            Request::Stop {
+                session_id,
+                override_protection: args.yes_really,
+                stop_observers: args.observers,
+            }
+        }
+        _ => {
+            let preview = expect_success(
+                send_request(Request::StopAll {
+                    override_protection: args.yes_really,
+                    dry_run: true,
+                })
+                .await?,
+            )?;
+            let affected: Vec<String> = preview["would_stop"]
+                .as_array()
+                .map(|sessions| sessions.iter().map(describe_session).collect())
+                .unwrap_or_default();
+
+            if affected.is_empty() {
+                println!("{}", serde_json::to_string_pretty(&preview)?);
+                return Ok(());
+            }
+            confirm::confirm("stop", &affected, args.yes)?;
+
+            Request::StopAll {
+                override_protection: args.yes_really,
+                dry_run: false,
+            }
+        }
     };
 
-    let resp = send_request(req).await?;
+    let data = expect_success(send_request(req).await?)?;
+    print_json(&data)?;
+    if let Some(running) = data["running_observers"].as_array().filter(|running| !running.is_empty()) {
+        let ids: Vec<&str> = running.iter().filter_map(|id| id.as_str()).collect();
+        eprintln!(
+            "Warning: {} still observe the stopped session's workspace; stop them too with --observers",
+            ids.join(", ")
+        );
+    }
+    Ok(())
+}
 
+/// Unwrap a success payload or turn an error response into an error
+/// A failure that makes `summ` exit with `code` instead of 1
+#[derive(Debug)]
+pub struct ExitError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl std::fmt::Display for ExitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ExitError {}
+
+fn expect_success(resp: Response) -> Result<serde_json::Value> {
     match resp {
-        Response::Success { data } => {
-            println!("{}", serde_json::to_string_pretty(&data)?);
-            Ok(())
-        }
-        Response::Error { code, message } => {
+        Response::Success { data } => Ok(data),
+        Response::Error { code, message, .. } => {
             anyhow::bail!("{}: {}", code, message);
         }
+        Response::Batch { .. } | Response::Skipped => {
+            anyhow::bail!("Daemon answered with a batch response to a single request");
+        }
     }
 }
 
+/// One-line summary of a session for confirmation prompts
+fn describe_session(session: &serde_json::Value) -> String {
+    format!(
+        "{} ({}) {}",
+        session["session_id"].as_str().unwrap_or("unknown"),
+        session["status"].as_str().unwrap_or("unknown"),
+        session["name"].as_str().unwrap_or("")
+    )
+    .trim_end()
+    .to_string()
+}
+
+pub async fn cmd_protect(args: ProtectArgs) -> Result<()> {
+    let req = Request::SetProtected {
+        session_id: args.session_id,
+        protected: !args.off,
+    };
+
+    print_json(&expect_success(send_request(req).await?)?)
+}
+
+pub async fn cmd_note(args: NoteArgs) -> Result<()> {
+    let req = Request::SetNote {
+        session_id: args.session_id,
+        note: args.text,
+    };
+
+    print_json(&expect_success(send_request(req).await?)?)
+}
+
 pub async fn cmd_list(args: ListArgs) -> Result<()> {
-    let status_filter = parse_status_filter(args.status)?;
+    if args.counts_only {
+        return cmd_list_counts().await;
+    }
+
+    let status_filter = if args.pending {
+        Some(SessionStatus::Pending)
+    } else {
+        args.status
+    };
 
-    let req = Request::List { status_filter };
+    // The daemon matches this against the uid it recorded for each session
+    let created_by_uid = if args.mine {
+        Some(summ_common::user::current_uid())
+    } else {
+        None
+    };
 
-    let resp = send_request(req).await?;
+    let req = Request::List {
+        status_filter,
+        created_by_uid,
+        include_archived: args.archived,
+    };
 
-    match resp {
-        Response::Success { data } => {
-            // Try to parse as array of sessions
-            if let Some(sessions) = data.as_array() {
-                print_colored_list(sessions);
-            } else {
-                println!("{}", serde_json::to_string_pretty(&data)?);
+    if args.ids {
+        let data = expect_success(send_request(req).await?)?;
+        let sessions = data.as_array().context("Malformed List response")?;
+        print!("{}", render_ids(sessions));
+        return Ok(());
+    }
+
+    // The daemon warnings printed under the list come in the same round trip
+    let [list, daemon_status]: [Response; 2] = client::batch(vec![req, Request::DaemonStatus], false)
+        .await?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Malformed Batch response"))?;
+    let data = expect_success(list)?;
+    // Try to parse as array of sessions
+    if let Some(sessions) = data.as_array() {
+        if let Some(problem) = malformed_sessions(sessions) {
+            if !args.lenient {
+                anyhow::bail!(
+                    "{}. The daemon may run a different version than this CLI ({}); \
+                     compare with `summ daemon status`, or pass --lenient to list them anyway",
+                    problem,
+                    env!("CARGO_PKG_VERSION")
+                );
             }
-            Ok(())
+            eprintln!("warning: {}", problem);
         }
-        Response::Error { code, message } => {
-            anyhow::bail!("{}: {}", code, message);
+        let now = chrono::Utc::now();
+        let color = style::stdout_color();
+        println!(
+            "{}",
+            render_list(sessions, args.wide, color, now, timestamps::time_style())
+        );
+        if sessions.iter().any(|session| hook_warning(session).is_some()) {
+            eprintln!("{}", HOOK_HINT);
         }
+        // Best effort: the list is already printed if this fails
+        if let Response::Success { data } = daemon_status {
+            print_daemon_warnings(&data);
+        }
+    } else {
+        print_json(&data)?;
     }
+    Ok(())
+}
+
+/// Describe the List entries that do not match `SessionInfo`, if any
+fn malformed_sessions(sessions: &[serde_json::Value]) -> Option<String> {
+    let errors: Vec<String> = sessions
+        .iter()
+        .filter_map(|session| {
+            serde_json::from_value::<SessionInfo>(session.clone())
+                .err()
+                .map(|e| format!("{}: {}", session["session_id"].as_str().unwrap_or("<no session_id>"), e))
+        })
+        .collect();
+    let first = errors.first()?;
+    Some(format!(
+        "{} of {} sessions in the daemon's response are malformed (first: {})",
+        errors.len(),
+        sessions.len(),
+        first
+    ))
+}
+
+async fn cmd_list_counts() -> Result<()> {
+    print_json(&expect_success(send_request(Request::Counts).await?)?)
+}
+
+pub async fn cmd_namespaces() -> Result<()> {
+    let data = expect_success(send_request(Request::Namespaces).await?)?;
+    let namespaces: Vec<NamespaceSummary> =
+        serde_json::from_value(data).context("Malformed Namespaces response")?;
+    println!("{}", render_namespaces(&namespaces));
+    Ok(())
+}
+
+/// The environment snapshot as aligned `name: value` lines, variables last
+fn render_environment(snapshot: &summ_common::EnvironmentSnapshot, time_style: TimeStyle) -> String {
+    let unknown = || "unknown".to_string();
+    let cli = match (&snapshot.cli_path, &snapshot.cli_version) {
+        (Some(path), Some(version)) => format!("{} ({})", path.display(), version),
+        (Some(path), None) => path.display().to_string(),
+        (None, _) => "not found on PATH".to_string(),
+    };
+    let mut lines = vec![
+        format!("captured: {}", timestamps::display(snapshot.captured_at, chrono::Utc::now(), time_style)),
+        format!("daemon:   {}", snapshot.daemon_version),
+        format!("tmux:     {}", snapshot.tmux_version.clone().unwrap_or_else(unknown)),
+        format!("cli:      {}", cli),
+        format!("host:     {}", snapshot.hostname.clone().unwrap_or_else(unknown)),
+        format!("os:       {}", snapshot.os),
+    ];
+    if !snapshot.env.is_empty() {
+        lines.push("env:".to_string());
+        lines.extend(snapshot.env.iter().map(|(name, value)| format!("  {}={}", name, value)));
+    }
+    lines.join("\n")
+}
+
+/// One line per namespace: its session total and the statuses it has
+fn render_namespaces(namespaces: &[NamespaceSummary]) -> String {
+    if namespaces.is_empty() {
+        return "No sessions found.".to_string();
+    }
+    namespaces
+        .iter()
+        .map(|summary| {
+            let counts = &summary.counts;
+            let statuses: Vec<String> = [
+                ("running", counts.running),
+                ("idle", counts.idle),
+                ("paused", counts.paused),
+                ("starting", counts.starting),
+                ("pending", counts.pending),
+                ("stopped", counts.stopped),
+                ("failed", counts.failed),
+                // Not in the total: the daemon no longer holds them
+                ("archived", counts.archived),
+            ]
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(status, count)| format!("{} {}", count, status))
+            .collect();
+            format!(
+                "{}: {} session{} ({})",
+                summary.namespace,
+                counts.total,
+                if counts.total == 1 { "" } else { "s" },
+                statuses.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 pub async fn cmd_status(args: StatusArgs) -> Result<()> {
     let req = Request::Status {
-        session_id: args.session_id,
+        session_id: args.session_id.clone(),
     };
 
-    let resp = send_request(req).await?;
-
-    match resp {
-        Response::Success { data } => {
-            println!("{}", serde_json::to_string_pretty(&data)?);
-            Ok(())
+    let mut data = expect_success(send_request(req).await?)?;
+    if args.env {
+        // Older daemons, and sessions created before them, have no snapshot
+        match serde_json::from_value::<summ_common::EnvironmentSnapshot>(data["environment"].take()) {
+            Ok(snapshot) => println!("{}", render_environment(&snapshot, timestamps::time_style())),
+            Err(_) => anyhow::bail!("No environment was recorded for session {}", args.session_id),
         }
-        Response::Error { code, message } => {
-            anyhow::bail!("{}: {}", code, message);
+        return Ok(());
+    }
+    if let Some(path) = args.field {
+        timestamps::apply_to_json(&mut data);
+        println!("{}", field_value(&data, &path)?);
+        return Ok(());
+    }
+    print_json(&data)?;
+    if let Ok(manifest) =
+        serde_json::from_value::<summ_common::InitManifest>(data["init_manifest"].clone())
+    {
+        eprintln!("init: {}", manifest.summary());
+        if !manifest.path_issues.is_empty() {
+            eprintln!(
+                "workspace: {} init entr{} left out or renamed (init_path_policy):",
+                manifest.path_issues.len(),
+                if manifest.path_issues.len() == 1 { "y" } else { "ies" }
+            );
+            for issue in &manifest.path_issues {
+                eprintln!("  {}", issue);
+            }
         }
     }
+    if let Ok(checkpoint) =
+        serde_json::from_value::<summ_common::Checkpoint>(data["checkpoint"].clone())
+    {
+        eprintln!("checkpoint: {}", checkpoint.summary());
+    }
+    if let Some(prompt) = data["waiting_for_input"]["text"].as_str() {
+        eprintln!("waiting for input: attach or inject to answer it\n{}", prompt);
+    } else if data["needs_attention"].as_bool().unwrap_or(false) {
+        eprintln!("needs attention: the CLI rang the bell; attach or inject to answer it");
+    }
+    if let Some(hooks) = hook_info(&data) {
+        eprintln!("hooks: {}", hook_summary(&hooks, chrono::Utc::now()));
+    }
+    if hook_warning(&data).is_some() {
+        eprintln!("{}", HOOK_HINT);
+    }
+    Ok(())
 }
 
 pub async fn cmd_attach(args: AttachArgs) -> Result<()> {
+    let Some(session_id) = args.session_id.clone() else {
+        return grid::cmd_attach_grid(args).await;
+    };
+
+    // First verify session exists via daemon
+    let req = Request::Status {
+        session_id: session_id.clone(),
+    };
+    let data = expect_success(send_request(req).await?)?;
+    // Daemons without namespaces do not report it: the default naming
+    let tmux_session = data["tmux_session"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| Namespace::default().tmux_session(&session_id));
+
+    // Attaching answers a bell: clear the alerts the daemon reports as
+    // needs_attention (best effort; older tmux may not support it)
+    let _ = Command::new("tmux")
+        .args(["kill-session", "-C", "-t", &tmux_session])
+        .status();
+
+    Err(exec_attach(&tmux_session, false))
+}
+
+/// Replace the current process with `tmux attach-session`; returns only on failure
+fn exec_attach(tmux_session: &str, read_only: bool) -> anyhow::Error {
     #[cfg(unix)]
     {
         use std::os::unix::process::CommandExt;
 
-        let tmux_session = format!("summ-{}", args.session_id);
-
-        // First verify session exists via daemon
-        let req = Request::Status {
-            session_id: args.session_id.clone(),
-        };
-        let resp = send_request(req).await?;
-
-        match resp {
-            Response::Success { .. } => {
-                // Session exists, use exec to replace current process with tmux attach
-                let err = Command::new("tmux")
-                    .args(["attach-session", "-t", &tmux_session])
-                    .exec();
-
-                // exec only returns on failure
-                Err(anyhow::anyhow!("Failed to attach to tmux session: {}", err))
-            }
-            Response::Error { code, message } => {
-                anyhow::bail!("{}: {}", code, message);
-            }
+        let mut command = Command::new("tmux");
+        command.arg("attach-session");
+        if read_only {
+            command.arg("-r");
         }
+        let err = command.args(["-t", tmux_session]).exec();
+
+        // exec only returns on failure
+        anyhow::anyhow!("Failed to attach to tmux session: {}", err)
     }
 
     #[cfg(not(unix))]
     {
-        Err(anyhow::anyhow!(
-            "attach command is only supported on Unix systems with tmux"
-        ))
+        let _ = (tmux_session, read_only);
+        anyhow::anyhow!("attach command is only supported on Unix systems with tmux")
     }
 }
 
+pub async fn cmd_path(args: PathArgs) -> Result<()> {
+    let field = if args.workspace {
+        "workspace_path"
+    } else if args.log {
+        "log_path"
+    } else if args.runtime {
+        "runtime_path"
+    } else {
+        "workdir"
+    };
+
+    let req = Request::Status {
+        session_id: args.session_id,
+    };
+
+    let data = expect_success(send_request(req).await?)?;
+    let path = data[field]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Daemon did not report {}", field))?;
+    println!("{}", path);
+    Ok(())
+}
+
 pub async fn cmd_inject(args: InjectArgs) -> Result<()> {
-    // Get message from --message or --file
+    // Get message from --message, --file, a template, or the history
     let message = if let Some(msg) = args.message {
         msg
     } else if let Some(file_path) = args.file {
@@ -262,26 +1121,69 @@ pub async fn cmd_inject(args: InjectArgs) -> Result<()> {
         // Read file content
         fs::read_to_string(&path)
             .map_err(|e| anyhow::anyhow!("Failed to read file {}: {}", path.display(), e))?
+    } else if let Some(template) = args.template {
+        templates::render_named_template(&template, &args.vars)?
+    } else if let Some(index) = args.resend.or(args.last.then_some(1)) {
+        history::resend_message(&args.session_id, index).await?
+    } else if let Some(index) = args.edit_resend {
+        history::edit_message(&history::recall(&args.session_id, index).await?.message)?
     } else {
-        anyhow::bail!("Either --message or --file must be provided");
+        anyhow::bail!("One of --message, --file, --template, --resend, --last or --edit-resend must be provided");
     };
 
     let req = Request::Inject {
         session_id: args.session_id,
         message,
+        typing_delay_ms: args.typing_delay,
+        line_delay_ms: args.line_delay,
+        bracketed_paste: args.bracketed_paste,
     };
 
-    let resp = send_request(req).await?;
+    print_json(&expect_success(send_request(req).await?)?)
+}
 
-    match resp {
-        Response::Success { data } => {
-            println!("{}", serde_json::to_string_pretty(&data)?);
-            Ok(())
-        }
-        Response::Error { code, message } => {
-            anyhow::bail!("{}: {}", code, message);
-        }
-    }
+pub async fn cmd_keys(args: KeysArgs) -> Result<()> {
+    let req = Request::SendKeys {
+        session_id: args.session_id,
+        keys: args.keys,
+    };
+
+    print_json(&expect_success(send_request(req).await?)?)
+}
+
+pub async fn cmd_answer(args: AnswerArgs) -> Result<()> {
+    let req = Request::Answer {
+        answer: args.prompt_answer(),
+        session_id: args.session_id,
+        force: args.force,
+    };
+
+    print_json(&expect_success(send_request(req).await?)?)
+}
+
+pub async fn cmd_signal(args: SignalArgs) -> Result<()> {
+    let req = Request::Signal {
+        session_id: args.session_id,
+        signal: args.signal,
+    };
+
+    print_json(&expect_success(send_request(req).await?)?)
+}
+
+pub async fn cmd_pause(args: PauseArgs) -> Result<()> {
+    let req = Request::Pause {
+        session_id: args.session_id,
+    };
+
+    print_json(&expect_success(send_request(req).await?)?)
+}
+
+pub async fn cmd_resume(args: ResumeArgs) -> Result<()> {
+    let req = Request::Resume {
+        session_id: args.session_id,
+    };
+
+    print_json(&expect_success(send_request(req).await?)?)
 }
 
 pub async fn cmd_daemon(args: DaemonArgs) -> Result<()> {
@@ -289,6 +1191,10 @@ pub async fn cmd_daemon(args: DaemonArgs) -> Result<()> {
         DaemonSubcommand::Start { port } => cmd_daemon_start(port).await,
         DaemonSubcommand::Stop => cmd_daemon_stop().await,
         DaemonSubcommand::Status => cmd_daemon_status().await,
+        DaemonSubcommand::Health => cmd_daemon_health().await,
+        DaemonSubcommand::SyncHooks { force } => cmd_daemon_sync_hooks(force).await,
+        DaemonSubcommand::TestHook { name, event } => cmd_daemon_test_hook(name, event).await,
+        DaemonSubcommand::Selftest { timeout, keep } => selftest::cmd_selftest(timeout, keep).await,
     }
 }
 
@@ -397,7 +1303,8 @@ pub async fn cmd_daemon_status() -> Result<()> {
         // Try to get detailed status from daemon
         match send_request(Request::DaemonStatus).await {
             Ok(Response::Success { data }) => {
-                println!("{}", serde_json::to_string_pretty(&data)?);
+                print_json(&data)?;
+                print_daemon_warnings(&data);
             }
             _ => {
                 // Daemon is running but status endpoint failed
@@ -412,6 +1319,38 @@ pub async fn cmd_daemon_status() -> Result<()> {
     Ok(())
 }
 
+pub async fn cmd_daemon_health() -> Result<()> {
+    let data = expect_success(send_request(Request::Healthz).await?)?;
+    print_json(&data)?;
+    let report: HealthReport = serde_json::from_value(data)?;
+    for reason in &report.reasons {
+        eprintln!("⚠ {}", reason);
+    }
+    if report.status == HealthStatus::Failing {
+        anyhow::bail!("Daemon health is failing");
+    }
+    Ok(())
+}
+
+pub async fn cmd_daemon_sync_hooks(force: bool) -> Result<()> {
+    let data = expect_success(send_request(Request::SyncHooks { force }).await?)?;
+    print_json(&data)?;
+    Ok(())
+}
+
+pub async fn cmd_daemon_test_hook(name: String, event: Option<LifecycleEvent>) -> Result<()> {
+    let data = expect_success(send_request(Request::TestHook { name, event }).await?)?;
+    print_json(&data)?;
+    let run: HookRun = serde_json::from_value(data).context("Malformed TestHook response")?;
+    if run.timed_out {
+        anyhow::bail!("Lifecycle hook {} timed out", run.name);
+    }
+    if run.exit_code != Some(0) {
+        anyhow::bail!("Lifecycle hook {} failed", run.name);
+    }
+    Ok(())
+}
+
 // Helper functions for daemon management
 
 /// Check if daemon is running by attempting to connect to the socket
@@ -469,51 +1408,828 @@ async fn find_daemon_pid() -> Result<Option<u32>> {
     }
 }
 
-// Helper function to parse status filter from string
+/// Parses `--status` values with `SessionStatus::from_str` and offers
+/// `SessionStatus::ALL` as the possible values in help and completions
+#[derive(Debug, Clone, Copy)]
+pub struct StatusParser;
+
+impl clap::builder::TypedValueParser for StatusParser {
+    type Value = SessionStatus;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<SessionStatus, clap::Error> {
+        let invalid = |message: String| {
+            let arg = arg.map(|arg| format!(" for '{}'", arg)).unwrap_or_default();
+            clap::Error::raw(
+                clap::error::ErrorKind::InvalidValue,
+                format!("invalid value{}: {}\n", arg, message),
+            )
+            .with_cmd(cmd)
+        };
+        let text = value
+            .to_str()
+            .ok_or_else(|| invalid(format!("Invalid status {:?}", value)))?;
+        text.parse().map_err(|e: summ_common::DaemonError| invalid(e.message))
+    }
 
-pub fn parse_status_filter(s: Option<String>) -> Result<Option<SessionStatus>> {
-    match s.as_deref() {
-        None => Ok(None),
-        Some("running") => Ok(Some(SessionStatus::Running)),
-        Some("idle") => Ok(Some(SessionStatus::Idle)),
-        Some("stopped") => Ok(Some(SessionStatus::Stopped)),
-        Some(other) => anyhow::bail!("Invalid status filter: {}. Use: running, idle, or stopped", other),
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_>> {
+        Some(Box::new(
+            SessionStatus::ALL.iter().map(|status| clap::builder::PossibleValue::new(status.as_str())),
+        ))
     }
 }
 
-// Helper function to print colored list output
+/// Printed when a session's hooks are not reporting
+const HOOK_HINT: &str = "⚠ Session hooks are not reporting; run `summ daemon sync-hooks` to redeploy them";
 
-fn print_colored_list(sessions: &[serde_json::Value]) {
-    use ansi_term::Colour;
+/// The session's `hook_health` when it needs attention (`missing` or `broken`)
+fn hook_warning(session: &serde_json::Value) -> Option<&str> {
+    session["hook_health"]
+        .as_str()
+        .filter(|health| matches!(*health, "missing" | "broken"))
+}
 
-    if sessions.is_empty() {
-        println!("{}", Colour::Purple.dimmed().paint("No sessions found."));
-        return;
+/// The session's `hooks`, when the daemon recorded them
+fn hook_info(session: &serde_json::Value) -> Option<HookInfo> {
+    serde_json::from_value(session["hooks"].clone()).ok()
+}
+
+/// Name of a hook state as sent by the daemon (`ok`, `silent`, ...)
+fn hook_state_name(state: HookState) -> String {
+    serde_json::to_value(state)
+        .ok()
+        .and_then(|state| state.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Compact hook indicator for `summ list --wide`, e.g. `hooks:ok 2m` or
+/// `hooks:silent 3h`, aged from the latest report (or the deployment)
+fn hook_indicator(hooks: &HookInfo, now: chrono::DateTime<chrono::Utc>) -> String {
+    let since = match hooks.state {
+        HookState::None => None,
+        _ => hooks.last_event_at.or(hooks.deployed_at),
+    };
+    match since {
+        Some(since) => format!("hooks:{} {}", hook_state_name(hooks.state), timestamps::age(since, now)),
+        None => format!("hooks:{}", hook_state_name(hooks.state)),
+    }
+}
+
+/// Hook detail for `summ status`, e.g. `claude, ok; last event stop 2m ago; deployed 3h ago`
+fn hook_summary(hooks: &HookInfo, now: chrono::DateTime<chrono::Utc>) -> String {
+    let kind = serde_json::to_value(hooks.kind)
+        .ok()
+        .and_then(|kind| kind.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let mut parts = vec![format!("{}, {}", kind, hook_state_name(hooks.state))];
+    if let Some(at) = hooks.last_event_at {
+        let event = hooks.last_event.as_deref().unwrap_or("report");
+        parts.push(format!("last event {} {}", event, timestamps::relative(at, now)));
+    } else if hooks.deployed_at.is_some() {
+        parts.push("no event yet".to_string());
+    }
+    if let Some(at) = hooks.deployed_at {
+        parts.push(format!("deployed {}", timestamps::relative(at, now)));
+    }
+    let mut summary = parts.join("; ");
+    if hooks.state == HookState::Silent {
+        summary.push_str(" (no report while busy; attach to check the CLI, or run `summ daemon sync-hooks`)");
+    }
+    summary
+}
+
+/// Print the recovery notice and low-disk warning of a DaemonStatus response to stderr
+fn print_daemon_warnings(daemon_status: &serde_json::Value) {
+    for warning in [
+        recovery_notice(daemon_status),
+        storage_warning(daemon_status),
+        disk_warning(daemon_status),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        eprintln!("{}", warning);
+    }
+}
+
+/// Notice line while the daemon is still loading sessions after a restart
+fn recovery_notice(daemon_status: &serde_json::Value) -> Option<String> {
+    let recovery = &daemon_status["recovery"];
+    if recovery["recovering"] != true {
+        return None;
+    }
+    Some(format!(
+        "⚠ Daemon is recovering sessions: {} of {} loaded; the list may be incomplete",
+        recovery["loaded"], recovery["total"]
+    ))
+}
+
+/// Warning line while the daemon cannot write to its sessions directory
+fn storage_warning(daemon_status: &serde_json::Value) -> Option<String> {
+    let storage = &daemon_status["storage"];
+    if storage["available"] != false {
+        return None;
     }
+    Some(format!(
+        "⚠ Storage unavailable: {} consecutive writes failed; state changes are refused",
+        storage["consecutive_failures"]
+    ))
+}
+
+/// Low-disk warning line from a DaemonStatus response, if the daemon raised one
+fn disk_warning(daemon_status: &serde_json::Value) -> Option<String> {
+    let warning = &daemon_status["disk_warning"];
+    let message = warning["message"].as_str()?;
+    let label = match warning["level"].as_str() {
+        Some("critical") => "Disk almost full",
+        _ => "Low disk space",
+    };
+    Some(format!("⚠ {}: {}", label, message))
+}
+
+/// Print daemon JSON, with timestamps in the chosen time style
+fn print_json(data: &serde_json::Value) -> Result<()> {
+    let mut data = data.clone();
+    timestamps::apply_to_json(&mut data);
+    println!("{}", serde_json::to_string_pretty(&data)?);
+    Ok(())
+}
 
-    for session in sessions {
-        let session_id = session["session_id"].as_str().unwrap_or("unknown");
-        let name = session["name"].as_str().unwrap_or("");
-        let cli = session["cli"].as_str().unwrap_or("unknown");
-        let status = session["status"].as_str().unwrap_or("unknown");
+/// One session ID per line, each ending in a newline; empty for no sessions
+fn render_ids(sessions: &[serde_json::Value]) -> String {
+    sessions
+        .iter()
+        .filter_map(|session| session["session_id"].as_str())
+        .map(|session_id| format!("{}\n", session_id))
+        .collect()
+}
 
-        let status_colored = match status {
-            "running" => Colour::Green.paint(status),
-            "idle" => Colour::Yellow.paint(status),
-            "stopped" => Colour::Red.paint(status),
-            _ => Colour::White.paint(status),
+/// The value at a dotted `path` of `data` (`hooks.state`; array elements by
+/// index) as a script wants it: strings bare, null as nothing, objects and
+/// arrays as compact JSON. A path a field of which `data` does not have is
+/// an error naming the fields it does; a path through null is null.
+fn field_value(data: &serde_json::Value, path: &str) -> Result<String> {
+    // The path up to (not including) the key at `depth`
+    let parent = |depth| path.split('.').take(depth).collect::<Vec<_>>().join(".");
+    let mut value = data;
+    for (depth, key) in path.split('.').enumerate() {
+        value = match value {
+            serde_json::Value::Null => break,
+            serde_json::Value::Object(fields) => match fields.get(key) {
+                Some(field) => field,
+                None => {
+                    let mut available: Vec<&str> = fields.keys().map(String::as_str).collect();
+                    available.sort_unstable();
+                    let within = if depth == 0 { String::new() } else { format!(" in `{}`", parent(depth)) };
+                    anyhow::bail!(
+                        "Unknown field `{}`{}; available fields: {}",
+                        key,
+                        within,
+                        available.join(", ")
+                    );
+                }
+            },
+            serde_json::Value::Array(items) => match key.parse().ok().and_then(|i: usize| items.get(i)) {
+                Some(item) => item,
+                None => anyhow::bail!("`{}` has no element {} ({} elements)", parent(depth), key, items.len()),
+            },
+            _ => anyhow::bail!("`{}` is {}, which has no fields", parent(depth), value),
         };
+    }
+    Ok(match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    })
+}
 
-        println!(
-            "{} {} {} {}",
-            Colour::Cyan.bold().paint(session_id),
-            Colour::White.dimmed().paint(format!("({})", cli)),
-            status_colored,
-            if name.is_empty() {
+pub async fn cmd_gc(args: GcArgs) -> Result<()> {
+    let data = expect_success(send_request(Request::Gc { dry_run: args.dry_run }).await?)?;
+    print_json(&data)
+}
+
+/// Print the protocol schema bundled with this CLI; needs no daemon
+fn cmd_schema() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&summ_common::protocol_schema())?);
+    Ok(())
+}
+
+// Helper function to render the session list
+
+/// Token counts as `12.3k`, `4.5M`, or the plain number below a thousand
+fn compact_count(count: u64) -> String {
+    match count {
+        n if n < 1_000 => n.to_string(),
+        n if n < 1_000_000 => format!("{:.1}k", n as f64 / 1_000.0),
+        n => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// Transcript usage of a session, e.g. `12.3k/678 tokens ~$0.05`, if it is known
+fn format_usage(session: &serde_json::Value) -> Option<String> {
+    let tokens_in = session["tokens_in"].as_u64()?;
+    let tokens_out = session["tokens_out"].as_u64().unwrap_or(0);
+    let cost = session["estimated_cost"]
+        .as_f64()
+        .map(|cost| format!(" ~${:.2}", cost))
+        .unwrap_or_default();
+    Some(format!(
+        "{}/{} tokens{}",
+        compact_count(tokens_in),
+        compact_count(tokens_out),
+        cost
+    ))
+}
+
+/// One line per session, coloured only when `color` is set. `--wide` adds the
+/// creator, creation time (relative to `now`), usage, foreground command and
+/// hook indicator.
+fn render_list(
+    sessions: &[serde_json::Value],
+    wide: bool,
+    color: bool,
+    now: chrono::DateTime<chrono::Utc>,
+    time_style: TimeStyle,
+) -> String {
+    use owo_colors::Style;
+    use style::paint;
+
+    if sessions.is_empty() {
+        return paint("No sessions found.", Style::new().purple().dimmed(), color);
+    }
+
+    let lines: Vec<String> = sessions
+        .iter()
+        .map(|session| {
+            let session_id = session["session_id"].as_str().unwrap_or("unknown");
+            let name = session["name"].as_str().unwrap_or("");
+            let cli = session["cli"].as_str().unwrap_or("unknown");
+            let status = session["status"].as_str().unwrap_or("unknown");
+            let created_by = session["created_by"].as_str().unwrap_or("unknown");
+            let protected = session["protected"].as_bool().unwrap_or(false);
+            let ephemeral = session["ephemeral"].as_bool().unwrap_or(false);
+            let needs_attention = session["needs_attention"].as_bool().unwrap_or(false);
+            let waiting = session["waiting_for_input"].is_object();
+            let note = session["note"].as_str().filter(|_| wide);
+            let current_task = session["current_task"].as_str().filter(|_| wide);
+            let created_at = session["created_at"]
+                .as_str()
+                .and_then(|s| s.parse::<chrono::DateTime<chrono::Utc>>().ok());
+
+            let hook_marker = hook_warning(session)
+                .map(|health| {
+                    let marker = format!("⚠ hooks {}", health);
+                    format!(" {}", paint(marker, Style::new().yellow(), color))
+                })
+                .unwrap_or_default();
+            // Init entries that were left out or renamed
+            let workspace_marker = session["workspace_issues"]
+                .as_u64()
+                .map(|issues| {
+                    let marker = format!("⚠ workspace {} path{}", issues, if issues == 1 { "" } else { "s" });
+                    format!(" {}", paint(marker, Style::new().yellow(), color))
+                })
+                .unwrap_or_default();
+            // What a pending start is waiting for
+            let trigger = serde_json::from_value::<StartTrigger>(session["start_after"].clone())
+                .ok()
+                .filter(|_| status == "pending")
+                .map(|trigger| {
+                    format!(" {}", paint(format!("after {}", trigger), Style::new().white().dimmed(), color))
+                })
+                .unwrap_or_default();
+            // summ did not stop it: someone killed its tmux session
+            let killed = if session["stop_reason"]["kind"] == "external_kill" {
+                format!(" {}", paint("✗ killed outside summ", Style::new().red(), color))
+            } else {
                 String::new()
+            };
+            // Throwaway sessions stay in the background of the list
+            let (id_style, scratch) = if ephemeral {
+                let marker = format!(" {}", paint("ephemeral", Style::new().white().dimmed(), color));
+                (Style::new().white().dimmed(), marker)
             } else {
-                format!("- {}", Colour::White.paint(name))
-            }
+                (Style::new().cyan().bold(), String::new())
+            };
+            // Workspace sharing, from either side
+            let observers = sessions
+                .iter()
+                .filter(|other| other["observer_of"].as_str() == Some(session_id))
+                .count();
+            let sharing = match session["observer_of"].as_str() {
+                Some(observed) => format!("observes {}", observed),
+                None if observers > 0 => format!("observed by {}", observers),
+                None => String::new(),
+            };
+            let sharing = if sharing.is_empty() {
+                sharing
+            } else {
+                format!(" {}", paint(sharing, Style::new().white().dimmed(), color))
+            };
+            // How long the current task has been going
+            let busy = session["busy_seconds"]
+                .as_i64()
+                .map(|seconds| {
+                    let busy = format!("for {}", timestamps::age(now - chrono::Duration::seconds(seconds), now));
+                    format!(" {}", paint(busy, Style::new().white().dimmed(), color))
+                })
+                .unwrap_or_default();
+            // Only shown with --all-namespaces or inside a namespace
+            let namespace = session["namespace"]
+                .as_str()
+                .filter(|namespace| *namespace != DEFAULT_NAMESPACE)
+                .map(|namespace| paint(format!("{}/", namespace), Style::new().white().dimmed(), color))
+                .unwrap_or_default();
+
+            format!(
+                "{}{}{} {} {}{}{}{}{}{}{}{}{}{} {}{}{}",
+                if protected { "🔒 " } else { "" },
+                namespace,
+                paint(session_id, id_style, color),
+                paint(format!("({})", cli), Style::new().white().dimmed(), color),
+                paint(status, style::status_style(status), color),
+                busy,
+                scratch,
+                sharing,
+                trigger,
+                killed,
+                if waiting {
+                    let marker = paint("⏳ WAITING FOR INPUT", Style::new().black().on_yellow().bold(), color);
+                    format!(" {}", marker)
+                } else if needs_attention {
+                    format!(" {}", paint("!", Style::new().red().bold(), color))
+                } else {
+                    String::new()
+                },
+                hook_marker,
+                workspace_marker,
+                if wide {
+                    let creator = format!("[{}]", created_by);
+                    let created = created_at
+                        .map(|ts| {
+                            let created = timestamps::display(ts, now, time_style);
+                            format!(" {}", paint(created, Style::new().white().dimmed(), color))
+                        })
+                        .unwrap_or_default();
+                    let usage = format_usage(session)
+                        .map(|usage| format!(" {}", paint(usage, Style::new().white().dimmed(), color)))
+                        .unwrap_or_default();
+                    let command = session["current_command"]
+                        .as_str()
+                        .map(|command| {
+                            format!(" {}", paint(format!("$ {}", command), Style::new().white().dimmed(), color))
+                        })
+                        .unwrap_or_default();
+                    let hooks = hook_info(session)
+                        .map(|hooks| {
+                            let style = match hooks.state {
+                                HookState::Silent => Style::new().yellow(),
+                                _ => Style::new().white().dimmed(),
+                            };
+                            format!(" {}", paint(hook_indicator(&hooks, now), style, color))
+                        })
+                        .unwrap_or_default();
+                    format!(
+                        " {}{}{}{}{}",
+                        paint(creator, Style::new().purple(), color),
+                        created,
+                        usage,
+                        command,
+                        hooks
+                    )
+                } else {
+                    String::new()
+                },
+                if name.is_empty() {
+                    String::new()
+                } else {
+                    format!("- {}", paint(name, Style::new().white(), color))
+                },
+                note.map(|note| {
+                    format!(" {}", paint(format!("# {}", note), Style::new().white().dimmed(), color))
+                })
+                .unwrap_or_default(),
+                current_task
+                    .map(|task| format!(" {}", paint(format!("» {}", task), Style::new().dimmed(), color)))
+                    .unwrap_or_default()
+            )
+        })
+        .collect();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sessions() -> Vec<serde_json::Value> {
+        vec![
+            serde_json::json!({
+                "session_id": "abc123",
+                "name": "frontend",
+                "cli": "claude",
+                "status": "running",
+                "created_by": "alice",
+                "created_at": "2026-10-16T09:45:00Z",
+                "protected": true,
+                "needs_attention": true,
+                "note": "waiting on review",
+                "current_task": "Fix the login form",
+                "current_command": "pytest",
+                "hooks": {
+                    "kind": "claude",
+                    "state": "ok",
+                    "deployed_at": "2026-10-16T09:45:00Z",
+                    "last_event_at": "2026-10-16T11:58:00Z",
+                    "last_event": "user-prompt-submit",
+                },
+                "tokens_in": 1_234_567,
+                "tokens_out": 8_900,
+                "estimated_cost": 4.1234,
+            }),
+            serde_json::json!({
+                "session_id": "ghi789",
+                "namespace": "work",
+                "name": "",
+                "cli": "claude",
+                "status": "idle",
+                "created_by": "carol",
+                "needs_attention": true,
+                "waiting_for_input": {
+                    "since": "2026-10-16T11:58:00Z",
+                    "source": "pane",
+                    "text": "Do you want to proceed?",
+                },
+            }),
+            serde_json::json!({
+                "session_id": "def456",
+                "name": "",
+                "cli": "aider",
+                "status": "failed",
+                "created_by": "bob",
+                "current_command": null,
+                "hook_health": "broken",
+                "hooks": { "kind": "aider-heuristic", "state": "none" },
+            }),
+        ]
+    }
+
+    fn now() -> chrono::DateTime<chrono::Utc> {
+        "2026-10-16T12:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_lifecycle_event() {
+        assert_eq!(parse_lifecycle_event("stopped"), Ok(LifecycleEvent::Stopped));
+        assert!(parse_lifecycle_event("Stopped").is_err());
+    }
+
+    #[test]
+    fn test_parse_trigger() {
+        let trigger = parse_trigger("session_ab12cd34:stopped").unwrap();
+        assert_eq!(trigger.session_id, "session_ab12cd34");
+        assert_eq!(trigger.status, SessionStatus::Stopped);
+        assert_eq!(parse_trigger("session_ab12cd34").unwrap().status, SessionStatus::Idle);
+        assert_eq!(trigger.to_string(), "session_ab12cd34:stopped");
+        assert!(parse_trigger("session_ab12cd34:done").is_err());
+    }
+
+    #[test]
+    fn test_status_parser() {
+        use clap::builder::TypedValueParser;
+
+        let cmd = clap::Command::new("summ");
+        let parse = |text: &str| StatusParser.parse_ref(&cmd, None, std::ffi::OsStr::new(text));
+        assert_eq!(parse("Paused").unwrap(), SessionStatus::Paused);
+        let error = parse("pendng").unwrap_err().to_string();
+        assert!(error.contains("did you mean pending?"), "{}", error);
+        let offered: Vec<String> = StatusParser
+            .possible_values()
+            .unwrap()
+            .map(|value| value.get_name().to_string())
+            .collect();
+        assert_eq!(offered, SessionStatus::ALL.map(|status| status.to_string()));
+    }
+
+    #[test]
+    fn test_resolve_init_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        let project = root.join("proj");
+        fs::create_dir(&project).unwrap();
+
+        // Relative to the client's working directory, not the daemon's
+        assert_eq!(resolve_init_path("./proj", &root).unwrap(), project);
+        assert_eq!(resolve_init_path("proj/../proj", &root).unwrap(), project);
+
+        // Symlinks are resolved before the path is sent
+        std::os::unix::fs::symlink(&project, root.join("link")).unwrap();
+        assert_eq!(resolve_init_path("link", &root).unwrap(), project);
+
+        // Variables and ~ are expanded once, on the client
+        std::env::set_var("SUMM_TEST_INIT_ROOT", &root);
+        assert_eq!(resolve_init_path("$SUMM_TEST_INIT_ROOT/proj", Path::new("/")).unwrap(), project);
+        if let Ok(home) = std::env::var("HOME") {
+            assert_eq!(
+                resolve_init_path("~", &root).unwrap(),
+                fs::canonicalize(home).unwrap()
+            );
+        }
+
+        let err = resolve_init_path("./missing", &root).unwrap_err().to_string();
+        assert!(err.starts_with("E001: Initialization source not found: ./missing (resolved to "), "{}", err);
+        let err = resolve_init_path("$SUMM_TEST_INIT_UNSET/proj", &root).unwrap_err().to_string();
+        assert!(err.contains("Failed to expand init path"), "{}", err);
+    }
+
+    #[test]
+    fn test_malformed_sessions() {
+        let session = serde_json::json!({
+            "session_id": "abc123",
+            "name": "frontend",
+            "cli": "claude",
+            "status": "running",
+            "created_at": "2026-10-16T09:45:00Z",
+            "last_activity": "2026-10-16T11:45:00Z",
+            "created_by": "alice",
+        });
+        assert_eq!(malformed_sessions(std::slice::from_ref(&session)), None);
+        assert_eq!(malformed_sessions(&[]), None);
+
+        // A renamed status field, as after a daemon upgrade
+        let mut renamed = session.clone();
+        let status = renamed.as_object_mut().unwrap().remove("status").unwrap();
+        renamed["state"] = status;
+        let mut unknown_status = session.clone();
+        unknown_status["session_id"] = "def456".into();
+        unknown_status["status"] = "hibernating".into();
+
+        let problem = malformed_sessions(&[renamed, session, unknown_status]).unwrap();
+        assert!(
+            problem.starts_with("2 of 3 sessions in the daemon's response are malformed (first: abc123: missing field `status`"),
+            "{}",
+            problem
+        );
+    }
+
+    #[test]
+    fn test_render_list_plain() {
+        assert_eq!(
+            render_list(&sessions(), true, false, now(), TimeStyle::Utc),
+            "🔒 abc123 (claude) running ! [alice] 2026-10-16 09:45 UTC (2h ago) 1.2M/8.9k tokens ~$4.12 $ pytest hooks:ok 2m - frontend \
+             # waiting on review » Fix the login form\n\
+             work/ghi789 (claude) idle ⏳ WAITING FOR INPUT [carol] \n\
+             def456 (aider) failed ⚠ hooks broken [bob] hooks:none "
+        );
+        assert_eq!(
+            render_list(&sessions()[..1], true, false, now(), TimeStyle::Iso),
+            "🔒 abc123 (claude) running ! [alice] 2026-10-16T09:45:00+00:00 1.2M/8.9k tokens ~$4.12 $ pytest hooks:ok 2m - frontend \
+             # waiting on review » Fix the login form"
+        );
+        assert_eq!(
+            render_list(&[], false, false, now(), TimeStyle::Local),
+            "No sessions found."
+        );
+
+        let mut killed = sessions()[2].clone();
+        killed["status"] = "stopped".into();
+        killed["stop_reason"] = serde_json::json!({ "kind": "external_kill" });
+        assert_eq!(
+            render_list(&[killed], false, false, now(), TimeStyle::Utc),
+            "def456 (aider) stopped ✗ killed outside summ ⚠ hooks broken "
+        );
+
+        let scratch = serde_json::json!({
+            "session_id": "session_5c9e01ab",
+            "name": "scratch-3",
+            "cli": "claude",
+            "status": "idle",
+            "ephemeral": true,
+        });
+        assert_eq!(
+            render_list(&[scratch], false, false, now(), TimeStyle::Utc),
+            "session_5c9e01ab (claude) idle ephemeral - scratch-3"
+        );
+
+        let renamed = serde_json::json!({
+            "session_id": "session_1d2e3f4a",
+            "cli": "claude",
+            "status": "idle",
+            "workspace_issues": 2,
+        });
+        assert_eq!(
+            render_list(&[renamed], false, false, now(), TimeStyle::Utc),
+            "session_1d2e3f4a (claude) idle ⚠ workspace 2 paths "
+        );
+
+        let observer = serde_json::json!({
+            "session_id": "session_0b5e7a11",
+            "cli": "bash",
+            "status": "running",
+            "observer_of": "abc123",
+            "busy_seconds": 3900,
+        });
+        let shared = [sessions()[0].clone(), observer];
+        let lines = render_list(&shared, false, false, now(), TimeStyle::Utc);
+        let lines: Vec<&str> = lines.lines().collect();
+        assert!(lines[0].starts_with("🔒 abc123 (claude) running observed by 1 !"), "{}", lines[0]);
+        assert!(lines[1].starts_with("session_0b5e7a11 (bash) running for 1h observes abc123"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn test_render_ids() {
+        assert_eq!(render_ids(&sessions()), "abc123\nghi789\ndef456\n");
+        assert_eq!(render_ids(&[]), "");
+    }
+
+    #[test]
+    fn test_field_value() {
+        let status = serde_json::json!({
+            "session_id": "abc123",
+            "pid": 4242,
+            "protected": true,
+            "note": null,
+            "hooks": { "state": "ok", "last_event": null },
+            "secrets": ["GITHUB_TOKEN"],
+        });
+        assert_eq!(field_value(&status, "session_id").unwrap(), "abc123");
+        assert_eq!(field_value(&status, "pid").unwrap(), "4242");
+        assert_eq!(field_value(&status, "protected").unwrap(), "true");
+        assert_eq!(field_value(&status, "hooks.state").unwrap(), "ok");
+        assert_eq!(field_value(&status, "secrets.0").unwrap(), "GITHUB_TOKEN");
+        assert_eq!(field_value(&status, "secrets").unwrap(), r#"["GITHUB_TOKEN"]"#);
+        // Null, and anything below it, prints nothing
+        assert_eq!(field_value(&status, "note").unwrap(), "");
+        assert_eq!(field_value(&status, "note.text").unwrap(), "");
+
+        assert_eq!(
+            field_value(&status, "pdi").unwrap_err().to_string(),
+            "Unknown field `pdi`; available fields: hooks, note, pid, protected, secrets, session_id"
+        );
+        assert_eq!(
+            field_value(&status, "hooks.kind").unwrap_err().to_string(),
+            "Unknown field `kind` in `hooks`; available fields: last_event, state"
+        );
+        assert_eq!(
+            field_value(&status, "secrets.3").unwrap_err().to_string(),
+            "`secrets` has no element 3 (1 elements)"
+        );
+        assert_eq!(
+            field_value(&status, "pid.value").unwrap_err().to_string(),
+            "`pid` is 4242, which has no fields"
+        );
+    }
+
+    #[test]
+    fn test_render_environment() {
+        let mut snapshot = summ_common::EnvironmentSnapshot {
+            captured_at: "2026-10-16T14:03:00Z".parse().unwrap(),
+            daemon_version: "0.4.0".to_string(),
+            tmux_version: Some("tmux 3.4".to_string()),
+            cli_path: Some(PathBuf::from("/usr/local/bin/claude")),
+            cli_version: Some("1.0.3 (Claude Code)".to_string()),
+            hostname: None,
+            os: "linux 6.8.0 x86_64".to_string(),
+            env: [("LANG".to_string(), "C.UTF-8".to_string())].into(),
+        };
+        assert_eq!(
+            render_environment(&snapshot, TimeStyle::Iso),
+            [
+                "captured: 2026-10-16T14:03:00+00:00",
+                "daemon:   0.4.0",
+                "tmux:     tmux 3.4",
+                "cli:      /usr/local/bin/claude (1.0.3 (Claude Code))",
+                "host:     unknown",
+                "os:       linux 6.8.0 x86_64",
+                "env:",
+                "  LANG=C.UTF-8",
+            ]
+            .join("\n")
+        );
+        snapshot.cli_path = None;
+        assert!(render_environment(&snapshot, TimeStyle::Iso).contains("cli:      not found on PATH"));
+    }
+
+    #[test]
+    fn test_render_namespaces() {
+        let summary = |namespace: &str, running: usize, stopped: usize| NamespaceSummary {
+            namespace: Namespace::new(namespace).unwrap(),
+            counts: summ_common::SessionCounts {
+                running,
+                stopped,
+                total: running + stopped,
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            render_namespaces(&[summary("default", 2, 1), summary("work", 1, 0)]),
+            "default: 3 sessions (2 running, 1 stopped)\nwork: 1 session (1 running)"
+        );
+        let archived = NamespaceSummary {
+            counts: summ_common::SessionCounts {
+                archived: 4,
+                ..summary("old", 0, 1).counts
+            },
+            ..summary("old", 0, 1)
+        };
+        assert_eq!(render_namespaces(&[archived]), "old: 1 session (1 stopped, 4 archived)");
+        assert_eq!(render_namespaces(&[]), "No sessions found.");
+    }
+
+    #[test]
+    fn test_hook_indicator() {
+        let hooks = hook_info(&sessions()[0]).unwrap();
+        assert_eq!(hook_indicator(&hooks, now()), "hooks:ok 2m");
+        assert_eq!(
+            hook_summary(&hooks, now()),
+            "claude, ok; last event user-prompt-submit 2m ago; deployed 2h ago"
+        );
+
+        let silent = HookInfo {
+            state: HookState::Silent,
+            last_event_at: None,
+            last_event: None,
+            ..hooks
+        };
+        assert_eq!(hook_indicator(&silent, now()), "hooks:silent 2h");
+        assert!(hook_summary(&silent, now()).starts_with("claude, silent; no event yet; deployed 2h ago (no report while busy;"));
+
+        let aider = hook_info(&sessions()[2]).unwrap();
+        assert_eq!(hook_indicator(&aider, now()), "hooks:none");
+        assert_eq!(hook_summary(&aider, now()), "aider-heuristic, none");
+        assert!(hook_info(&sessions()[1]).is_none());
+    }
+
+    #[test]
+    fn test_disk_warning() {
+        assert_eq!(disk_warning(&serde_json::json!({ "disk_warning": null })), None);
+        assert_eq!(
+            disk_warning(&serde_json::json!({
+                "disk_warning": { "level": "critical", "message": "Only 0.5 GB (0.4%) free" }
+            }))
+            .as_deref(),
+            Some("⚠ Disk almost full: Only 0.5 GB (0.4%) free")
+        );
+    }
+
+    #[test]
+    fn test_storage_warning() {
+        assert_eq!(storage_warning(&serde_json::json!({ "running": true })), None);
+        let status = serde_json::json!({
+            "storage": { "available": true, "consecutive_failures": 1 }
+        });
+        assert_eq!(storage_warning(&status), None);
+        let status = serde_json::json!({
+            "storage": { "available": false, "consecutive_failures": 4 }
+        });
+        assert_eq!(
+            storage_warning(&status).as_deref(),
+            Some("⚠ Storage unavailable: 4 consecutive writes failed; state changes are refused")
+        );
+    }
+
+    #[test]
+    fn test_recovery_notice() {
+        assert_eq!(recovery_notice(&serde_json::json!({ "running": true })), None);
+        let status = serde_json::json!({
+            "recovery": { "recovering": false, "loaded": 12, "total": 12 }
+        });
+        assert_eq!(recovery_notice(&status), None);
+        let status = serde_json::json!({
+            "recovery": { "recovering": true, "loaded": 128, "total": 3000 }
+        });
+        assert_eq!(
+            recovery_notice(&status).as_deref(),
+            Some("⚠ Daemon is recovering sessions: 128 of 3000 loaded; the list may be incomplete")
+        );
+    }
+
+    #[test]
+    fn test_format_usage() {
+        assert_eq!(compact_count(999), "999");
+        assert_eq!(compact_count(12_345), "12.3k");
+        assert_eq!(
+            format_usage(&serde_json::json!({ "tokens_in": 500, "tokens_out": 20 })).as_deref(),
+            Some("500/20 tokens")
+        );
+        assert_eq!(format_usage(&serde_json::json!({ "session_id": "abc" })), None);
+    }
+
+    #[test]
+    fn test_render_list_colored() {
+        assert_eq!(
+            render_list(&sessions(), false, true, now(), TimeStyle::Local),
+            "🔒 \u{1b}[36;1mabc123\u{1b}[0m \u{1b}[37;2m(claude)\u{1b}[0m \u{1b}[32mrunning\u{1b}[0m \u{1b}[31;1m!\u{1b}[0m - \u{1b}[37mfrontend\u{1b}[0m\n\
+             \u{1b}[37;2mwork/\u{1b}[0m\u{1b}[36;1mghi789\u{1b}[0m \u{1b}[37;2m(claude)\u{1b}[0m \u{1b}[33midle\u{1b}[0m \u{1b}[30;43;1m⏳ WAITING FOR INPUT\u{1b}[0m \n\
+             \u{1b}[36;1mdef456\u{1b}[0m \u{1b}[37;2m(aider)\u{1b}[0m \u{1b}[31;1mfailed\u{1b}[0m \u{1b}[33m⚠ hooks broken\u{1b}[0m "
+        );
+        assert_eq!(
+            render_list(&[], false, true, now(), TimeStyle::Local),
+            "\u{1b}[35;2mNo sessions found.\u{1b}[0m"
         );
     }
 }
@@ -0,0 +1,167 @@
+// summ-cli/src/commands/open.rs
+// Open a session's workspace or log in the user's editor or file manager
+use anyhow::Result;
+use clap::Args;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::expect_success;
+use crate::client::send_request;
+use summ_common::Request;
+
+/// Editor used when neither $VISUAL nor $EDITOR is set
+const FALLBACK_EDITOR: &str = "vi";
+
+/// Arguments for the `open` command
+#[derive(Debug, Args)]
+pub struct OpenArgs {
+    /// Session ID to open
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Open the terminal log instead of the workspace
+    #[clap(long)]
+    pub log: bool,
+
+    /// Use the desktop opener (xdg-open / open) instead of $VISUAL/$EDITOR
+    #[clap(long)]
+    pub gui: bool,
+}
+
+pub async fn cmd_open(args: OpenArgs) -> Result<()> {
+    let req = Request::Status {
+        session_id: args.session_id.clone(),
+    };
+
+    let data = expect_success(send_request(req).await?)?;
+    let path = target_path(&data, args.log)?;
+
+    if !path.exists() {
+        anyhow::bail!(
+            "{} for session {} no longer exists: {}",
+            if args.log { "Log file" } else { "Workspace" },
+            args.session_id,
+            path.display()
+        );
+    }
+
+    // Print the path so it shows up in the terminal scrollback / history
+    println!("{}", path.display());
+
+    if args.gui {
+        open_gui(&path)
+    } else {
+        open_in_editor(&path)
+    }
+}
+
+/// The session's terminal log or workspace, from its Status response
+fn target_path(status: &serde_json::Value, log: bool) -> Result<PathBuf> {
+    let field = if log { "log_path" } else { "workspace_path" };
+    status[field]
+        .as_str()
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow::anyhow!("Daemon did not report {}", field))
+}
+
+/// Pick the editor command from $VISUAL, then $EDITOR, then a fallback
+pub(super) fn editor_command() -> Vec<String> {
+    pick_editor(std::env::var("VISUAL").ok(), std::env::var("EDITOR").ok())
+}
+
+/// The first of `visual` and `editor` that names a command, split into the
+/// program and its arguments
+fn pick_editor(visual: Option<String>, editor: Option<String>) -> Vec<String> {
+    [visual, editor]
+        .into_iter()
+        .flatten()
+        .map(|value| value.split_whitespace().map(str::to_string).collect::<Vec<_>>())
+        .find(|parts| !parts.is_empty())
+        .unwrap_or_else(|| vec![FALLBACK_EDITOR.to_string()])
+}
+
+/// The desktop opener of the platform
+fn desktop_opener(macos: bool) -> &'static str {
+    if macos { "open" } else { "xdg-open" }
+}
+
+/// Replace the current process with a terminal editor
+fn open_in_editor(path: &Path) -> Result<()> {
+    let editor = editor_command();
+    let mut command = Command::new(&editor[0]);
+    command.args(&editor[1..]).arg(path);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        // exec only returns on failure
+        let err = command.exec();
+        Err(anyhow::anyhow!("Failed to run editor {}: {}", editor[0], err))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let status = command.status()?;
+        if !status.success() {
+            anyhow::bail!("Editor {} exited with {}", editor[0], status);
+        }
+        Ok(())
+    }
+}
+
+/// Launch the desktop opener detached from the terminal
+fn open_gui(path: &Path) -> Result<()> {
+    let opener = desktop_opener(cfg!(target_os = "macos"));
+    Command::new(opener)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", opener, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn some(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    #[test]
+    fn test_pick_editor() {
+        assert_eq!(pick_editor(some("code --wait"), some("nano")), ["code", "--wait"]);
+        assert_eq!(pick_editor(None, some("nano")), ["nano"]);
+        // A blank $VISUAL falls through to $EDITOR, and both to vi
+        assert_eq!(pick_editor(some("  "), some("emacs -nw")), ["emacs", "-nw"]);
+        assert_eq!(pick_editor(some(""), None), [FALLBACK_EDITOR]);
+        assert_eq!(pick_editor(None, None), [FALLBACK_EDITOR]);
+    }
+
+    #[test]
+    fn test_desktop_opener() {
+        assert_eq!(desktop_opener(true), "open");
+        assert_eq!(desktop_opener(false), "xdg-open");
+    }
+
+    #[test]
+    fn test_target_path() {
+        let status = serde_json::json!({
+            "workspace_path": "/home/me/.summ-daemon/sessions/abc123/workspace",
+            "log_path": "/home/me/.summ-daemon/sessions/abc123/runtime/terminal.log",
+        });
+        assert_eq!(
+            target_path(&status, false).unwrap(),
+            Path::new("/home/me/.summ-daemon/sessions/abc123/workspace")
+        );
+        assert_eq!(
+            target_path(&status, true).unwrap(),
+            Path::new("/home/me/.summ-daemon/sessions/abc123/runtime/terminal.log")
+        );
+        let error = target_path(&serde_json::json!({}), true).unwrap_err();
+        assert_eq!(error.to_string(), "Daemon did not report log_path");
+    }
+}
@@ -0,0 +1,96 @@
+// summ-cli/src/commands/prompt.rs
+// Compact session counts for embedding in a shell prompt
+use anyhow::Result;
+use clap::Args;
+use std::time::Duration;
+
+use crate::client::send_request;
+use crate::style;
+use summ_common::{Request, Response, SessionCounts};
+
+/// Give up on the daemon after this long so the prompt never stalls
+const PROMPT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Default output, e.g. `summ:2R/1I/0S`
+const DEFAULT_FORMAT: &str = "summ:{running}R/{idle}I/{stopped}S";
+
+/// Arguments for the `prompt-status` command
+#[derive(Debug, Args)]
+pub struct PromptStatusArgs {
+    /// Output template; placeholders: {running} {idle} {stopped} {paused} {total}
+    #[clap(long, default_value = DEFAULT_FORMAT)]
+    pub format: String,
+
+    /// Colour the counts by status (prompts are not terminals, so this is opt-in;
+    /// it overrides NO_COLOR but not --no-color)
+    #[clap(long)]
+    pub color: bool,
+}
+
+/// Print session counts, or nothing at all if the daemon does not answer in time
+pub async fn cmd_prompt_status(args: PromptStatusArgs) -> Result<()> {
+    let counts = match tokio::time::timeout(PROMPT_TIMEOUT, send_request(Request::Counts)).await {
+        Ok(Ok(Response::Success { data })) => serde_json::from_value::<SessionCounts>(data).ok(),
+        _ => None,
+    };
+
+    if let Some(counts) = counts {
+        let color = args.color && !style::color_disabled_by_flag();
+        println!("{}", render(&args.format, &counts, color));
+    }
+    Ok(())
+}
+
+/// Substitute the counts into the template
+fn render(format: &str, counts: &SessionCounts, color: bool) -> String {
+    let fields = [
+        ("running", counts.running),
+        ("idle", counts.idle),
+        ("stopped", counts.stopped),
+        ("paused", counts.paused),
+        ("total", counts.total),
+    ];
+
+    fields.iter().fold(format.to_string(), |out, (status, value)| {
+        let value = style::paint(value, style::status_style(status), color);
+        out.replace(&format!("{{{}}}", status), &value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts() -> SessionCounts {
+        SessionCounts {
+            running: 2,
+            idle: 1,
+            stopped: 0,
+            paused: 3,
+            total: 6,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_default_format() {
+        assert_eq!(render(DEFAULT_FORMAT, &counts(), false), "summ:2R/1I/0S");
+    }
+
+    #[test]
+    fn test_render_custom_format() {
+        assert_eq!(
+            render("{running}R/{idle}I {paused}P of {total}", &counts(), false),
+            "2R/1I 3P of 6"
+        );
+    }
+
+    #[test]
+    fn test_render_plain_has_no_escape_codes() {
+        assert!(!render(DEFAULT_FORMAT, &counts(), false).contains('\x1b'));
+        assert_eq!(
+            render(DEFAULT_FORMAT, &counts(), true),
+            "summ:\u{1b}[32m2\u{1b}[0mR/\u{1b}[33m1\u{1b}[0mI/\u{1b}[31m0\u{1b}[0mS"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+// summ-cli/src/commands/secret.rs
+// Named secrets in ~/.summ-daemon/secrets.json, exported into sessions with
+// `summ start --secret NAME` without their values crossing the socket
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use summ_common::{secrets, DaemonConfig};
+
+/// Arguments for the `secret` command
+#[derive(Debug, Args)]
+pub struct SecretArgs {
+    #[clap(subcommand)]
+    pub command: SecretCommand,
+}
+
+/// Secret store subcommands
+#[derive(Debug, Subcommand)]
+pub enum SecretCommand {
+    /// Store a secret, read from a hidden prompt or (when piped) stdin
+    Set {
+        /// Secret name, also the environment variable it is exported as
+        #[clap(value_name = "NAME", value_parser = parse_secret_name)]
+        name: String,
+    },
+    /// List the names of stored secrets (never their values)
+    List,
+    /// Remove a stored secret
+    Rm {
+        /// Secret name
+        #[clap(value_name = "NAME")]
+        name: String,
+    },
+}
+
+pub async fn cmd_secret(args: SecretArgs) -> Result<()> {
+    let path = DaemonConfig::secrets_path();
+    match args.command {
+        SecretCommand::Set { name } => {
+            let value = read_value(&name)?;
+            if value.is_empty() {
+                anyhow::bail!("Refusing to store an empty value for secret {}", name);
+            }
+            set_secret(&path, &name, value)?;
+            println!("Stored secret {} in {}", name, path.display());
+        }
+        SecretCommand::List => {
+            let names: Vec<String> = secrets::load(&path)?.into_keys().collect();
+            if names.is_empty() {
+                println!("No secrets stored in {}", path.display());
+            }
+            for name in names {
+                println!("{}", name);
+            }
+        }
+        SecretCommand::Rm { name } => {
+            remove_secret(&path, &name)?;
+            println!("Removed secret {}", name);
+        }
+    }
+    Ok(())
+}
+
+/// Check a `--secret` or `summ secret set` name before it is used
+pub fn parse_secret_name(name: &str) -> Result<String, String> {
+    secrets::validate_secret_name(name)
+        .map(|()| name.to_string())
+        .map_err(|e| e.message)
+}
+
+/// Add or replace a secret in the store at `path`
+fn set_secret(path: &Path, name: &str, value: String) -> Result<()> {
+    let mut stored = secrets::load(path)?;
+    stored.insert(name.to_string(), value);
+    secrets::save(path, &stored)
+}
+
+/// Remove a secret from the store at `path`, failing if it is not there
+fn remove_secret(path: &Path, name: &str) -> Result<()> {
+    let mut stored = secrets::load(path)?;
+    if stored.remove(name).is_none() {
+        anyhow::bail!("Secret {} not found in {}", name, path.display());
+    }
+    secrets::save(path, &stored)
+}
+
+/// Read a secret value: from a prompt with echo off on a terminal, otherwise
+/// the first line of stdin. The trailing newline is not part of the value.
+fn read_value(name: &str) -> Result<String> {
+    let stdin = io::stdin();
+    let mut value = String::new();
+    if stdin.is_terminal() {
+        eprint!("Value for {}: ", name);
+        io::stderr().flush()?;
+        let _echo = EchoOff::new(libc::STDIN_FILENO)?;
+        stdin.lock().read_line(&mut value)?;
+        eprintln!();
+    } else {
+        stdin.lock().read_line(&mut value)?;
+    }
+    Ok(value.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Turns terminal echo off until dropped
+struct EchoOff {
+    fd: libc::c_int,
+    saved: libc::termios,
+}
+
+impl EchoOff {
+    fn new(fd: libc::c_int) -> Result<Self> {
+        let mut saved: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(fd, &mut saved) } != 0 {
+            return Err(io::Error::last_os_error()).context("Failed to read terminal settings");
+        }
+        let mut hidden = saved;
+        hidden.c_lflag &= !libc::ECHO;
+        hidden.c_lflag |= libc::ECHONL;
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &hidden) } != 0 {
+            return Err(io::Error::last_os_error()).context("Failed to turn off terminal echo");
+        }
+        Ok(Self { fd, saved })
+    }
+}
+
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(self.fd, libc::TCSANOW, &self.saved) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_remove_secret() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("secrets.json");
+
+        set_secret(&path, "OPENAI_API_KEY", "sk-one".to_string()).unwrap();
+        set_secret(&path, "GH_TOKEN", "ghp-two".to_string()).unwrap();
+        set_secret(&path, "OPENAI_API_KEY", "sk-three".to_string()).unwrap();
+        let stored = secrets::load(&path).unwrap();
+        assert_eq!(stored.keys().collect::<Vec<_>>(), ["GH_TOKEN", "OPENAI_API_KEY"]);
+        assert_eq!(stored["OPENAI_API_KEY"], "sk-three");
+
+        remove_secret(&path, "GH_TOKEN").unwrap();
+        let err = remove_secret(&path, "GH_TOKEN").unwrap_err().to_string();
+        assert!(err.starts_with("Secret GH_TOKEN not found in "), "{}", err);
+        assert_eq!(secrets::load(&path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_secret_name() {
+        assert_eq!(parse_secret_name("OPENAI_API_KEY").unwrap(), "OPENAI_API_KEY");
+        assert!(parse_secret_name("OPENAI-API-KEY").unwrap_err().contains("Invalid secret name"));
+    }
+}
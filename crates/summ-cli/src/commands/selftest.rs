@@ -0,0 +1,247 @@
+// summ-cli/src/commands/selftest.rs
+// Drive a throwaway bash session through the running daemon end to end, to
+// check an installation: start, inject, output, status reports, stop, removal
+use anyhow::{Context, Result};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::expect_success;
+use crate::client::send_request_in;
+use summ_common::{CliState, CliStatus, Namespace, Request, Response, Scope};
+
+/// Namespace the self-test session is created in, away from real sessions
+const SELFTEST_NAMESPACE: &str = "selftest";
+
+/// Start of the line the injected command prints; the shell appends a
+/// random number, so the typed command itself never matches
+const MARKER: &str = "summ-selftest-";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The session under test, as far as the stages got
+#[derive(Default)]
+struct Subject {
+    session_id: Option<String>,
+    workdir: Option<PathBuf>,
+}
+
+pub async fn cmd_selftest(timeout: Duration, keep: bool) -> Result<()> {
+    let scope = Scope {
+        namespace: Namespace::new(SELFTEST_NAMESPACE)?,
+        all_namespaces: false,
+    };
+    let started = Instant::now();
+    let mut subject = Subject::default();
+    let Err(failed) = run(&scope, timeout, &mut subject).await else {
+        println!("Self-test passed in {:.1}s", started.elapsed().as_secs_f64());
+        return Ok(());
+    };
+
+    if let Some(session_id) = &subject.session_id {
+        if keep {
+            eprintln!(
+                "Kept session {} for debugging ({}); remove it with `summ --namespace {} stop {}`",
+                session_id,
+                subject.workdir.as_deref().unwrap_or(Path::new("?")).display(),
+                SELFTEST_NAMESPACE,
+                session_id
+            );
+        } else {
+            // Best effort: the session is ephemeral, so stopping it removes it
+            send_request_in(stop_request(session_id), &scope).await.ok();
+        }
+    }
+    anyhow::bail!("Self-test failed at the {} stage", failed)
+}
+
+/// Run the stages in order; the name of the first that fails is the error
+async fn run(scope: &Scope, timeout: Duration, subject: &mut Subject) -> Result<(), &'static str> {
+    let session = stage("start", start(scope, timeout)).await?;
+    let session_id = session["session_id"].as_str().unwrap_or_default().to_string();
+    let workdir = path_field(&session, "workdir");
+    let log_path = path_field(&session, "log_path");
+    subject.session_id = Some(session_id.clone());
+    subject.workdir = Some(workdir.clone());
+    let runtime_dir = workdir.join("runtime");
+
+    stage("inject", inject(scope, &session_id)).await?;
+    stage("output", find_marker(&log_path, timeout)).await?;
+    stage("report", report_round_trip(scope, &session_id, &runtime_dir, timeout)).await?;
+    stage("stop", request(scope, stop_request(&session_id))).await?;
+    stage("remove", wait_removed(scope, &session_id, &workdir, timeout)).await?;
+    Ok(())
+}
+
+/// Run one stage and print PASS or FAIL with its timing
+async fn stage<T>(name: &'static str, work: impl Future<Output = Result<T>>) -> Result<T, &'static str> {
+    let started = Instant::now();
+    let result = work.await;
+    let elapsed = started.elapsed().as_secs_f64();
+    match &result {
+        Ok(_) => println!("PASS {:<7} {:>5.1}s", name, elapsed),
+        Err(e) => println!("FAIL {:<7} {:>5.1}s  {:#}", name, elapsed, e),
+    }
+    result.map_err(|_| name)
+}
+
+async fn request(scope: &Scope, request: Request) -> Result<serde_json::Value> {
+    expect_success(send_request_in(request, scope).await?)
+}
+
+fn status_request(session_id: &str) -> Request {
+    Request::Status {
+        session_id: session_id.to_string(),
+    }
+}
+
+fn stop_request(session_id: &str) -> Request {
+    Request::Stop {
+        session_id: session_id.to_string(),
+        override_protection: false,
+        stop_observers: false,
+    }
+}
+
+fn path_field(status: &serde_json::Value, field: &str) -> PathBuf {
+    PathBuf::from(status[field].as_str().unwrap_or_default())
+}
+
+/// Start an ephemeral bash session in an empty workspace; the response is
+/// the new session
+async fn start(scope: &Scope, timeout: Duration) -> Result<serde_json::Value> {
+    let session = request(
+        scope,
+        Request::Start {
+            cli: "bash".to_string(),
+            init: PathBuf::new(),
+            init_from_session: None,
+            after: None,
+            name: None,
+            protected: false,
+            deadline_ms: Some(timeout.as_millis() as u64),
+            background: false,
+            allow_large_init: false,
+            git_checkpoint: None,
+            secrets: Vec::new(),
+            workdir_subpath: None,
+            hooks_in_subpath: false,
+            ephemeral: true,
+            idle_timeout_seconds: None,
+            observer_of: None,
+            busy_alert_minutes: None,
+            inject_rate_limit: None,
+            no_inject_history: false,
+        },
+    )
+    .await?;
+    if !session["session_id"].is_string() || !session["workdir"].is_string() {
+        anyhow::bail!("Start response is not a session");
+    }
+    Ok(session)
+}
+
+async fn inject(scope: &Scope, session_id: &str) -> Result<serde_json::Value> {
+    request(
+        scope,
+        Request::Inject {
+            session_id: session_id.to_string(),
+            message: format!("echo {}$RANDOM", MARKER),
+            typing_delay_ms: None,
+            line_delay_ms: None,
+            bracketed_paste: false,
+        },
+    )
+    .await
+}
+
+/// Whether the log holds the marker followed by a number, i.e. the shell ran
+/// the injected command rather than only echoing what was typed
+fn has_marker(log: &str) -> bool {
+    log.match_indices(MARKER).any(|(at, _)| {
+        log[at + MARKER.len()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit())
+    })
+}
+
+/// Wait for the injected command's output to reach the session log
+async fn find_marker(log_path: &Path, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let log = fs::read(log_path).unwrap_or_default();
+        if has_marker(&String::from_utf8_lossy(&log)) {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!("no `{}<n>` line in {}", MARKER, log_path.display());
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Write an idle report where the hooks would, and wait for Status to say so
+async fn report_round_trip(scope: &Scope, session_id: &str, runtime_dir: &Path, timeout: Duration) -> Result<()> {
+    let report = CliStatus {
+        state: CliState::Idle,
+        message: Some("summ daemon selftest".to_string()),
+        event: None,
+        timestamp: chrono::Utc::now(),
+        transcript_path: None,
+    };
+    // Renamed into place, as the hook does, so the daemon never reads half of it
+    let path = runtime_dir.join("status.json");
+    let partial = runtime_dir.join("status.json.selftest");
+    fs::write(&partial, serde_json::to_string(&report)?)
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    fs::rename(&partial, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let status = request(scope, status_request(session_id)).await?;
+        if status["status"] == "idle" {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!("status is still {} after an idle report", status["status"]);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Wait for the daemon to forget the stopped session and delete its directory
+async fn wait_removed(scope: &Scope, session_id: &str, workdir: &Path, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let known = match send_request_in(status_request(session_id), scope).await? {
+            Response::Error { code, .. } => code != "E002",
+            _ => true,
+        };
+        let on_disk = workdir.exists();
+        if !known && !on_disk {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "session is {}",
+                if known { "still known to the daemon" } else { "gone but its directory remains" }
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_marker() {
+        assert!(!has_marker(""));
+        assert!(!has_marker("$ echo summ-selftest-$RANDOM\r\n"));
+        assert!(has_marker("$ echo summ-selftest-$RANDOM\r\nsumm-selftest-20417\r\n$ "));
+        assert!(!has_marker("summ-selftest-"));
+    }
+}
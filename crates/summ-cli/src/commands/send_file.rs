@@ -0,0 +1,128 @@
+// summ-cli/src/commands/send_file.rs
+// Copy a local file into a session's workspace, optionally telling the agent about it
+use anyhow::{Context, Result};
+use clap::Args;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::{expect_success, print_json};
+use crate::client::send_request;
+use summ_common::protocol::MAX_FILE_CHUNK_BYTES;
+use summ_common::Request;
+
+/// Arguments for the `send-file` command
+#[derive(Debug, Args)]
+pub struct SendFileArgs {
+    /// Session ID to send the file to
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Local file to send
+    #[clap(value_name = "LOCAL_PATH")]
+    pub local_path: PathBuf,
+
+    /// Destination in the workspace, e.g. `docs/spec.md` (default: the file's
+    /// name at the workspace root)
+    #[clap(long, value_name = "PATH")]
+    pub dest: Option<PathBuf>,
+
+    /// Replace a file that already exists at the destination
+    #[clap(long)]
+    pub overwrite: bool,
+
+    /// Inject this message once the file is in place, e.g. "please read docs/spec.md"
+    #[clap(long, value_name = "MESSAGE")]
+    pub then_inject: Option<String>,
+}
+
+pub async fn cmd_send_file(args: SendFileArgs) -> Result<()> {
+    let dest = match args.dest {
+        Some(dest) => dest,
+        None => default_dest(&args.local_path)?,
+    };
+    // Refused by the daemon too; checked here before reading anything
+    summ_common::validate_workspace_file(&dest).map_err(|e| anyhow::anyhow!("{}: {}", e.code.code(), e.message))?;
+    let mut file = File::open(&args.local_path)
+        .with_context(|| format!("Failed to open {}", args.local_path.display()))?;
+    let len = file
+        .metadata()
+        .with_context(|| format!("Failed to read {}", args.local_path.display()))?
+        .len();
+
+    let mut data = serde_json::Value::Null;
+    for (offset, size, done) in chunks(len, MAX_FILE_CHUNK_BYTES) {
+        let mut chunk = vec![0; size];
+        file.read_exact(&mut chunk)
+            .with_context(|| format!("Failed to read {}", args.local_path.display()))?;
+        data = expect_success(
+            send_request(Request::PutFile {
+                session_id: args.session_id.clone(),
+                dest: dest.clone(),
+                data: chunk,
+                offset,
+                done,
+                overwrite: args.overwrite,
+            })
+            .await?,
+        )?;
+    }
+
+    if let Some(message) = args.then_inject {
+        expect_success(
+            send_request(Request::Inject {
+                session_id: args.session_id,
+                message,
+                typing_delay_ms: None,
+                line_delay_ms: None,
+                bracketed_paste: false,
+            })
+            .await?,
+        )?;
+        data["injected"] = true.into();
+    }
+    print_json(&data)
+}
+
+/// The local file's name, at the workspace root
+fn default_dest(local_path: &Path) -> Result<PathBuf> {
+    local_path
+        .file_name()
+        .map(PathBuf::from)
+        .with_context(|| format!("{} names no file; pass --dest", local_path.display()))
+}
+
+/// `(offset, size, done)` of the chunks a file of `len` bytes is sent in; an
+/// empty file is one empty chunk
+fn chunks(len: u64, max: usize) -> Vec<(u64, usize, bool)> {
+    let mut chunks = Vec::new();
+    let mut offset = 0;
+    loop {
+        let size = (len - offset).min(max as u64);
+        let done = offset + size == len;
+        chunks.push((offset, size as usize, done));
+        if done {
+            return chunks;
+        }
+        offset += size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks() {
+        assert_eq!(chunks(0, 4), [(0, 0, true)]);
+        assert_eq!(chunks(3, 4), [(0, 3, true)]);
+        assert_eq!(chunks(8, 4), [(0, 4, false), (4, 4, true)]);
+        assert_eq!(chunks(10, 4), [(0, 4, false), (4, 4, false), (8, 2, true)]);
+    }
+
+    #[test]
+    fn test_default_dest() {
+        assert_eq!(default_dest(Path::new("/home/alice/design/spec.md")).unwrap(), PathBuf::from("spec.md"));
+        assert!(default_dest(Path::new("/")).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+// summ-cli/src/commands/templates.rs
+// Inject templates stored in ~/.summ-daemon/prompts/<name>.txt
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use summ_common::DaemonConfig;
+
+/// File extension of template files
+const TEMPLATE_EXTENSION: &str = "txt";
+
+/// Arguments for the `prompts` command
+#[derive(Debug, Args)]
+pub struct PromptsArgs {
+    #[clap(subcommand)]
+    pub command: PromptsCommand,
+}
+
+/// Prompt template subcommands
+#[derive(Debug, Subcommand)]
+pub enum PromptsCommand {
+    /// List available templates and the variables they require
+    List,
+}
+
+pub async fn cmd_prompts(args: PromptsArgs) -> Result<()> {
+    match args.command {
+        PromptsCommand::List => {
+            let dir = templates_dir();
+            let templates = list_templates(&dir)?;
+            if templates.is_empty() {
+                println!("No templates found in {}", dir.display());
+            }
+            for (name, variables) in templates {
+                if variables.is_empty() {
+                    println!("{}", name);
+                } else {
+                    println!("{} ({})", name, variables.join(", "));
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Directory holding the templates
+pub fn templates_dir() -> PathBuf {
+    DaemonConfig::base_dir().join("prompts")
+}
+
+/// Parse a `--var key=value` argument
+pub fn parse_var(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if is_variable_name(key) => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!(
+            "expected KEY=VALUE with a KEY of letters, digits, '_' or '-', got '{}'",
+            arg
+        )),
+    }
+}
+
+/// Load a template and substitute its variables
+pub fn render_named_template(name: &str, vars: &[(String, String)]) -> Result<String> {
+    let text = load_template(&templates_dir(), name)?;
+    render_template(&text, &vars.iter().cloned().collect())
+}
+
+/// Read `<dir>/<name>.txt`, listing the available templates when it is missing
+fn load_template(dir: &Path, name: &str) -> Result<String> {
+    let path = dir.join(format!("{}.{}", name, TEMPLATE_EXTENSION));
+    if !is_variable_name(name) || !path.is_file() {
+        let available: Vec<String> = list_templates(dir)?.into_iter().map(|(n, _)| n).collect();
+        anyhow::bail!(
+            "Template '{}' not found in {} (available: {})",
+            name,
+            dir.display(),
+            if available.is_empty() {
+                "none".to_string()
+            } else {
+                available.join(", ")
+            }
+        );
+    }
+    fs::read_to_string(&path).context(format!("Failed to read template: {}", path.display()))
+}
+
+/// All templates in `dir` with their variables, sorted by name
+fn list_templates(dir: &Path) -> Result<Vec<(String, Vec<String>)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut templates = Vec::new();
+    for entry in fs::read_dir(dir).context(format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(TEMPLATE_EXTENSION) {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let text = fs::read_to_string(&path)
+            .context(format!("Failed to read template: {}", path.display()))?;
+        templates.push((name.to_string(), template_variables(&text)));
+    }
+    templates.sort();
+    Ok(templates)
+}
+
+/// Placeholder names (`{name}`) in order of first appearance
+fn template_variables(text: &str) -> Vec<String> {
+    let mut variables: Vec<String> = Vec::new();
+    for name in placeholders(text).map(|(_, name)| name) {
+        if !variables.iter().any(|v| v == name) {
+            variables.push(name.to_string());
+        }
+    }
+    variables
+}
+
+/// Substitute every placeholder, failing if any variable is missing
+fn render_template(text: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let missing: Vec<String> = template_variables(text)
+        .into_iter()
+        .filter(|name| !vars.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "Unresolved template variables: {} (pass --var NAME=VALUE)",
+            missing.join(", ")
+        );
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, name) in placeholders(text) {
+        out.push_str(&text[last..start]);
+        out.push_str(&vars[name]);
+        last = start + name.len() + 2;
+    }
+    out.push_str(&text[last..]);
+    Ok(out)
+}
+
+/// Iterate `(byte offset, name)` of `{name}` placeholders; other braces are left alone
+fn placeholders(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.match_indices('{').filter_map(move |(start, _)| {
+        let rest = &text[start + 1..];
+        let end = rest.find('}')?;
+        let name = &rest[..end];
+        is_variable_name(name).then_some((start, name))
+    })
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_template_variables() {
+        let text = "run tests for {component} in {env}, then {component} again; json {\"a\": 1}";
+        assert_eq!(template_variables(text), vec!["component", "env"]);
+    }
+
+    #[test]
+    fn test_render_template() {
+        let text = "run the test suite and summarize failures for {component}";
+        let rendered = render_template(text, &vars(&[("component", "auth")])).unwrap();
+        assert_eq!(
+            rendered,
+            "run the test suite and summarize failures for auth"
+        );
+    }
+
+    #[test]
+    fn test_render_template_unresolved() {
+        let err = render_template("{a} and {b}", &vars(&[("a", "1")]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unresolved template variables: b"));
+    }
+
+    #[test]
+    fn test_parse_var() {
+        assert_eq!(
+            parse_var("component=auth=v2").unwrap(),
+            ("component".to_string(), "auth=v2".to_string())
+        );
+        assert!(parse_var("novalue").is_err());
+        assert!(parse_var("bad key=x").is_err());
+    }
+
+    #[test]
+    fn test_list_and_load_templates() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("run-tests.txt"), "test {component}").unwrap();
+        fs::write(temp_dir.path().join("notes.md"), "ignored").unwrap();
+
+        assert_eq!(
+            list_templates(temp_dir.path()).unwrap(),
+            vec![("run-tests".to_string(), vec!["component".to_string()])]
+        );
+        assert_eq!(
+            load_template(temp_dir.path(), "run-tests").unwrap(),
+            "test {component}"
+        );
+
+        let err = load_template(temp_dir.path(), "missing")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Template 'missing' not found"));
+        assert!(err.contains("available: run-tests"));
+    }
+}
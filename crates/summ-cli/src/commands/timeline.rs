@@ -0,0 +1,133 @@
+// summ-cli/src/commands/timeline.rs
+// Show a session's events, injections, and status changes in time order
+use anyhow::{Context, Result};
+use clap::Args;
+use std::time::Duration;
+
+use super::{expect_success, print_json};
+use crate::client::send_request;
+use crate::timestamps::{self, TimeStyle};
+use summ_common::{Request, TimelineEntry};
+
+/// Arguments for the `timeline` command
+#[derive(Debug, Args)]
+pub struct TimelineArgs {
+    /// Session ID to show
+    #[clap(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Only show events from the last DURATION, e.g. `2h` or `30m`
+    #[clap(long, value_name = "DURATION", value_parser = summ_common::parse_duration)]
+    pub since: Option<Duration>,
+
+    /// Print the events as JSON instead of a table
+    #[clap(long)]
+    pub json: bool,
+}
+
+pub async fn cmd_timeline(args: TimelineArgs) -> Result<()> {
+    let data = expect_success(
+        send_request(Request::Timeline {
+            session_id: args.session_id.clone(),
+            since_seconds: args.since.map(|since| since.as_secs()),
+        })
+        .await?,
+    )?;
+    if args.json {
+        return print_json(&data);
+    }
+    let entries: Vec<TimelineEntry> =
+        serde_json::from_value(data).context("Malformed Timeline response")?;
+    println!("{}", render_timeline(&args.session_id, &entries, timestamps::time_style()));
+    Ok(())
+}
+
+/// One line per event: offset from creation, clock time, kind, and detail
+fn render_timeline(session_id: &str, entries: &[TimelineEntry], style: TimeStyle) -> String {
+    if entries.is_empty() {
+        return format!("No events for session {} in this window.", session_id);
+    }
+    entries
+        .iter()
+        .map(|entry| {
+            let kind = serde_json::to_value(entry.event.kind)
+                .ok()
+                .and_then(|kind| kind.as_str().map(str::to_string))
+                .unwrap_or_default();
+            format!(
+                "{:>9}  {}  {:<14} {}",
+                format_offset(entry.offset_seconds),
+                timestamps::clock(entry.event.at, style),
+                kind,
+                entry.event.detail
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Offset from the session's creation in its two largest units, e.g. `+45s`,
+/// `+1m02s`, `+2h05m`, `+3d04h`
+fn format_offset(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "+" };
+    let s = seconds.unsigned_abs();
+    let text = match s {
+        s if s < 60 => format!("{}s", s),
+        s if s < 60 * 60 => format!("{}m{:02}s", s / 60, s % 60),
+        s if s < 24 * 60 * 60 => format!("{}h{:02}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d{:02}h", s / 86400, s % 86400 / 3600),
+    };
+    format!("{}{}", sign, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use summ_common::{TimelineEvent, TimelineKind};
+
+    fn entry(offset_seconds: i64, kind: TimelineKind, detail: &str) -> TimelineEntry {
+        let created: DateTime<Utc> = "2026-10-16T12:00:00Z".parse().unwrap();
+        TimelineEntry {
+            event: TimelineEvent {
+                at: created + chrono::Duration::seconds(offset_seconds),
+                kind,
+                detail: detail.to_string(),
+            },
+            offset_seconds,
+        }
+    }
+
+    #[test]
+    fn test_format_offset() {
+        assert_eq!(format_offset(0), "+0s");
+        assert_eq!(format_offset(62), "+1m02s");
+        assert_eq!(format_offset(2 * 3600 + 5 * 60 + 9), "+2h05m");
+        assert_eq!(format_offset(3 * 86400 + 4 * 3600), "+3d04h");
+        assert_eq!(format_offset(-5), "-5s");
+    }
+
+    #[test]
+    fn test_render_timeline() {
+        let entries = [
+            entry(0, TimelineKind::Created, "claude session created by alice"),
+            entry(62, TimelineKind::Inject, "Fix the flaky test"),
+            entry(3 * 3600, TimelineKind::Output, "+10MB output"),
+        ];
+        assert_eq!(
+            render_timeline("abc123", &entries, TimeStyle::Utc),
+            [
+                "      +0s  10-16 12:00:00Z  created        claude session created by alice",
+                "   +1m02s  10-16 12:01:02Z  inject         Fix the flaky test",
+                "   +3h00m  10-16 15:00:00Z  output         +10MB output",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            render_timeline("abc123", &[], TimeStyle::Utc),
+            "No events for session abc123 in this window."
+        );
+    }
+}
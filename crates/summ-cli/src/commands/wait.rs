@@ -0,0 +1,185 @@
+// summ-cli/src/commands/wait.rs
+// Wait for sessions to reach a status (e.g. after `summ start --background`)
+use anyhow::Result;
+use clap::Args;
+use std::time::{Duration, Instant};
+
+use super::{expect_success, print_json, ExitError};
+use crate::client::send_request;
+use summ_common::wait::{progress_any, Progress};
+use summ_common::{Request, SessionStatus, WaitMode, WaitOutcome};
+
+/// Delay between status polls
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Exit status when some but not all sessions reached the status
+const EXIT_PARTIAL: i32 = 2;
+/// Exit status when the timeout passed with no session at the status
+const EXIT_TIMEOUT: i32 = 3;
+
+/// Arguments for the `wait` command
+#[derive(Debug, Args)]
+pub struct WaitArgs {
+    /// Sessions to wait for
+    #[clap(value_name = "SESSION_ID", required = true)]
+    pub session_ids: Vec<String>,
+
+    /// Status to wait for (running/idle/stopped/paused/failed); several may
+    /// be given separated by commas, e.g. `idle,failed`
+    #[clap(long = "for", value_name = "STATUS", default_value = "running")]
+    pub status: String,
+
+    /// Wait until every session is there (the default)
+    #[clap(long, conflicts_with = "any")]
+    pub all: bool,
+
+    /// Wait until the first session is there
+    #[clap(long)]
+    pub any: bool,
+
+    /// Give up after this long: seconds, or a duration such as `90s` or `30m`
+    #[clap(long, value_name = "DURATION", value_parser = parse_timeout)]
+    pub timeout: Option<Duration>,
+}
+
+/// Parse `--timeout`: plain seconds, or a duration with a unit
+fn parse_timeout(text: &str) -> Result<Duration, String> {
+    match text.trim().parse::<u64>() {
+        Ok(seconds) => Ok(Duration::from_secs(seconds)),
+        Err(_) => summ_common::parse_duration(text),
+    }
+}
+
+/// Parse `--for`, a comma-separated list of statuses
+fn parse_targets(text: &str) -> Result<Vec<SessionStatus>> {
+    text.split(',')
+        .map(|name| name.parse().map_err(|e: summ_common::DaemonError| anyhow::anyhow!(e.message)))
+        .collect()
+}
+
+pub async fn cmd_wait(args: WaitArgs) -> Result<()> {
+    let targets = parse_targets(&args.status)?;
+    if let [session_id] = &args.session_ids[..] {
+        return wait_one(session_id, &targets, &args).await;
+    }
+
+    let mode = if args.any { WaitMode::Any } else { WaitMode::All };
+    let deadline = args.timeout.map(|timeout| Instant::now() + timeout);
+    // The daemon caps each wait, so ask again until our own timeout passes
+    let (data, outcome) = loop {
+        let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        let data = expect_success(
+            send_request(Request::WaitMany {
+                session_ids: args.session_ids.clone(),
+                mode,
+                target: targets.clone(),
+                timeout_ms: remaining.map(|remaining| remaining.as_millis() as u64),
+            })
+            .await?,
+        )?;
+        let outcome: WaitOutcome = serde_json::from_value(data["outcome"].clone())?;
+        if outcome != WaitOutcome::TimedOut || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break (data, outcome);
+        }
+    };
+    print_json(&data)?;
+
+    let satisfied = data["satisfied"].as_array().map_or(0, Vec::len);
+    let total = args.session_ids.len();
+    match (outcome, satisfied) {
+        (WaitOutcome::Satisfied, _) => Ok(()),
+        (_, 1..) => Err(ExitError {
+            code: EXIT_PARTIAL,
+            message: format!(
+                "Only {} of {} sessions became {} ({})",
+                satisfied,
+                total,
+                args.status,
+                outcome_name(outcome)
+            ),
+        }
+        .into()),
+        (WaitOutcome::TimedOut, _) => Err(ExitError {
+            code: EXIT_TIMEOUT,
+            message: format!("Timed out waiting for {} sessions to become {}", total, args.status),
+        }
+        .into()),
+        (WaitOutcome::Unreachable, _) => {
+            anyhow::bail!("None of the {} sessions will become {}", total, args.status)
+        }
+    }
+}
+
+/// Wait for one session by polling its status, which any daemon answers
+async fn wait_one(session_id: &str, targets: &[SessionStatus], args: &WaitArgs) -> Result<()> {
+    let deadline = args.timeout.map(|timeout| Instant::now() + timeout);
+
+    loop {
+        let data = expect_success(
+            send_request(Request::Status {
+                session_id: session_id.to_string(),
+            })
+            .await?,
+        )?;
+        let current: SessionStatus = serde_json::from_value(data["status"].clone())?;
+
+        match progress_any(Some(&current), targets) {
+            Progress::Done => {
+                print_json(&data)?;
+                return Ok(());
+            }
+            Progress::Unreachable => match data["start_error"].as_str() {
+                Some(error) => anyhow::bail!("Session {} failed to start: {}", session_id, error),
+                None => anyhow::bail!(
+                    "Session {} is {} and will not become {}",
+                    session_id,
+                    current,
+                    args.status
+                ),
+            },
+            Progress::Pending => {}
+        }
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(ExitError {
+                code: EXIT_TIMEOUT,
+                message: format!(
+                    "Timed out waiting for session {} to become {} (currently {})",
+                    session_id,
+                    args.status,
+                    current
+                ),
+            }
+            .into());
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// How an unsatisfied wait ended, for its error message
+fn outcome_name(outcome: WaitOutcome) -> &'static str {
+    match outcome {
+        WaitOutcome::Satisfied => "satisfied",
+        WaitOutcome::Unreachable => "the others cannot get there",
+        WaitOutcome::TimedOut => "timed out",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeout_and_targets() {
+        assert_eq!(parse_timeout("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_timeout("30m").unwrap(), Duration::from_secs(1800));
+        assert!(parse_timeout("soon").is_err());
+
+        assert_eq!(parse_targets("idle").unwrap(), vec![SessionStatus::Idle]);
+        assert_eq!(
+            parse_targets("idle, failed").unwrap(),
+            vec![SessionStatus::Idle, SessionStatus::Failed]
+        );
+        assert!(parse_targets("idle,done").is_err());
+    }
+}
@@ -1,9 +1,13 @@
 use anyhow::Result;
 use clap::Parser;
 use commands::Commands;
+use summ_common::ipc::WireFormat;
+use summ_common::{Namespace, Scope};
 
 mod client;
 mod commands;
+mod style;
+mod timestamps;
 
 /// SUMM CLI - Client for SUMM Daemon process management service
 #[derive(Parser, Debug)]
@@ -12,13 +16,90 @@ mod commands;
 #[command(version = "0.1.0")]
 #[command(about = "CLI client for SUMM Daemon", long_about = None)]
 struct Cli {
+    /// Retries for transient daemon connection failures (default: $SUMM_RETRIES or 2)
+    #[arg(long, global = true, value_name = "N")]
+    retries: Option<u32>,
+
+    /// IPC encoding: json (default) or msgpack (default: $SUMM_WIRE_FORMAT or json)
+    #[arg(long, global = true, value_name = "FORMAT", value_parser = parse_wire_format)]
+    wire_format: Option<WireFormat>,
+
+    /// Never colour output (colour is also off when NO_COLOR is set or stdout is not a terminal)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Show timestamps in UTC instead of the local time zone
+    #[arg(long, global = true, conflicts_with = "iso")]
+    utc: bool,
+
+    /// Print timestamps exactly as the daemon sends them (UTC RFC3339, no relative times)
+    #[arg(long, global = true)]
+    iso: bool,
+
+    /// Namespace of sessions to work with (default: $SUMM_NAMESPACE or default)
+    #[arg(long, global = true, value_name = "NS", value_parser = parse_namespace)]
+    namespace: Option<Namespace>,
+
+    /// Reach the sessions of every namespace (cannot start sessions)
+    #[arg(long, global = true, conflicts_with = "namespace")]
+    all_namespaces: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+fn parse_wire_format(name: &str) -> Result<WireFormat, String> {
+    WireFormat::parse(name).ok_or_else(|| format!("unknown wire format '{}' (json, msgpack)", name))
+}
+
+fn parse_namespace(name: &str) -> Result<Namespace, String> {
+    Namespace::new(name).map_err(|e| e.message)
+}
+
+/// Namespace: `--namespace`, then `SUMM_NAMESPACE`, then the default one.
+/// A bad `SUMM_NAMESPACE` is an error rather than a silent fall back to the
+/// default namespace, which would let commands reach the wrong sessions.
+fn namespace(flag: Option<Namespace>) -> Result<Namespace> {
+    if let Some(namespace) = flag {
+        return Ok(namespace);
+    }
+    match std::env::var("SUMM_NAMESPACE") {
+        Ok(name) if !name.is_empty() => Namespace::new(&name)
+            .map_err(|e| anyhow::anyhow!("SUMM_NAMESPACE: {}", e.message)),
+        _ => Ok(Namespace::default()),
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    cli.command.execute().await
+    if let Some(retries) = cli.retries {
+        client::set_retries(retries);
+    }
+    if let Some(format) = cli.wire_format {
+        client::set_wire_format(format);
+    }
+    client::set_scope(Scope {
+        namespace: namespace(cli.namespace)?,
+        all_namespaces: cli.all_namespaces,
+    });
+    if cli.no_color {
+        style::disable_color();
+    }
+    if cli.iso {
+        timestamps::set_time_style(timestamps::TimeStyle::Iso);
+    } else if cli.utc {
+        timestamps::set_time_style(timestamps::TimeStyle::Utc);
+    }
+
+    if let Err(e) = cli.command.execute().await {
+        // Printed like `main` returning the error would, plus the drift notice
+        eprintln!("Error: {:?}", e);
+        if let Some(note) = client::version_drift_note().await {
+            eprintln!("{}", note);
+        }
+        std::process::exit(e.downcast_ref::<commands::ExitError>().map_or(1, |e| e.code));
+    }
+    Ok(())
 }
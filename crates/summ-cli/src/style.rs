@@ -0,0 +1,67 @@
+// summ-cli/src/style.rs
+// Colour decisions and the status palette shared by all CLI output
+use owo_colors::{OwoColorize, Style};
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// Set from the command line (`--no-color`)
+static NO_COLOR_FLAG: OnceLock<bool> = OnceLock::new();
+
+/// Turn colour off for this process (from `--no-color`)
+pub fn disable_color() {
+    let _ = NO_COLOR_FLAG.set(true);
+}
+
+/// Whether `--no-color` was given
+pub fn color_disabled_by_flag() -> bool {
+    NO_COLOR_FLAG.get().copied().unwrap_or(false)
+}
+
+/// Whether output to stdout should be coloured: never with `--no-color` or a
+/// non-empty `NO_COLOR`, and only when stdout is a terminal
+pub fn stdout_color() -> bool {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    !color_disabled_by_flag() && !no_color_env && std::io::stdout().is_terminal()
+}
+
+/// Style for a session status (`running`, `idle`, ...), the same everywhere it is shown
+pub fn status_style(status: &str) -> Style {
+    match status {
+        "running" => Style::new().green(),
+        "idle" => Style::new().yellow(),
+        "stopped" => Style::new().red(),
+        "paused" => Style::new().blue(),
+        "starting" => Style::new().cyan(),
+        "failed" => Style::new().red().bold(),
+        "pending" => Style::new().cyan().dimmed(),
+        _ => Style::new().white(),
+    }
+}
+
+/// `text` in `style`, or as plain text when `color` is off
+pub fn paint(text: impl Display, style: Style, color: bool) -> String {
+    if color {
+        text.style(style).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_plain_and_colored() {
+        assert_eq!(paint("idle", status_style("idle"), false), "idle");
+        assert_eq!(
+            paint("idle", status_style("idle"), true),
+            "\u{1b}[33midle\u{1b}[0m"
+        );
+        assert_eq!(
+            paint("failed", status_style("failed"), true),
+            "\u{1b}[31;1mfailed\u{1b}[0m"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+// summ-cli/src/timestamps.rs
+// Display formatting for daemon timestamps (the wire format stays UTC RFC3339)
+use chrono::{DateTime, Local, TimeZone, Utc};
+use std::sync::OnceLock;
+
+/// How timestamps are shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeStyle {
+    /// Local time zone, with relative phrasing in human output
+    #[default]
+    Local,
+    /// UTC, with relative phrasing in human output (`--utc`)
+    Utc,
+    /// Timestamps exactly as the daemon sent them (`--iso`)
+    Iso,
+}
+
+/// Set from the command line (`--utc` / `--iso`)
+static TIME_STYLE: OnceLock<TimeStyle> = OnceLock::new();
+
+/// Override the timestamp style for this process
+pub fn set_time_style(style: TimeStyle) {
+    let _ = TIME_STYLE.set(style);
+}
+
+/// Timestamp style: `--iso`, `--utc`, or local time
+pub fn time_style() -> TimeStyle {
+    TIME_STYLE.get().copied().unwrap_or_default()
+}
+
+/// How long ago `then` was, e.g. `just now`, `5m ago`, `2h ago`, `3d ago`.
+///
+/// Timestamps in the future (clock skew between daemon and client) read `just now`.
+pub fn relative(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    if (now - then).num_seconds() < 60 {
+        return "just now".to_string();
+    }
+    format!("{} ago", age(then, now))
+}
+
+/// Time since `then` in its largest whole unit, e.g. `40s`, `5m`, `2h`, `3d`;
+/// `0s` for timestamps in the future
+pub fn age(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - then).num_seconds().max(0);
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 60 * 60 => format!("{}m", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h", s / (60 * 60)),
+        s => format!("{}d", s / (24 * 60 * 60)),
+    }
+}
+
+/// Human rendering of a timestamp, e.g. `2026-10-16 14:03 (2h ago)`
+pub fn display(ts: DateTime<Utc>, now: DateTime<Utc>, style: TimeStyle) -> String {
+    match style {
+        TimeStyle::Local => format!(
+            "{} ({})",
+            ts.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            relative(ts, now)
+        ),
+        TimeStyle::Utc => format!("{} UTC ({})", ts.format("%Y-%m-%d %H:%M"), relative(ts, now)),
+        TimeStyle::Iso => ts.to_rfc3339(),
+    }
+}
+
+/// Compact rendering for columns of timestamps (no relative part), e.g.
+/// `10-16 14:03:27`
+pub fn clock(ts: DateTime<Utc>, style: TimeStyle) -> String {
+    match style {
+        TimeStyle::Local => ts.with_timezone(&Local).format("%m-%d %H:%M:%S").to_string(),
+        TimeStyle::Utc => ts.format("%m-%d %H:%M:%SZ").to_string(),
+        TimeStyle::Iso => ts.to_rfc3339(),
+    }
+}
+
+/// Whether a JSON key holds a timestamp
+fn is_timestamp_key(key: &str) -> bool {
+    key.ends_with("_at") || key == "last_activity" || key == "timestamp"
+}
+
+/// Rewrite the timestamps in daemon JSON into `tz`, keeping RFC3339 so the output
+/// still parses; other values are left alone
+pub fn localize_json<Tz: TimeZone>(value: &mut serde_json::Value, tz: &Tz)
+where
+    Tz::Offset: std::fmt::Display,
+{
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let parsed = field
+                    .as_str()
+                    .filter(|_| is_timestamp_key(key))
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok());
+                match parsed {
+                    Some(ts) => *field = ts.with_timezone(tz).to_rfc3339().into(),
+                    None => localize_json(field, tz),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                localize_json(item, tz);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Apply the current time style to daemon JSON before printing it
+pub fn apply_to_json(value: &mut serde_json::Value) {
+    match time_style() {
+        TimeStyle::Local => localize_json(value, &Local),
+        TimeStyle::Utc => localize_json(value, &Utc),
+        TimeStyle::Iso => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, FixedOffset};
+
+    fn now() -> DateTime<Utc> {
+        "2026-10-16T12:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_relative_boundaries() {
+        let ago = |seconds: i64| relative(now() - Duration::seconds(seconds), now());
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(59), "just now");
+        assert_eq!(ago(60), "1m ago");
+        assert_eq!(ago(59 * 60 + 59), "59m ago");
+        assert_eq!(ago(60 * 60), "1h ago");
+        assert_eq!(ago(24 * 60 * 60 - 1), "23h ago");
+        assert_eq!(ago(24 * 60 * 60), "1d ago");
+        assert_eq!(ago(10 * 24 * 60 * 60), "10d ago");
+    }
+
+    #[test]
+    fn test_age() {
+        let age_of = |seconds: i64| age(now() - Duration::seconds(seconds), now());
+        assert_eq!(age_of(45), "45s");
+        assert_eq!(age_of(2 * 60 + 30), "2m");
+        assert_eq!(age_of(3 * 60 * 60), "3h");
+        assert_eq!(age_of(-60), "0s");
+    }
+
+    #[test]
+    fn test_relative_future_is_not_negative() {
+        assert_eq!(relative(now() + Duration::hours(3), now()), "just now");
+    }
+
+    #[test]
+    fn test_display_styles() {
+        let ts = now() - Duration::minutes(90);
+        assert_eq!(display(ts, now(), TimeStyle::Utc), "2026-10-16 10:30 UTC (1h ago)");
+        assert_eq!(display(ts, now(), TimeStyle::Iso), "2026-10-16T10:30:00+00:00");
+        assert_eq!(clock(ts, TimeStyle::Utc), "10-16 10:30:00Z");
+        assert_eq!(clock(ts, TimeStyle::Iso), "2026-10-16T10:30:00+00:00");
+    }
+
+    #[test]
+    fn test_localize_json() {
+        let mut value = serde_json::json!({
+            "created_at": "2026-10-16T10:30:00Z",
+            "last_activity": "2026-10-16T11:00:00.5Z",
+            "name": "2026-10-16T10:30:00Z",
+            "sessions": [{ "stopped_at": "2026-10-16T11:30:00Z", "started_at": "not a time" }],
+        });
+        let tz = FixedOffset::east_opt(2 * 60 * 60).unwrap();
+        localize_json(&mut value, &tz);
+
+        assert_eq!(value["created_at"], "2026-10-16T12:30:00+02:00");
+        assert_eq!(value["last_activity"], "2026-10-16T13:00:00.500+02:00");
+        // Only timestamp keys are touched
+        assert_eq!(value["name"], "2026-10-16T10:30:00Z");
+        assert_eq!(value["sessions"][0]["stopped_at"], "2026-10-16T13:30:00+02:00");
+        assert_eq!(value["sessions"][0]["started_at"], "not a time");
+    }
+}
@@ -0,0 +1,67 @@
+//! Round-trip latency of a large log-like response with and without frame compression.
+//!
+//! Run with `cargo bench -p summ-common --bench ipc_compression`.
+use std::time::{Duration, Instant};
+use summ_common::ipc::{read_message, write_message, WireFormat};
+use summ_common::Response;
+
+const ITERATIONS: u32 = 20;
+
+/// Roughly 10MB of terminal output, shaped like a session log tail
+fn log_tail() -> String {
+    let mut out = String::new();
+    let mut i = 0u64;
+    while out.len() < 10 * 1024 * 1024 {
+        out.push_str(&format!(
+            "\x1b[32m[2026-10-16T12:{:02}:{:02}Z]\x1b[0m test suite::case_{} ... ok ({} ms)\n",
+            (i / 60) % 60,
+            i % 60,
+            i,
+            i % 997
+        ));
+        i += 1;
+    }
+    out
+}
+
+async fn round_trip(response: &Response, compress: bool) -> (Duration, usize) {
+    let (mut writer, mut reader) = tokio::io::duplex(64 * 1024);
+    let start = Instant::now();
+    let send = async {
+        write_message(&mut writer, response, Some(WireFormat::Json), compress)
+            .await
+            .unwrap();
+    };
+    let recv = async {
+        let (_, _): (Response, _) = read_message(&mut reader).await.unwrap();
+    };
+    tokio::join!(send, recv);
+    let elapsed = start.elapsed();
+
+    let mut wire = Vec::new();
+    write_message(&mut wire, response, Some(WireFormat::Json), compress)
+        .await
+        .unwrap();
+    (elapsed, wire.len())
+}
+
+#[tokio::main]
+async fn main() {
+    let response = Response::success(serde_json::json!({ "lines": log_tail() }));
+
+    for compress in [false, true] {
+        let mut total = Duration::ZERO;
+        let mut size = 0;
+        for _ in 0..ITERATIONS {
+            let (elapsed, bytes) = round_trip(&response, compress).await;
+            total += elapsed;
+            size = bytes;
+        }
+        println!(
+            "compress={:<5} wire={:>9} bytes  mean round trip={:?}",
+            compress,
+            size,
+            total / ITERATIONS
+        );
+    }
+}
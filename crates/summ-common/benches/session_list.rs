@@ -0,0 +1,61 @@
+//! Cost of building a List response over 1000 sessions: cloning each session
+//! into a `SessionInfo` against borrowing it as a `SessionInfoRef`.
+//!
+//! Run with `cargo bench -p summ-common --bench session_list`.
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use summ_common::{CreationTimings, Session, SessionInfo, SessionInfoRef, SessionStatus};
+
+const SESSIONS: usize = 1000;
+const ITERATIONS: u32 = 200;
+
+/// Sessions shaped like a busy daemon's: long paths, notes, timings
+fn sessions() -> HashMap<String, Session> {
+    (0..SESSIONS)
+        .map(|i| {
+            let session_id = format!("session_{:08x}", i);
+            let session = Session {
+                name: format!("agent-{}", i),
+                tmux_session: format!("summ-{}", session_id),
+                cli: "claude --dangerously-skip-permissions".to_string(),
+                workdir: PathBuf::from(format!("/home/user/.summ-daemon/sessions/{}", session_id)),
+                init_source: PathBuf::from("/home/user/projects/monorepo"),
+                status: if i % 3 == 0 { SessionStatus::Idle } else { SessionStatus::Running },
+                created_by: "user".to_string(),
+                current_task: Some(format!("Fix the flaky test in module {}", i)),
+                current_command: Some("claude".to_string()),
+                note: Some("waiting on review".to_string()),
+                creation_timings: Some(CreationTimings::default()),
+                secrets: vec!["OPENAI_API_KEY".to_string()],
+                session_id: session_id.clone(),
+                ..Default::default()
+            };
+            (session_id, session)
+        })
+        .collect()
+}
+
+fn time(label: &str, mut list: impl FnMut() -> serde_json::Value) {
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        black_box(list());
+        total += start.elapsed();
+    }
+    println!("{:<8} mean list of {} sessions={:?}", label, SESSIONS, total / ITERATIONS);
+}
+
+fn main() {
+    let sessions = sessions();
+
+    time("cloned", || {
+        let infos: Vec<SessionInfo> = sessions.values().cloned().map(SessionInfo::from).collect();
+        serde_json::to_value(infos).unwrap()
+    });
+    time("borrowed", || {
+        let infos: Vec<SessionInfoRef> = sessions.values().map(SessionInfoRef::from).collect();
+        serde_json::to_value(infos).unwrap()
+    });
+}
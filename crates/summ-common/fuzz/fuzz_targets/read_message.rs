@@ -0,0 +1,13 @@
+// Fuzz the daemon's request path: framing, decompression, and decoding
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use summ_common::ipc;
+use summ_common::Request;
+
+fuzz_target!(|data: &[u8]| {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let _ = runtime.block_on(ipc::read_message::<_, Request>(&mut &data[..]));
+});
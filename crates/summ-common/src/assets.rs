@@ -0,0 +1,204 @@
+// Embedded assets installed on the host (hook script, systemd unit)
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version of the hook assets (script and deployed settings).
+///
+/// Bump this whenever the script or the generated hook settings change so
+/// that the daemon refreshes assets installed by older releases.
+pub const HOOK_ASSETS_VERSION: u32 = 5;
+
+/// Marker line in the installed script carrying `HOOK_ASSETS_VERSION`
+const HOOK_VERSION_MARKER: &str = "# summ-hook-version: ";
+
+/// The summ-hook script content
+pub const SUMM_HOOK_SCRIPT: &str = r#"#!/bin/bash
+# summ-hook: Claude Code Hook handler script
+# summ-hook-version: 5
+# Usage: summ-hook <event> [args...]
+
+set -e
+
+EVENT="$1"
+shift
+RUNTIME_DIR="${SUMM_RUNTIME_DIR:-$PWD/../runtime}"
+STATUS_FILE="$RUNTIME_DIR/status.json"
+
+# From stdin read Hook input (JSON)
+INPUT=$(cat)
+
+# Extract session_id (from environment variable or input)
+SESSION_ID="${SUMM_SESSION_ID:-unknown}"
+
+# Conversation transcript, used by the daemon for token counts (best effort)
+TRANSCRIPT_PATH=$(echo "$INPUT" | jq -r '.transcript_path // empty' 2>/dev/null || true)
+
+# Ensure runtime directory exists
+mkdir -p "$(dirname "$STATUS_FILE")"
+
+write_status() {
+    local state="$1"
+    local message="$2"
+
+    # Coalesce bursts (e.g. SubagentStop on multi-agent tasks): the same state
+    # and message written within the last second is left untouched
+    if [ -f "$STATUS_FILE" ] \
+        && [ $(( $(date +%s) - $(stat -c %Y "$STATUS_FILE" 2>/dev/null || echo 0) )) -le 1 ] \
+        && grep -qF "\"state\": \"$state\"" "$STATUS_FILE" \
+        && grep -qF "\"message\": \"$message\"" "$STATUS_FILE"; then
+        return 0
+    fi
+
+    local transcript=""
+    if [ -n "$TRANSCRIPT_PATH" ]; then
+        transcript="
+  \"transcript_path\": \"$TRANSCRIPT_PATH\","
+    fi
+
+    cat > "$STATUS_FILE" << EOF
+{
+  "state": "$state",
+  "message": "$message",
+  "event": "$EVENT",$transcript
+  "timestamp": "$(date -Iseconds)"
+}
+EOF
+}
+
+case "$EVENT" in
+    session-start)
+        write_status "idle" "Session started, ready for tasks"
+        ;;
+
+    stop)
+        # Claude main agent completed response
+        write_status "idle" "Task completed"
+        ;;
+
+    subagent-stop)
+        # Subagent completed
+        write_status "idle" "Subagent task completed"
+        ;;
+
+    user-prompt-submit)
+        # The user submitted a prompt: busy, with the prompt as the current task
+        PROMPT=$(echo "$INPUT" | jq -r '.prompt // empty | .[0:200]' 2>/dev/null || true)
+        PROMPT=$(printf '%s' "$PROMPT" | tr '\n\t' '  ' | tr -d '\000-\037"\\')
+        write_status "busy" "$PROMPT"
+        ;;
+
+    notification)
+        # Claude asks for a tool permission, or reminds that it is waiting
+        MESSAGE=$(echo "$INPUT" | jq -r '.message // empty' 2>/dev/null || true)
+        MESSAGE="${MESSAGE//[\"\\]/}"
+        case "$MESSAGE" in
+            *permission*) write_status "waiting_for_input" "$MESSAGE" ;;
+            *) write_status "idle" "${MESSAGE:-Waiting for input}" ;;
+        esac
+        ;;
+
+    session-end)
+        # Session ended
+        REASON=$(echo "$INPUT" | jq -r '.reason // "unknown"' 2>/dev/null || echo "unknown")
+        write_status "stopped" "Session ended: $REASON"
+        ;;
+
+    *)
+        echo "Unknown event: $EVENT" >&2
+        exit 1
+        ;;
+esac
+
+exit 0
+"#;
+
+/// systemd user unit for the daemon
+pub const SYSTEMD_UNIT: &str = include_str!("../../../systemd/summ-daemon.service");
+
+/// Path of the installed hook script under the daemon base directory
+pub fn hook_script_path(base_dir: &Path) -> PathBuf {
+    base_dir.join("bin").join("summ-hook")
+}
+
+/// Read the asset version recorded in an installed hook script
+pub fn installed_hook_version(script_path: &Path) -> Option<u32> {
+    let content = fs::read_to_string(script_path).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix(HOOK_VERSION_MARKER))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Install the summ-hook script to `<base_dir>/bin/summ-hook` and return its path
+pub fn install_hook_script(base_dir: &Path) -> Result<PathBuf> {
+    let hook_script = hook_script_path(base_dir);
+    write_executable(&hook_script, SUMM_HOOK_SCRIPT)?;
+    Ok(hook_script)
+}
+
+/// Write a file (creating parent directories) and mark it executable
+pub fn write_executable(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create directory: {}", parent.display()))?;
+    }
+
+    fs::write(path, content).context(format!("Failed to write file: {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut perms = fs::metadata(path)
+            .context(format!("Failed to get metadata: {}", path.display()))?
+            .permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)
+            .context(format!("Failed to set permissions: {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_install_hook_script_is_executable() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = install_hook_script(temp_dir.path()).unwrap();
+        assert_eq!(path, temp_dir.path().join("bin/summ-hook"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+
+    #[test]
+    fn test_script_marker_matches_version() {
+        let marker = format!("{}{}", HOOK_VERSION_MARKER, HOOK_ASSETS_VERSION);
+        assert!(SUMM_HOOK_SCRIPT.lines().any(|line| line == marker));
+    }
+
+    #[test]
+    fn test_installed_hook_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = install_hook_script(temp_dir.path()).unwrap();
+        assert_eq!(installed_hook_version(&path), Some(HOOK_ASSETS_VERSION));
+
+        fs::write(&path, "#!/bin/bash\n").unwrap();
+        assert_eq!(installed_hook_version(&path), None);
+    }
+
+    #[test]
+    fn test_systemd_unit_embedded() {
+        assert!(SYSTEMD_UNIT.contains("ExecStart="));
+        assert!(SYSTEMD_UNIT.contains("Type=notify"));
+    }
+}
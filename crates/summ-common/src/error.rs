@@ -21,6 +21,24 @@ pub enum ErrorCode {
     E008,
     #[error("E009: tmux not available")]
     E009,
+    #[error("E010: Invalid request argument")]
+    E010,
+    #[error("E011: Session is protected")]
+    E011,
+    #[error("E012: Deadline exceeded")]
+    E012,
+    #[error("E013: Insufficient disk space")]
+    E013,
+    #[error("E014: Session busy with another operation")]
+    E014,
+    #[error("E015: Daemon is recovering sessions")]
+    E015,
+    #[error("E016: Storage unavailable")]
+    E016,
+    #[error("E017: Session is not waiting for input")]
+    E017,
+    #[error("E018: Injection rate limit exceeded")]
+    E018,
 }
 
 impl ErrorCode {
@@ -35,6 +53,15 @@ impl ErrorCode {
             ErrorCode::E007 => "E007",
             ErrorCode::E008 => "E008",
             ErrorCode::E009 => "E009",
+            ErrorCode::E010 => "E010",
+            ErrorCode::E011 => "E011",
+            ErrorCode::E012 => "E012",
+            ErrorCode::E013 => "E013",
+            ErrorCode::E014 => "E014",
+            ErrorCode::E015 => "E015",
+            ErrorCode::E016 => "E016",
+            ErrorCode::E017 => "E017",
+            ErrorCode::E018 => "E018",
         }
     }
 }
@@ -88,6 +115,42 @@ impl DaemonError {
     pub fn e009(message: impl Into<String>) -> Self {
         Self::new(ErrorCode::E009, message)
     }
+
+    pub fn e010(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E010, message)
+    }
+
+    pub fn e011(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E011, message)
+    }
+
+    pub fn e012(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E012, message)
+    }
+
+    pub fn e013(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E013, message)
+    }
+
+    pub fn e014(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E014, message)
+    }
+
+    pub fn e015(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E015, message)
+    }
+
+    pub fn e016(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E016, message)
+    }
+
+    pub fn e017(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E017, message)
+    }
+
+    pub fn e018(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::E018, message)
+    }
 }
 
 impl fmt::Display for DaemonError {
@@ -104,6 +167,15 @@ mod tests {
     fn test_error_code_display() {
         assert_eq!(ErrorCode::E001.code(), "E001");
         assert_eq!(ErrorCode::E002.code(), "E002");
+        assert_eq!(ErrorCode::E010.code(), "E010");
+        assert_eq!(ErrorCode::E011.code(), "E011");
+        assert_eq!(ErrorCode::E012.code(), "E012");
+        assert_eq!(ErrorCode::E013.code(), "E013");
+        assert_eq!(ErrorCode::E014.code(), "E014");
+        assert_eq!(ErrorCode::E015.code(), "E015");
+        assert_eq!(ErrorCode::E016.code(), "E016");
+        assert_eq!(ErrorCode::E017.code(), "E017");
+        assert_eq!(ErrorCode::E018.code(), "E018");
     }
 
     #[test]
@@ -0,0 +1,196 @@
+// Claude Code hook settings deployed into session workspaces
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::assets::HOOK_ASSETS_VERSION;
+use crate::types::HookKind;
+
+/// Claude Code hook events handled by summ-hook, with the script argument for each
+const CLAUDE_HOOK_EVENTS: &[(&str, &str)] = &[
+    ("SessionStart", "session-start"),
+    ("UserPromptSubmit", "user-prompt-submit"),
+    ("Stop", "stop"),
+    ("SubagentStop", "subagent-stop"),
+    ("Notification", "notification"),
+    ("SessionEnd", "session-end"),
+];
+
+/// Substring identifying hook commands managed by summ
+pub const SUMM_HOOK_COMMAND: &str = "summ-hook";
+
+/// Environment variable carrying the asset version in deployed hook commands
+pub const HOOK_VERSION_ENV: &str = "SUMM_HOOK_VERSION=";
+
+/// Settings file Claude Code reads hooks from, relative to the directory it runs in
+pub const CLAUDE_SETTINGS_FILE: &str = ".claude/settings.local.json";
+
+/// Path of the Claude Code settings file in `dir`
+pub fn claude_settings_path(dir: &Path) -> PathBuf {
+    dir.join(CLAUDE_SETTINGS_FILE)
+}
+
+/// How the status of a session running this CLI is detected
+pub fn hook_kind(cli: &str) -> HookKind {
+    if cli.contains("claude") {
+        HookKind::Claude
+    } else if cli.contains("aider") {
+        HookKind::AiderHeuristic
+    } else {
+        HookKind::None
+    }
+}
+
+/// The hook event and command line of every summ hook of a session
+pub fn hook_commands(session_id: &str, runtime_dir: &Path) -> Vec<(&'static str, String)> {
+    let hook_base = format!(
+        "{}{} SUMM_SESSION_ID={} SUMM_RUNTIME_DIR={} ~/.summ-daemon/bin/summ-hook",
+        HOOK_VERSION_ENV,
+        HOOK_ASSETS_VERSION,
+        session_id,
+        runtime_dir.display()
+    );
+    CLAUDE_HOOK_EVENTS
+        .iter()
+        .map(|(event, arg)| (*event, format!("{} {}", hook_base, arg)))
+        .collect()
+}
+
+/// Build the summ hook configuration for a session
+fn claude_hooks_config(session_id: &str, runtime_dir: &Path) -> Value {
+    let mut hooks = serde_json::Map::new();
+    for (event, command) in hook_commands(session_id, runtime_dir) {
+        hooks.insert(
+            event.to_string(),
+            serde_json::json!([{
+                "hooks": [{
+                    "type": "command",
+                    "command": command
+                }]
+            }]),
+        );
+    }
+    Value::Object(hooks)
+}
+
+/// Whether a hook matcher entry runs summ-hook
+fn is_summ_entry(entry: &Value) -> bool {
+    entry["hooks"]
+        .as_array()
+        .map(|hooks| {
+            hooks.iter().any(|h| {
+                h["command"]
+                    .as_str()
+                    .is_some_and(|c| c.contains(SUMM_HOOK_COMMAND))
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Merge summ hooks into existing settings, replacing older summ entries
+/// and keeping every other key and hook untouched
+fn merge_hooks(mut settings: Value, summ_hooks: Value) -> Value {
+    if !settings.is_object() {
+        settings = serde_json::json!({});
+    }
+    if !settings["hooks"].is_object() {
+        settings["hooks"] = serde_json::json!({});
+    }
+
+    if let (Some(hooks), Value::Object(ours)) = (settings["hooks"].as_object_mut(), summ_hooks) {
+        for (event, entries) in ours {
+            let mut merged: Vec<Value> = hooks
+                .get(&event)
+                .and_then(|v| v.as_array())
+                .map(|existing| existing.iter().filter(|e| !is_summ_entry(e)).cloned().collect())
+                .unwrap_or_default();
+            merged.extend(entries.as_array().cloned().unwrap_or_default());
+            hooks.insert(event, Value::Array(merged));
+        }
+    }
+
+    settings
+}
+
+/// The Claude Code settings a session's hooks are deployed as, given the
+/// content of the settings file already there (e.g. from the init source).
+/// Existing settings and hooks are kept; content that is not valid JSON is
+/// replaced.
+pub fn claude_settings(existing: Option<&str>, session_id: &str, runtime_dir: &Path) -> Value {
+    let existing = existing
+        .and_then(|content| serde_json::from_str(content).ok())
+        .unwrap_or_else(|| serde_json::json!({}));
+    merge_hooks(existing, claude_hooks_config(session_id, runtime_dir))
+}
+
+/// Asset version recorded in the summ hooks of Claude Code settings
+pub fn deployed_version(settings: &Value) -> Option<u32> {
+    settings["hooks"]
+        .as_object()?
+        .values()
+        .filter_map(|entries| entries.as_array())
+        .flatten()
+        .filter_map(|entry| entry["hooks"].as_array())
+        .flatten()
+        .filter_map(|hook| hook["command"].as_str())
+        .filter(|command| command.contains(SUMM_HOOK_COMMAND))
+        .find_map(|command| {
+            command
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix(HOOK_VERSION_ENV))
+                .and_then(|v| v.parse().ok())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claude_settings_without_existing_file() {
+        let settings = claude_settings(None, "s1", Path::new("/sessions/s1/runtime"));
+        let hooks = settings["hooks"].as_object().unwrap();
+        assert_eq!(hooks.len(), CLAUDE_HOOK_EVENTS.len());
+        assert_eq!(
+            settings["hooks"]["Stop"][0]["hooks"][0]["command"],
+            format!(
+                "SUMM_HOOK_VERSION={} SUMM_SESSION_ID=s1 SUMM_RUNTIME_DIR=/sessions/s1/runtime \
+                 ~/.summ-daemon/bin/summ-hook stop",
+                HOOK_ASSETS_VERSION
+            )
+        );
+        assert_eq!(deployed_version(&settings), Some(HOOK_ASSETS_VERSION));
+    }
+
+    #[test]
+    fn test_claude_settings_merges_existing_settings() {
+        // A project-provided setting, a user hook and an outdated summ hook
+        let existing = serde_json::json!({
+            "permissions": {"allow": ["Bash(ls)"]},
+            "hooks": {
+                "Stop": [
+                    {"hooks": [{"type": "command", "command": "notify-send done"}]},
+                    {"hooks": [{"type": "command", "command": "SUMM_SESSION_ID=old ~/.summ-daemon/bin/summ-hook stop"}]}
+                ]
+            }
+        })
+        .to_string();
+
+        let settings = claude_settings(Some(&existing), "s1", Path::new("/runtime"));
+        assert_eq!(settings["permissions"]["allow"][0], "Bash(ls)");
+        let stop = settings["hooks"]["Stop"].as_array().unwrap();
+        assert_eq!(stop.len(), 2);
+        assert_eq!(stop[0]["hooks"][0]["command"], "notify-send done");
+        assert!(!settings.to_string().contains("SUMM_SESSION_ID=old"));
+
+        // Content that does not parse is replaced
+        let settings = claude_settings(Some("{not json"), "s1", Path::new("/runtime"));
+        assert_eq!(settings.as_object().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_hook_kind() {
+        assert_eq!(hook_kind("claude --resume"), HookKind::Claude);
+        assert_eq!(hook_kind("aider"), HookKind::AiderHeuristic);
+        assert_eq!(hook_kind("bash"), HookKind::None);
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// File in a session's runtime directory where the daemon keeps the
+/// messages injected into it
+pub const INJECTIONS_FILE: &str = "injections.jsonl";
+
+/// A message injected into a session, as kept in its history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct InjectionRecord {
+    pub at: DateTime<Utc>,
+    /// The message as sent, with secrets redacted
+    pub message: String,
+    /// Secrets were redacted out of the message
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+    /// Only the start of the message was kept
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl InjectionRecord {
+    /// Whether the message can be sent again exactly as it was
+    pub fn is_complete(&self) -> bool {
+        !self.redacted && !self.truncated
+    }
+}
+
+/// A history entry, in the response to `Request::InjectionHistory`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct InjectionEntry {
+    /// Position counting back from the latest message, which is 1
+    pub index: usize,
+    #[serde(flatten)]
+    pub record: InjectionRecord,
+}
+
+/// Response to `Request::InjectionHistory`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct InjectionHistory {
+    pub session_id: String,
+    /// Whether the session keeps a history; `false` for sessions started
+    /// with `no_inject_history`
+    pub recording: bool,
+    /// Latest first
+    pub entries: Vec<InjectionEntry>,
+}
@@ -0,0 +1,378 @@
+//! Length-prefixed IPC framing shared by the daemon and its clients.
+//!
+//! A frame is `[u32 big-endian length][header byte][payload]`, where the length
+//! covers the header and payload. The low nibble of the header is the format
+//! (`0` JSON, `1` MessagePack); [`FLAG_ACCEPTS_COMPRESSION`] advertises that the
+//! sender can read compressed replies and [`FLAG_COMPRESSED`] marks a zstd
+//! compressed payload. Frames without a header (payload starting directly with
+//! `{`) are plain JSON from clients that predate it; they are answered the same way.
+//!
+//! JSON is the default and the only format documented for third parties.
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, Read};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Largest frame accepted or sent on the wire (16MB)
+pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Largest payload after decompression (guards against decompression bombs)
+pub const MAX_DECOMPRESSED_SIZE: usize = 128 * 1024 * 1024;
+
+/// Payloads larger than this are compressed when the peer supports it
+pub const COMPRESSION_THRESHOLD: usize = 64 * 1024;
+
+/// Buffer reserved before a frame's bytes arrive; larger frames grow as they are read
+const INITIAL_READ_CAPACITY: usize = 64 * 1024;
+
+/// zstd level: fast, still shrinks terminal logs several times over
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Largest zstd window accepted (8MB). Level 3 uses at most 2MB; the cap stops a
+/// tiny frame from making the decoder reserve the default 128MB maximum.
+const MAX_WINDOW_LOG: u32 = 23;
+
+/// Header flag: the payload is zstd compressed
+pub const FLAG_COMPRESSED: u8 = 0x80;
+
+/// Header flag: the sender can read compressed frames in reply
+pub const FLAG_ACCEPTS_COMPRESSION: u8 = 0x40;
+
+/// Header bits holding the wire format
+const FORMAT_MASK: u8 = 0x0f;
+
+/// Header details of a received frame, used to shape the reply
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Format of the payload (`None` for an untagged legacy JSON frame)
+    pub format: Option<WireFormat>,
+    /// Whether the sender accepts compressed replies
+    pub accepts_compression: bool,
+}
+
+/// Payload encoding of a frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WireFormat {
+    /// JSON (tag 0)
+    #[default]
+    Json,
+    /// MessagePack via rmp-serde (tag 1)
+    MessagePack,
+}
+
+impl WireFormat {
+    /// Tag byte written after the length prefix
+    pub fn tag(self) -> u8 {
+        match self {
+            WireFormat::Json => 0,
+            WireFormat::MessagePack => 1,
+        }
+    }
+
+    /// Format for a tag byte, if it is one
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(WireFormat::Json),
+            1 => Some(WireFormat::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// Parse a user-supplied format name (`json`, `msgpack`/`messagepack`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(WireFormat::Json),
+            "msgpack" | "messagepack" => Some(WireFormat::MessagePack),
+            _ => None,
+        }
+    }
+}
+
+/// Encode a message in the given format
+pub fn encode<T: Serialize>(value: &T, format: WireFormat) -> Result<Vec<u8>> {
+    match format {
+        WireFormat::Json => serde_json::to_vec(value).context("Failed to encode JSON message"),
+        // Named (map) encoding is required for internally tagged enums and skipped fields
+        WireFormat::MessagePack => {
+            rmp_serde::to_vec_named(value).context("Failed to encode MessagePack message")
+        }
+    }
+}
+
+/// Decode a message in the given format
+pub fn decode<T: DeserializeOwned>(bytes: &[u8], format: WireFormat) -> Result<T> {
+    match format {
+        WireFormat::Json => serde_json::from_slice(bytes).context("Failed to parse JSON message"),
+        WireFormat::MessagePack => {
+            rmp_serde::from_slice(bytes).context("Failed to parse MessagePack message")
+        }
+    }
+}
+
+fn too_large(what: &str, len: usize, max: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} size {} exceeds maximum allowed size of {}", what, len, max),
+    )
+}
+
+/// Write one frame. `None` writes an untagged JSON frame for legacy peers.
+///
+/// With `compress`, payloads above [`COMPRESSION_THRESHOLD`] are zstd compressed;
+/// only pass it when the peer advertised [`FLAG_ACCEPTS_COMPRESSION`] (or is a
+/// daemon, which always accepts compressed requests).
+pub async fn write_frame<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    payload: &[u8],
+    format: Option<WireFormat>,
+    compress: bool,
+) -> io::Result<()> {
+    let Some(format) = format else {
+        if payload.len() > MAX_FRAME_SIZE {
+            return Err(too_large("Frame", payload.len(), MAX_FRAME_SIZE));
+        }
+        writer.write_all(&(payload.len() as u32).to_be_bytes()).await?;
+        writer.write_all(payload).await?;
+        return writer.flush().await;
+    };
+
+    if payload.len() > MAX_DECOMPRESSED_SIZE {
+        return Err(too_large("Payload", payload.len(), MAX_DECOMPRESSED_SIZE));
+    }
+
+    let mut header = format.tag() | FLAG_ACCEPTS_COMPRESSION;
+    let compressed;
+    let body = if compress && payload.len() > COMPRESSION_THRESHOLD {
+        compressed = zstd::encode_all(payload, COMPRESSION_LEVEL)?;
+        header |= FLAG_COMPRESSED;
+        &compressed[..]
+    } else {
+        payload
+    };
+
+    let len = body.len() + 1;
+    if len > MAX_FRAME_SIZE {
+        return Err(too_large("Frame", len, MAX_FRAME_SIZE));
+    }
+
+    writer.write_all(&(len as u32).to_be_bytes()).await?;
+    writer.write_all(&[header]).await?;
+    writer.write_all(body).await?;
+    writer.flush().await
+}
+
+/// Read one frame, returning its (decompressed) payload and header details
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<(Vec<u8>, FrameInfo)> {
+    let mut len_buf = [0u8; 4];
+    reader.read_exact(&mut len_buf).await?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+
+    if len > MAX_FRAME_SIZE {
+        return Err(too_large("Frame", len, MAX_FRAME_SIZE));
+    }
+    if len == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Received empty frame"));
+    }
+
+    // Grow with the data that actually arrives rather than trusting the length prefix
+    let mut buf = Vec::with_capacity(len.min(INITIAL_READ_CAPACITY));
+    (&mut *reader).take(len as u64).read_to_end(&mut buf).await?;
+    if buf.len() < len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("Frame truncated: expected {} bytes, got {}", len, buf.len()),
+        ));
+    }
+
+    let header = buf[0];
+    let known_bits = FORMAT_MASK | FLAG_COMPRESSED | FLAG_ACCEPTS_COMPRESSION;
+    let format = if header & !known_bits == 0 {
+        WireFormat::from_tag(header & FORMAT_MASK)
+    } else {
+        None
+    };
+
+    let Some(format) = format else {
+        return Ok((buf, FrameInfo::default()));
+    };
+
+    let info = FrameInfo {
+        format: Some(format),
+        accepts_compression: header & FLAG_ACCEPTS_COMPRESSION != 0,
+    };
+    let payload = if header & FLAG_COMPRESSED != 0 {
+        decompress(&buf[1..])?
+    } else {
+        buf.remove(0);
+        buf
+    };
+    Ok((payload, info))
+}
+
+/// Decompress a zstd payload, refusing output beyond [`MAX_DECOMPRESSED_SIZE`]
+fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut decoder = zstd::stream::read::Decoder::new(data)?;
+    decoder.window_log_max(MAX_WINDOW_LOG)?;
+    decoder
+        .take(MAX_DECOMPRESSED_SIZE as u64 + 1)
+        .read_to_end(&mut out)?;
+    if out.len() > MAX_DECOMPRESSED_SIZE {
+        return Err(too_large("Decompressed payload", out.len(), MAX_DECOMPRESSED_SIZE));
+    }
+    Ok(out)
+}
+
+/// Encode and write a message (`None` = untagged JSON)
+pub async fn write_message<W: AsyncWrite + Unpin, T: Serialize>(
+    writer: &mut W,
+    value: &T,
+    format: Option<WireFormat>,
+    compress: bool,
+) -> Result<()> {
+    let payload = encode(value, format.unwrap_or_default())?;
+    write_frame(writer, &payload, format, compress)
+        .await
+        .context("Failed to write message")
+}
+
+/// Read and decode a message, returning the header details it arrived with
+pub async fn read_message<R: AsyncRead + Unpin, T: DeserializeOwned>(
+    reader: &mut R,
+) -> Result<(T, FrameInfo)> {
+    let (payload, info) = read_frame(reader)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read message: {}", e))?;
+    let value = decode(&payload, info.format.unwrap_or_default())?;
+    Ok((value, info))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Request;
+
+    #[test]
+    fn test_wire_format_tags() {
+        for format in [WireFormat::Json, WireFormat::MessagePack] {
+            assert_eq!(WireFormat::from_tag(format.tag()), Some(format));
+        }
+        // '{' starts an untagged JSON payload
+        assert_eq!(WireFormat::from_tag(b'{'), None);
+    }
+
+    #[test]
+    fn test_wire_format_parse() {
+        assert_eq!(WireFormat::parse("JSON"), Some(WireFormat::Json));
+        assert_eq!(WireFormat::parse("msgpack"), Some(WireFormat::MessagePack));
+        assert_eq!(WireFormat::parse("cbor"), None);
+    }
+
+    #[tokio::test]
+    async fn test_frame_round_trip() {
+        for format in [None, Some(WireFormat::Json), Some(WireFormat::MessagePack)] {
+            let (mut client, mut server) = tokio::io::duplex(1024);
+            write_message(&mut client, &Request::DaemonStatus, format, true)
+                .await
+                .unwrap();
+
+            let (request, info): (Request, _) = read_message(&mut server).await.unwrap();
+            assert!(matches!(request, Request::DaemonStatus));
+            assert_eq!(info.format, format);
+            assert_eq!(info.accepts_compression, format.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_large_payload_is_compressed() {
+        let message = "build output line\n".repeat(COMPRESSION_THRESHOLD / 8);
+        let request = Request::Inject {
+            session_id: "s1".to_string(),
+            message: message.clone(),
+            typing_delay_ms: None,
+            line_delay_ms: None,
+            bracketed_paste: false,
+        };
+        let payload = encode(&request, WireFormat::Json).unwrap();
+
+        let mut wire = Vec::new();
+        write_frame(&mut wire, &payload, Some(WireFormat::Json), true)
+            .await
+            .unwrap();
+        assert_ne!(wire[4] & FLAG_COMPRESSED, 0);
+        assert!(wire.len() < payload.len() / 10);
+
+        let (decoded, info): (Request, _) = read_message(&mut &wire[..]).await.unwrap();
+        assert_eq!(info.format, Some(WireFormat::Json));
+        match decoded {
+            Request::Inject { message: m, .. } => assert_eq!(m, message),
+            _ => panic!("Expected Inject request"),
+        }
+
+        // Without `compress` the same payload goes out as is
+        let mut wire = Vec::new();
+        write_frame(&mut wire, &payload, Some(WireFormat::Json), false)
+            .await
+            .unwrap();
+        assert_eq!(wire[4] & FLAG_COMPRESSED, 0);
+        assert_eq!(wire.len(), payload.len() + 5);
+    }
+
+    #[tokio::test]
+    async fn test_decompression_bomb_is_rejected() {
+        let bomb = zstd::encode_all(&vec![0u8; MAX_DECOMPRESSED_SIZE + 1][..], 3).unwrap();
+        assert!(bomb.len() < MAX_FRAME_SIZE);
+
+        let mut wire = Vec::new();
+        wire.extend_from_slice(&((bomb.len() + 1) as u32).to_be_bytes());
+        wire.push(WireFormat::Json.tag() | FLAG_COMPRESSED);
+        wire.extend_from_slice(&bomb);
+
+        let err = read_frame(&mut &wire[..]).await.unwrap_err();
+        assert!(err.to_string().contains("Decompressed payload size"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_zstd_window_is_rejected() {
+        // Frame header declaring a 128MB window, followed by one raw byte
+        let frame = [0x28, 0xb5, 0x2f, 0xfd, 0x00, 0x88, 0x09, 0x00, 0x00, b'x'];
+        let mut wire = Vec::new();
+        wire.extend_from_slice(&((frame.len() + 1) as u32).to_be_bytes());
+        wire.push(WireFormat::Json.tag() | FLAG_COMPRESSED);
+        wire.extend_from_slice(&frame);
+
+        assert!(read_frame(&mut &wire[..]).await.is_err());
+
+        // The same frame with a 1KB window decodes
+        let mut small = frame;
+        small[5] = 0x00;
+        wire.truncate(5);
+        wire.extend_from_slice(&small);
+        let (payload, _) = read_frame(&mut &wire[..]).await.unwrap();
+        assert_eq!(payload, b"x");
+    }
+
+    #[test]
+    fn test_legacy_json_header_is_not_a_tag() {
+        // '{' has bits outside the known header bits, so it reads as untagged JSON
+        let known_bits = FORMAT_MASK | FLAG_COMPRESSED | FLAG_ACCEPTS_COMPRESSION;
+        assert_ne!(b'{' & !known_bits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_rejects_empty_and_oversized() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client.write_all(&0u32.to_be_bytes()).await.unwrap();
+        let err = read_frame(&mut server).await.unwrap_err();
+        assert!(err.to_string().contains("empty"));
+
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client
+            .write_all(&((MAX_FRAME_SIZE + 1) as u32).to_be_bytes())
+            .await
+            .unwrap();
+        let err = read_frame(&mut server).await.unwrap_err();
+        assert!(err.to_string().contains("exceeds maximum"));
+    }
+}
@@ -5,9 +5,45 @@ pub use error::{ErrorCode, DaemonError};
 // Core data types
 pub mod types;
 pub use types::{
-    SessionStatus, Session, CliState, CliStatus, DaemonConfig, SessionInfo,
+    SessionStatus, Session, AnswerKeys, CheckpointMode, Checkpoint, CliState, CliStatus, CreationTimings, DaemonConfig, DiskThreshold, EnvironmentSnapshot, HookHealth, HookInfo, HookKind, HookState, IdFormat, InitManifest, InitPathIssue, InitPathPolicy, InitPathProblem, InputPrompt, LifecycleEvent, LifecycleHook, ModelPrice, Namespace, OptionKeys, OtelConfig, PromptAnswer, PromptSource, SessionInfo, SessionInfoRef, StartTrigger, StopMethod, StopReason,
+    DEFAULT_NAMESPACE, MAX_NAMESPACE_LEN, MAX_SESSION_ID_LEN, NAMESPACES_DIR, SESSION_SCHEMA_VERSION, parse_duration, validate_session_id, validate_workdir_subpath, validate_workspace_file,
 };
 
-// IPC protocol
+// Waiting for session statuses
+pub mod wait;
+pub use wait::{WaitMode, WaitOutcome};
+
+// Per-session timeline
+pub mod timeline;
+pub use timeline::{TimelineEntry, TimelineEvent, TimelineKind};
+
+// History of injected messages
+pub mod injections;
+pub use injections::{InjectionEntry, InjectionHistory, InjectionRecord};
+
+// The local user
+pub mod user;
+
+// Required tmux version
+pub mod tmux;
+
+// Named environment secrets
+pub mod secrets;
+
+// Embedded host assets
+pub mod assets;
+
+// Hook settings deployed into workspaces
+pub mod hook_settings;
+
+// IPC protocol and framing
+pub mod ipc;
 pub mod protocol;
-pub use protocol::{Request, Response, DaemonStatusResponse};
+pub use protocol::{
+    Request, Response, DaemonStatusResponse, FileChunk, FileEntry, FileKind, FileListing, GcCategory, GcReport, HealthReport, HookRun, HealthStatus,
+    NamespaceSummary, Scope, ScopedRequest, SessionCounts, PROTOCOL_VERSION,
+};
+
+// JSON Schema of the protocol
+pub mod schema;
+pub use schema::protocol_schema;
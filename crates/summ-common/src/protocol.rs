@@ -1,51 +1,315 @@
 use crate::error::DaemonError;
-use crate::types::SessionStatus;
+use crate::types::{CheckpointMode, LifecycleEvent, Namespace, PromptAnswer, SessionStatus, StartTrigger};
+use crate::wait::WaitMode;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+/// Version of the IPC protocol described by `schema::protocol_schema`; bumped
+/// on changes that old clients or daemons cannot read
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Most requests one `Request::Batch` may carry
+pub const MAX_BATCH_REQUESTS: usize = 32;
+
+/// Largest encoded size of the requests of one `Request::Batch`, in bytes
+pub const MAX_BATCH_BYTES: usize = 1024 * 1024;
+
+/// Largest chunk of file contents one `Request::PutFile` may carry or one
+/// `Request::GetFile` returns, in bytes; larger files take several chunks
+pub const MAX_FILE_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
 /// IPC request types sent from CLI to daemon
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum Request {
     /// Start a new session
     Start {
         /// CLI command to execute
         cli: String,
-        /// Initialization source path (directory, .zip, or .tar.gz)
+        /// Initialization source path (directory, .zip, or .tar.gz); unused
+        /// with `init_from_session`
+        #[serde(default)]
         init: PathBuf,
+        /// Initialize from a copy of this session's workspace instead of `init`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        init_from_session: Option<String>,
+        /// Keep the start pending until this session reaches this status
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after: Option<StartTrigger>,
         /// Optional custom name for the session
         #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
+        /// Protect the session from stop and cleanup sweeps
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        protected: bool,
+        /// Give up (and clean up) if the session is not ready within this many milliseconds
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deadline_ms: Option<u64>,
+        /// Return as soon as the session is allocated and set it up in the background
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        background: bool,
+        /// Skip the `max_init_size_mb` check on the init source
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        allow_large_init: bool,
+        /// Checkpoint the workspace with git when the session stops
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        git_checkpoint: Option<CheckpointMode>,
+        /// Names of secrets (from secrets.json) to export into the session; the
+        /// daemon resolves the values, which never appear in the request
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        secrets: Vec<String>,
+        /// Start the CLI in this directory below the workspace root
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        workdir_subpath: Option<PathBuf>,
+        /// Deploy hooks into `workdir_subpath` rather than the workspace root
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        hooks_in_subpath: bool,
+        /// Remove the session's directory and log as soon as it stops; an
+        /// empty `init` starts it in an empty workspace
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        ephemeral: bool,
+        /// Stop the session once its CLI has been idle this many seconds
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        idle_timeout_seconds: Option<u64>,
+        /// Alert once the CLI has been busy on one task this many minutes,
+        /// overriding `busy_alert_minutes` in config; 0 turns the alert off
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        busy_alert_minutes: Option<u64>,
+        /// Injections per minute the session accepts, overriding
+        /// `inject_rate_limit` in config; 0 removes the limit
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        inject_rate_limit: Option<u32>,
+        /// Do not keep a history of the messages injected into the session
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        no_inject_history: bool,
+        /// Start as an observer working in this session's workspace: nothing
+        /// is copied and no hooks are deployed; `init` must be empty
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        observer_of: Option<String>,
     },
     /// Stop a running session
     Stop {
         /// Session ID to stop
         session_id: String,
+        /// Stop the session even if it is protected
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        override_protection: bool,
+        /// Also stop the sessions observing its workspace (otherwise they
+        /// are left running and listed in the response)
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        stop_observers: bool,
+    },
+    /// Stop every session that is not already stopped
+    StopAll {
+        /// Also stop protected sessions (otherwise they are skipped)
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        override_protection: bool,
+        /// Report the sessions that would be stopped without stopping them
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        dry_run: bool,
+    },
+    /// Set or clear a session's protected flag
+    SetProtected {
+        /// Target session ID
+        session_id: String,
+        /// New value of the flag
+        protected: bool,
+    },
+    /// Set or clear a session's free-text note
+    SetNote {
+        /// Target session ID
+        session_id: String,
+        /// New note, or `None` to clear it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        note: Option<String>,
     },
     /// List all sessions, optionally filtered by status
     List {
-        /// Optional status filter (running/idle/stopped)
+        /// Optional status filter (running/idle/stopped/paused)
         #[serde(skip_serializing_if = "Option::is_none")]
         status_filter: Option<SessionStatus>,
+        /// Only list sessions created by this uid (`summ list --mine`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        created_by_uid: Option<u32>,
+        /// Also list sessions archived out of memory, read from disk
+        /// (`summ list --archived`)
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        include_archived: bool,
     },
     /// Query detailed session status
     Status {
         /// Session ID to query
         session_id: String,
     },
+    /// Wait until all (or any) of several sessions reach one of the target statuses
+    WaitMany {
+        /// Sessions to wait for
+        session_ids: Vec<String>,
+        /// Whether every session or the first one must get there
+        #[serde(default)]
+        mode: WaitMode,
+        /// Statuses that count as reached
+        target: Vec<SessionStatus>,
+        /// Give up after this many milliseconds; the daemon never waits more
+        /// than an hour, which is also the default
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
     /// Inject a message into a running session
     Inject {
         /// Target session ID
         session_id: String,
         /// Message to inject
         message: String,
+        /// Delay in milliseconds between typed characters (simulated typing)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        typing_delay_ms: Option<u64>,
+        /// Delay in milliseconds between lines
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        line_delay_ms: Option<u64>,
+        /// Wrap the payload in bracketed paste escape sequences
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        bracketed_paste: bool,
+    },
+    /// Messages injected into a session, latest first
+    InjectionHistory {
+        /// Target session ID
+        session_id: String,
+        /// Only return the latest this many messages
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<usize>,
+    },
+    /// Write (a chunk of) a file into a session's workspace.
+    ///
+    /// `offset` 0 starts the file, each following chunk continues where the
+    /// previous one ended, and the chunk with `done` moves it into place.
+    PutFile {
+        /// Target session ID
+        session_id: String,
+        /// Destination, relative to the workspace
+        dest: PathBuf,
+        /// Contents of this chunk (base64 on the wire), at most
+        /// `MAX_FILE_CHUNK_BYTES`
+        #[serde(with = "base64_bytes")]
+        #[schemars(with = "String")]
+        data: Vec<u8>,
+        /// Position of this chunk in the file
+        #[serde(default)]
+        offset: u64,
+        /// This is the last chunk
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        done: bool,
+        /// Replace an existing file at `dest`
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        overwrite: bool,
+    },
+    /// List the files of a session's workspace (or of a directory in it)
+    ListFiles {
+        /// Target session ID
+        session_id: String,
+        /// Directory to list, relative to the workspace (default: its root)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subpath: Option<PathBuf>,
+        /// Levels of directories to descend into (default: 1, just the entries
+        /// of the directory)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        depth: Option<u32>,
+    },
+    /// Read (a chunk of) a file of a session's workspace; the response is a
+    /// `FileChunk` of at most `MAX_FILE_CHUNK_BYTES`
+    GetFile {
+        /// Target session ID
+        session_id: String,
+        /// File to read, relative to the workspace
+        path: PathBuf,
+        /// Position in the file to read from
+        #[serde(default)]
+        offset: u64,
+    },
+    /// Send raw tmux key names (e.g. `C-c`, `Escape`) to a running session
+    SendKeys {
+        /// Target session ID
+        session_id: String,
+        /// tmux key names, sent in order
+        keys: Vec<String>,
+    },
+    /// Answer the permission or confirmation prompt a session is waiting on
+    Answer {
+        /// Target session ID
+        session_id: String,
+        answer: PromptAnswer,
+        /// Send the answer even if the session is not flagged as waiting
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        force: bool,
+    },
+    /// Send a named signal (INT, TERM, HUP, QUIT) to the session's foreground process
+    Signal {
+        /// Target session ID
+        session_id: String,
+        /// Signal name, with or without the SIG prefix
+        signal: String,
+    },
+    /// Pause a session by stopping its foreground process group (SIGSTOP)
+    Pause {
+        /// Session ID to pause
+        session_id: String,
+    },
+    /// Resume a paused session (SIGCONT)
+    Resume {
+        /// Session ID to resume
+        session_id: String,
+    },
+    /// Refresh out-of-date hook assets (script and per-session settings)
+    SyncHooks {
+        /// Rewrite all hook assets even if they are current
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        force: bool,
+    },
+    /// Run a configured lifecycle hook once with a made-up session, and
+    /// report how it went (`summ daemon test-hook`)
+    TestHook {
+        /// Name of the hook in `lifecycle_hooks`
+        name: String,
+        /// Event to fire it for (default: its first event, or `running`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        event: Option<LifecycleEvent>,
+    },
+    /// Delete terminal logs and session directories no session owns any more
+    Gc {
+        /// Report what would be deleted without deleting it
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        dry_run: bool,
+    },
+    /// Events, injections, and status changes of a session in time order
+    Timeline {
+        /// Target session ID
+        session_id: String,
+        /// Only include events from the last this many seconds
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        since_seconds: Option<u64>,
     },
+    /// Count sessions by effective status without listing them
+    Counts,
+    /// Namespaces that have sessions, with their counts (`summ namespaces`)
+    Namespaces,
     /// Query daemon status
     DaemonStatus,
+    /// Cheap health check from the daemon's cached internal state
+    Healthz,
+    /// Run several requests in order over one connection, each as if sent alone
+    Batch {
+        /// Requests to run; a batch cannot contain another batch
+        requests: Vec<Request>,
+        /// Skip the requests after the first one that fails
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        stop_on_error: bool,
+    },
 }
 
 /// IPC response types sent from daemon to CLI
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum Response {
     /// Successful response with data payload
@@ -59,7 +323,166 @@ pub enum Response {
         code: String,
         /// Error message
         message: String,
+        /// Version of the daemon that sent the error, so clients can spot a
+        /// stale daemon without another round trip
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        daemon_version: Option<String>,
+        /// Seconds after which the request may succeed when repeated, for
+        /// refusals that only last a while (`E018`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retry_after_seconds: Option<u64>,
+    },
+    /// Responses to a `Request::Batch`, one per request, in order
+    Batch {
+        responses: Vec<Response>,
     },
+    /// In a batch response: the request was not run because an earlier one
+    /// failed and the batch had `stop_on_error`
+    Skipped,
+}
+
+impl Request {
+    /// Variant name as it appears in the `type` tag (for logs and spans)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Request::Start { .. } => "Start",
+            Request::Stop { .. } => "Stop",
+            Request::StopAll { .. } => "StopAll",
+            Request::SetProtected { .. } => "SetProtected",
+            Request::SetNote { .. } => "SetNote",
+            Request::List { .. } => "List",
+            Request::Status { .. } => "Status",
+            Request::WaitMany { .. } => "WaitMany",
+            Request::Inject { .. } => "Inject",
+            Request::InjectionHistory { .. } => "InjectionHistory",
+            Request::PutFile { .. } => "PutFile",
+            Request::ListFiles { .. } => "ListFiles",
+            Request::GetFile { .. } => "GetFile",
+            Request::SendKeys { .. } => "SendKeys",
+            Request::Answer { .. } => "Answer",
+            Request::Signal { .. } => "Signal",
+            Request::Pause { .. } => "Pause",
+            Request::Resume { .. } => "Resume",
+            Request::SyncHooks { .. } => "SyncHooks",
+            Request::Gc { .. } => "Gc",
+            Request::TestHook { .. } => "TestHook",
+            Request::Timeline { .. } => "Timeline",
+            Request::Counts => "Counts",
+            Request::Namespaces => "Namespaces",
+            Request::DaemonStatus => "DaemonStatus",
+            Request::Healthz => "Healthz",
+            Request::Batch { .. } => "Batch",
+        }
+    }
+
+    /// Session the request targets, if it targets a single existing session
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            Request::Stop { session_id, .. }
+            | Request::SetProtected { session_id, .. }
+            | Request::SetNote { session_id, .. }
+            | Request::Status { session_id }
+            | Request::Inject { session_id, .. }
+            | Request::InjectionHistory { session_id, .. }
+            | Request::PutFile { session_id, .. }
+            | Request::ListFiles { session_id, .. }
+            | Request::GetFile { session_id, .. }
+            | Request::SendKeys { session_id, .. }
+            | Request::Answer { session_id, .. }
+            | Request::Signal { session_id, .. }
+            | Request::Pause { session_id }
+            | Request::Resume { session_id }
+            | Request::Timeline { session_id, .. } => Some(session_id),
+            _ => None,
+        }
+    }
+
+    /// Top-level fields of `raw`, the envelope this request was decoded from,
+    /// that the request does not know and ignored.
+    ///
+    /// A field is known when it survives re-encoding the request or belongs
+    /// to its [`Scope`]. Fields that are skipped on encoding when unset
+    /// (`null` or `false`) look the same as unknown ones, so unknown fields
+    /// holding those values are not reported.
+    pub fn unknown_fields(&self, raw: &serde_json::Value) -> Vec<String> {
+        let Some(raw) = raw.as_object() else {
+            return Vec::new();
+        };
+        let known = serde_json::to_value(self).unwrap_or_default();
+        raw.iter()
+            .filter(|(key, value)| {
+                known.get(key.as_str()).is_none()
+                    && !Scope::FIELDS.contains(&key.as_str())
+                    && !matches!(value, serde_json::Value::Null | serde_json::Value::Bool(false))
+            })
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Whether sending the request twice has the same effect as sending it once.
+    ///
+    /// Only these requests are resent by the client after a lost response.
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            Request::Batch { requests, .. } => requests.iter().all(Request::is_idempotent),
+            _ => matches!(
+                self,
+                Request::List { .. }
+                    | Request::Status { .. }
+                    | Request::WaitMany { .. }
+                    | Request::Timeline { .. }
+                    | Request::InjectionHistory { .. }
+                    | Request::ListFiles { .. }
+                    | Request::GetFile { .. }
+                    | Request::Counts
+                    | Request::Namespaces
+                    | Request::DaemonStatus
+                    | Request::Healthz
+            ),
+        }
+    }
+}
+
+/// The namespaces a request applies to.
+///
+/// Sent as top-level fields next to the request's own (`namespace`,
+/// `all_namespaces`), so every request carries it without each variant
+/// declaring it. Requests without them apply to the default namespace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Scope {
+    /// Namespace new sessions go to and other requests are limited to
+    #[serde(default, skip_serializing_if = "Namespace::is_default")]
+    pub namespace: Namespace,
+    /// Reach sessions of every namespace (`--all-namespaces`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub all_namespaces: bool,
+}
+
+impl Scope {
+    /// Top-level request fields that belong to the scope
+    pub const FIELDS: &'static [&'static str] = &["namespace", "all_namespaces"];
+
+    /// Scope of one namespace
+    pub fn namespace(namespace: Namespace) -> Self {
+        Self {
+            namespace,
+            all_namespaces: false,
+        }
+    }
+
+    /// Whether a session in `namespace` is within the scope
+    pub fn contains(&self, namespace: &Namespace) -> bool {
+        self.all_namespaces || self.namespace == *namespace
+    }
+}
+
+/// A request with its scope, as sent by clients
+#[derive(Debug, Serialize)]
+pub struct ScopedRequest<'a> {
+    #[serde(flatten)]
+    pub request: &'a Request,
+    #[serde(flatten)]
+    pub scope: &'a Scope,
 }
 
 impl Response {
@@ -73,12 +496,49 @@ impl Response {
         Self::Error {
             code: err.code.code().to_string(),
             message: err.message.clone(),
+            daemon_version: None,
+            retry_after_seconds: None,
+        }
+    }
+
+    /// An error response telling the client when to try again
+    pub fn error_retry_after(err: &DaemonError, seconds: u64) -> Self {
+        Self::Error {
+            code: err.code.code().to_string(),
+            message: err.message.clone(),
+            daemon_version: None,
+            retry_after_seconds: Some(seconds),
+        }
+    }
+
+    /// Stamp an error response (or the errors of a batch response) with the
+    /// version of the daemon sending it
+    pub fn with_daemon_version(self, version: &str) -> Self {
+        match self {
+            Self::Error {
+                code,
+                message,
+                retry_after_seconds,
+                ..
+            } => Self::Error {
+                code,
+                message,
+                daemon_version: Some(version.to_string()),
+                retry_after_seconds,
+            },
+            Self::Batch { responses } => Self::Batch {
+                responses: responses
+                    .into_iter()
+                    .map(|response| response.with_daemon_version(version))
+                    .collect(),
+            },
+            response => response,
         }
     }
 }
 
 /// Daemon status response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DaemonStatusResponse {
     /// Daemon running state
     pub running: bool,
@@ -88,6 +548,228 @@ pub struct DaemonStatusResponse {
     pub version: String,
 }
 
+/// Session counts by effective status (response to `Request::Counts`).
+///
+/// Computed in one pass without serializing sessions, so it is safe to poll
+/// at high frequency (prompts, watch headers, monitoring).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SessionCounts {
+    /// Sessions whose CLI is processing a task
+    pub running: usize,
+    /// Sessions waiting for input
+    pub idle: usize,
+    /// Sessions whose tmux session has exited
+    pub stopped: usize,
+    /// Sessions frozen with `summ pause`
+    pub paused: usize,
+    /// Sessions still being set up by a background start
+    #[serde(default)]
+    pub starting: usize,
+    /// Sessions whose background start failed
+    #[serde(default)]
+    pub failed: usize,
+    /// Starts waiting for another session (`summ start --after`)
+    #[serde(default)]
+    pub pending: usize,
+    /// All sessions the daemon holds in memory; archived ones are not included
+    pub total: usize,
+    /// Stopped and failed sessions archived out of memory (`archive_after_hours`),
+    /// which the counts above do not cover
+    #[serde(default)]
+    pub archived: usize,
+    /// Longest time any running session has been busy, in seconds
+    #[serde(default)]
+    pub busy_longest_seconds: Option<i64>,
+    /// Longest time any idle session has been waiting, in seconds
+    #[serde(default)]
+    pub oldest_idle_seconds: Option<i64>,
+}
+
+impl SessionCounts {
+    /// Count one session with the given effective status, in that status for `age_seconds`
+    pub fn add(&mut self, status: &SessionStatus, age_seconds: i64) {
+        match status {
+            SessionStatus::Running => {
+                self.running += 1;
+                self.busy_longest_seconds = self.busy_longest_seconds.max(Some(age_seconds));
+            }
+            SessionStatus::Idle => {
+                self.idle += 1;
+                self.oldest_idle_seconds = self.oldest_idle_seconds.max(Some(age_seconds));
+            }
+            SessionStatus::Stopped => self.stopped += 1,
+            SessionStatus::Paused => self.paused += 1,
+            SessionStatus::Starting => self.starting += 1,
+            SessionStatus::Failed => self.failed += 1,
+            SessionStatus::Pending => self.pending += 1,
+        }
+        self.total += 1;
+    }
+}
+
+/// One namespace in the response to `Request::Namespaces`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct NamespaceSummary {
+    pub namespace: Namespace,
+    pub counts: SessionCounts,
+}
+
+/// Response to `Request::TestHook`: one run of a lifecycle hook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct HookRun {
+    /// Name of the hook
+    pub name: String,
+    pub event: LifecycleEvent,
+    /// Exit code; none when the command was killed or could not be started
+    pub exit_code: Option<i32>,
+    /// The command ran past its timeout and was killed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    /// Output of the command (the start of it, for long output)
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// One kind of leftover `Request::Gc` deletes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GcCategory {
+    /// Entries deleted (or, on a dry run, that would be)
+    pub count: usize,
+    /// Bytes they took up
+    pub bytes: u64,
+    /// Their paths
+    pub paths: Vec<PathBuf>,
+}
+
+/// Response to `Request::Gc`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GcReport {
+    /// Nothing was deleted; the counts are what would be
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+    /// Terminal logs of sessions that no longer exist, past the retention window
+    pub orphaned_logs: GcCategory,
+    /// Session directories without metadata or a tmux session, left by failed
+    /// starts or partial deletions
+    pub partial_session_dirs: GcCategory,
+}
+
+/// What a workspace entry is; symlinks are listed, never followed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FileKind {
+    File,
+    Directory,
+    Symlink,
+}
+
+/// One entry of a workspace listing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FileEntry {
+    /// Path relative to the workspace
+    pub path: PathBuf,
+    pub kind: FileKind,
+    /// Size in bytes (0 for directories)
+    pub size: u64,
+    /// Last modification, when the filesystem records it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// Response to `Request::ListFiles`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FileListing {
+    /// Entries in path order
+    pub entries: Vec<FileEntry>,
+    /// The listing stopped at the daemon's entry limit
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// Response to `Request::GetFile`: a chunk of a workspace file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct FileChunk {
+    /// Path relative to the workspace
+    pub path: PathBuf,
+    /// Position of the chunk in the file
+    pub offset: u64,
+    /// Contents of the chunk (base64 on the wire)
+    #[serde(with = "base64_bytes")]
+    #[schemars(with = "String")]
+    pub data: Vec<u8>,
+    /// Size of the whole file when the chunk was read
+    pub size: u64,
+    /// Last modification of the file when the chunk was read; a change
+    /// between chunks means the file changed while it was read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Utc>>,
+    /// This chunk ends the file
+    pub done: bool,
+}
+
+/// Overall daemon health
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// Everything checked is fine
+    #[default]
+    Ok,
+    /// The daemon serves requests, but something needs attention
+    Degraded,
+    /// The daemon cannot do its job (e.g. start sessions)
+    Failing,
+}
+
+/// Response to `Request::Healthz`.
+///
+/// Built from state cached by the daemon's background tasks, so it is cheap
+/// enough for supervisors to poll. A `None` check has not run yet.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct HealthReport {
+    /// Worst status over all checks
+    pub status: HealthStatus,
+    /// Why the status is not `ok`, one line per problem
+    #[serde(default)]
+    pub reasons: Vec<String>,
+    /// Whether tmux answered on the last monitor tick
+    #[serde(default)]
+    pub tmux_reachable: Option<bool>,
+    /// Whether the last write probe in the sessions directory succeeded
+    #[serde(default)]
+    pub sessions_dir_writable: Option<bool>,
+    /// Seconds since the session monitor last completed a tick
+    #[serde(default)]
+    pub monitor_heartbeat_age_seconds: Option<u64>,
+    /// Free-space level of the sessions filesystem: `ok`, `low`, or `critical`
+    #[serde(default)]
+    pub disk: Option<String>,
+}
+
+impl HealthReport {
+    /// Record a problem, raising the overall status to at least `status`
+    pub fn add_problem(&mut self, status: HealthStatus, reason: impl Into<String>) {
+        self.status = self.status.max(status);
+        self.reasons.push(reason.into());
+    }
+}
+
+/// Bytes as a standard base64 string, so file contents stay compact in JSON
+mod base64_bytes {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        STANDARD.decode(text).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,6 +781,22 @@ mod tests {
             cli: "claude".to_string(),
             init: PathBuf::from("/path/to/init"),
             name: Some("test-session".to_string()),
+            protected: false,
+            deadline_ms: None,
+            background: false,
+            allow_large_init: false,
+            git_checkpoint: None,
+            secrets: Vec::new(),
+            workdir_subpath: None,
+            hooks_in_subpath: false,
+            ephemeral: false,
+            idle_timeout_seconds: None,
+            init_from_session: None,
+            after: None,
+            observer_of: None,
+            busy_alert_minutes: None,
+            inject_rate_limit: None,
+            no_inject_history: false,
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -111,6 +809,8 @@ mod tests {
     fn test_request_with_none_filter() {
         let req = Request::List {
             status_filter: None,
+            created_by_uid: None,
+            include_archived: false,
         };
 
         let json = serde_json::to_string(&req).unwrap();
@@ -140,7 +840,7 @@ mod tests {
         let resp = Response::error(&err);
 
         match resp {
-            Response::Error { code, message } => {
+            Response::Error { code, message, .. } => {
                 assert_eq!(code, "E002");
                 assert_eq!(message, "session not found");
             }
@@ -152,11 +852,14 @@ mod tests {
     fn test_request_list_with_status_filter() {
         let req = Request::List {
             status_filter: Some(SessionStatus::Running),
+            created_by_uid: Some(1000),
+            include_archived: false,
         };
 
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains(r#""type":"List""#));
         assert!(json.contains(r#""status_filter":"running""#));
+        assert!(json.contains(r#""created_by_uid":1000"#));
     }
 
     #[test]
@@ -164,12 +867,37 @@ mod tests {
         let req = Request::Inject {
             session_id: "session-123".to_string(),
             message: "test message".to_string(),
+            typing_delay_ms: None,
+            line_delay_ms: None,
+            bracketed_paste: false,
         };
 
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains(r#""type":"Inject""#));
         assert!(json.contains(r#""session_id":"session-123""#));
         assert!(json.contains(r#""message":"test message""#));
+        assert!(!json.contains(r#""typing_delay_ms""#));
+        assert!(!json.contains(r#""bracketed_paste""#));
+    }
+
+    #[test]
+    fn test_request_inject_pacing_defaults() {
+        let json = r#"{"type":"Inject","session_id":"s1","message":"hi"}"#;
+        let req: Request = serde_json::from_str(json).unwrap();
+
+        match req {
+            Request::Inject {
+                typing_delay_ms,
+                line_delay_ms,
+                bracketed_paste,
+                ..
+            } => {
+                assert_eq!(typing_delay_ms, None);
+                assert_eq!(line_delay_ms, None);
+                assert!(!bracketed_paste);
+            }
+            _ => panic!("Expected Inject request"),
+        }
     }
 
     #[test]
@@ -186,6 +914,116 @@ mod tests {
         assert!(json.contains(r#""version":"0.1.0""#));
     }
 
+    #[test]
+    fn test_session_counts_add() {
+        let mut counts = SessionCounts::default();
+        counts.add(&SessionStatus::Running, 30);
+        counts.add(&SessionStatus::Running, 90);
+        counts.add(&SessionStatus::Idle, 5);
+        counts.add(&SessionStatus::Stopped, 600);
+
+        assert_eq!(counts.running, 2);
+        assert_eq!(counts.idle, 1);
+        assert_eq!(counts.stopped, 1);
+        assert_eq!(counts.paused, 0);
+        assert_eq!(counts.total, 4);
+        assert_eq!(counts.busy_longest_seconds, Some(90));
+        assert_eq!(counts.oldest_idle_seconds, Some(5));
+    }
+
+    #[test]
+    fn test_health_report_worst_status_wins() {
+        let mut report = HealthReport::default();
+        assert_eq!(report.status, HealthStatus::Ok);
+        report.add_problem(HealthStatus::Failing, "tmux is not reachable");
+        report.add_problem(HealthStatus::Degraded, "Low disk space");
+        assert_eq!(report.status, HealthStatus::Failing);
+        assert_eq!(report.reasons.len(), 2);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["status"], "failing");
+        let parsed: HealthReport = serde_json::from_value(serde_json::json!({"status": "ok"})).unwrap();
+        assert_eq!(parsed, HealthReport::default());
+    }
+
+    #[test]
+    fn test_request_is_idempotent() {
+        assert!(Request::DaemonStatus.is_idempotent());
+        assert!(Request::Healthz.is_idempotent());
+        assert!(Request::Status {
+            session_id: "s1".to_string()
+        }
+        .is_idempotent());
+        assert!(Request::WaitMany {
+            session_ids: vec!["s1".to_string()],
+            mode: WaitMode::All,
+            target: vec![SessionStatus::Idle],
+            timeout_ms: None,
+        }
+        .is_idempotent());
+        assert!(!Request::Stop {
+            session_id: "s1".to_string(),
+            override_protection: false,
+            stop_observers: false,
+        }
+        .is_idempotent());
+        // A batch is as safe to resend as its least safe request
+        let batch = |requests| Request::Batch { requests, stop_on_error: false };
+        assert!(batch(vec![Request::Counts, Request::Healthz]).is_idempotent());
+        assert!(!batch(vec![Request::Counts, Request::Pause { session_id: "s1".to_string() }]).is_idempotent());
+    }
+
+    #[test]
+    fn test_batch_round_trip() {
+        let json = r#"{"type":"Batch","requests":[{"type":"Status","session_id":"s1"},{"type":"Counts"}]}"#;
+        let req: Request = serde_json::from_str(json).unwrap();
+        assert!(matches!(&req, Request::Batch { requests, stop_on_error: false } if requests.len() == 2));
+        assert_eq!(req.kind(), "Batch");
+        assert_eq!(req.session_id(), None);
+
+        let resp = Response::Batch {
+            responses: vec![Response::success(serde_json::json!({})), Response::Skipped],
+        };
+        assert_eq!(
+            serde_json::to_string(&resp).unwrap(),
+            r#"{"type":"Batch","responses":[{"type":"Success","data":{}},{"type":"Skipped"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_put_file_data_is_base64() {
+        let req = Request::PutFile {
+            session_id: "s1".to_string(),
+            dest: PathBuf::from("docs/spec.md"),
+            data: b"# Spec\n".to_vec(),
+            offset: 0,
+            done: true,
+            overwrite: false,
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"PutFile","session_id":"s1","dest":"docs/spec.md","data":"IyBTcGVjCg==","offset":0,"done":true}"#
+        );
+        assert_eq!(req.session_id(), Some("s1"));
+
+        match serde_json::from_str(&json).unwrap() {
+            Request::PutFile { data, .. } => assert_eq!(data, b"# Spec\n"),
+            other => panic!("Expected PutFile, got {:?}", other),
+        }
+        let bad = r#"{"type":"PutFile","session_id":"s1","dest":"a","data":"not base64!"}"#;
+        assert!(serde_json::from_str::<Request>(bad).is_err());
+    }
+
+    #[test]
+    fn test_request_session_id() {
+        let req = Request::Pause {
+            session_id: "s1".to_string(),
+        };
+        assert_eq!(req.session_id(), Some("s1"));
+        assert_eq!(Request::Counts.session_id(), None);
+    }
+
     #[test]
     fn test_request_deserialization() {
         let json = r#"{"type":"Status","session_id":"test-session"}"#;
@@ -218,11 +1056,69 @@ mod tests {
         let resp: Response = serde_json::from_str(json).unwrap();
 
         match resp {
-            Response::Error { code, message } => {
+            Response::Error { code, message, .. } => {
                 assert_eq!(code, "E002");
                 assert_eq!(message, "not found");
             }
             _ => panic!("Expected Error response"),
         }
     }
+
+    #[test]
+    fn test_error_carries_daemon_version() {
+        let err = DaemonError::new(crate::error::ErrorCode::E002, "session not found");
+        let json = serde_json::to_string(&Response::error(&err)).unwrap();
+        assert!(!json.contains("daemon_version"), "{}", json);
+
+        let stamped = Response::error(&err).with_daemon_version("0.4.2");
+        let json = serde_json::to_string(&stamped).unwrap();
+        assert!(json.contains(r#""daemon_version":"0.4.2""#), "{}", json);
+        // Successes are left alone
+        let success = Response::success(serde_json::json!({})).with_daemon_version("0.4.2");
+        assert!(matches!(success, Response::Success { .. }));
+    }
+
+    #[test]
+    fn test_request_unknown_fields() {
+        let raw = serde_json::json!({
+            "type": "Stop",
+            "session_id": "session_abc",
+            "override_protection": false,
+            "force": true,
+            "trace": null
+        });
+        let request: Request = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(request.unknown_fields(&raw), ["force"]);
+
+        let raw = serde_json::json!({"type": "Counts"});
+        let request: Request = serde_json::from_value(raw.clone()).unwrap();
+        assert!(request.unknown_fields(&raw).is_empty());
+    }
+
+    #[test]
+    fn test_scoped_request_round_trip() {
+        let request = Request::Status {
+            session_id: "session_abc".to_string(),
+        };
+        let scope = Scope::namespace(Namespace::new("work").unwrap());
+        let raw = serde_json::to_value(ScopedRequest { request: &request, scope: &scope }).unwrap();
+        assert_eq!(raw["namespace"], "work");
+        assert!(raw.get("all_namespaces").is_none());
+
+        let decoded: Request = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(decoded.session_id(), Some("session_abc"));
+        assert!(decoded.unknown_fields(&raw).is_empty());
+        assert_eq!(serde_json::from_value::<Scope>(raw).unwrap(), scope);
+
+        // Older clients send no scope: the default namespace
+        let raw = serde_json::json!({"type": "Counts"});
+        let scope: Scope = serde_json::from_value(raw).unwrap();
+        assert!(scope.namespace.is_default());
+        assert!(scope.contains(&Namespace::default()));
+        assert!(!scope.contains(&Namespace::new("work").unwrap()));
+        assert!(Scope { all_namespaces: true, ..scope }.contains(&Namespace::new("work").unwrap()));
+
+        let raw = serde_json::json!({"type": "Counts", "namespace": "../etc"});
+        assert!(serde_json::from_value::<Scope>(raw).is_err());
+    }
 }
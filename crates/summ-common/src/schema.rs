@@ -0,0 +1,77 @@
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+use crate::protocol::{
+    DaemonStatusResponse, FileChunk, FileListing, GcReport, HealthReport, HookRun, NamespaceSummary, Request, Response,
+    Scope, SessionCounts, PROTOCOL_VERSION,
+};
+use crate::timeline::TimelineEntry;
+use crate::types::{CliStatus, Session, SessionInfo};
+use crate::wait::WaitOutcome;
+
+/// JSON Schema bundle of the IPC protocol: every request, response, and
+/// typed payload under `$defs`, stamped with `PROTOCOL_VERSION`.
+///
+/// Printed by `summ schema` and `summ-daemon --dump-schema`, and checked in
+/// as `docs/protocol.schema.json`.
+pub fn protocol_schema() -> Value {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    fn add<T: JsonSchema>(generator: &mut schemars::SchemaGenerator) {
+        generator.subschema_for::<T>();
+    }
+    add::<Request>(&mut generator);
+    add::<Scope>(&mut generator);
+    add::<Response>(&mut generator);
+    add::<Session>(&mut generator);
+    add::<SessionInfo>(&mut generator);
+    add::<CliStatus>(&mut generator);
+    add::<DaemonStatusResponse>(&mut generator);
+    add::<SessionCounts>(&mut generator);
+    add::<NamespaceSummary>(&mut generator);
+    add::<HealthReport>(&mut generator);
+    add::<TimelineEntry>(&mut generator);
+    add::<WaitOutcome>(&mut generator);
+    add::<FileListing>(&mut generator);
+    add::<FileChunk>(&mut generator);
+    add::<GcReport>(&mut generator);
+    add::<HookRun>(&mut generator);
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "SUMM daemon protocol",
+        "description": "Requests are sent as `Request` with the `Scope` fields next to their own; \
+                        every reply is a `Response`, whose `data` holds the typed payloads below",
+        "protocol_version": PROTOCOL_VERSION,
+        "$defs": generator.take_definitions(true),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAPSHOT: &str = include_str!("../../../docs/protocol.schema.json");
+
+    #[test]
+    fn test_schema_matches_snapshot() {
+        let snapshot: Value = serde_json::from_str(SNAPSHOT).unwrap();
+        assert!(
+            protocol_schema() == snapshot,
+            "The protocol schema changed. If that is intended, bump PROTOCOL_VERSION when the \
+             change is incompatible and regenerate the snapshot with \
+             `cargo run -p summ-cli -- schema > docs/protocol.schema.json`"
+        );
+    }
+
+    #[test]
+    fn test_schema_covers_protocol_types() {
+        let schema = protocol_schema();
+        assert_eq!(schema["protocol_version"], PROTOCOL_VERSION);
+        for name in ["Request", "Response", "Session", "SessionInfo", "CliStatus", "StopReason"] {
+            assert!(schema["$defs"][name].is_object(), "{} is missing", name);
+        }
+        let variants = serde_json::to_string(&schema["$defs"]["Request"]).unwrap();
+        assert!(variants.contains("\"Timeline\""));
+    }
+}
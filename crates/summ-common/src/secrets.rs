@@ -0,0 +1,165 @@
+// summ-common/src/secrets.rs
+// Named environment secrets in ~/.summ-daemon/secrets.json. The CLI manages
+// the file; the daemon resolves names into a session's environment, so
+// values never cross the socket or land in session metadata.
+use anyhow::Context;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+
+use crate::DaemonError;
+
+/// Mode of the secrets file: only its owner may read or write it
+pub const SECRETS_FILE_MODE: u32 = 0o600;
+
+/// Secret values by name
+pub type Secrets = BTreeMap<String, String>;
+
+/// Check that `name` can be exported as an environment variable
+pub fn validate_secret_name(name: &str) -> Result<(), DaemonError> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(DaemonError::e010(format!(
+            "Invalid secret name '{}': use letters, digits and '_', not starting with a digit",
+            name
+        )))
+    }
+}
+
+/// Read the secrets file; a missing file holds no secrets.
+///
+/// Like ssh with private keys, a file other users can access is refused.
+pub fn load(path: &Path) -> anyhow::Result<Secrets> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Secrets::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let mode = metadata.permissions().mode() & 0o777;
+    if mode & 0o077 != 0 {
+        anyhow::bail!(
+            "{} is accessible by other users (mode {:o}); run `chmod 600 {}`",
+            path.display(),
+            mode,
+            path.display()
+        );
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Replace the secrets file, creating it with `SECRETS_FILE_MODE`
+pub fn save(path: &Path, secrets: &Secrets) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    // A temporary file with a fixed name, so a crash leaves no stray copies
+    let temp = path.with_extension("json.tmp");
+    let _ = fs::remove_file(&temp);
+    let write = || -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(SECRETS_FILE_MODE)
+            .open(&temp)?;
+        file.write_all(serde_json::to_string_pretty(secrets)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    };
+    write().with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Values of the named secrets, in the order given.
+///
+/// All missing names are reported at once, as E010.
+pub fn resolve(path: &Path, names: &[String]) -> Result<Vec<(String, String)>, DaemonError> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    for name in names {
+        validate_secret_name(name)?;
+    }
+    let mut secrets = load(path).map_err(|e| DaemonError::e010(format!("{:#}", e)))?;
+    let missing: Vec<&str> = names
+        .iter()
+        .filter(|name| !secrets.contains_key(*name))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(DaemonError::e010(format!(
+            "Secret not found in {}: {} (add it with `summ secret set NAME`)",
+            path.display(),
+            missing.join(", ")
+        )));
+    }
+    Ok(names
+        .iter()
+        .map(|name| (name.clone(), secrets.remove(name).unwrap_or_default()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_secret_name() {
+        assert!(validate_secret_name("OPENAI_API_KEY").is_ok());
+        assert!(validate_secret_name("_token2").is_ok());
+        for name in ["", "2FA", "API-KEY", "A=B", "KEY NAME"] {
+            let err = validate_secret_name(name).unwrap_err();
+            assert_eq!(err.code.code(), "E010", "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_save_and_resolve() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("secrets.json");
+        assert!(load(&path).unwrap().is_empty());
+
+        let mut secrets = Secrets::new();
+        secrets.insert("OPENAI_API_KEY".to_string(), "sk-test".to_string());
+        secrets.insert("GH_TOKEN".to_string(), "it's \"quoted\"".to_string());
+        save(&path, &secrets).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, SECRETS_FILE_MODE);
+        assert_eq!(load(&path).unwrap(), secrets);
+
+        let names = ["GH_TOKEN".to_string(), "OPENAI_API_KEY".to_string()];
+        assert_eq!(
+            resolve(&path, &names).unwrap(),
+            vec![
+                ("GH_TOKEN".to_string(), "it's \"quoted\"".to_string()),
+                ("OPENAI_API_KEY".to_string(), "sk-test".to_string()),
+            ]
+        );
+
+        let names = ["MISSING".to_string(), "OPENAI_API_KEY".to_string(), "OTHER".to_string()];
+        let err = resolve(&path, &names).unwrap_err();
+        assert_eq!(err.code.code(), "E010");
+        assert!(err.message.contains(": MISSING, OTHER (add it with"), "{}", err.message);
+        assert!(!err.message.contains("sk-test"));
+    }
+
+    #[test]
+    fn test_load_refuses_shared_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("secrets.json");
+        save(&path, &Secrets::new()).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let err = format!("{:#}", load(&path).unwrap_err());
+        assert!(err.contains("accessible by other users (mode 644)"), "{}", err);
+        let err = resolve(&path, &["KEY".to_string()]).unwrap_err();
+        assert_eq!(err.code.code(), "E010");
+    }
+}
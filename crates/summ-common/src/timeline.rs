@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// File in a session's runtime directory where the daemon journals its events
+pub const EVENTS_FILE: &str = "events.jsonl";
+
+/// What happened at a point of a session's timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    /// The session was created
+    Created,
+    /// Status hooks were deployed into the workspace
+    HooksDeployed,
+    /// A hook reported an event (prompt submitted, subagent stop, ...)
+    Hook,
+    /// The session's status changed
+    Status,
+    /// A message was injected
+    Inject,
+    /// A file was copied into the workspace (`summ send-file`)
+    File,
+    /// The CLI started waiting on a prompt
+    Prompt,
+    /// The CLI has been busy on one task past `busy_alert_minutes`
+    BusyAlert,
+    /// Injections were refused for going over the rate limit
+    Throttled,
+    /// The terminal log grew past another milestone
+    Output,
+    /// The workspace was checkpointed
+    Checkpoint,
+    /// The session stopped or failed to start
+    Stopped,
+    /// The session's tmux session was killed outside summ
+    ExternalKill,
+}
+
+/// One event of a session, as journaled by the daemon or derived from its metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TimelineEvent {
+    pub at: DateTime<Utc>,
+    pub kind: TimelineKind,
+    /// Human-readable description, e.g. `idle -> running` or `+10MB output`
+    pub detail: String,
+}
+
+/// An event placed on the timeline, in the response to `Request::Timeline`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TimelineEntry {
+    #[serde(flatten)]
+    pub event: TimelineEvent,
+    /// Seconds since the session was created
+    pub offset_seconds: i64,
+}
+
+/// Merge events from every source into one stream sorted by time, keeping
+/// those at or after `since`. Events at the same instant keep their order.
+pub fn merge(
+    created_at: DateTime<Utc>,
+    mut events: Vec<TimelineEvent>,
+    since: Option<DateTime<Utc>>,
+) -> Vec<TimelineEntry> {
+    events.sort_by_key(|event| event.at);
+    events
+        .into_iter()
+        .filter(|event| since.is_none_or(|since| event.at >= since))
+        .map(|event| TimelineEntry {
+            offset_seconds: (event.at - created_at).num_seconds(),
+            event,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(minute: u32, kind: TimelineKind, detail: &str) -> TimelineEvent {
+        TimelineEvent {
+            at: Utc.with_ymd_and_hms(2026, 10, 16, 14, minute, 0).unwrap(),
+            kind,
+            detail: detail.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_merge_sorts_and_offsets() {
+        let created = event(0, TimelineKind::Created, "claude session created");
+        let events = vec![
+            event(5, TimelineKind::Status, "idle -> stopped"),
+            event(2, TimelineKind::Inject, "Fix the tests"),
+            created.clone(),
+            event(2, TimelineKind::Status, "idle -> running"),
+        ];
+        let timeline = merge(created.at, events, None);
+        let order: Vec<(&str, i64)> = timeline
+            .iter()
+            .map(|entry| (entry.event.detail.as_str(), entry.offset_seconds))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("claude session created", 0),
+                ("Fix the tests", 120),
+                ("idle -> running", 120),
+                ("idle -> stopped", 300),
+            ]
+        );
+
+        let events = timeline.into_iter().map(|entry| entry.event).collect();
+        let since = merge(created.at, events, Some(event(3, TimelineKind::Status, "").at));
+        assert_eq!(since.len(), 1);
+        assert_eq!(since[0].offset_seconds, 300);
+
+        let json = serde_json::to_value(&since[0]).unwrap();
+        assert_eq!(json["kind"], "status");
+        assert_eq!(json["offset_seconds"], 300);
+    }
+}
@@ -0,0 +1,61 @@
+// summ-common/src/tmux.rs
+// The tmux version the daemon needs, checked by the daemon and by `summ init`
+
+/// Oldest tmux the daemon works with
+pub const MIN_TMUX_VERSION: (u32, u32) = (3, 0);
+
+/// Major and minor version from `tmux -V` output: `tmux 3.3a` is (3, 3)
+pub fn parse_tmux_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.split_whitespace().nth(1)?;
+    let mut parts = version.split('.');
+    let digits = |s: &str| -> Option<u32> {
+        s.chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>()
+            .parse()
+            .ok()
+    };
+    let major = digits(parts.next()?)?;
+    let minor = digits(parts.next()?)?;
+    Some((major, minor))
+}
+
+/// The version in `tmux -V` output, when it is at least `MIN_TMUX_VERSION`
+pub fn check_tmux_version(output: &str) -> anyhow::Result<(u32, u32)> {
+    let Some((major, minor)) = parse_tmux_version(output) else {
+        anyhow::bail!("Invalid tmux version output: {}", output.trim());
+    };
+    if (major, minor) < MIN_TMUX_VERSION {
+        anyhow::bail!(
+            "tmux version {}.{} is below minimum required {}.{}",
+            major,
+            minor,
+            MIN_TMUX_VERSION.0,
+            MIN_TMUX_VERSION.1
+        );
+    }
+    Ok((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tmux_version() {
+        assert_eq!(parse_tmux_version("tmux 3.3a"), Some((3, 3)));
+        assert_eq!(parse_tmux_version("tmux 2.9\n"), Some((2, 9)));
+        assert_eq!(parse_tmux_version("garbage"), None);
+        assert_eq!(parse_tmux_version("tmux 3"), None);
+    }
+
+    #[test]
+    fn test_check_tmux_version() {
+        assert_eq!(check_tmux_version("tmux 3.3a").unwrap(), (3, 3));
+        assert_eq!(check_tmux_version("tmux 3.0").unwrap(), (3, 0));
+        let old = check_tmux_version("tmux 2.9").unwrap_err().to_string();
+        assert_eq!(old, "tmux version 2.9 is below minimum required 3.0");
+        let invalid = check_tmux_version("invalid").unwrap_err().to_string();
+        assert_eq!(invalid, "Invalid tmux version output: invalid");
+    }
+}
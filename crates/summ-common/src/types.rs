@@ -1,26 +1,513 @@
 use anyhow::Context;
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::DaemonError;
 
 /// Session status represents the current state of a session
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SessionStatus {
     /// CLI is executing a task
+    #[default]
     Running,
     /// CLI is idle, waiting for new tasks (reported via Hook)
     Idle,
     /// tmux session has exited
     Stopped,
+    /// CLI process group is stopped with SIGSTOP (via `summ pause`)
+    Paused,
+    /// Workspace and tmux session are still being set up (`summ start --background`)
+    Starting,
+    /// A background start failed; the error is kept in `start_error`
+    Failed,
+    /// Waiting for another session to reach a status before starting (`summ start --after`)
+    Pending,
+}
+
+impl SessionStatus {
+    /// Every status, in the order they are listed to users
+    pub const ALL: [SessionStatus; 7] = [
+        SessionStatus::Running,
+        SessionStatus::Idle,
+        SessionStatus::Stopped,
+        SessionStatus::Paused,
+        SessionStatus::Starting,
+        SessionStatus::Failed,
+        SessionStatus::Pending,
+    ];
+
+    /// Lowercase name of the status, as it is sent and accepted
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStatus::Running => "running",
+            SessionStatus::Idle => "idle",
+            SessionStatus::Stopped => "stopped",
+            SessionStatus::Paused => "paused",
+            SessionStatus::Starting => "starting",
+            SessionStatus::Failed => "failed",
+            SessionStatus::Pending => "pending",
+        }
+    }
+}
+
+impl std::fmt::Display for SessionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses a status name given by a user, ignoring case and surrounding
+/// whitespace. The error lists the valid names, with a suggestion for a typo.
+impl std::str::FromStr for SessionStatus {
+    type Err = DaemonError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let wanted = name.trim().to_ascii_lowercase();
+        if let Some(status) = Self::ALL.iter().find(|status| status.as_str() == wanted) {
+            return Ok(status.clone());
+        }
+        let suggestion = Self::ALL
+            .iter()
+            .map(|status| (edit_distance(&wanted, status.as_str()), status))
+            .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, status)| format!(" (did you mean {}?)", status))
+            .unwrap_or_default();
+        let valid: Vec<&str> = Self::ALL.iter().map(SessionStatus::as_str).collect();
+        Err(DaemonError::e010(format!(
+            "Invalid status {:?}{}; valid statuses: {}",
+            name,
+            suggestion,
+            valid.join(", ")
+        )))
+    }
+}
+
+/// Largest number of edits between a typo and the status it suggests
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Levenshtein distance between two short ASCII names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.as_bytes().iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Whether a session's deployed hooks are reporting, as seen by the daemon
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HookHealth {
+    /// The hooks have written `runtime/status.json`
+    Ok,
+    /// No hook report arrived within the check window
+    Missing,
+    /// No hook report arrived and the terminal shows hook errors
+    Broken,
+}
+
+/// How a session's status is detected
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookKind {
+    /// Claude Code hooks deployed into the workspace
+    Claude,
+    /// No hooks; the status comes from tmux alone
+    #[default]
+    None,
+    /// No hooks; prompts are detected from the pane (aider)
+    AiderHeuristic,
+}
+
+/// Whether a session's hooks look like they are working
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HookState {
+    /// The CLI has no hooks
+    #[default]
+    None,
+    /// Hooks are deployed and have not reported yet
+    Deployed,
+    /// Hooks are reporting
+    Ok,
+    /// Hooks are deployed but have not reported for `hook_silent_seconds`
+    /// while the CLI was busy (or at all since the session started)
+    Silent,
+}
+
+/// Hook deployment and the latest hook report of a session
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct HookInfo {
+    pub kind: HookKind,
+    #[serde(default)]
+    pub state: HookState,
+    /// When the hooks were written into the workspace (absent for CLIs
+    /// without hooks and sessions started before this was recorded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deployed_at: Option<DateTime<Utc>>,
+    /// Timestamp of the latest hook report
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_event_at: Option<DateTime<Utc>>,
+    /// Hook event of the latest report (e.g. `stop`, `user-prompt-submit`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_event: Option<String>,
+}
+
+/// Why a session ended up stopped
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StopReason {
+    /// Stopped through `summ stop` / `summ stop --all`
+    UserRequested {
+        /// How the CLI was ended, when recorded
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        method: Option<StopMethod>,
+    },
+    /// The CLI exited on its own (reported by its hooks)
+    ProcessExited {
+        /// Exit code, when known
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<i32>,
+    },
+    /// The tmux session disappeared while the daemon was watching (recorded
+    /// by daemons that could not tell an exit from a kill)
+    TmuxGone,
+    /// The tmux session was killed from outside summ (e.g. `tmux kill-session`)
+    /// while the CLI was still running
+    ExternalKill,
+    /// Stopped after being idle for too long
+    IdleTimeout,
+    /// Stopped because its deadline passed
+    Deadline,
+    /// Stopped by daemon cleanup
+    DaemonCleanup,
+    /// Found stopped without knowing why (e.g. lost while the daemon was down)
+    Unknown,
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StopReason::UserRequested { method: None } => write!(f, "requested by user"),
+            StopReason::UserRequested { method: Some(method) } => {
+                let method = match method {
+                    StopMethod::QuitCommand => "quit command",
+                    StopMethod::QuitThenKill => "quit command, then killed",
+                    StopMethod::KillSession => "tmux session killed",
+                };
+                write!(f, "requested by user ({method})")
+            }
+            StopReason::ProcessExited { code: None } => write!(f, "process exited"),
+            StopReason::ProcessExited { code: Some(code) } => {
+                write!(f, "process exited (code {code})")
+            }
+            StopReason::TmuxGone => write!(f, "tmux session gone"),
+            StopReason::ExternalKill => write!(f, "killed outside summ"),
+            StopReason::IdleTimeout => write!(f, "idle timeout"),
+            StopReason::Deadline => write!(f, "deadline passed"),
+            StopReason::DaemonCleanup => write!(f, "daemon cleanup"),
+            StopReason::Unknown => write!(f, "unknown reason"),
+        }
+    }
+}
+
+/// The session and status a pending start waits for (`summ start --after`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct StartTrigger {
+    pub session_id: String,
+    pub status: SessionStatus,
+}
+
+impl std::fmt::Display for StartTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.session_id, self.status)
+    }
+}
+
+/// How a requested stop ended a session's CLI
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StopMethod {
+    /// The CLI exited after its quit command was typed
+    QuitCommand,
+    /// The CLI was still running when the grace period after its quit command
+    /// ran out, so its tmux session was killed
+    QuitThenKill,
+    /// The tmux session was killed (the CLI gets a hangup) without a quit command
+    KillSession,
+}
+
+/// How `--git-checkpoint` captures a session's workspace when the session stops
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointMode {
+    /// Commit the workspace to the `summ/<session_id>` branch
+    Commit,
+    /// Write the workspace's changes to `runtime/final.patch`
+    Patch,
+}
+
+impl std::str::FromStr for CheckpointMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "commit" => Ok(Self::Commit),
+            "patch" => Ok(Self::Patch),
+            other => Err(format!("unknown checkpoint mode {:?} (expected commit or patch)", other)),
+        }
+    }
+}
+
+/// Outcome of the git checkpoint taken when a session stopped
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct Checkpoint {
+    pub mode: CheckpointMode,
+    /// When the checkpoint was attempted
+    pub created_at: DateTime<Utc>,
+    /// Checkpoint commit (commit mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Branch pointing at the commit (commit mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Patch against the workspace's HEAD (patch mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch_path: Option<PathBuf>,
+    /// Why no checkpoint could be taken; the stop itself is unaffected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl Checkpoint {
+    /// One-line summary, e.g. `commit 1a2b3c4d5e6f on summ/session_abc`
+    pub fn summary(&self) -> String {
+        if let Some(warning) = &self.warning {
+            return format!("failed: {}", warning);
+        }
+        match (&self.commit, &self.branch, &self.patch_path) {
+            (Some(commit), Some(branch), _) => {
+                format!("commit {} on {}", &commit[..commit.len().min(12)], branch)
+            }
+            (_, _, Some(path)) => format!("patch {}", path.display()),
+            _ => "none".to_string(),
+        }
+    }
+}
+
+/// Current version of the meta.json schema written by this release
+pub const SESSION_SCHEMA_VERSION: u32 = 3;
+
+fn default_schema_version() -> u32 {
+    1
+}
+
+fn default_created_by() -> String {
+    "unknown".to_string()
+}
+
+/// Longest session ID accepted from clients or found on disk
+pub const MAX_SESSION_ID_LEN: usize = 64;
+
+/// Longest namespace name
+pub const MAX_NAMESPACE_LEN: usize = 32;
+
+/// Why `name` cannot be used as a path component, if it cannot: only ASCII
+/// letters, digits, `_` and `-` are allowed, up to `max_len` bytes
+fn name_problem(name: &str, max_len: usize) -> Option<&'static str> {
+    if name.is_empty() {
+        Some("must not be empty")
+    } else if name.len() > max_len {
+        Some("is too long")
+    } else if !name
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+    {
+        Some("may only contain letters, digits, '_' and '-'")
+    } else {
+        None
+    }
+}
+
+/// Check that a session ID is safe to use as a path component.
+///
+/// Only ASCII letters, digits, `_` and `-` are allowed, so separators, dot
+/// segments, and unicode lookalikes of either can never reach a path join.
+pub fn validate_session_id(session_id: &str) -> Result<(), DaemonError> {
+    let Some(problem) = name_problem(session_id, MAX_SESSION_ID_LEN) else {
+        return Ok(());
+    };
+    let shown: String = session_id.chars().take(MAX_SESSION_ID_LEN).collect();
+    Err(DaemonError::e010(format!(
+        "Invalid session ID {:?}: {}",
+        shown, problem
+    )))
+}
+
+/// Check a session's working subdirectory (`summ start --cwd`) and return it
+/// without `.` segments.
+///
+/// The subpath is relative to the workspace and may not climb out of it, so
+/// absolute paths and `..` segments are refused. Whether it exists (and
+/// whether symlinks keep it inside the workspace) is only known after init.
+pub fn validate_workdir_subpath(subpath: &Path) -> Result<PathBuf, DaemonError> {
+    normalize_below_workspace(subpath, "working subdirectory", "directory")
+}
+
+/// Check the destination of a file sent into a workspace (`summ send-file
+/// --dest`) and return it without `.` segments, by the same rules as
+/// [`validate_workdir_subpath`]
+pub fn validate_workspace_file(dest: &Path) -> Result<PathBuf, DaemonError> {
+    normalize_below_workspace(dest, "destination", "file")
+}
+
+/// `path` without `.` segments, if it is relative and stays below the workspace
+fn normalize_below_workspace(path: &Path, what: &str, names: &str) -> Result<PathBuf, DaemonError> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(part) => normalized.push(part),
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                return Err(DaemonError::e010(format!(
+                    "Invalid {} {:?}: `..` would leave the workspace",
+                    what, path
+                )));
+            }
+            std::path::Component::RootDir | std::path::Component::Prefix(_) => {
+                return Err(DaemonError::e010(format!(
+                    "Invalid {} {:?}: must be relative to the workspace",
+                    what, path
+                )));
+            }
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        return Err(DaemonError::e010(format!(
+            "Invalid {} {:?}: names no {} below the workspace",
+            what, path, names
+        )));
+    }
+    Ok(normalized)
+}
+
+/// Name of the namespace sessions belong to unless another one is chosen
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// An isolated group of sessions (`summ --namespace work ...`).
+///
+/// Sessions of the default namespace live directly in the sessions directory
+/// and their tmux sessions are named `summ-<id>`, as before namespaces
+/// existed; other namespaces use `<sessions_dir>/namespaces/<name>/` and
+/// `summ-<name>-<id>`. Names follow the session ID rules, so they are safe
+/// in paths and tmux names.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct Namespace(String);
+
+impl Namespace {
+    /// A namespace from a name given by a client or found on disk
+    pub fn new(name: &str) -> Result<Self, DaemonError> {
+        match name_problem(name, MAX_NAMESPACE_LEN) {
+            None => Ok(Self(name.to_string())),
+            Some(problem) => {
+                let shown: String = name.chars().take(MAX_NAMESPACE_LEN).collect();
+                Err(DaemonError::e010(format!(
+                    "Invalid namespace {:?}: {}",
+                    shown, problem
+                )))
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.0 == DEFAULT_NAMESPACE
+    }
+
+    /// Name of the tmux session of `session_id` in this namespace
+    pub fn tmux_session(&self, session_id: &str) -> String {
+        if self.is_default() {
+            format!("summ-{}", session_id)
+        } else {
+            format!("summ-{}-{}", self.0, session_id)
+        }
+    }
+
+    /// Directory holding this namespace's sessions under `sessions_dir`
+    pub fn sessions_dir(&self, sessions_dir: &Path) -> PathBuf {
+        if self.is_default() {
+            sessions_dir.to_path_buf()
+        } else {
+            sessions_dir.join(NAMESPACES_DIR).join(&self.0)
+        }
+    }
+}
+
+/// Directory under the sessions directory holding the other namespaces
+pub const NAMESPACES_DIR: &str = "namespaces";
+
+impl Default for Namespace {
+    fn default() -> Self {
+        Self(DEFAULT_NAMESPACE.to_string())
+    }
+}
+
+impl std::fmt::Display for Namespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for Namespace {
+    type Err = DaemonError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::new(name)
+    }
+}
+
+impl TryFrom<String> for Namespace {
+    type Error = DaemonError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::new(&name)
+    }
+}
+
+impl From<Namespace> for String {
+    fn from(namespace: Namespace) -> Self {
+        namespace.0
+    }
 }
 
 /// Session metadata stored in meta.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Session {
+    /// Version of the meta.json schema this record was written with
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
     /// Unique session identifier
     pub session_id: String,
-    /// tmux session name (summ-{session_id})
+    /// Namespace the session belongs to (`default` for older sessions)
+    #[serde(default)]
+    pub namespace: Namespace,
+    /// tmux session name (`summ-{session_id}`, or `summ-{namespace}-{session_id}`)
     pub tmux_session: String,
     /// User-readable name
     pub name: String,
@@ -28,6 +515,11 @@ pub struct Session {
     pub cli: String,
     /// Working directory
     pub workdir: PathBuf,
+    /// Terminal log file, recorded when the session is created so that a
+    /// later change of `logs_dir` does not lose it; empty in metadata written
+    /// before it was recorded, until recovery fills it in
+    #[serde(default)]
+    pub log_path: PathBuf,
     /// Initialization source path
     pub init_source: PathBuf,
     /// Current session status
@@ -38,22 +530,416 @@ pub struct Session {
     pub created_at: DateTime<Utc>,
     /// Last activity timestamp
     pub last_activity: DateTime<Utc>,
+    /// Whether the CLI process group was paused via `summ pause`
+    #[serde(default)]
+    pub paused: bool,
+    /// The CLI rang the terminal bell since the user last attached or injected
+    #[serde(default)]
+    pub needs_attention: bool,
+    /// The permission or confirmation prompt the CLI is waiting on, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting_for_input: Option<InputPrompt>,
+    /// What the CLI is working on, from its latest hook report (cleared when idle)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_task: Option<String>,
+    /// Foreground command of the session's active pane, from the monitor's
+    /// last tmux snapshot (kept in memory only)
+    #[serde(skip)]
+    pub current_command: Option<String>,
+    /// Login name of the user whose client started the session
+    #[serde(default = "default_created_by")]
+    pub created_by: String,
+    /// uid of the user whose client started the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by_uid: Option<u32>,
+    /// Protected sessions are skipped by stop sweeps unless explicitly overridden
+    #[serde(default)]
+    pub protected: bool,
+    /// Why the session stopped (set when it is marked stopped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<StopReason>,
+    /// How long each phase of `summ start` took (absent for older sessions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creation_timings: Option<CreationTimings>,
+    /// Why a background start failed (set with the `failed` status)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_error: Option<String>,
+    /// Result of the post-start hook check (absent until checked or for CLIs without hooks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_health: Option<HookHealth>,
+    /// Hook deployment and reporting, kept up to date by the monitor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<HookInfo>,
+    /// Free-text note set with `summ note`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Input tokens (including cache reads and writes) counted from the CLI transcript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_in: Option<u64>,
+    /// Output tokens counted from the CLI transcript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_out: Option<u64>,
+    /// Estimated cost in USD, when every model used has a price in `model_prices`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cost: Option<f64>,
+    /// What the workspace was initialized from (also in `runtime/init_manifest.json`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub init_manifest: Option<InitManifest>,
+    /// Checkpoint the workspace with git when the session stops (`--git-checkpoint`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_checkpoint: Option<CheckpointMode>,
+    /// The checkpoint taken when the session stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<Checkpoint>,
+    /// Names of the secrets exported into the session (never their values)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<String>,
+    /// The trigger a pending start waits for (kept after it fires)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_after: Option<StartTrigger>,
+    /// Directory below the workspace the CLI starts in (`--cwd`); the
+    /// workspace root when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir_subpath: Option<PathBuf>,
+    /// Deploy the CLI's hooks into `workdir_subpath` instead of the workspace root
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hooks_in_subpath: bool,
+    /// Throwaway session (`summ scratch`): its directory and log are removed
+    /// as soon as it stops or fails, without waiting for retention
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ephemeral: bool,
+    /// Stop the session once its CLI has been idle this many seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_timeout_seconds: Option<u64>,
+    /// When the CLI went from idle to busy on its current task; cleared once
+    /// it is idle again or the session ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub busy_since: Option<DateTime<Utc>>,
+    /// Whether the busy alert fired for the current `busy_since`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub busy_alerted: bool,
+    /// Minutes of busy work after which the session alerts, overriding
+    /// `busy_alert_minutes` in config; 0 turns the alert off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub busy_alert_minutes: Option<u64>,
+    /// Injections per minute the session accepts, overriding
+    /// `inject_rate_limit` in config; 0 removes the limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inject_rate_limit: Option<u32>,
+    /// When an injection was first refused for going over the rate limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throttled_at: Option<DateTime<Utc>>,
+    /// The messages injected into the session are not kept in its
+    /// `runtime/injections.jsonl` history
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_inject_history: bool,
+    /// Session whose workspace this one works in (`--share-workspace`): its
+    /// `workspace` is a symlink to that session's, and it deploys no hooks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observer_of: Option<String>,
+}
+
+impl Session {
+    /// Seconds the CLI has been busy on its current task, if it is
+    pub fn busy_seconds(&self) -> Option<u64> {
+        self.busy_since
+            .map(|since| (Utc::now() - since).num_seconds().max(0) as u64)
+    }
+
+    /// Init source entries left out or renamed, if there were any
+    pub fn workspace_issues(&self) -> Option<usize> {
+        self.init_manifest
+            .as_ref()
+            .map(|manifest| manifest.path_issues.len())
+            .filter(|issues| *issues > 0)
+    }
+}
+
+/// Which signal showed that a CLI is waiting on a prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptSource {
+    /// The pane matched one of the CLI's prompt patterns
+    Pane,
+    /// The CLI's hooks reported a permission request
+    Hook,
+    /// The CLI rang the bell and has no prompt patterns to check
+    Bell,
+}
+
+/// A permission or confirmation prompt a session is waiting on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct InputPrompt {
+    /// When the prompt was first seen
+    pub since: DateTime<Utc>,
+    pub source: PromptSource,
+    /// The prompt as shown in the pane, or the hook's message
+    pub text: String,
+}
+
+/// An answer to the prompt a session is waiting on (`summ answer`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum PromptAnswer {
+    Yes,
+    No,
+    /// Choose a numbered option, counting from 1
+    Option(u32),
+    /// Type this text and press Enter
+    Text(String),
+}
+
+impl std::fmt::Display for PromptAnswer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptAnswer::Yes => write!(f, "yes"),
+            PromptAnswer::No => write!(f, "no"),
+            PromptAnswer::Option(n) => write!(f, "option {}", n),
+            PromptAnswer::Text(text) => write!(f, "text {:?}", text),
+        }
+    }
+}
+
+/// How a CLI's numbered options are chosen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionKeys {
+    /// Type the number, then Enter
+    #[default]
+    Number,
+    /// Press Down from the first option to the chosen one, then Enter
+    Arrows,
+}
+
+/// Keys `summ answer` sends to one CLI, as tmux key names (see `summ send-keys`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AnswerKeys {
+    /// Keys for `yes` (default: `y`, `Enter`)
+    pub yes: Vec<String>,
+    /// Keys for `no` (default: `n`, `Enter`)
+    pub no: Vec<String>,
+    /// How `--option N` is chosen (default: `number`)
+    pub options: OptionKeys,
+}
+
+impl Default for AnswerKeys {
+    fn default() -> Self {
+        Self {
+            yes: vec!["y".to_string(), "Enter".to_string()],
+            no: vec!["n".to_string(), "Enter".to_string()],
+            options: OptionKeys::Number,
+        }
+    }
+}
+
+impl AnswerKeys {
+    /// Keys for an answer; `None` for text, which is typed literally
+    pub fn keys_for(&self, answer: &PromptAnswer) -> Option<Vec<String>> {
+        let enter = "Enter".to_string();
+        match answer {
+            PromptAnswer::Yes => Some(self.yes.clone()),
+            PromptAnswer::No => Some(self.no.clone()),
+            PromptAnswer::Option(n) => Some(match self.options {
+                OptionKeys::Number => n.to_string().chars().map(String::from).chain([enter]).collect(),
+                OptionKeys::Arrows => std::iter::repeat_n("Down".to_string(), n.saturating_sub(1) as usize)
+                    .chain([enter])
+                    .collect(),
+            }),
+            PromptAnswer::Text(_) => None,
+        }
+    }
+}
+
+/// Provenance of a session workspace: the init source and what it contained
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct InitManifest {
+    /// Resolved init source path
+    pub source: PathBuf,
+    /// Kind of source: `directory`, `zip`, `tar.gz`, or `empty` (no source)
+    pub kind: String,
+    /// sha256 of the archive, or of a directory's files (relative path, size, and
+    /// contents, in path order); absent when hashing was skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Files in the initialized workspace
+    pub file_count: u64,
+    /// Total size of those files in bytes
+    pub total_bytes: u64,
+    /// Entries of the source that did not fit the workspace's filesystem as
+    /// they were, and what was done about them (`init_path_policy`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_issues: Vec<InitPathIssue>,
+}
+
+impl InitManifest {
+    /// One-line summary, e.g. `repo.tar.gz (sha256 ab12cd34ef56…, 1,204 files, 86.0 MB)`
+    pub fn summary(&self) -> String {
+        let name = self
+            .source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.source.display().to_string());
+        let mut details = Vec::new();
+        if let Some(sha256) = &self.sha256 {
+            details.push(format!("sha256 {}…", &sha256[..sha256.len().min(12)]));
+        }
+        details.push(format!("{} files", group_thousands(self.file_count)));
+        let mb = self.total_bytes as f64 / (1024.0 * 1024.0);
+        details.push(if mb >= 1024.0 {
+            format!("{:.1} GB", mb / 1024.0)
+        } else {
+            format!("{:.1} MB", mb)
+        });
+        format!("{} ({})", name, details.join(", "))
+    }
+}
+
+/// What to do with init source entries the workspace cannot hold as they
+/// are: paths differing only by case, or names and paths too long for its
+/// filesystem
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum InitPathPolicy {
+    /// Refuse the start with `E004` listing the entries, leaving no workspace
+    #[default]
+    Fail,
+    /// Leave the entries out and record them on the session
+    WarnAndSkip,
+    /// Write case collisions under a new name (`README~2.md`); entries that
+    /// are too long are left out. Both are recorded on the session.
+    Rename,
+}
+
+/// Why an init source entry did not fit the workspace as it was
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InitPathProblem {
+    /// Its path differs from an earlier entry's only by case
+    CaseCollision,
+    /// A name in it, or the whole path, is longer than the filesystem allows
+    TooLong,
+}
+
+/// An init source entry that did not fit the workspace as it was
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct InitPathIssue {
+    /// Path of the entry in the source. For a directory, everything under it
+    /// went the same way.
+    pub entry: String,
+    pub problem: InitPathProblem,
+    /// The earlier entry a case collision is with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collides_with: Option<String>,
+    /// Where the entry was written instead; absent when it was left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_to: Option<String>,
+}
+
+impl std::fmt::Display for InitPathIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.problem, &self.collides_with) {
+            (InitPathProblem::CaseCollision, Some(other)) => {
+                write!(f, "{} differs only by case from {}", self.entry, other)?
+            }
+            (InitPathProblem::CaseCollision, None) => write!(f, "{} differs only by case from another entry", self.entry)?,
+            (InitPathProblem::TooLong, _) => write!(f, "{} is too long for the workspace's filesystem", self.entry)?,
+        }
+        match &self.renamed_to {
+            Some(renamed) => write!(f, " (written as {})", renamed),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What a session ran under, captured best-effort when it was created
+/// (`runtime/environment.json`); values pass through secret redaction
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct EnvironmentSnapshot {
+    pub captured_at: DateTime<Utc>,
+    /// Version of the daemon that created the session
+    pub daemon_version: String,
+    /// `tmux -V` output, e.g. `tmux 3.4`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmux_version: Option<String>,
+    /// The CLI's executable, as found on the daemon's `PATH`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cli_path: Option<PathBuf>,
+    /// First line of `<cli> --version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cli_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// Operating system, kernel release and architecture, e.g. `linux 6.8.0 x86_64`
+    pub os: String,
+    /// `PATH`, `LANG` and the other captured variables that were set
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// `1204` as `1,204`
+fn group_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+/// Time spent in each phase of session creation, in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CreationTimings {
+    /// Session directories and workspace copy or archive extraction
+    pub init_ms: u64,
+    /// Hook deployment and hook script installation
+    pub hooks_ms: u64,
+    /// tmux session creation and pane PID lookup
+    pub tmux_ms: u64,
+    /// Terminal log setup (tmux pipe-pane)
+    pub logging_ms: u64,
+    /// Whole creation up to the metadata write
+    pub total_ms: u64,
+}
+
+impl CreationTimings {
+    /// One-line breakdown, slowest phase first (e.g. `init 37.2s · tmux 0.4s · hooks 0.1s`)
+    pub fn summary(&self) -> String {
+        let mut phases = [
+            ("init", self.init_ms),
+            ("hooks", self.hooks_ms),
+            ("tmux", self.tmux_ms),
+            ("logging", self.logging_ms),
+        ];
+        phases.sort_by_key(|(_, ms)| std::cmp::Reverse(*ms));
+        phases
+            .iter()
+            .map(|(name, ms)| format!("{} {:.1}s", name, *ms as f64 / 1000.0))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
 }
 
 /// CLI state reported by hooks
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum CliState {
     /// CLI is idle, waiting for input
     Idle,
     /// CLI is processing a task
     Busy,
+    /// CLI is waiting on a permission or confirmation prompt
+    #[serde(rename = "waiting_for_input")]
+    WaitingForInput,
     /// CLI has stopped
     Stopped,
 }
 
 /// CLI status reported via hook mechanism
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CliStatus {
     /// Current CLI state
     pub state: CliState,
@@ -65,10 +951,14 @@ pub struct CliStatus {
     pub event: Option<String>,
     /// Status update timestamp
     pub timestamp: DateTime<Utc>,
+    /// Conversation transcript reported by the hook payload (Claude Code)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript_path: Option<PathBuf>,
 }
 
 /// Daemon configuration loaded from config.json or using defaults
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DaemonConfig {
     /// Directory for session data (default: ~/.summ-daemon/sessions)
     pub sessions_dir: PathBuf,
@@ -78,18 +968,345 @@ pub struct DaemonConfig {
     pub socket_path: PathBuf,
     /// Hours to retain stopped sessions before cleanup (default: 24)
     pub cleanup_retention_hours: u64,
+    /// Hours after their last activity that stopped and failed sessions are
+    /// dropped from the daemon's memory ("archived"); their metadata stays on
+    /// disk and is read again when needed. 0 keeps them (default: 24)
+    pub archive_after_hours: u64,
     /// Prefix for tmux session names (default: "summ-")
     pub tmux_prefix: String,
+    /// Resume paused sessions when the daemon recovers them (default: false)
+    pub resume_paused_on_recover: bool,
+    /// Seconds after creation a session's hooks have to report before its
+    /// `hook_health` is flagged (default: 30)
+    pub hook_check_seconds: u64,
+    /// Seconds without a hook report, while the CLI is busy, after which its
+    /// hooks are shown as `silent`; never shorter than the two minutes after
+    /// which a report is considered stale (default: 1800)
+    pub hook_silent_seconds: u64,
+    /// OTLP trace export (only honoured by daemons built with the `otel` feature)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otel: Option<OtelConfig>,
+    /// Per-model token prices for `estimated_cost`, keyed by model name or prefix
+    /// (e.g. `claude-sonnet-4`); empty disables the estimate
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub model_prices: HashMap<String, ModelPrice>,
+    /// Free space on the sessions filesystem below which the daemon raises a
+    /// low-disk warning (default: 10%)
+    pub disk_warn: DiskThreshold,
+    /// Free space below which Start requests are refused with `E013` (default: never)
+    pub disk_refuse: DiskThreshold,
+    /// Largest init source, in megabytes, a Start request may copy or extract
+    /// without `allow_large_init`; 0 disables the check (default: 2048)
+    pub max_init_size_mb: u64,
+    /// Hash init sources into the session's `init_manifest` (default: true)
+    pub hash_init: bool,
+    /// Directory sources larger than this many megabytes are not hashed; archives
+    /// are always hashed when `hash_init` is on (default: 512)
+    pub hash_init_max_dir_mb: u64,
+    /// Remove group/other access from daemon directories, session directories,
+    /// logs, and the socket (default: true); turn off to share them deliberately
+    pub private_permissions: bool,
+    /// Replace secret-looking values (API keys, tokens, passwords) with a
+    /// placeholder in the daemon's logs and session metadata (default: true)
+    pub redact_secrets: bool,
+    /// Extra regular expressions to redact; a named group `secret` limits the
+    /// replacement to that group, otherwise the whole match is replaced
+    pub redact_patterns: Vec<String>,
+    /// Environment variables recorded in each session's `environment.json`
+    /// besides `PATH`, `LANG`, `LC_ALL`, `SHELL` and `TERM`
+    pub environment_vars: Vec<String>,
+    /// Shell command run when a session rings the bell or starts waiting on a
+    /// prompt, with `SUMM_SESSION_ID`, `SUMM_SESSION_NAME`, `SUMM_CLI`,
+    /// `SUMM_ATTENTION_REASON` and (for prompts) `SUMM_PROMPT` set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attention_command: Option<String>,
+    /// Regular expressions matched against the bottom of a session's pane to
+    /// detect permission prompts, keyed by CLI name (e.g. `claude`); replaces
+    /// the built-in patterns for that CLI, and an empty list turns detection off
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub prompt_patterns: HashMap<String, Vec<String>>,
+    /// Keys `summ answer` sends, keyed by CLI name; replaces the built-in keys
+    /// for that CLI
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub answer_keys: HashMap<String, AnswerKeys>,
+    /// Text typed into a CLI to make it exit when its session is stopped,
+    /// keyed by CLI name; replaces the built-in command for that CLI, and an
+    /// empty string turns it off
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub quit_commands: HashMap<String, String>,
+    /// Seconds a CLI has to exit after its quit command before its tmux
+    /// session is killed (default: 10)
+    pub stop_grace_seconds: u64,
+    /// Commands run on session lifecycle events (`summ daemon test-hook`
+    /// fires one by name)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lifecycle_hooks: Vec<LifecycleHook>,
+    /// Minutes a CLI may stay busy on one task before its session alerts
+    /// (timeline event, `attention_command`, `busy_alert` lifecycle hooks);
+    /// sessions may override it (default: no alert)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub busy_alert_minutes: Option<u64>,
+    /// Injections per minute a session accepts before further ones are
+    /// refused with `E018`; sessions may override it, and 0 removes the
+    /// limit (default: 30)
+    pub inject_rate_limit: u32,
+    /// How new session IDs are generated (default: `uuid-short`)
+    pub id_format: IdFormat,
+    /// What to do with init source entries that differ only by case or are
+    /// too long for the workspace's filesystem (default: `fail`)
+    pub init_path_policy: InitPathPolicy,
+}
+
+/// Format of the session IDs the daemon generates. Every format starts with
+/// `session_` and only uses characters [`validate_session_id`] allows, so the
+/// IDs are safe as directory and tmux session names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdFormat {
+    /// 8 random hex digits, e.g. `session_ab12cd34`
+    #[default]
+    UuidShort,
+    /// A lowercase ULID, which sorts by creation time, e.g.
+    /// `session_01j0g4zr7m6v3q8x2k5n9tbw4d`
+    Ulid,
+    /// UTC creation time and 4 random hex digits, e.g.
+    /// `session_20240601-153012-ab12`
+    Timestamp,
+}
+
+/// A point of a session's life that runs `lifecycle_hooks`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    /// The session was registered (before its workspace is ready)
+    Created,
+    /// The CLI started, or went back to work after being idle
+    Running,
+    /// The CLI finished its turn
+    Idle,
+    /// The session stopped
+    Stopped,
+    /// The session failed to start
+    Failed,
+    /// An ephemeral session was deleted
+    Removed,
+    /// The CLI has been busy on one task for longer than `busy_alert_minutes`
+    BusyAlert,
+    /// An injection was refused for going over the session's rate limit, for
+    /// the first time
+    Throttled,
+}
+
+impl std::fmt::Display for LifecycleEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LifecycleEvent::Created => "created",
+            LifecycleEvent::Running => "running",
+            LifecycleEvent::Idle => "idle",
+            LifecycleEvent::Stopped => "stopped",
+            LifecycleEvent::Failed => "failed",
+            LifecycleEvent::Removed => "removed",
+            LifecycleEvent::BusyAlert => "busy_alert",
+            LifecycleEvent::Throttled => "throttled",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A shell command the daemon runs on lifecycle events, with the session as
+/// JSON on stdin and `SUMM_SESSION_ID`, `SUMM_SESSION_NAME`, `SUMM_EVENT` and
+/// `SUMM_HOOK_NAME` set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifecycleHook {
+    /// Name for logs and `summ daemon test-hook`
+    pub name: String,
+    /// Events that run it; every event when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<LifecycleEvent>,
+    /// Run with `sh -c`
+    pub command: String,
+    /// Set to false to keep the hook configured but not run it
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Seconds the command may run before it is killed (default: 30)
+    #[serde(default = "default_lifecycle_hook_timeout")]
+    pub timeout_seconds: u64,
+}
+
+impl LifecycleHook {
+    /// Whether the hook runs on `event`
+    pub fn runs_on(&self, event: LifecycleEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_lifecycle_hook_timeout() -> u64 {
+    30
+}
+
+/// A free-space floor, as a percentage of the filesystem and/or in megabytes;
+/// free space is below it when it is below either value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskThreshold {
+    /// Percentage of the filesystem that must stay free
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent: Option<f64>,
+    /// Megabytes that must stay free
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mb: Option<u64>,
+}
+
+impl DiskThreshold {
+    /// Whether `free_bytes` of `total_bytes` is below the floor scaled by `factor`
+    pub fn is_below(&self, free_bytes: u64, total_bytes: u64, factor: f64) -> bool {
+        let below_percent = self.percent.is_some_and(|percent| {
+            total_bytes > 0 && (free_bytes as f64 / total_bytes as f64) * 100.0 < percent * factor
+        });
+        let below_mb = self
+            .mb
+            .is_some_and(|mb| (free_bytes as f64) < (mb * 1024 * 1024) as f64 * factor);
+        below_percent || below_mb
+    }
+}
+
+/// Parse a human duration: a whole number followed by `s`, `m`, `h`, `d` or `w`
+/// (e.g. `90m`, `36h`, `7d`)
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration {:?} (expected e.g. \"36h\" or \"7d\")", text))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid duration {:?}: unit must be one of s, m, h, d, w",
+                text
+            ))
+        }
+    };
+    number
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration {:?} is too long", text))
+}
+
+/// Token prices of one model in USD per million tokens (`"model_prices"` in config.json)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// Uncached input tokens
+    pub input_per_mtok: f64,
+    /// Output tokens
+    pub output_per_mtok: f64,
+    /// Tokens written to the prompt cache (default: the input price)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_per_mtok: Option<f64>,
+    /// Tokens read from the prompt cache (default: the input price)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_per_mtok: Option<f64>,
+}
+
+/// OpenTelemetry exporter settings (`"otel"` block of config.json)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OtelConfig {
+    /// OTLP gRPC collector endpoint, e.g. `http://localhost:4317`
+    pub endpoint: String,
+    /// `service.name` attached to exported spans (default: "summ-daemon")
+    #[serde(default = "default_otel_service_name")]
+    pub service_name: String,
+}
+
+fn default_otel_service_name() -> String {
+    "summ-daemon".to_string()
 }
 
 impl DaemonConfig {
+    /// Build a configuration rooted at `base` with default settings
+    pub fn with_base_dir(base: &Path) -> Self {
+        Self {
+            sessions_dir: base.join("sessions"),
+            logs_dir: base.join("logs"),
+            socket_path: base.join("daemon.sock"),
+            cleanup_retention_hours: 24,
+            archive_after_hours: 24,
+            tmux_prefix: "summ-".to_string(),
+            resume_paused_on_recover: false,
+            hook_check_seconds: 30,
+            hook_silent_seconds: 1800,
+            otel: None,
+            model_prices: HashMap::new(),
+            disk_warn: DiskThreshold {
+                percent: Some(10.0),
+                mb: None,
+            },
+            disk_refuse: DiskThreshold::default(),
+            max_init_size_mb: 2048,
+            hash_init: true,
+            hash_init_max_dir_mb: 512,
+            private_permissions: true,
+            redact_secrets: true,
+            redact_patterns: Vec::new(),
+            environment_vars: Vec::new(),
+            attention_command: None,
+            prompt_patterns: HashMap::new(),
+            answer_keys: HashMap::new(),
+            quit_commands: HashMap::new(),
+            stop_grace_seconds: 10,
+            lifecycle_hooks: Vec::new(),
+            busy_alert_minutes: None,
+            inject_rate_limit: 30,
+            id_format: IdFormat::default(),
+            init_path_policy: InitPathPolicy::default(),
+        }
+    }
+
+    /// Base directory for daemon data (~/.summ-daemon)
+    pub fn base_dir() -> PathBuf {
+        dirs::home_dir()
+            .expect("HOME directory not found")
+            .join(".summ-daemon")
+    }
+
+    /// Path to the global config.json
+    pub fn config_path() -> PathBuf {
+        Self::base_dir().join("config.json")
+    }
+
+    /// Path to secrets.json, the store `summ secret` manages
+    pub fn secrets_path() -> PathBuf {
+        Self::base_dir().join("secrets.json")
+    }
+
     /// Load the daemon configuration and ensure all required directories exist
     pub fn load() -> anyhow::Result<Self> {
-        let config = Self::default();
+        let config = Self::load_from(&Self::config_path())?;
         config.ensure_directories()?;
         Ok(config)
     }
 
+    /// Read a config file, falling back to defaults when it does not exist.
+    ///
+    /// Missing keys take their default value; unknown keys are ignored.
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))
+    }
+
     /// Create all required directories for the daemon
     pub fn ensure_directories(&self) -> anyhow::Result<()> {
         std::fs::create_dir_all(&self.sessions_dir)
@@ -99,41 +1316,72 @@ impl DaemonConfig {
         Ok(())
     }
 
+    /// `cleanup_retention_hours` as a duration
+    pub fn cleanup_retention(&self) -> Duration {
+        Duration::from_secs(self.cleanup_retention_hours.saturating_mul(60 * 60))
+    }
+
+    /// Directory holding the sessions directory, where the daemon keeps its
+    /// own files such as `bin/summ-hook` (`~/.summ-daemon` by default)
+    pub fn data_dir(&self) -> PathBuf {
+        self.sessions_dir
+            .parent()
+            .map_or_else(|| self.sessions_dir.join(".."), Path::to_path_buf)
+    }
+
+    /// Marker the daemon writes on a graceful shutdown (next to the sessions directory)
+    pub fn last_shutdown_path(&self) -> PathBuf {
+        self.data_dir().join("last_shutdown.json")
+    }
+
     /// Get the path to a session's meta.json file
-    pub fn session_meta_path(&self, session_id: &str) -> PathBuf {
-        self.sessions_dir.join(session_id).join("meta.json")
+    pub fn session_meta_path(&self, session_id: &str) -> Result<PathBuf, DaemonError> {
+        validate_session_id(session_id)?;
+        Ok(self.sessions_dir.join(session_id).join("meta.json"))
     }
 
     /// Get the path to a session's runtime status.json file
-    pub fn session_status_path(&self, session_id: &str) -> PathBuf {
-        self.sessions_dir.join(session_id).join("runtime").join("status.json")
+    pub fn session_status_path(&self, session_id: &str) -> Result<PathBuf, DaemonError> {
+        validate_session_id(session_id)?;
+        Ok(self.sessions_dir.join(session_id).join("runtime").join("status.json"))
     }
 
     /// Get the path to a session's workspace directory
-    pub fn session_workspace_path(&self, session_id: &str) -> PathBuf {
-        self.sessions_dir.join(session_id).join("workspace")
+    pub fn session_workspace_path(&self, session_id: &str) -> Result<PathBuf, DaemonError> {
+        validate_session_id(session_id)?;
+        Ok(self.sessions_dir.join(session_id).join("workspace"))
+    }
+
+    /// Get the path to a session's terminal log file
+    pub fn session_log_path(&self, session_id: &str) -> Result<PathBuf, DaemonError> {
+        validate_session_id(session_id)?;
+        Ok(self.logs_dir.join(format!("{}.log", session_id)))
+    }
+
+    /// Price for `model`: the longest `model_prices` key the model name starts with
+    pub fn model_price(&self, model: &str) -> Option<&ModelPrice> {
+        self.model_prices
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| price)
     }
 }
 
 impl Default for DaemonConfig {
     fn default() -> Self {
-        let home = dirs::home_dir().expect("HOME directory not found");
-        let base = home.join(".summ-daemon");
-        Self {
-            sessions_dir: base.join("sessions"),
-            logs_dir: base.join("logs"),
-            socket_path: base.join("daemon.sock"),
-            cleanup_retention_hours: 24,
-            tmux_prefix: "summ-".to_string(),
-        }
+        Self::with_base_dir(&Self::base_dir())
     }
 }
 
 /// Session information returned by list commands (subset of Session)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SessionInfo {
     /// Unique session identifier
     pub session_id: String,
+    /// Namespace the session belongs to
+    #[serde(default)]
+    pub namespace: Namespace,
     /// User-readable name
     pub name: String,
     /// CLI command
@@ -144,17 +1392,173 @@ pub struct SessionInfo {
     pub created_at: DateTime<Utc>,
     /// Last activity timestamp
     pub last_activity: DateTime<Utc>,
+    /// Login name of the session creator
+    pub created_by: String,
+    /// Whether the session is protected from stop sweeps
+    #[serde(default)]
+    pub protected: bool,
+    /// Whether the session is removed as soon as it stops (`summ scratch`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ephemeral: bool,
+    /// Session whose workspace this one observes, if it is an observer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observer_of: Option<String>,
+    /// Whether the CLI rang the bell since the user last attached or injected
+    #[serde(default)]
+    pub needs_attention: bool,
+    /// The prompt the CLI is waiting on, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting_for_input: Option<InputPrompt>,
+    /// What the CLI is working on, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_task: Option<String>,
+    /// Seconds the CLI has been busy on its current task, if it is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub busy_seconds: Option<u64>,
+    /// Foreground command of the active pane (null when unknown or not running)
+    #[serde(default)]
+    pub current_command: Option<String>,
+    /// Why the session stopped, if it has
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<StopReason>,
+    /// Why a background start failed, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_error: Option<String>,
+    /// The trigger a pending start waits for, if it was started with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_after: Option<StartTrigger>,
+    /// Result of the post-start hook check, if it ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_health: Option<HookHealth>,
+    /// Hook deployment and reporting, if recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<HookInfo>,
+    /// Free-text note, if one is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Input tokens counted from the transcript, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_in: Option<u64>,
+    /// Output tokens counted from the transcript, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_out: Option<u64>,
+    /// Estimated cost in USD, if it could be priced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cost: Option<f64>,
+    /// Init source entries left out or renamed, if there were any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_issues: Option<usize>,
 }
 
 impl From<Session> for SessionInfo {
     fn from(session: Session) -> Self {
         Self {
+            busy_seconds: session.busy_seconds(),
+            workspace_issues: session.workspace_issues(),
             session_id: session.session_id,
+            namespace: session.namespace,
             name: session.name,
             cli: session.cli,
             status: session.status,
             created_at: session.created_at,
             last_activity: session.last_activity,
+            created_by: session.created_by,
+            protected: session.protected,
+            ephemeral: session.ephemeral,
+            observer_of: session.observer_of,
+            needs_attention: session.needs_attention,
+            waiting_for_input: session.waiting_for_input,
+            current_task: session.current_task,
+            current_command: session.current_command,
+            stop_reason: session.stop_reason,
+            start_error: session.start_error,
+            start_after: session.start_after,
+            hook_health: session.hook_health,
+            hooks: session.hooks,
+            note: session.note,
+            tokens_in: session.tokens_in,
+            tokens_out: session.tokens_out,
+            estimated_cost: session.estimated_cost,
+        }
+    }
+}
+
+/// A `SessionInfo` borrowed from a `Session`, serialized exactly like it, so
+/// the daemon can answer List without cloning every session
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SessionInfoRef<'a> {
+    pub session_id: &'a str,
+    pub namespace: &'a Namespace,
+    pub name: &'a str,
+    pub cli: &'a str,
+    pub status: &'a SessionStatus,
+    pub created_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub created_by: &'a str,
+    pub protected: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub ephemeral: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observer_of: Option<&'a str>,
+    pub needs_attention: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waiting_for_input: Option<&'a InputPrompt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_task: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub busy_seconds: Option<u64>,
+    pub current_command: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<&'a StopReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_error: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_after: Option<&'a StartTrigger>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hook_health: Option<&'a HookHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<&'a HookInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_in: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_out: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_cost: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_issues: Option<usize>,
+}
+
+impl<'a> From<&'a Session> for SessionInfoRef<'a> {
+    fn from(session: &'a Session) -> Self {
+        Self {
+            session_id: &session.session_id,
+            namespace: &session.namespace,
+            name: &session.name,
+            cli: &session.cli,
+            status: &session.status,
+            created_at: session.created_at,
+            last_activity: session.last_activity,
+            created_by: &session.created_by,
+            protected: session.protected,
+            ephemeral: session.ephemeral,
+            observer_of: session.observer_of.as_deref(),
+            needs_attention: session.needs_attention,
+            waiting_for_input: session.waiting_for_input.as_ref(),
+            current_task: session.current_task.as_deref(),
+            busy_seconds: session.busy_seconds(),
+            current_command: session.current_command.as_deref(),
+            stop_reason: session.stop_reason.as_ref(),
+            start_error: session.start_error.as_deref(),
+            start_after: session.start_after.as_ref(),
+            hook_health: session.hook_health.as_ref(),
+            hooks: session.hooks.as_ref(),
+            note: session.note.as_deref(),
+            tokens_in: session.tokens_in,
+            tokens_out: session.tokens_out,
+            estimated_cost: session.estimated_cost,
+            workspace_issues: session.workspace_issues(),
         }
     }
 }
@@ -174,6 +1578,70 @@ mod tests {
 
         let stopped = SessionStatus::Stopped;
         assert_eq!(serde_json::to_string(&stopped).unwrap(), r#""stopped""#);
+
+        let paused = SessionStatus::Paused;
+        assert_eq!(serde_json::to_string(&paused).unwrap(), r#""paused""#);
+
+        let starting = SessionStatus::Starting;
+        assert_eq!(serde_json::to_string(&starting).unwrap(), r#""starting""#);
+
+        let failed = SessionStatus::Failed;
+        assert_eq!(serde_json::to_string(&failed).unwrap(), r#""failed""#);
+    }
+
+    #[test]
+    fn test_session_schema_version_defaults_to_1() {
+        let json = r#"{
+            "session_id": "s1",
+            "tmux_session": "summ-s1",
+            "name": "s1",
+            "cli": "claude",
+            "workdir": "/tmp/s1",
+            "init_source": "/tmp/init",
+            "status": "running",
+            "pid": null,
+            "created_at": "2025-01-01T00:00:00Z",
+            "last_activity": "2025-01-01T00:00:00Z"
+        }"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        assert_eq!(session.schema_version, 1);
+    }
+
+    #[test]
+    fn test_session_paused_defaults_to_false() {
+        let json = r#"{
+            "session_id": "s1",
+            "tmux_session": "summ-s1",
+            "name": "s1",
+            "cli": "claude",
+            "workdir": "/tmp/s1",
+            "init_source": "/tmp/init",
+            "status": "running",
+            "pid": null,
+            "created_at": "2025-01-01T00:00:00Z",
+            "last_activity": "2025-01-01T00:00:00Z"
+        }"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        assert!(!session.paused);
+    }
+
+    #[test]
+    fn test_session_created_by_defaults_to_unknown() {
+        let json = r#"{
+            "session_id": "s1",
+            "tmux_session": "summ-s1",
+            "name": "s1",
+            "cli": "claude",
+            "workdir": "/tmp/s1",
+            "init_source": "/tmp/init",
+            "status": "running",
+            "pid": null,
+            "created_at": "2025-01-01T00:00:00Z",
+            "last_activity": "2025-01-01T00:00:00Z"
+        }"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        assert_eq!(session.created_by, "unknown");
+        assert_eq!(session.created_by_uid, None);
     }
 
     #[test]
@@ -188,6 +1656,46 @@ mod tests {
         assert_eq!(stopped, SessionStatus::Stopped);
     }
 
+    #[test]
+    fn test_creation_timings_summary() {
+        let timings = CreationTimings {
+            init_ms: 37_200,
+            hooks_ms: 120,
+            tmux_ms: 400,
+            logging_ms: 10,
+            total_ms: 37_730,
+        };
+        assert_eq!(
+            timings.summary(),
+            "init 37.2s · tmux 0.4s · hooks 0.1s · logging 0.0s"
+        );
+    }
+
+    #[test]
+    fn test_stop_reason_serialization() {
+        let reason = StopReason::ProcessExited { code: Some(1) };
+        assert_eq!(
+            serde_json::to_string(&reason).unwrap(),
+            r#"{"kind":"process_exited","code":1}"#
+        );
+
+        let reason: StopReason = serde_json::from_str(r#"{"kind":"tmux_gone"}"#).unwrap();
+        assert_eq!(reason, StopReason::TmuxGone);
+        assert_eq!(
+            serde_json::to_string(&StopReason::ExternalKill).unwrap(),
+            r#"{"kind":"external_kill"}"#
+        );
+
+        // Stops recorded before the method was tracked still load
+        let reason: StopReason = serde_json::from_str(r#"{"kind":"user_requested"}"#).unwrap();
+        assert_eq!(reason, StopReason::UserRequested { method: None });
+        let reason = StopReason::UserRequested { method: Some(StopMethod::QuitThenKill) };
+        assert_eq!(
+            serde_json::to_string(&reason).unwrap(),
+            r#"{"kind":"user_requested","method":"quit_then_kill"}"#
+        );
+    }
+
     #[test]
     fn test_cli_state_serialization() {
         let idle = CliState::Idle;
@@ -198,6 +1706,28 @@ mod tests {
 
         let stopped = CliState::Stopped;
         assert_eq!(serde_json::to_string(&stopped).unwrap(), r#""stopped""#);
+
+        let waiting: CliState = serde_json::from_str(r#""waiting_for_input""#).unwrap();
+        assert_eq!(waiting, CliState::WaitingForInput);
+    }
+
+    #[test]
+    fn test_answer_keys() {
+        let keys = AnswerKeys::default();
+        assert_eq!(keys.keys_for(&PromptAnswer::Yes).unwrap(), ["y", "Enter"]);
+        assert_eq!(keys.keys_for(&PromptAnswer::Option(12)).unwrap(), ["1", "2", "Enter"]);
+        assert_eq!(keys.keys_for(&PromptAnswer::Text("fix it".into())), None);
+
+        let arrows: AnswerKeys =
+            serde_json::from_str(r#"{"yes": ["Enter"], "options": "arrows"}"#).unwrap();
+        assert_eq!(arrows.no, ["n", "Enter"]);
+        assert_eq!(arrows.keys_for(&PromptAnswer::Yes).unwrap(), ["Enter"]);
+        assert_eq!(arrows.keys_for(&PromptAnswer::Option(1)).unwrap(), ["Enter"]);
+        assert_eq!(arrows.keys_for(&PromptAnswer::Option(3)).unwrap(), ["Down", "Down", "Enter"]);
+
+        let answer: PromptAnswer = serde_json::from_str(r#"{"kind":"option","value":2}"#).unwrap();
+        assert_eq!(answer, PromptAnswer::Option(2));
+        assert_eq!(answer.to_string(), "option 2");
     }
 
     #[test]
@@ -206,6 +1736,13 @@ mod tests {
         assert!(config.sessions_dir.ends_with(".summ-daemon/sessions"));
         assert!(config.logs_dir.ends_with(".summ-daemon/logs"));
         assert!(config.socket_path.ends_with(".summ-daemon/daemon.sock"));
+        assert!(config.last_shutdown_path().ends_with(".summ-daemon/last_shutdown.json"));
+        assert!(config.data_dir().ends_with(".summ-daemon"));
+        let custom = DaemonConfig {
+            sessions_dir: PathBuf::from("/srv/summ/sessions"),
+            ..config.clone()
+        };
+        assert_eq!(custom.data_dir(), Path::new("/srv/summ"));
         assert_eq!(config.cleanup_retention_hours, 24);
         assert_eq!(config.tmux_prefix, "summ-");
     }
@@ -223,6 +1760,8 @@ mod tests {
             pid: Some(12345),
             created_at: Utc::now(),
             last_activity: Utc::now(),
+            created_by: "alice".to_string(),
+            ..Default::default()
         };
 
         let info: SessionInfo = session.clone().into();
@@ -232,6 +1771,7 @@ mod tests {
         assert_eq!(info.status, session.status);
         assert_eq!(info.created_at, session.created_at);
         assert_eq!(info.last_activity, session.last_activity);
+        assert_eq!(info.created_by, "alice");
     }
 
     #[test]
@@ -241,6 +1781,7 @@ mod tests {
             message: Some("Ready for tasks".to_string()),
             event: Some("SessionStart".to_string()),
             timestamp: Utc::now(),
+            transcript_path: None,
         };
 
         let json = serde_json::to_string(&status).unwrap();
@@ -256,6 +1797,7 @@ mod tests {
             message: None,
             event: None,
             timestamp: Utc::now(),
+            transcript_path: None,
         };
 
         let json = serde_json::to_string(&status).unwrap();
@@ -267,36 +1809,293 @@ mod tests {
     #[test]
     fn test_config_load_creates_directories() {
         let temp_dir = TempDir::new().unwrap();
-        let config = DaemonConfig {
-            sessions_dir: temp_dir.path().join("sessions"),
-            logs_dir: temp_dir.path().join("logs"),
-            socket_path: temp_dir.path().join("daemon.sock"),
-            cleanup_retention_hours: 24,
-            tmux_prefix: "summ-".to_string(),
-        };
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
         assert!(config.ensure_directories().is_ok());
         assert!(config.sessions_dir.exists());
         assert!(config.logs_dir.exists());
     }
 
+    #[test]
+    fn test_config_load_from_partial_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        std::fs::write(
+            &path,
+            r#"{"_comment": "ignored", "cleanup_retention_hours": 48}"#,
+        )
+        .unwrap();
+
+        let config = DaemonConfig::load_from(&path).unwrap();
+        assert_eq!(config.cleanup_retention_hours, 48);
+        assert_eq!(config.tmux_prefix, "summ-");
+        assert_eq!(config.otel, None);
+    }
+
+    #[test]
+    fn test_config_load_otel_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        std::fs::write(&path, r#"{"otel": {"endpoint": "http://collector:4317"}}"#).unwrap();
+
+        let otel = DaemonConfig::load_from(&path).unwrap().otel.unwrap();
+        assert_eq!(otel.endpoint, "http://collector:4317");
+        assert_eq!(otel.service_name, "summ-daemon");
+    }
+
+    #[test]
+    fn test_config_model_price_lookup() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        std::fs::write(
+            &path,
+            r#"{"model_prices": {
+                "claude-sonnet-4": {"input_per_mtok": 3.0, "output_per_mtok": 15.0},
+                "claude-sonnet-4-5": {"input_per_mtok": 3.5, "output_per_mtok": 16.0},
+                "claude-opus-4-1-20250805": {"input_per_mtok": 15.0, "output_per_mtok": 75.0}
+            }}"#,
+        )
+        .unwrap();
+
+        let config = DaemonConfig::load_from(&path).unwrap();
+        let price = |model: &str| config.model_price(model).map(|p| p.input_per_mtok);
+        assert_eq!(price("claude-opus-4-1-20250805"), Some(15.0));
+        assert_eq!(price("claude-sonnet-4-5-20250929"), Some(3.5));
+        assert_eq!(price("claude-sonnet-4-20250514"), Some(3.0));
+        assert_eq!(price("claude-haiku-4-5"), None);
+        assert_eq!(DaemonConfig::with_base_dir(temp_dir.path()).model_price("claude-sonnet-4"), None);
+    }
+
+    #[test]
+    fn test_init_manifest_summary() {
+        let manifest = InitManifest {
+            source: PathBuf::from("/src/repo.tar.gz"),
+            kind: "tar.gz".to_string(),
+            sha256: Some("ab12cd34ef5678900000".to_string()),
+            file_count: 1204,
+            total_bytes: 86 * 1024 * 1024,
+            path_issues: Vec::new(),
+        };
+        assert_eq!(manifest.summary(), "repo.tar.gz (sha256 ab12cd34ef56…, 1,204 files, 86.0 MB)");
+
+        let unhashed = InitManifest { sha256: None, file_count: 7, ..manifest };
+        assert_eq!(unhashed.summary(), "repo.tar.gz (7 files, 86.0 MB)");
+        assert_eq!(group_thousands(1_234_567), "1,234,567");
+        assert_eq!(group_thousands(999), "999");
+    }
+
+    #[test]
+    fn test_checkpoint_summary_and_mode() {
+        let checkpoint = Checkpoint {
+            mode: CheckpointMode::Commit,
+            created_at: Utc::now(),
+            commit: Some("1a2b3c4d5e6f7a8b9c0d".to_string()),
+            branch: Some("summ/session_abc".to_string()),
+            patch_path: None,
+            warning: None,
+        };
+        assert_eq!(checkpoint.summary(), "commit 1a2b3c4d5e6f on summ/session_abc");
+        let patch = Checkpoint {
+            mode: CheckpointMode::Patch,
+            commit: None,
+            branch: None,
+            patch_path: Some(PathBuf::from("/s/runtime/final.patch")),
+            ..checkpoint.clone()
+        };
+        assert_eq!(patch.summary(), "patch /s/runtime/final.patch");
+        let failed = Checkpoint {
+            warning: Some("Workspace is not a git repository".to_string()),
+            ..patch
+        };
+        assert_eq!(failed.summary(), "failed: Workspace is not a git repository");
+
+        assert_eq!("patch".parse::<CheckpointMode>(), Ok(CheckpointMode::Patch));
+        assert!("stash".parse::<CheckpointMode>().is_err());
+        assert_eq!(serde_json::to_string(&CheckpointMode::Commit).unwrap(), r#""commit""#);
+    }
+
+    #[test]
+    fn test_disk_threshold() {
+        const GB: u64 = 1024 * 1024 * 1024;
+        let percent = DiskThreshold { percent: Some(10.0), mb: None };
+        assert!(percent.is_below(9 * GB, 100 * GB, 1.0));
+        assert!(!percent.is_below(10 * GB, 100 * GB, 1.0));
+        assert!(percent.is_below(11 * GB, 100 * GB, 1.25));
+
+        let both = DiskThreshold { percent: Some(1.0), mb: Some(5 * 1024) };
+        assert!(both.is_below(4 * GB, 1000 * GB, 1.0));
+        assert!(!both.is_below(20 * GB, 1000 * GB, 1.0));
+
+        assert!(!DiskThreshold::default().is_below(0, 100 * GB, 1.0));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("36h"), Ok(Duration::from_secs(36 * 3600)));
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(7 * 86400)));
+        assert_eq!(parse_duration(" 90m "), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("2w"), Ok(Duration::from_secs(14 * 86400)));
+        assert_eq!(parse_duration("0s"), Ok(Duration::ZERO));
+        for bad in ["", "h", "36", "7 d", "1.5h", "-1d", "3y", "99999999999999999999w"] {
+            assert!(parse_duration(bad).is_err(), "{:?} parsed", bad);
+        }
+    }
+
+    #[test]
+    fn test_config_load_from_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::load_from(&temp_dir.path().join("config.json")).unwrap();
+        assert_eq!(config.cleanup_retention_hours, 24);
+    }
+
+    #[test]
+    fn test_validate_session_id() {
+        for id in ["session_3f9a1c2b", "test001", "my-session", &"a".repeat(MAX_SESSION_ID_LEN)] {
+            assert!(validate_session_id(id).is_ok(), "{}", id);
+        }
+
+        let rejected = [
+            "",
+            ".",
+            "..",
+            "../../../etc",
+            "..\\..\\windows",
+            "/etc/passwd",
+            "session/../../x",
+            "session_abc/",
+            "session.abc",
+            "session abc",
+            "session\0abc",
+            "session\nabc",
+            "~root",
+            // Unicode lookalikes of '/', '.', and '\\'
+            "\u{2215}etc",
+            "\u{ff0f}etc",
+            "\u{2024}\u{2024}",
+            "\u{ff0e}\u{ff0e}",
+            "\u{2216}x",
+            "sessiоn_abc", // Cyrillic 'о'
+            "session_abc\u{200b}",
+        ];
+        for id in rejected {
+            let error = validate_session_id(id).unwrap_err();
+            assert_eq!(error.code.code(), "E010", "{:?}", id);
+        }
+        let error = validate_session_id(&"a".repeat(MAX_SESSION_ID_LEN + 1)).unwrap_err();
+        assert!(error.message.contains("too long"));
+        assert!(DaemonConfig::default().session_meta_path("../../etc").is_err());
+        assert!(DaemonConfig::default().session_log_path("../x").is_err());
+    }
+
+    #[test]
+    fn test_validate_workdir_subpath() {
+        assert_eq!(
+            validate_workdir_subpath(Path::new("services/api")).unwrap(),
+            PathBuf::from("services/api")
+        );
+        assert_eq!(
+            validate_workdir_subpath(Path::new("./services/./api/")).unwrap(),
+            PathBuf::from("services/api")
+        );
+        for rejected in ["", ".", "./", "/srv/api", "../api", "services/../../etc", "services/.."] {
+            let error = validate_workdir_subpath(Path::new(rejected)).unwrap_err();
+            assert_eq!(error.code.code(), "E010", "{:?}", rejected);
+        }
+    }
+
+    #[test]
+    fn test_validate_workspace_file() {
+        assert_eq!(
+            validate_workspace_file(Path::new("./docs/spec.md")).unwrap(),
+            PathBuf::from("docs/spec.md")
+        );
+        let error = validate_workspace_file(Path::new("../../etc/passwd")).unwrap_err();
+        assert_eq!(error.message, "Invalid destination \"../../etc/passwd\": `..` would leave the workspace");
+        let error = validate_workspace_file(Path::new(".")).unwrap_err();
+        assert_eq!(error.message, "Invalid destination \".\": names no file below the workspace");
+    }
+
+    #[test]
+    fn test_session_status_from_str() {
+        for status in SessionStatus::ALL {
+            assert_eq!(status.as_str().parse::<SessionStatus>().unwrap(), status);
+            assert_eq!(status.as_str().to_uppercase().parse::<SessionStatus>().unwrap(), status);
+            // The names parsed are the names sent
+            assert_eq!(serde_json::to_value(&status).unwrap(), status.as_str());
+        }
+        assert_eq!(" Idle ".parse::<SessionStatus>().unwrap(), SessionStatus::Idle);
+
+        let error = "runing".parse::<SessionStatus>().unwrap_err();
+        assert_eq!(error.code.code(), "E010");
+        assert_eq!(
+            error.message,
+            "Invalid status \"runing\" (did you mean running?); \
+             valid statuses: running, idle, stopped, paused, starting, failed, pending"
+        );
+        assert!("stoped".parse::<SessionStatus>().unwrap_err().message.contains("did you mean stopped?"));
+        let error = "busy".parse::<SessionStatus>().unwrap_err();
+        assert!(!error.message.contains("did you mean"), "{}", error.message);
+        assert!("".parse::<SessionStatus>().is_err());
+    }
+
+    #[test]
+    fn test_namespace() {
+        let default = Namespace::default();
+        assert!(default.is_default());
+        assert_eq!(default.tmux_session("session_abc"), "summ-session_abc");
+        assert_eq!(default.sessions_dir(Path::new("/s")), Path::new("/s"));
+
+        let work: Namespace = "work".parse().unwrap();
+        assert_eq!(work.tmux_session("session_abc"), "summ-work-session_abc");
+        assert_eq!(work.sessions_dir(Path::new("/s")), Path::new("/s/namespaces/work"));
+        assert_eq!(serde_json::to_value(&work).unwrap(), "work");
+
+        for name in ["", "..", "a/b", "work space", &"a".repeat(MAX_NAMESPACE_LEN + 1)] {
+            assert_eq!(Namespace::new(name).unwrap_err().code.code(), "E010", "{:?}", name);
+        }
+        assert!(serde_json::from_value::<Namespace>(serde_json::json!("../x")).is_err());
+
+        // Metadata written before namespaces existed
+        let session: Session = serde_json::from_value(serde_json::json!({
+            "session_id": "test001",
+            "tmux_session": "summ-test001",
+            "name": "test",
+            "cli": "claude",
+            "workdir": "/tmp/test001",
+            "init_source": "/tmp/init",
+            "status": "running",
+            "pid": null,
+            "created_at": "2025-01-01T00:00:00Z",
+            "last_activity": "2025-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert!(session.namespace.is_default());
+    }
+
     #[test]
     fn test_session_meta_path() {
         let config = DaemonConfig::default();
-        let path = config.session_meta_path("test001");
+        let path = config.session_meta_path("test001").unwrap();
         assert!(path.ends_with("sessions/test001/meta.json"));
     }
 
     #[test]
     fn test_session_status_path() {
         let config = DaemonConfig::default();
-        let path = config.session_status_path("test001");
+        let path = config.session_status_path("test001").unwrap();
         assert!(path.ends_with("sessions/test001/runtime/status.json"));
     }
 
+    #[test]
+    fn test_session_log_path() {
+        let config = DaemonConfig::default();
+        let path = config.session_log_path("test001").unwrap();
+        assert!(path.ends_with(".summ-daemon/logs/test001.log"));
+    }
+
     #[test]
     fn test_session_workspace_path() {
         let config = DaemonConfig::default();
-        let path = config.session_workspace_path("test001");
+        let path = config.session_workspace_path("test001").unwrap();
         assert!(path.ends_with("sessions/test001/workspace"));
     }
 }
@@ -0,0 +1,21 @@
+// summ-common/src/user.rs
+// The local user the current process runs as
+
+/// Real uid of the current process, as the daemon records it for sessions
+/// the user creates (`created_by_uid`)
+pub fn current_uid() -> u32 {
+    // SAFETY: getuid(2) takes no arguments and always succeeds
+    unsafe { libc::getuid() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_uid_matches_the_process_owner() {
+        use std::os::unix::fs::MetadataExt;
+        let file = tempfile::NamedTempFile::new().unwrap();
+        assert_eq!(current_uid(), file.as_file().metadata().unwrap().uid());
+    }
+}
@@ -0,0 +1,126 @@
+// summ-common/src/wait.rs
+// When a wait for session statuses is over: shared by `summ wait` for one
+// session and the daemon's WaitMany
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::SessionStatus;
+
+/// Whether every session or the first one must reach the target
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitMode {
+    #[default]
+    All,
+    Any,
+}
+
+/// What a polled status means for a session's wait
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// A target status was reached
+    Done,
+    /// Not there yet; poll again
+    Pending,
+    /// The session can no longer reach a target status
+    Unreachable,
+}
+
+/// How a wait on several sessions ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitOutcome {
+    /// The condition holds
+    Satisfied,
+    /// The condition can no longer hold
+    Unreachable,
+    /// The timeout passed first
+    TimedOut,
+}
+
+/// Progress of a session in `current` status toward `target`
+pub fn progress(current: &SessionStatus, target: &SessionStatus) -> Progress {
+    if current == target {
+        return Progress::Done;
+    }
+    match (current, target) {
+        (SessionStatus::Starting | SessionStatus::Pending, _) => Progress::Pending,
+        // A live session may still go idle, busy, paused, or stop
+        (
+            SessionStatus::Running | SessionStatus::Idle | SessionStatus::Paused,
+            SessionStatus::Running | SessionStatus::Idle | SessionStatus::Paused | SessionStatus::Stopped,
+        ) => Progress::Pending,
+        _ => Progress::Unreachable,
+    }
+}
+
+/// Progress toward any of `targets`. A session that no longer exists
+/// (`None`) can never get there.
+pub fn progress_any(current: Option<&SessionStatus>, targets: &[SessionStatus]) -> Progress {
+    let Some(current) = current else {
+        return Progress::Unreachable;
+    };
+    let progress = targets.iter().map(|target| progress(current, target));
+    if progress.clone().any(|p| p == Progress::Done) {
+        Progress::Done
+    } else if progress.clone().any(|p| p == Progress::Pending) {
+        Progress::Pending
+    } else {
+        Progress::Unreachable
+    }
+}
+
+/// The outcome once the sessions have made `progress`, or `None` to keep waiting
+pub fn settle(mode: WaitMode, progress: &[Progress]) -> Option<WaitOutcome> {
+    let done = |p: &Progress| *p == Progress::Done;
+    let unreachable = |p: &Progress| *p == Progress::Unreachable;
+    match mode {
+        WaitMode::All if progress.iter().all(done) => Some(WaitOutcome::Satisfied),
+        WaitMode::All if progress.iter().any(unreachable) => Some(WaitOutcome::Unreachable),
+        WaitMode::Any if progress.iter().any(done) => Some(WaitOutcome::Satisfied),
+        WaitMode::Any if progress.iter().all(unreachable) => Some(WaitOutcome::Unreachable),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        use SessionStatus::*;
+
+        assert_eq!(progress(&Running, &Running), Progress::Done);
+        assert_eq!(progress(&Starting, &Running), Progress::Pending);
+        assert_eq!(progress(&Idle, &Running), Progress::Pending);
+        assert_eq!(progress(&Running, &Stopped), Progress::Pending);
+        assert_eq!(progress(&Failed, &Running), Progress::Unreachable);
+        assert_eq!(progress(&Stopped, &Running), Progress::Unreachable);
+        assert_eq!(progress(&Starting, &Failed), Progress::Pending);
+        assert_eq!(progress(&Failed, &Failed), Progress::Done);
+        assert_eq!(progress(&Pending, &Idle), Progress::Pending);
+    }
+
+    #[test]
+    fn test_progress_any() {
+        use SessionStatus::*;
+
+        assert_eq!(progress_any(Some(&Failed), &[Idle, Failed]), Progress::Done);
+        assert_eq!(progress_any(Some(&Running), &[Idle, Failed]), Progress::Pending);
+        assert_eq!(progress_any(Some(&Stopped), &[Idle, Failed]), Progress::Unreachable);
+        assert_eq!(progress_any(None, &[Stopped]), Progress::Unreachable);
+    }
+
+    #[test]
+    fn test_settle() {
+        use Progress::*;
+
+        assert_eq!(settle(WaitMode::All, &[Done, Done]), Some(WaitOutcome::Satisfied));
+        assert_eq!(settle(WaitMode::All, &[Done, Pending]), None);
+        assert_eq!(settle(WaitMode::All, &[Pending, Unreachable]), Some(WaitOutcome::Unreachable));
+        assert_eq!(settle(WaitMode::Any, &[Pending, Done]), Some(WaitOutcome::Satisfied));
+        assert_eq!(settle(WaitMode::Any, &[Pending, Unreachable]), None);
+        assert_eq!(settle(WaitMode::Any, &[Unreachable, Unreachable]), Some(WaitOutcome::Unreachable));
+    }
+}
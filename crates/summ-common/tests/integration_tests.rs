@@ -1,7 +1,11 @@
 // Integration tests for SUMM Daemon
 // These tests verify protocol serialization, error handling, and session lifecycle
 
-use summ_common::{DaemonConfig, Request, Response, Session, SessionStatus, SessionInfo, DaemonError};
+use summ_common::ipc::{self, WireFormat};
+use summ_common::{
+    DaemonConfig, DaemonError, HookHealth, InputPrompt, PromptSource, Request, Response, Session, SessionInfo,
+    SessionInfoRef, SessionStatus, StartTrigger, StopReason,
+};
 use tempfile::TempDir;
 
 /// Test protocol request serialization
@@ -11,6 +15,22 @@ fn test_protocol_serialization() {
         cli: "claude".to_string(),
         init: std::path::PathBuf::from("/tmp/test-init"),
         name: Some("test-session".to_string()),
+        protected: false,
+        deadline_ms: None,
+        background: false,
+        allow_large_init: false,
+        git_checkpoint: None,
+        secrets: Vec::new(),
+        workdir_subpath: None,
+        hooks_in_subpath: false,
+        ephemeral: false,
+        idle_timeout_seconds: None,
+        init_from_session: None,
+        after: None,
+        observer_of: None,
+        busy_alert_minutes: None,
+        inject_rate_limit: None,
+        no_inject_history: false,
     };
 
     let json = serde_json::to_string(&req).expect("Failed to serialize");
@@ -21,7 +41,7 @@ fn test_protocol_serialization() {
     // Verify deserialization works
     let req2: Request = serde_json::from_str(&json).expect("Failed to deserialize");
     match req2 {
-        Request::Start { cli, init, name } => {
+        Request::Start { cli, init, name, .. } => {
             assert_eq!(cli, "claude");
             assert_eq!(init, std::path::PathBuf::from("/tmp/test-init"));
             assert_eq!(name, Some("test-session".to_string()));
@@ -67,7 +87,7 @@ fn test_error_response_format() {
     // Verify deserialization
     let resp2: Response = serde_json::from_str(&json).expect("Failed to deserialize");
     match resp2 {
-        Response::Error { code, message } => {
+        Response::Error { code, message, .. } => {
             assert_eq!(code, "E002");
             assert_eq!(message, "Session not found");
         }
@@ -75,42 +95,189 @@ fn test_error_response_format() {
     }
 }
 
-/// Test all request types can be serialized
-#[test]
-fn test_all_request_types_serialization() {
+/// One instance of every request variant
+fn all_requests() -> Vec<Request> {
     let init_path = std::path::PathBuf::from("/tmp/test");
 
-    let requests = vec![
+    vec![
         Request::Start {
             cli: "claude".to_string(),
             init: init_path.clone(),
             name: Some("test".to_string()),
+            protected: false,
+            deadline_ms: Some(30_000),
+            background: true,
+            allow_large_init: true,
+            git_checkpoint: None,
+            secrets: vec!["OPENAI_API_KEY".to_string()],
+            workdir_subpath: Some(std::path::PathBuf::from("services/api")),
+            hooks_in_subpath: true,
+            ephemeral: true,
+            idle_timeout_seconds: Some(1800),
+            init_from_session: Some("sess122".to_string()),
+            after: Some(StartTrigger {
+                session_id: "sess122".to_string(),
+                status: SessionStatus::Idle,
+            }),
+            observer_of: Some("sess121".to_string()),
+            busy_alert_minutes: None,
+            inject_rate_limit: None,
+            no_inject_history: false,
         },
         Request::Stop {
             session_id: "sess123".to_string(),
+            override_protection: false,
+            stop_observers: true,
+        },
+        Request::StopAll {
+            override_protection: true,
+            dry_run: true,
+        },
+        Request::SetProtected {
+            session_id: "sess123".to_string(),
+            protected: true,
+        },
+        Request::SetNote {
+            session_id: "sess123".to_string(),
+            note: Some("waiting on review".to_string()),
+        },
+        Request::SetNote {
+            session_id: "sess123".to_string(),
+            note: None,
         },
         Request::List {
             status_filter: None,
+            created_by_uid: None,
+            include_archived: false,
         },
         Request::List {
             status_filter: Some(SessionStatus::Running),
+            created_by_uid: Some(1000),
+            include_archived: false,
         },
         Request::Status {
             session_id: "sess456".to_string(),
         },
+        Request::WaitMany {
+            session_ids: vec!["sess456".to_string(), "sess789".to_string()],
+            mode: summ_common::WaitMode::Any,
+            target: vec![SessionStatus::Idle, SessionStatus::Failed],
+            timeout_ms: Some(1_800_000),
+        },
         Request::Inject {
             session_id: "sess789".to_string(),
             message: "test message".to_string(),
+            typing_delay_ms: Some(20),
+            line_delay_ms: None,
+            bracketed_paste: true,
         },
+        Request::PutFile {
+            session_id: "sess789".to_string(),
+            dest: std::path::PathBuf::from("docs/spec.md"),
+            data: vec![0, 159, 146, 150, b'\n'],
+            offset: 4096,
+            done: true,
+            overwrite: true,
+        },
+        Request::ListFiles {
+            session_id: "sess789".to_string(),
+            subpath: Some(std::path::PathBuf::from("out")),
+            depth: Some(2),
+        },
+        Request::GetFile {
+            session_id: "sess789".to_string(),
+            path: std::path::PathBuf::from("out/report.pdf"),
+            offset: 4096,
+        },
+        Request::SendKeys {
+            session_id: "sess789".to_string(),
+            keys: vec!["C-c".to_string(), "Enter".to_string()],
+        },
+        Request::Signal {
+            session_id: "sess789".to_string(),
+            signal: "INT".to_string(),
+        },
+        Request::Pause {
+            session_id: "sess789".to_string(),
+        },
+        Request::Resume {
+            session_id: "sess789".to_string(),
+        },
+        Request::SyncHooks { force: true },
+        Request::Gc { dry_run: true },
+        Request::TestHook {
+            name: "notify".to_string(),
+            event: Some(summ_common::LifecycleEvent::Failed),
+        },
+        Request::Timeline {
+            session_id: "sess789".to_string(),
+            since_seconds: Some(7200),
+        },
+        Request::Counts,
+        Request::Namespaces,
         Request::DaemonStatus,
-    ];
+        Request::Healthz,
+    ]
+}
 
-    for req in requests {
-        let json = serde_json::to_string(&req).expect(&format!("Failed to serialize {:?}", req));
-        let req2: Request = serde_json::from_str(&json).expect(&format!("Failed to deserialize {:?}", json));
+/// One instance of every response variant, with varied payload types
+fn all_responses() -> Vec<Response> {
+    vec![
+        Response::success(serde_json::json!({
+            "session_id": "sess123",
+            "pid": 4242,
+            "offset": -3,
+            "ratio": 0.5,
+            "paused": false,
+            "stop_reason": {"kind": "process_exited", "code": 1},
+            "missing": null,
+        })),
+        Response::success(serde_json::json!([{"session_id": "a"}, {"session_id": "b"}])),
+        Response::success(serde_json::Value::Null),
+        Response::error(&DaemonError::e002("Session not found: sess123")),
+    ]
+}
+
+/// Test all request types can be serialized
+#[test]
+fn test_all_request_types_serialization() {
+    for req in all_requests() {
+        let json = serde_json::to_string(&req).unwrap_or_else(|_| panic!("Failed to serialize {:?}", req));
+        let req2: Request = serde_json::from_str(&json).unwrap_or_else(|_| panic!("Failed to deserialize {:?}", json));
         // Verify round-trip works
         let json2 = serde_json::to_string(&req2).expect("Failed to serialize round-trip");
         assert_eq!(json, json2, "Round-trip serialization mismatch");
+        assert_eq!(serde_json::to_value(&req).unwrap()["type"], req.kind());
+    }
+}
+
+/// Every request and response survives both wire formats unchanged
+#[test]
+fn test_wire_format_round_trip_all_variants() {
+    for format in [WireFormat::Json, WireFormat::MessagePack] {
+        for req in all_requests() {
+            let bytes = ipc::encode(&req, format).unwrap();
+            let decoded: Request = ipc::decode(&bytes, format)
+                .unwrap_or_else(|e| panic!("{:?}: failed to decode {:?}: {:#}", format, req, e));
+            assert_eq!(
+                serde_json::to_value(&req).unwrap(),
+                serde_json::to_value(&decoded).unwrap(),
+                "{:?} round-trip mismatch",
+                format
+            );
+        }
+
+        for resp in all_responses() {
+            let bytes = ipc::encode(&resp, format).unwrap();
+            let decoded: Response = ipc::decode(&bytes, format)
+                .unwrap_or_else(|e| panic!("{:?}: failed to decode {:?}: {:#}", format, resp, e));
+            assert_eq!(
+                serde_json::to_value(&resp).unwrap(),
+                serde_json::to_value(&decoded).unwrap(),
+                "{:?} round-trip mismatch",
+                format
+            );
+        }
     }
 }
 
@@ -131,6 +298,7 @@ fn test_session_metadata_persistence() {
         pid: Some(12345),
         created_at: chrono::Utc::now(),
         last_activity: chrono::Utc::now(),
+        ..Default::default()
     };
 
     // Save metadata
@@ -167,6 +335,7 @@ fn test_session_status_serialization() {
         SessionStatus::Running,
         SessionStatus::Idle,
         SessionStatus::Stopped,
+        SessionStatus::Paused,
     ];
 
     for status in statuses {
@@ -180,13 +349,7 @@ fn test_session_status_serialization() {
 #[test]
 fn test_session_creation_simulated() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
-    let config = DaemonConfig {
-        sessions_dir: temp_dir.path().join("sessions"),
-        logs_dir: temp_dir.path().join("logs"),
-        socket_path: temp_dir.path().join("daemon.sock"),
-        cleanup_retention_hours: 24,
-        tmux_prefix: "summ-".to_string(),
-    };
+    let config = DaemonConfig::with_base_dir(temp_dir.path());
 
     // Create session directory structure
     let session_dir = config.sessions_dir.join("test_session");
@@ -214,6 +377,7 @@ fn test_session_info_conversion() {
         pid: None,
         created_at: chrono::Utc::now(),
         last_activity: chrono::Utc::now(),
+        ..Default::default()
     };
 
     // Clone values before the move
@@ -229,3 +393,46 @@ fn test_session_info_conversion() {
     assert_eq!(info.cli, cli);
     assert_eq!(info.status, status);
 }
+
+/// The borrowed view used by List serializes exactly like SessionInfo
+#[test]
+fn test_session_info_ref_matches_session_info() {
+    let bare = Session {
+        session_id: "sess123".to_string(),
+        name: "Test".to_string(),
+        ..Default::default()
+    };
+    let full = Session {
+        cli: "claude".to_string(),
+        created_by: "alice".to_string(),
+        status: SessionStatus::Pending,
+        protected: true,
+        needs_attention: true,
+        waiting_for_input: Some(InputPrompt {
+            since: chrono::Utc::now(),
+            source: PromptSource::Hook,
+            text: "Allow?".to_string(),
+        }),
+        current_task: Some("tests".to_string()),
+        current_command: Some("pytest".to_string()),
+        stop_reason: Some(StopReason::UserRequested { method: None }),
+        start_error: Some("E001: missing".to_string()),
+        start_after: Some(StartTrigger {
+            session_id: "sess122".to_string(),
+            status: SessionStatus::Idle,
+        }),
+        hook_health: Some(HookHealth::Ok),
+        note: Some("review".to_string()),
+        tokens_in: Some(1200),
+        tokens_out: Some(300),
+        estimated_cost: Some(0.25),
+        ..bare.clone()
+    };
+
+    for session in [bare, full] {
+        assert_eq!(
+            serde_json::to_value(SessionInfoRef::from(&session)).unwrap(),
+            serde_json::to_value(SessionInfo::from(session.clone())).unwrap()
+        );
+    }
+}
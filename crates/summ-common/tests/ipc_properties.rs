@@ -0,0 +1,448 @@
+// Property tests for IPC framing and protocol decoding
+// Arbitrary and hostile byte streams must fail gracefully: no panics, no hangs,
+// and no allocations sized by an untrusted length prefix.
+
+use proptest::prelude::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::path::PathBuf;
+use summ_common::ipc::{
+    self, WireFormat, FLAG_ACCEPTS_COMPRESSION, FLAG_COMPRESSED, MAX_DECOMPRESSED_SIZE,
+    MAX_FRAME_SIZE,
+};
+use summ_common::{
+    CheckpointMode, LifecycleEvent, PromptAnswer, Request, Response, SessionStatus, StartTrigger, WaitMode,
+};
+
+/// Records the largest single allocation made on the current thread
+struct TrackingAllocator;
+
+thread_local! {
+    static LARGEST_ALLOCATION: Cell<usize> = const { Cell::new(0) };
+}
+
+fn record_allocation(size: usize) {
+    let _ = LARGEST_ALLOCATION.try_with(|largest| largest.set(largest.get().max(size)));
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_allocation(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// Run a future on a current-thread runtime, so its allocations are tracked here
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Read one frame from `bytes`, returning the result and the largest allocation made
+fn read_tracked(bytes: &[u8]) -> (std::io::Result<(Vec<u8>, ipc::FrameInfo)>, usize) {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    LARGEST_ALLOCATION.with(|largest| largest.set(0));
+    let result = runtime.block_on(ipc::read_frame(&mut &bytes[..]));
+    (result, LARGEST_ALLOCATION.with(Cell::get))
+}
+
+/// Encode `payload` as one frame
+fn frame(payload: &[u8], format: Option<WireFormat>, compress: bool) -> Vec<u8> {
+    let mut wire = Vec::new();
+    block_on(ipc::write_frame(&mut wire, payload, format, compress)).unwrap();
+    wire
+}
+
+fn arb_status() -> impl Strategy<Value = SessionStatus> {
+    prop_oneof![
+        Just(SessionStatus::Running),
+        Just(SessionStatus::Idle),
+        Just(SessionStatus::Stopped),
+        Just(SessionStatus::Paused),
+        Just(SessionStatus::Starting),
+        Just(SessionStatus::Failed),
+        Just(SessionStatus::Pending),
+    ]
+}
+
+fn arb_request() -> impl Strategy<Value = Request> {
+    let id = ".{0,40}";
+    prop_oneof![
+        (
+            ".{0,40}",
+            ".{0,80}",
+            proptest::option::of(".{0,40}"),
+            any::<bool>(),
+            proptest::option::of(any::<u64>()),
+            any::<bool>(),
+            any::<bool>(),
+            proptest::option::of(prop_oneof![Just(CheckpointMode::Commit), Just(CheckpointMode::Patch)]),
+            proptest::collection::vec("[A-Z_][A-Z0-9_]{0,12}", 0..3),
+            proptest::option::of(id),
+            proptest::option::of((id, arb_status()).prop_map(|(session_id, status)| StartTrigger { session_id, status })),
+            (
+                proptest::option::of("[a-z/._]{0,20}"),
+                any::<bool>(),
+                any::<bool>(),
+                proptest::option::of(any::<u64>()),
+                proptest::option::of(id),
+                proptest::option::of(any::<u64>()),
+                proptest::option::of(any::<u32>()),
+                any::<bool>(),
+            ),
+        )
+            .prop_map(
+                |(
+                    cli,
+                    init,
+                    name,
+                    protected,
+                    deadline_ms,
+                    background,
+                    allow_large_init,
+                    git_checkpoint,
+                    secrets,
+                    init_from_session,
+                    after,
+                    (
+                        workdir_subpath,
+                        hooks_in_subpath,
+                        ephemeral,
+                        idle_timeout_seconds,
+                        observer_of,
+                        busy_alert_minutes,
+                        inject_rate_limit,
+                        no_inject_history,
+                    ),
+                )| {
+                    Request::Start {
+                        cli,
+                        init: PathBuf::from(init),
+                        init_from_session,
+                        after,
+                        name,
+                        protected,
+                        deadline_ms,
+                        background,
+                        allow_large_init,
+                        git_checkpoint,
+                        secrets,
+                        workdir_subpath: workdir_subpath.map(PathBuf::from),
+                        hooks_in_subpath,
+                        ephemeral,
+                        idle_timeout_seconds,
+                        observer_of,
+                        busy_alert_minutes,
+                        inject_rate_limit,
+                        no_inject_history,
+                    }
+                }
+            ),
+        (id, any::<bool>(), any::<bool>()).prop_map(|(session_id, override_protection, stop_observers)| {
+            Request::Stop {
+                session_id,
+                override_protection,
+                stop_observers,
+            }
+        }),
+        (any::<bool>(), any::<bool>()).prop_map(|(override_protection, dry_run)| {
+            Request::StopAll {
+                override_protection,
+                dry_run,
+            }
+        }),
+        (id, any::<bool>()).prop_map(|(session_id, protected)| Request::SetProtected {
+            session_id,
+            protected,
+        }),
+        (id, proptest::option::of(".{0,200}"))
+            .prop_map(|(session_id, note)| Request::SetNote { session_id, note }),
+        (proptest::option::of(arb_status()), proptest::option::of(any::<u32>()), any::<bool>()).prop_map(
+            |(status_filter, created_by_uid, include_archived)| Request::List {
+                status_filter,
+                created_by_uid,
+                include_archived,
+            }
+        ),
+        id.prop_map(|session_id| Request::Status { session_id }),
+        (
+            proptest::collection::vec(id, 0..4),
+            prop_oneof![Just(WaitMode::All), Just(WaitMode::Any)],
+            proptest::collection::vec(arb_status(), 0..3),
+            proptest::option::of(any::<u64>()),
+        )
+            .prop_map(|(session_ids, mode, target, timeout_ms)| Request::WaitMany {
+                session_ids,
+                mode,
+                target,
+                timeout_ms,
+            }),
+        (
+            id,
+            ".{0,200}",
+            proptest::option::of(any::<u64>()),
+            proptest::option::of(any::<u64>()),
+            any::<bool>()
+        )
+            .prop_map(|(session_id, message, typing_delay_ms, line_delay_ms, bracketed_paste)| {
+                Request::Inject {
+                    session_id,
+                    message,
+                    typing_delay_ms,
+                    line_delay_ms,
+                    bracketed_paste,
+                }
+            }),
+        (id, proptest::option::of(any::<usize>()))
+            .prop_map(|(session_id, limit)| Request::InjectionHistory { session_id, limit }),
+        (
+            id,
+            "[a-z/._]{0,20}",
+            proptest::collection::vec(any::<u8>(), 0..64),
+            any::<u64>(),
+            any::<bool>(),
+            any::<bool>(),
+        )
+            .prop_map(|(session_id, dest, data, offset, done, overwrite)| Request::PutFile {
+                session_id,
+                dest: PathBuf::from(dest),
+                data,
+                offset,
+                done,
+                overwrite,
+            }),
+        (
+            id,
+            proptest::option::of("[a-z/._]{0,20}"),
+            proptest::option::of(any::<u32>()),
+        )
+            .prop_map(|(session_id, subpath, depth)| Request::ListFiles {
+                session_id,
+                subpath: subpath.map(PathBuf::from),
+                depth,
+            }),
+        (id, "[a-z/._]{0,20}", any::<u64>()).prop_map(|(session_id, path, offset)| Request::GetFile {
+            session_id,
+            path: PathBuf::from(path),
+            offset,
+        }),
+        (id, proptest::collection::vec(".{0,10}", 0..5))
+            .prop_map(|(session_id, keys)| Request::SendKeys { session_id, keys }),
+        (
+            id,
+            prop_oneof![
+                Just(PromptAnswer::Yes),
+                Just(PromptAnswer::No),
+                any::<u32>().prop_map(PromptAnswer::Option),
+                ".{0,20}".prop_map(PromptAnswer::Text),
+            ],
+            any::<bool>(),
+        )
+            .prop_map(|(session_id, answer, force)| Request::Answer { session_id, answer, force }),
+        (id, ".{0,10}").prop_map(|(session_id, signal)| Request::Signal { session_id, signal }),
+        id.prop_map(|session_id| Request::Pause { session_id }),
+        id.prop_map(|session_id| Request::Resume { session_id }),
+        any::<bool>().prop_map(|force| Request::SyncHooks { force }),
+        any::<bool>().prop_map(|dry_run| Request::Gc { dry_run }),
+        (
+            ".{0,20}",
+            proptest::option::of(prop_oneof![
+                Just(LifecycleEvent::Created),
+                Just(LifecycleEvent::Stopped),
+                Just(LifecycleEvent::Removed),
+                Just(LifecycleEvent::BusyAlert),
+                Just(LifecycleEvent::Throttled),
+            ]),
+        )
+            .prop_map(|(name, event)| Request::TestHook { name, event }),
+        (id, proptest::option::of(any::<u64>()))
+            .prop_map(|(session_id, since_seconds)| Request::Timeline { session_id, since_seconds }),
+        Just(Request::Counts),
+        Just(Request::Namespaces),
+        Just(Request::DaemonStatus),
+        Just(Request::Healthz),
+    ]
+}
+
+fn arb_json() -> impl Strategy<Value = serde_json::Value> {
+    let leaf = prop_oneof![
+        Just(serde_json::Value::Null),
+        any::<bool>().prop_map(serde_json::Value::from),
+        any::<i64>().prop_map(serde_json::Value::from),
+        any::<u64>().prop_map(serde_json::Value::from),
+        // serde_json's default float parser is not always correctly rounded at 17
+        // significant digits, so stick to floats with short exact decimals
+        any::<i32>().prop_map(|n| serde_json::Value::from(n as f64 / 8.0)),
+        ".{0,40}".prop_map(serde_json::Value::from),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..8).prop_map(serde_json::Value::Array),
+            proptest::collection::btree_map(".{0,12}", inner, 0..8)
+                .prop_map(|map| serde_json::Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+fn arb_response() -> impl Strategy<Value = Response> {
+    prop_oneof![
+        arb_json().prop_map(|data| Response::Success { data }),
+        (".{0,8}", ".{0,80}", proptest::option::of("[0-9.]{1,8}"), proptest::option::of(0u64..3600)).prop_map(
+            |(code, message, daemon_version, retry_after_seconds)| Response::Error {
+                code,
+                message,
+                daemon_version,
+                retry_after_seconds,
+            }
+        ),
+    ]
+}
+
+fn arb_format() -> impl Strategy<Value = Option<WireFormat>> {
+    prop_oneof![
+        Just(None),
+        Just(Some(WireFormat::Json)),
+        Just(Some(WireFormat::MessagePack)),
+    ]
+}
+
+/// Largest allocation tolerated while reading a frame that is mostly missing
+const SMALL_ALLOCATION: usize = 1024 * 1024;
+
+proptest! {
+    #[test]
+    fn prop_arbitrary_bytes_fail_gracefully(bytes in proptest::collection::vec(any::<u8>(), 0..4096)) {
+        let (result, largest) = read_tracked(&bytes);
+        prop_assert!(largest < SMALL_ALLOCATION);
+        if let Ok((payload, info)) = result {
+            prop_assert!(payload.len() <= MAX_DECOMPRESSED_SIZE);
+            // Decoding whatever arrived must not panic either
+            let _ = ipc::decode::<Request>(&payload, info.format.unwrap_or_default());
+        }
+    }
+
+    #[test]
+    fn prop_length_prefix_is_not_trusted(
+        len in prop_oneof![
+            Just(MAX_FRAME_SIZE as u32 - 1),
+            Just(MAX_FRAME_SIZE as u32),
+            Just(MAX_FRAME_SIZE as u32 + 1),
+            Just(u32::MAX),
+            1u32..=MAX_FRAME_SIZE as u32,
+        ],
+        header in any::<u8>(),
+        body in proptest::collection::vec(any::<u8>(), 0..256),
+    ) {
+        prop_assume!(body.len() + 1 < len as usize);
+        let mut wire = len.to_be_bytes().to_vec();
+        wire.push(header);
+        wire.extend_from_slice(&body);
+
+        let (result, largest) = read_tracked(&wire);
+        prop_assert!(result.is_err());
+        prop_assert!(largest < SMALL_ALLOCATION, "allocated {} bytes", largest);
+    }
+
+    #[test]
+    fn prop_truncated_frames_are_rejected(
+        request in arb_request(),
+        format in arb_format(),
+        cut in any::<prop::sample::Index>(),
+    ) {
+        let payload = ipc::encode(&request, format.unwrap_or_default()).unwrap();
+        let wire = frame(&payload, format, false);
+        let cut = cut.index(wire.len());
+
+        let (result, _) = read_tracked(&wire[..cut]);
+        prop_assert!(result.is_err());
+    }
+
+    #[test]
+    fn prop_invalid_payloads_are_errors(
+        format in prop_oneof![Just(WireFormat::Json), Just(WireFormat::MessagePack)],
+        body in proptest::collection::vec(any::<u8>(), 0..1024),
+    ) {
+        let wire = frame(&body, Some(format), false);
+        let result = block_on(ipc::read_message::<_, Request>(&mut &wire[..]));
+        if let Ok((request, _)) = result {
+            // Only bytes that really encode a request may decode
+            let reencoded = ipc::encode(&request, format).unwrap();
+            let again: Request = ipc::decode(&reencoded, format).unwrap();
+            prop_assert_eq!(
+                serde_json::to_value(&request).unwrap(),
+                serde_json::to_value(&again).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn prop_unknown_request_types_are_rejected(tag in "[A-Za-z]{1,20}", extra in arb_json()) {
+        let known = [
+            "Start", "Stop", "StopAll", "SetProtected", "SetNote", "List", "Status", "Inject",
+            "SendKeys", "Answer", "Signal", "Pause", "Resume", "SyncHooks", "Counts", "DaemonStatus",
+            "Healthz", "Gc",
+        ];
+        prop_assume!(!known.contains(&tag.as_str()));
+
+        let message = serde_json::json!({ "type": tag, "extra": extra });
+        let wire = frame(&serde_json::to_vec(&message).unwrap(), Some(WireFormat::Json), false);
+        let result = block_on(ipc::read_message::<_, Request>(&mut &wire[..]));
+        prop_assert!(result.is_err());
+    }
+
+    #[test]
+    fn prop_request_round_trip(request in arb_request(), format in arb_format(), compress in any::<bool>()) {
+        let mut wire = Vec::new();
+        block_on(ipc::write_message(&mut wire, &request, format, compress)).unwrap();
+        let (decoded, info): (Request, _) = block_on(ipc::read_message(&mut &wire[..])).unwrap();
+
+        prop_assert_eq!(info.format, format);
+        prop_assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::to_value(&decoded).unwrap()
+        );
+    }
+
+    #[test]
+    fn prop_response_round_trip(response in arb_response(), format in arb_format(), compress in any::<bool>()) {
+        let mut wire = Vec::new();
+        block_on(ipc::write_message(&mut wire, &response, format, compress)).unwrap();
+        let (decoded, _): (Response, _) = block_on(ipc::read_message(&mut &wire[..])).unwrap();
+
+        prop_assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::to_value(&decoded).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_compressed_frame_with_garbage_payload_is_rejected() {
+    let mut wire = Vec::new();
+    wire.extend_from_slice(&65u32.to_be_bytes());
+    wire.push(WireFormat::Json.tag() | FLAG_COMPRESSED | FLAG_ACCEPTS_COMPRESSION);
+    wire.extend_from_slice(&[0xab; 64]);
+
+    let (result, largest) = read_tracked(&wire);
+    assert!(result.is_err());
+    assert!(largest < SMALL_ALLOCATION);
+}
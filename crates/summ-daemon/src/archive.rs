@@ -0,0 +1,349 @@
+// summ-daemon/src/archive.rs
+// Extraction of init archives, with libarchive or pure-Rust backends
+use anyhow::Result;
+use std::fs::{self, File};
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path, PathBuf};
+use summ_common::DaemonError;
+
+use crate::workspace_paths::{PathChecker, Placement};
+
+#[cfg(not(any(feature = "libarchive", feature = "pure-rust-archives")))]
+compile_error!("summ-daemon needs an archive backend: enable `libarchive` or `pure-rust-archives`");
+
+#[cfg(all(feature = "libarchive", not(feature = "pure-rust-archives")))]
+mod libarchive;
+#[cfg(all(feature = "libarchive", not(feature = "pure-rust-archives")))]
+use libarchive as backend;
+
+#[cfg(feature = "pure-rust-archives")]
+mod pure;
+#[cfg(feature = "pure-rust-archives")]
+use pure as backend;
+
+/// Name of the archive backend compiled in, for error messages
+pub const BACKEND: &str = backend::NAME;
+
+/// Archive formats accepted as init sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Format of an init source, from its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.to_string_lossy();
+        if path.extension().is_some_and(|e| e == "zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Zip => "ZIP",
+            Self::TarGz => "tar.gz",
+        }
+    }
+}
+
+/// Why an archive could not be extracted
+#[derive(Debug)]
+pub enum ExtractFailure {
+    /// The archive ends in the middle of an entry
+    Truncated(String),
+    /// An entry is compressed with a method the backend cannot read
+    UnsupportedCompression(String),
+    /// The archive is damaged or not in a format the backend recognizes
+    Corrupt(String),
+    /// An entry names a path outside the destination
+    UnsafePath,
+    /// Writing the entry to the destination failed
+    Destination(std::io::Error),
+}
+
+impl std::fmt::Display for ExtractFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated(details) => write!(f, "the archive is truncated ({})", details),
+            Self::UnsupportedCompression(details) => write!(
+                f,
+                "the entry uses a compression method the {} backend cannot read ({})",
+                BACKEND, details
+            ),
+            Self::Corrupt(details) => {
+                write!(f, "the archive is damaged or in an unrecognized format ({})", details)
+            }
+            Self::UnsafePath => write!(f, "the entry points outside the destination"),
+            Self::Destination(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                write!(f, "permission denied writing to the destination ({})", e)
+            }
+            Self::Destination(e) => write!(f, "cannot write to the destination ({})", e),
+        }
+    }
+}
+
+/// What an archive entry is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    Directory,
+    File,
+    /// Symlinks, hard links and special files, which are skipped
+    Other,
+}
+
+/// Where an archive entry goes under `destination`; None for absolute paths
+/// and paths climbing out with `..`
+fn entry_destination(destination: &Path, name: &str) -> Option<PathBuf> {
+    let relative = Path::new(name);
+    relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        .then(|| destination.join(relative))
+}
+
+/// Writes a backend's entries under the destination, keeping the name of the
+/// entry being processed for error messages
+struct Extractor<'a> {
+    destination: &'a Path,
+    checker: &'a mut PathChecker,
+    entry: Option<String>,
+    output: Option<(File, PathBuf, u32)>,
+}
+
+impl<'a> Extractor<'a> {
+    fn new(destination: &'a Path, checker: &'a mut PathChecker) -> Self {
+        Self { destination, checker, entry: None, output: None }
+    }
+
+    /// Begin an entry; `mode` holds its permission bits (0 when unknown).
+    /// Entries the checker leaves out are read past without being written.
+    fn start_entry(&mut self, name: &str, kind: EntryKind, mode: u32) -> Result<(), ExtractFailure> {
+        self.entry = Some(name.to_string());
+        entry_destination(self.destination, name).ok_or(ExtractFailure::UnsafePath)?;
+        if kind == EntryKind::Other {
+            return Ok(());
+        }
+        let path = match self.checker.place(Path::new(name)) {
+            Placement::Write(relative) => self.destination.join(relative),
+            Placement::Skip => return Ok(()),
+        };
+        match kind {
+            EntryKind::Directory => fs::create_dir_all(&path).map_err(ExtractFailure::Destination)?,
+            EntryKind::File => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(ExtractFailure::Destination)?;
+                }
+                let file = File::create(&path).map_err(ExtractFailure::Destination)?;
+                self.output = Some((file, path, mode & 0o777));
+            }
+            EntryKind::Other => {}
+        }
+        Ok(())
+    }
+
+    /// Write data of the current entry (dropped for skipped entries)
+    fn write(&mut self, chunk: &[u8]) -> Result<(), ExtractFailure> {
+        if let Some((file, _, _)) = self.output.as_mut() {
+            file.write_all(chunk).map_err(ExtractFailure::Destination)?;
+        }
+        Ok(())
+    }
+
+    /// Finish the current entry, applying its permission bits
+    fn end_entry(&mut self) -> Result<(), ExtractFailure> {
+        if let Some((_, path, permissions)) = self.output.take() {
+            if permissions != 0 {
+                fs::set_permissions(&path, fs::Permissions::from_mode(permissions))
+                    .map_err(ExtractFailure::Destination)?;
+            }
+        }
+        self.entry = None;
+        Ok(())
+    }
+}
+
+/// Extract an archive entry by entry, so a failure can name the entry it
+/// happened in. Directories and regular files are extracted with their
+/// permission bits; symlinks and special files are skipped, as they are when
+/// copying a directory source. `checker` decides where each entry goes.
+/// Failures are `E004` errors naming the archive, the entry, and the
+/// destination.
+pub fn extract(
+    archive_path: &Path,
+    destination: &Path,
+    format: ArchiveFormat,
+    checker: &mut PathChecker,
+) -> Result<()> {
+    if !archive_path.exists() {
+        anyhow::bail!("Archive not found: {}", archive_path.display());
+    }
+    let mut extractor = Extractor::new(destination, checker);
+    let result = fs::create_dir_all(destination)
+        .map_err(ExtractFailure::Destination)
+        .and_then(|()| backend::extract(archive_path, format, &mut extractor));
+    result.map_err(|failure| {
+        let at = extractor.entry.map(|name| format!(" at entry {}", name)).unwrap_or_default();
+        DaemonError::e004(format!(
+            "Failed to extract {} archive {} into {}{}: {}",
+            format.name(),
+            archive_path.display(),
+            destination.display(),
+            at,
+            failure
+        ))
+        .into()
+    })
+}
+
+/// Uncompressed size of an archive's entries, stopping (incomplete) past
+/// `limit` bytes or `max_entries` entries; None if it cannot be listed
+pub fn measure(archive_path: &Path, limit: u64, max_entries: usize) -> Option<(u64, bool)> {
+    backend::measure(archive_path, limit, max_entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use summ_common::InitPathPolicy;
+    use tempfile::TempDir;
+
+    // The same suite runs against whichever backend is compiled in:
+    //   cargo test -p summ-daemon
+    //   cargo test -p summ-daemon --no-default-features --features pure-rust-archives
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    fn extract_error(result: Result<()>) -> DaemonError {
+        result.unwrap_err().downcast::<DaemonError>().expect("extraction failures are DaemonErrors")
+    }
+
+    fn checker(destination: &Path) -> PathChecker {
+        PathChecker::new(destination, InitPathPolicy::Fail)
+    }
+
+    fn extract_fixture(name: &str) -> (TempDir, Result<()>) {
+        let dest_dir = TempDir::new().unwrap();
+        let archive = fixture(name);
+        let format = ArchiveFormat::from_path(&archive).unwrap();
+        let result = extract(&archive, dest_dir.path(), format, &mut checker(dest_dir.path()));
+        (dest_dir, result)
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(ArchiveFormat::from_path(Path::new("a.zip")), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::from_path(Path::new("a.tar.gz")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_path(Path::new("a.tgz")), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_path(Path::new("a.rar")), None);
+    }
+
+    #[test]
+    fn test_extract_valid_archives() {
+        for name in ["workspace.zip", "workspace.tar.gz"] {
+            let (dest_dir, result) = extract_fixture(name);
+            result.unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+            let workspace = dest_dir.path();
+            assert_eq!(fs::read_to_string(workspace.join("README.md")).unwrap(), "fixture workspace\n");
+            assert!(fs::read_to_string(workspace.join("src/main.rs")).unwrap().starts_with("fn main()"));
+            let script = fs::metadata(workspace.join("bin/run.sh")).unwrap();
+            assert_eq!(script.permissions().mode() & 0o777, 0o755, "{}", name);
+            assert!(workspace.join("empty").is_dir(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_extract_measures_entries() {
+        for name in ["workspace.zip", "workspace.tar.gz"] {
+            let (bytes, complete) = measure(&fixture(name), u64::MAX, 100).unwrap();
+            assert!(complete, "{}", name);
+            assert_eq!(bytes, 18 + 13 * 400 + 22, "{}", name);
+            assert_eq!(measure(&fixture(name), 100, 100).map(|(_, complete)| complete), Some(false));
+        }
+    }
+
+    #[test]
+    fn test_extract_truncated_tar_gz() {
+        let (dest_dir, result) = extract_fixture("truncated.tar.gz");
+        let error = extract_error(result);
+        assert_eq!(error.code.code(), "E004");
+        assert!(error.message.contains(&fixture("truncated.tar.gz").display().to_string()), "{}", error.message);
+        assert!(error.message.contains(&dest_dir.path().display().to_string()), "{}", error.message);
+        assert!(error.message.contains("at entry src/main.rs: the archive is truncated"), "{}", error.message);
+    }
+
+    #[test]
+    fn test_extract_zip_with_unsupported_method() {
+        let (_dest_dir, result) = extract_fixture("unsupported_method.zip");
+        let error = extract_error(result);
+        assert_eq!(error.code.code(), "E004");
+        assert!(error.message.starts_with("Failed to extract ZIP archive"), "{}", error.message);
+        assert!(
+            error.message.contains(&format!(
+                "at entry notes.txt: the entry uses a compression method the {} backend cannot read",
+                BACKEND
+            )),
+            "{}",
+            error.message
+        );
+    }
+
+    #[test]
+    fn test_extract_rejects_paths_outside_destination() {
+        for name in ["zip_slip.zip", "zip_slip.tar.gz"] {
+            let (dest_dir, result) = extract_fixture(name);
+            let error = extract_error(result);
+            assert!(
+                error.message.contains("at entry ../escape.txt: the entry points outside the destination"),
+                "{}",
+                error.message
+            );
+            assert!(!dest_dir.path().join("../escape.txt").exists(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_extract_into_unwritable_destination() {
+        let temp_dir = TempDir::new().unwrap();
+        let blocker = temp_dir.path().join("file");
+        fs::write(&blocker, "not a directory").unwrap();
+        let destination = blocker.join("workspace");
+        let result = extract(&fixture("workspace.zip"), &destination, ArchiveFormat::Zip, &mut checker(&destination));
+        let error = extract_error(result);
+        assert_eq!(error.code.code(), "E004");
+        assert!(error.message.contains("cannot write to the destination"), "{}", error.message);
+    }
+
+    #[test]
+    fn test_failure_messages_are_distinguishable() {
+        let messages = [
+            ExtractFailure::Truncated("t".into()).to_string(),
+            ExtractFailure::UnsupportedCompression("u".into()).to_string(),
+            ExtractFailure::Corrupt("c".into()).to_string(),
+            ExtractFailure::Destination(std::io::ErrorKind::PermissionDenied.into()).to_string(),
+            ExtractFailure::Destination(std::io::ErrorKind::StorageFull.into()).to_string(),
+            ExtractFailure::UnsafePath.to_string(),
+        ];
+        assert!(messages[3].starts_with("permission denied writing to the destination"));
+        let distinct: std::collections::HashSet<_> =
+            messages.iter().map(|message| message.split(" (").next().unwrap()).collect();
+        assert_eq!(distinct.len(), messages.len());
+    }
+
+    #[test]
+    fn test_entry_destination_stays_inside() {
+        let destination = Path::new("/work");
+        assert_eq!(entry_destination(destination, "src/main.rs"), Some(PathBuf::from("/work/src/main.rs")));
+        assert_eq!(entry_destination(destination, "./README.md"), Some(PathBuf::from("/work/./README.md")));
+        assert_eq!(entry_destination(destination, "../escape"), None);
+        assert_eq!(entry_destination(destination, "/etc/passwd"), None);
+    }
+}
@@ -0,0 +1,95 @@
+// summ-daemon/src/archive/libarchive.rs
+// Archive backend reading every format libarchive supports
+use super::{ArchiveFormat, EntryKind, ExtractFailure, Extractor};
+use compress_tools::{ArchiveContents, ArchiveIterator};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+pub const NAME: &str = "libarchive";
+
+/// Sort a libarchive error message into the failure it describes
+fn classify_archive_error(details: String) -> ExtractFailure {
+    let lower = details.to_lowercase();
+    if lower.contains("truncated") || lower.contains("premature end") || lower.contains("unexpected end") {
+        ExtractFailure::Truncated(details)
+    } else if lower.contains("unsupported") && (lower.contains("compression") || lower.contains("method")) {
+        ExtractFailure::UnsupportedCompression(details)
+    } else {
+        ExtractFailure::Corrupt(details)
+    }
+}
+
+fn open(archive_path: &Path) -> Result<ArchiveIterator<BufReader<File>>, ExtractFailure> {
+    let file = File::open(archive_path).map_err(|e| ExtractFailure::Corrupt(e.to_string()))?;
+    ArchiveIterator::from_read(BufReader::new(file)).map_err(|e| classify_archive_error(e.to_string()))
+}
+
+/// libarchive detects the format itself, so `_format` only names it in errors
+pub(super) fn extract(
+    archive_path: &Path,
+    _format: ArchiveFormat,
+    extractor: &mut Extractor,
+) -> Result<(), ExtractFailure> {
+    for contents in open(archive_path)? {
+        match contents {
+            ArchiveContents::StartOfEntry(name, stat) => {
+                let kind = match stat.st_mode & libc::S_IFMT {
+                    libc::S_IFDIR => EntryKind::Directory,
+                    libc::S_IFREG => EntryKind::File,
+                    _ => EntryKind::Other,
+                };
+                extractor.start_entry(&name, kind, stat.st_mode)?;
+            }
+            ArchiveContents::DataChunk(chunk) => extractor.write(&chunk)?,
+            ArchiveContents::EndOfEntry => extractor.end_entry()?,
+            ArchiveContents::Err(e) => return Err(classify_archive_error(e.to_string())),
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn measure(archive_path: &Path, limit: u64, max_entries: usize) -> Option<(u64, bool)> {
+    let mut bytes = 0u64;
+    let mut entries = 0;
+    for contents in open(archive_path).ok()? {
+        match contents {
+            ArchiveContents::StartOfEntry(_, stat) => {
+                entries += 1;
+                bytes += stat.st_size.max(0) as u64;
+                if entries > max_entries || bytes > limit {
+                    return Some((bytes, false));
+                }
+            }
+            ArchiveContents::Err(_) => return None,
+            _ => {}
+        }
+    }
+    Some((bytes, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_libarchive_messages() {
+        // Messages libarchive gives for the truncated and unsupported_method fixtures
+        assert!(matches!(
+            classify_archive_error("Truncated tar archive detected while reading data".to_string()),
+            ExtractFailure::Truncated(_)
+        ));
+        assert!(matches!(
+            classify_archive_error("truncated gzip input".to_string()),
+            ExtractFailure::Truncated(_)
+        ));
+        assert!(matches!(
+            classify_archive_error("Unsupported ZIP compression method (10: ibm-terse)".to_string()),
+            ExtractFailure::UnsupportedCompression(_)
+        ));
+        assert!(matches!(
+            classify_archive_error("Unrecognized archive format".to_string()),
+            ExtractFailure::Corrupt(_)
+        ));
+    }
+}
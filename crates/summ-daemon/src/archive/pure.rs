@@ -0,0 +1,143 @@
+// summ-daemon/src/archive/pure.rs
+// Archive backend built on the zip, tar and flate2 crates, with no system
+// libraries
+use super::{ArchiveFormat, EntryKind, ExtractFailure, Extractor};
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::Path;
+use zip::result::ZipError;
+
+pub const NAME: &str = "pure Rust";
+
+/// Sort an error reading archive data into the failure it describes
+fn classify_io_error(e: std::io::Error) -> ExtractFailure {
+    match e.kind() {
+        ErrorKind::UnexpectedEof => ExtractFailure::Truncated(e.to_string()),
+        _ => ExtractFailure::Corrupt(e.to_string()),
+    }
+}
+
+fn classify_zip_error(e: ZipError) -> ExtractFailure {
+    match e {
+        ZipError::UnsupportedArchive(details) => ExtractFailure::UnsupportedCompression(details.to_string()),
+        ZipError::Io(e) => classify_io_error(e),
+        e => ExtractFailure::Corrupt(e.to_string()),
+    }
+}
+
+fn open(archive_path: &Path) -> Result<BufReader<File>, ExtractFailure> {
+    File::open(archive_path).map(BufReader::new).map_err(|e| ExtractFailure::Corrupt(e.to_string()))
+}
+
+fn open_tar(archive_path: &Path) -> Result<tar::Archive<GzDecoder<BufReader<File>>>, ExtractFailure> {
+    Ok(tar::Archive::new(GzDecoder::new(open(archive_path)?)))
+}
+
+/// Copy an entry's data, which should be `size` bytes; an entry ending early
+/// means the archive was cut short
+fn copy(extractor: &mut Extractor, reader: &mut impl Read, size: u64) -> Result<(), ExtractFailure> {
+    let mut buffer = [0u8; 64 * 1024];
+    let mut copied = 0u64;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(classify_io_error(e)),
+        };
+        extractor.write(&buffer[..read])?;
+        copied += read as u64;
+    }
+    if copied < size {
+        return Err(ExtractFailure::Truncated(format!("the entry ends after {} of {} bytes", copied, size)));
+    }
+    Ok(())
+}
+
+pub(super) fn extract(
+    archive_path: &Path,
+    format: ArchiveFormat,
+    extractor: &mut Extractor,
+) -> Result<(), ExtractFailure> {
+    match format {
+        ArchiveFormat::Zip => extract_zip(archive_path, extractor),
+        ArchiveFormat::TarGz => extract_tar_gz(archive_path, extractor),
+    }
+}
+
+fn extract_zip(archive_path: &Path, extractor: &mut Extractor) -> Result<(), ExtractFailure> {
+    let mut archive = zip::ZipArchive::new(open(archive_path)?).map_err(classify_zip_error)?;
+    for index in 0..archive.len() {
+        // Name the entry before opening it, so errors opening it are reported at it
+        extractor.entry = archive.name_for_index(index).map(str::to_string);
+        let mut file = archive.by_index(index).map_err(classify_zip_error)?;
+        let kind = if file.is_dir() {
+            EntryKind::Directory
+        } else if file.is_symlink() {
+            EntryKind::Other
+        } else {
+            EntryKind::File
+        };
+        let name = file.name().to_string();
+        extractor.start_entry(&name, kind, file.unix_mode().unwrap_or(0))?;
+        if kind == EntryKind::File {
+            let size = file.size();
+            copy(extractor, &mut file, size)?;
+        }
+        extractor.end_entry()?;
+    }
+    Ok(())
+}
+
+fn extract_tar_gz(archive_path: &Path, extractor: &mut Extractor) -> Result<(), ExtractFailure> {
+    let mut archive = open_tar(archive_path)?;
+    for entry in archive.entries().map_err(classify_io_error)? {
+        let mut entry = entry.map_err(classify_io_error)?;
+        let header = entry.header();
+        let kind = if header.entry_type().is_dir() {
+            EntryKind::Directory
+        } else if header.entry_type().is_file() {
+            EntryKind::File
+        } else {
+            EntryKind::Other
+        };
+        let mode = header.mode().unwrap_or(0);
+        let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+        extractor.start_entry(&name, kind, mode)?;
+        if kind == EntryKind::File {
+            let size = entry.size();
+            copy(extractor, &mut entry, size)?;
+        }
+        extractor.end_entry()?;
+    }
+    Ok(())
+}
+
+pub(super) fn measure(archive_path: &Path, limit: u64, max_entries: usize) -> Option<(u64, bool)> {
+    match ArchiveFormat::from_path(archive_path)? {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(open(archive_path).ok()?).ok()?;
+            let sizes = (0..archive.len()).map(|index| archive.by_index_raw(index).ok().map(|file| file.size()));
+            total(sizes, limit, max_entries)
+        }
+        ArchiveFormat::TarGz => {
+            let mut archive = open_tar(archive_path).ok()?;
+            let sizes = archive.entries().ok()?.map(|entry| entry.ok().map(|entry| entry.size()));
+            total(sizes, limit, max_entries)
+        }
+    }
+}
+
+/// Sum entry sizes, stopping (incomplete) past `limit` bytes or `max_entries`
+/// entries; None if an entry cannot be read
+fn total(sizes: impl Iterator<Item = Option<u64>>, limit: u64, max_entries: usize) -> Option<(u64, bool)> {
+    let mut bytes = 0u64;
+    for (index, size) in sizes.enumerate() {
+        bytes += size?;
+        if index + 1 > max_entries || bytes > limit {
+            return Some((bytes, false));
+        }
+    }
+    Some((bytes, true))
+}
@@ -0,0 +1,221 @@
+// summ-daemon/src/attention.rs
+// Bridge tmux activity and bell monitoring into session status
+use chrono::{DateTime, Utc};
+use std::process::Stdio;
+use summ_common::{DaemonConfig, Session, SessionStatus};
+use tracing::{debug, info};
+
+use crate::storage;
+use crate::tmux::{TmuxManager, WindowState};
+use crate::warnings;
+
+/// Update `last_activity` and `needs_attention` from the session's tmux
+/// windows; returns true when the session starts needing attention
+pub fn record(session: &mut Session, alerts: Option<&WindowState>) -> bool {
+    if session.status == SessionStatus::Stopped {
+        return false;
+    }
+
+    let output_at = alerts
+        .and_then(|alerts| alerts.activity)
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0));
+    match output_at {
+        // A last_activity in the future (the clock stepped back since) would
+        // hold back every newer timestamp
+        Some(at) if at > session.last_activity || session.last_activity > Utc::now() => {
+            session.last_activity = at;
+        }
+        Some(_) => {}
+        // Without the timestamp, the session counts as active unless tmux
+        // reports no output since its flags were cleared
+        None if alerts.and_then(|alerts| alerts.activity_flag) != Some(false) => {
+            session.last_activity = Utc::now();
+        }
+        None => {}
+    }
+
+    // Leave the flag alone when tmux cannot say (window missing, old tmux)
+    let Some(bell) = alerts.and_then(|alerts| alerts.bell_flag) else {
+        return false;
+    };
+    if bell == session.needs_attention {
+        return false;
+    }
+    session.needs_attention = bell;
+    storage::save(session).ok();
+    if bell {
+        info!("Session {} rang the bell and needs attention", session.session_id);
+    }
+    bell
+}
+
+/// Clear the session's alerts once the user has answered it
+pub fn clear(session: &mut Session) {
+    if let Err(e) = TmuxManager::clear_alerts(&session.tmux_session) {
+        debug!("Could not clear tmux alerts of session {}: {}", session.session_id, e);
+    }
+    if session.needs_attention {
+        session.needs_attention = false;
+        storage::save(session).ok();
+    }
+}
+
+/// Run `attention_command` for a session that rang the bell or started
+/// waiting on a prompt, without waiting for it
+pub fn notify(session: &Session, config: &DaemonConfig) {
+    let Some(mut command) = command(session, config) else {
+        return;
+    };
+    match &session.waiting_for_input {
+        Some(prompt) => command
+            .env("SUMM_ATTENTION_REASON", "waiting_for_input")
+            .env("SUMM_PROMPT", &prompt.text),
+        None => command.env("SUMM_ATTENTION_REASON", "bell"),
+    };
+    spawn(session, command);
+}
+
+/// Run `attention_command` for a session that has been busy past its
+/// `busy_alert_minutes`, without waiting for it
+pub fn notify_busy(session: &Session, config: &DaemonConfig) {
+    let Some(mut command) = command(session, config) else {
+        return;
+    };
+    command
+        .env("SUMM_ATTENTION_REASON", "busy_too_long")
+        .env("SUMM_BUSY_SECONDS", session.busy_seconds().unwrap_or_default().to_string());
+    spawn(session, command);
+}
+
+/// Run `attention_command` for a session that first refused an injection
+/// over its rate limit, without waiting for it
+pub fn notify_throttled(session: &Session, config: &DaemonConfig, limit: u32) {
+    let Some(mut command) = command(session, config) else {
+        return;
+    };
+    command
+        .env("SUMM_ATTENTION_REASON", "inject_rate_limited")
+        .env("SUMM_INJECT_RATE_LIMIT", limit.to_string());
+    spawn(session, command);
+}
+
+/// The configured `attention_command` with the session's environment
+fn command(session: &Session, config: &DaemonConfig) -> Option<tokio::process::Command> {
+    let script = config.attention_command.as_ref()?;
+    let mut command = tokio::process::Command::new("sh");
+    command
+        .arg("-c")
+        .arg(script)
+        .env("SUMM_SESSION_ID", &session.session_id)
+        .env("SUMM_SESSION_NAME", &session.name)
+        .env("SUMM_CLI", &session.cli);
+    Some(command)
+}
+
+fn spawn(session: &Session, mut command: tokio::process::Command) {
+    let spawned = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Err(e) = spawned {
+        warnings::warn(
+            "attention_command",
+            Some(&session.session_id),
+            format!("Failed to run attention_command for session {}: {}", session.session_id, e),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn running_session(temp_dir: &TempDir) -> Session {
+        let workdir = temp_dir.path().join("attn001");
+        std::fs::create_dir_all(workdir.join("runtime")).unwrap();
+        Session {
+            session_id: "attn001".to_string(),
+            tmux_session: "summ-attn001".to_string(),
+            workdir,
+            status: SessionStatus::Running,
+            last_activity: DateTime::from_timestamp(1_760_000_000, 0).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn alerts(activity: i64, bell: bool) -> WindowState {
+        WindowState {
+            activity: Some(activity),
+            activity_flag: Some(true),
+            bell_flag: Some(bell),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_activity_timestamp_drives_last_activity() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = running_session(&temp_dir);
+
+        assert!(!record(&mut session, Some(&alerts(1_760_000_300, false))));
+        assert_eq!(session.last_activity.timestamp(), 1_760_000_300);
+
+        // An older timestamp never moves it back
+        record(&mut session, Some(&alerts(1_760_000_100, false)));
+        assert_eq!(session.last_activity.timestamp(), 1_760_000_300);
+
+        // No output since the flags were cleared and no timestamp: unchanged
+        let quiet = WindowState {
+            activity_flag: Some(false),
+            ..Default::default()
+        };
+        record(&mut session, Some(&quiet));
+        assert_eq!(session.last_activity.timestamp(), 1_760_000_300);
+    }
+
+    #[test]
+    fn test_last_activity_recovers_from_clock_step_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = running_session(&temp_dir);
+        // Recorded before the clock stepped back an hour
+        session.last_activity = Utc::now() + chrono::Duration::hours(1);
+
+        let output_at = Utc::now().timestamp() - 5;
+        record(&mut session, Some(&alerts(output_at, false)));
+        assert_eq!(session.last_activity.timestamp(), output_at);
+    }
+
+    #[test]
+    fn test_bell_raises_attention_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = running_session(&temp_dir);
+
+        assert!(record(&mut session, Some(&alerts(1_760_000_100, true))));
+        assert!(session.needs_attention);
+        // Still ringing: not raised again
+        assert!(!record(&mut session, Some(&alerts(1_760_000_200, true))));
+
+        // Unknown to tmux, or the window is gone: left as it was
+        assert!(!record(&mut session, Some(&WindowState::default())));
+        assert!(!record(&mut session, None));
+        assert!(session.needs_attention);
+
+        // The user attached, which cleared the flag in tmux
+        assert!(!record(&mut session, Some(&alerts(1_760_000_300, false))));
+        assert!(!session.needs_attention);
+    }
+
+    #[test]
+    fn test_stopped_sessions_are_left_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = running_session(&temp_dir);
+        session.status = SessionStatus::Stopped;
+        let before = session.last_activity;
+
+        assert!(!record(&mut session, Some(&alerts(1_760_000_900, true))));
+        assert_eq!(session.last_activity, before);
+        assert!(!session.needs_attention);
+    }
+}
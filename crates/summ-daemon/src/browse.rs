@@ -0,0 +1,236 @@
+// summ-daemon/src/browse.rs
+// Reading a session's workspace over the socket (`summ ls`, `summ get-file`)
+use chrono::{DateTime, Utc};
+use std::fs::{self, File, Metadata};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use summ_common::protocol::MAX_FILE_CHUNK_BYTES;
+use summ_common::{DaemonError, FileChunk, FileEntry, FileKind, FileListing};
+
+/// Most entries one listing returns; a longer one is marked truncated
+pub const MAX_LIST_ENTRIES: usize = 10_000;
+
+/// Resolve `relative` inside `workspace`, following symlinks, and refuse
+/// paths that do not exist or that end up outside the workspace
+fn resolve(root: &Path, relative: &Path) -> Result<PathBuf, DaemonError> {
+    for component in relative.components() {
+        match component {
+            Component::Normal(_) | Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(DaemonError::e010(format!(
+                    "Invalid path {:?}: `..` would leave the workspace",
+                    relative
+                )));
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(DaemonError::e010(format!(
+                    "Invalid path {:?}: must be relative to the workspace",
+                    relative
+                )));
+            }
+        }
+    }
+    let resolved = fs::canonicalize(root.join(relative))
+        .map_err(|_| DaemonError::e010(format!("{} does not exist in the workspace", relative.display())))?;
+    if !resolved.starts_with(root) {
+        return Err(DaemonError::e010(format!(
+            "{} leaves the workspace through a symlink",
+            relative.display()
+        )));
+    }
+    Ok(resolved)
+}
+
+/// The workspace itself, resolved
+fn root(workspace: &Path) -> Result<PathBuf, DaemonError> {
+    fs::canonicalize(workspace).map_err(|e| read_error(workspace, e))
+}
+
+/// List `subpath` of the workspace (its root when `None`), descending
+/// `depth` levels of directories. Symlinks are listed but never followed.
+pub fn list(workspace: &Path, subpath: Option<&Path>, depth: u32) -> Result<FileListing, DaemonError> {
+    let root = root(workspace)?;
+    let dir = resolve(&root, subpath.unwrap_or(Path::new("")))?;
+    if !dir.is_dir() {
+        return Err(DaemonError::e010(format!(
+            "{} is not a directory in the workspace",
+            subpath.unwrap_or(Path::new("")).display()
+        )));
+    }
+    let mut listing = FileListing {
+        entries: Vec::new(),
+        truncated: false,
+    };
+    visit(&root, &dir, depth.max(1), &mut listing)?;
+    Ok(listing)
+}
+
+fn visit(root: &Path, dir: &Path, depth: u32, listing: &mut FileListing) -> Result<(), DaemonError> {
+    let mut children: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| read_error(dir, e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    children.sort();
+    for child in children {
+        if listing.entries.len() >= MAX_LIST_ENTRIES {
+            listing.truncated = true;
+            return Ok(());
+        }
+        // Vanished since read_dir: skip it
+        let Ok(metadata) = child.symlink_metadata() else {
+            continue;
+        };
+        let entry = entry(root, &child, &metadata);
+        let descend = entry.kind == FileKind::Directory && depth > 1;
+        listing.entries.push(entry);
+        if descend {
+            visit(root, &child, depth - 1, listing)?;
+        }
+    }
+    Ok(())
+}
+
+fn entry(root: &Path, path: &Path, metadata: &Metadata) -> FileEntry {
+    let kind = if metadata.file_type().is_symlink() {
+        FileKind::Symlink
+    } else if metadata.is_dir() {
+        FileKind::Directory
+    } else {
+        FileKind::File
+    };
+    FileEntry {
+        path: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+        kind,
+        size: if kind == FileKind::Directory { 0 } else { metadata.len() },
+        modified: modified(metadata),
+    }
+}
+
+fn modified(metadata: &Metadata) -> Option<DateTime<Utc>> {
+    metadata.modified().ok().map(DateTime::<Utc>::from)
+}
+
+/// Read the chunk of a workspace file starting at `offset`, at most
+/// `MAX_FILE_CHUNK_BYTES` long
+pub fn read_chunk(workspace: &Path, path: &Path, offset: u64) -> Result<FileChunk, DaemonError> {
+    let root = root(workspace)?;
+    let resolved = resolve(&root, path)?;
+    let mut file = File::open(&resolved).map_err(|e| read_error(path, e))?;
+    let metadata = file.metadata().map_err(|e| read_error(path, e))?;
+    if metadata.is_dir() {
+        return Err(DaemonError::e010(format!(
+            "{} is a directory in the workspace; list it with ListFiles",
+            path.display()
+        )));
+    }
+    let size = metadata.len();
+    if offset > size {
+        return Err(DaemonError::e010(format!(
+            "Offset {} is past the end of {} ({} bytes)",
+            offset,
+            path.display(),
+            size
+        )));
+    }
+
+    let len = (size - offset).min(MAX_FILE_CHUNK_BYTES as u64);
+    let mut data = Vec::with_capacity(len as usize);
+    file.seek(SeekFrom::Start(offset)).map_err(|e| read_error(path, e))?;
+    file.take(len).read_to_end(&mut data).map_err(|e| read_error(path, e))?;
+    Ok(FileChunk {
+        path: resolved.strip_prefix(&root).unwrap_or(path).to_path_buf(),
+        offset,
+        done: offset + data.len() as u64 >= size,
+        data,
+        size,
+        modified: modified(&metadata),
+    })
+}
+
+fn read_error(path: &Path, error: std::io::Error) -> DaemonError {
+    DaemonError::e016(format!("Failed to read {}: {}", path.display(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn workspace() -> TempDir {
+        let workspace = TempDir::new().unwrap();
+        fs::create_dir_all(workspace.path().join("src/nested")).unwrap();
+        fs::write(workspace.path().join("README.md"), "# Readme\n").unwrap();
+        fs::write(workspace.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(workspace.path().join("src/nested/deep.txt"), "deep").unwrap();
+        workspace
+    }
+
+    fn paths(listing: &FileListing) -> Vec<String> {
+        listing
+            .entries
+            .iter()
+            .map(|entry| entry.path.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_list() {
+        let workspace = workspace();
+        let listing = list(workspace.path(), None, 1).unwrap();
+        assert_eq!(paths(&listing), ["README.md", "src"]);
+        assert!(!listing.truncated);
+        assert_eq!(listing.entries[0].kind, FileKind::File);
+        assert_eq!(listing.entries[0].size, 9);
+        assert!(listing.entries[0].modified.is_some());
+        assert_eq!(listing.entries[1].kind, FileKind::Directory);
+
+        let listing = list(workspace.path(), Some(Path::new("src")), 2).unwrap();
+        assert_eq!(paths(&listing), ["src/main.rs", "src/nested", "src/nested/deep.txt"]);
+
+        let error = list(workspace.path(), Some(Path::new("README.md")), 1).unwrap_err();
+        assert_eq!(error.code.code(), "E010");
+        let error = list(workspace.path(), Some(Path::new("missing")), 1).unwrap_err();
+        assert_eq!(error.message, "missing does not exist in the workspace");
+    }
+
+    #[test]
+    fn test_paths_outside_workspace_are_refused() {
+        let workspace = workspace();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(outside.path(), workspace.path().join("out")).unwrap();
+
+        // The symlink is listed, not followed
+        let listing = list(workspace.path(), None, 3).unwrap();
+        let out = listing.entries.iter().find(|entry| entry.path == Path::new("out")).unwrap();
+        assert_eq!(out.kind, FileKind::Symlink);
+        assert!(!paths(&listing).contains(&"out/secret.txt".to_string()));
+
+        for path in ["out/secret.txt", "../secret.txt", "/etc/passwd"] {
+            let error = read_chunk(workspace.path(), Path::new(path), 0).unwrap_err();
+            assert_eq!(error.code.code(), "E010", "{}", path);
+        }
+        assert!(list(workspace.path(), Some(Path::new("out")), 1).is_err());
+    }
+
+    #[test]
+    fn test_read_chunk_round_trips_binary_files() {
+        let workspace = workspace();
+        let data: Vec<u8> = (0..=255u8).cycle().take(MAX_FILE_CHUNK_BYTES + 10).collect();
+        fs::write(workspace.path().join("blob.bin"), &data).unwrap();
+
+        let first = read_chunk(workspace.path(), Path::new("./blob.bin"), 0).unwrap();
+        assert_eq!(first.path, Path::new("blob.bin"));
+        assert_eq!(first.data.len(), MAX_FILE_CHUNK_BYTES);
+        assert_eq!(first.size, data.len() as u64);
+        assert!(!first.done);
+        let rest = read_chunk(workspace.path(), Path::new("blob.bin"), first.data.len() as u64).unwrap();
+        assert!(rest.done);
+        assert_eq!([first.data, rest.data].concat(), data);
+
+        let error = read_chunk(workspace.path(), Path::new("blob.bin"), data.len() as u64 + 1).unwrap_err();
+        assert_eq!(error.code.code(), "E010");
+        let error = read_chunk(workspace.path(), Path::new("src"), 0).unwrap_err();
+        assert_eq!(error.code.code(), "E010");
+    }
+}
@@ -0,0 +1,164 @@
+// summ-daemon/src/busy.rs
+// How long a session's CLI has been busy on its task, and the alert for one
+// that stays busy too long (often a sign it is stuck in a loop)
+use chrono::{DateTime, Utc};
+use summ_common::{CliState, CliStatus, DaemonConfig, LifecycleEvent, Session, SessionStatus, TimelineKind};
+use tracing::warn;
+
+use crate::attention;
+use crate::events;
+use crate::lifecycle;
+use crate::storage;
+
+/// Update `busy_since` for a status change from `previous`; returns true when
+/// it changed. The CLI starts a task when it goes from idle to running, at
+/// the time of the busy hook report that says so (now, without one). A task
+/// ends when the CLI is idle again or the session ends; a pause only holds it.
+pub fn record(session: &mut Session, previous: &SessionStatus, report: Option<&CliStatus>) -> bool {
+    let busy_since = match (previous, &session.status) {
+        (SessionStatus::Idle, SessionStatus::Running) => Some(started_at(report, Utc::now())),
+        (_, SessionStatus::Running | SessionStatus::Paused) => return false,
+        _ => None,
+    };
+    if busy_since == session.busy_since {
+        return false;
+    }
+    session.busy_since = busy_since;
+    session.busy_alerted = false;
+    storage::save(session).ok();
+    true
+}
+
+/// When the task began: the busy report's timestamp, unless there is none or
+/// it is in the future
+fn started_at(report: Option<&CliStatus>, now: DateTime<Utc>) -> DateTime<Utc> {
+    report
+        .filter(|report| report.state == CliState::Busy)
+        .map(|report| report.timestamp.min(now))
+        .unwrap_or(now)
+}
+
+/// Minutes of busy work after which the session alerts: its own setting,
+/// else the config's; `None` when it never does
+fn alert_minutes(session: &Session, config: &DaemonConfig) -> Option<u64> {
+    session
+        .busy_alert_minutes
+        .or(config.busy_alert_minutes)
+        .filter(|minutes| *minutes > 0)
+}
+
+/// Whether the session has been busy past its alert threshold and has not
+/// alerted for this task yet
+pub fn is_overdue(session: &Session, config: &DaemonConfig) -> bool {
+    match (alert_minutes(session, config), session.busy_seconds()) {
+        (Some(minutes), Some(seconds)) => !session.busy_alerted && seconds >= minutes * 60,
+        _ => false,
+    }
+}
+
+/// Alert once for a task that has run too long: journal it, run
+/// `attention_command` and the `busy_alert` lifecycle hooks
+pub fn alert(session: &mut Session, config: &DaemonConfig) {
+    let minutes = session.busy_seconds().unwrap_or_default() / 60;
+    warn!("Session {} has been busy for {} minutes", session.session_id, minutes);
+    session.busy_alerted = true;
+    events::record(session, TimelineKind::BusyAlert, format!("busy for {}m", minutes));
+    storage::save(session).ok();
+    attention::notify_busy(session, config);
+    lifecycle::fire(session, LifecycleEvent::BusyAlert);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionExt;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn session(temp_dir: &TempDir) -> Session {
+        let workdir = temp_dir.path().join("busy001");
+        crate::init::create_session_structure(&workdir).unwrap();
+        Session {
+            session_id: "busy001".to_string(),
+            tmux_session: "summ-busy001".to_string(),
+            workdir,
+            status: SessionStatus::Running,
+            ..Default::default()
+        }
+    }
+
+    /// Write a hook report and apply the status it describes, as the monitor does
+    fn report(session: &mut Session, state: &str, at: DateTime<Utc>) -> bool {
+        let status = serde_json::json!({"state": state, "timestamp": at});
+        fs::write(session.workdir.join("runtime/status.json"), status.to_string()).unwrap();
+        let previous = session.status.clone();
+        session.status = session.reported_status();
+        record(session, &previous, session.read_cli_status().as_ref())
+    }
+
+    #[test]
+    fn test_busy_since_follows_reports() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = session(&temp_dir);
+        let now = Utc::now();
+
+        // Running from the start is not a task begun
+        assert!(!report(&mut session, "busy", now - chrono::Duration::seconds(50)));
+        assert_eq!(session.busy_since, None);
+
+        assert!(!report(&mut session, "idle", now - chrono::Duration::seconds(40)));
+        let submitted = now - chrono::Duration::seconds(30);
+        assert!(report(&mut session, "busy", submitted));
+        assert_eq!(session.busy_since, Some(submitted));
+        assert!(session.busy_seconds().unwrap() >= 30);
+        assert_eq!(Session::load_metadata(&session.workdir).unwrap().busy_since, Some(submitted));
+
+        // Still busy, or paused: the task goes on
+        assert!(!report(&mut session, "busy", now - chrono::Duration::seconds(10)));
+        let previous = session.status.clone();
+        session.status = SessionStatus::Paused;
+        assert!(!record(&mut session, &previous, None));
+        session.status = SessionStatus::Running;
+        assert_eq!(session.busy_since, Some(submitted));
+
+        assert!(report(&mut session, "waiting_for_input", now));
+        assert_eq!(session.busy_since, None);
+    }
+
+    #[test]
+    fn test_alert_fires_once_per_task() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = session(&temp_dir);
+        let config = DaemonConfig {
+            busy_alert_minutes: Some(30),
+            ..DaemonConfig::with_base_dir(temp_dir.path())
+        };
+        let now = Utc::now();
+
+        // Reports are fresh; the task is made older by hand
+        report(&mut session, "idle", now - chrono::Duration::seconds(20));
+        report(&mut session, "busy", now - chrono::Duration::seconds(10));
+        assert!(!is_overdue(&session, &config));
+
+        session.busy_since = Some(now - chrono::Duration::minutes(45));
+        assert!(is_overdue(&session, &config));
+        alert(&mut session, &config);
+        assert!(!is_overdue(&session, &config));
+        let journal = events::read(&session);
+        assert_eq!(journal.last().unwrap().kind, TimelineKind::BusyAlert);
+        assert_eq!(journal.last().unwrap().detail, "busy for 45m");
+
+        // The next task may alert again
+        report(&mut session, "idle", now - chrono::Duration::seconds(5));
+        report(&mut session, "busy", now);
+        assert!(!session.busy_alerted);
+        session.busy_since = Some(now - chrono::Duration::minutes(35));
+        assert!(is_overdue(&session, &config));
+
+        // A session's own setting wins, and 0 turns the alert off
+        session.busy_alert_minutes = Some(0);
+        assert!(!is_overdue(&session, &config));
+        session.busy_alert_minutes = Some(60);
+        assert!(!is_overdue(&session, &config));
+    }
+}
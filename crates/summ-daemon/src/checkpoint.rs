@@ -0,0 +1,392 @@
+// summ-daemon/src/checkpoint.rs
+// Opt-in git checkpoint of a session's workspace once the session has stopped
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use summ_common::{Checkpoint, CheckpointMode, Session, SessionStatus};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::storage;
+
+/// Longest a single git command may run before it is killed
+const GIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// git's well-known empty tree, the base of a patch in a repository without commits
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// Patch written in patch mode, relative to the session directory
+const PATCH_FILE: &str = "runtime/final.patch";
+
+/// Scratch index, so the workspace's own index is never touched
+const SCRATCH_INDEX: &str = "runtime/checkpoint.index";
+
+/// Sessions with a checkpoint being taken
+static IN_FLIGHT: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Whether a session asked for a checkpoint, has stopped, and has none yet
+pub fn is_due(session: &Session) -> bool {
+    session.git_checkpoint.is_some()
+        && session.status == SessionStatus::Stopped
+        && session.checkpoint.is_none()
+}
+
+/// Take the checkpoint of a due session in the background and record it on
+/// the session; at most one runs per session
+pub fn spawn(sessions: Arc<RwLock<HashMap<String, Session>>>, session: Session) {
+    let Some(mode) = session.git_checkpoint else {
+        return;
+    };
+    let id = session.session_id.clone();
+    if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+        if in_flight.contains(&id) {
+            return;
+        }
+        in_flight.push(id.clone());
+    }
+
+    tokio::spawn(async move {
+        let checkpoint = tokio::task::spawn_blocking(move || take(&session, mode))
+            .await
+            .unwrap_or_else(|e| failed(mode, format!("Checkpoint task failed: {}", e)));
+        if let Some(session) = sessions.write().await.get_mut(&id) {
+            session.checkpoint = Some(checkpoint);
+            storage::save(session).ok();
+        }
+        if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+            in_flight.retain(|other| *other != id);
+        }
+    });
+}
+
+fn empty(mode: CheckpointMode) -> Checkpoint {
+    Checkpoint {
+        mode,
+        created_at: chrono::Utc::now(),
+        commit: None,
+        branch: None,
+        patch_path: None,
+        warning: None,
+    }
+}
+
+fn failed(mode: CheckpointMode, warning: String) -> Checkpoint {
+    Checkpoint {
+        warning: Some(warning),
+        ..empty(mode)
+    }
+}
+
+/// Checkpoint the session's workspace; failures end up in `warning`
+pub fn take(session: &Session, mode: CheckpointMode) -> Checkpoint {
+    let mut checkpoint = empty(mode);
+    let scratch_index = session.workdir.join(SCRATCH_INDEX);
+    let result = capture(session, mode, &mut checkpoint, &scratch_index);
+    let _ = std::fs::remove_file(&scratch_index);
+
+    match result {
+        Ok(()) => info!(
+            "Checkpointed session {}: {}",
+            session.session_id,
+            checkpoint
+                .commit
+                .as_deref()
+                .map(|commit| format!("{} on {}", commit, checkpoint.branch.as_deref().unwrap_or("")))
+                .or_else(|| checkpoint.patch_path.as_ref().map(|path| path.display().to_string()))
+                .unwrap_or_default()
+        ),
+        Err(e) => {
+            warn!("Failed to checkpoint session {}: {:#}", session.session_id, e);
+            checkpoint.warning = Some(format!("{:#}", e));
+        }
+    }
+    checkpoint
+}
+
+fn capture(
+    session: &Session,
+    mode: CheckpointMode,
+    checkpoint: &mut Checkpoint,
+    scratch_index: &Path,
+) -> Result<()> {
+    let workspace = session.workdir.join("workspace");
+    if !workspace.join(".git").exists() {
+        bail!("Workspace is not a git repository");
+    }
+    let git = Git { workspace: &workspace, index: scratch_index };
+
+    // Stage everything (respecting .gitignore) into the scratch index
+    let head = git.run_optional(&["rev-parse", "--verify", "--quiet", "HEAD^{commit}"]);
+    let _ = std::fs::remove_file(scratch_index);
+    if head.is_some() {
+        git.run(&["read-tree", "HEAD"])?;
+    }
+    git.run(&["add", "--all"])?;
+    let tree = git.run(&["write-tree"])?;
+
+    match mode {
+        CheckpointMode::Commit => {
+            let branch = format!("summ/{}", session.session_id);
+            let reference = format!("refs/heads/{}", branch);
+            let parent = git
+                .run_optional(&["rev-parse", "--verify", "--quiet", &reference])
+                .or(head);
+            let message = format!("summ checkpoint: {} {}", session.session_id, stop_reason(session));
+            let mut args = vec!["commit-tree", tree.as_str(), "-m", message.as_str()];
+            if let Some(parent) = &parent {
+                args.extend(["-p", parent.as_str()]);
+            }
+            let commit = git.run(&args)?;
+            git.run(&["update-ref", &reference, &commit])?;
+            checkpoint.commit = Some(commit);
+            checkpoint.branch = Some(branch);
+        }
+        CheckpointMode::Patch => {
+            let base = head.as_deref().unwrap_or(EMPTY_TREE);
+            let patch = git.output(&["diff", "--binary", base, &tree])?;
+            let path = session.workdir.join(PATCH_FILE);
+            std::fs::write(&path, patch)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            checkpoint.patch_path = Some(path);
+        }
+    }
+    Ok(())
+}
+
+/// The stop reason's `kind`, for the commit message
+fn stop_reason(session: &Session) -> String {
+    session
+        .stop_reason
+        .as_ref()
+        .and_then(|reason| serde_json::to_value(reason).ok())
+        .and_then(|value| value["kind"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "stopped".to_string())
+}
+
+/// git in a workspace, with the scratch index
+struct Git<'a> {
+    workspace: &'a Path,
+    index: &'a Path,
+}
+
+impl Git<'_> {
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(self.workspace)
+            .args(args)
+            .env("GIT_INDEX_FILE", self.index)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // commit-tree needs an identity; supply one only if git has none
+        if args.first() == Some(&"commit-tree") && !self.has_identity() {
+            for key in ["GIT_AUTHOR_NAME", "GIT_COMMITTER_NAME"] {
+                command.env(key, "summ-daemon");
+            }
+            for key in ["GIT_AUTHOR_EMAIL", "GIT_COMMITTER_EMAIL"] {
+                command.env(key, "summ-daemon@localhost");
+            }
+        }
+        command
+    }
+
+    fn has_identity(&self) -> bool {
+        Command::new("git")
+            .arg("-C")
+            .arg(self.workspace)
+            .args(["var", "GIT_COMMITTER_IDENT"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    /// Raw stdout of a successful command
+    fn output(&self, args: &[&str]) -> Result<Vec<u8>> {
+        let mut child = self
+            .command(args)
+            .spawn()
+            .context("Failed to run git")?;
+        // Read stdout on a thread so a large diff cannot fill the pipe while we wait
+        let mut stdout = child.stdout.take().expect("piped stdout");
+        let reader = std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            std::io::Read::read_to_end(&mut stdout, &mut buffer).map(|_| buffer)
+        });
+
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() > GIT_TIMEOUT {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!("git {} timed out after {}s", args[0], GIT_TIMEOUT.as_secs());
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        let stdout = reader
+            .join()
+            .map_err(|_| anyhow::anyhow!("Failed to read git output"))??;
+        if !status.success() {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                let _ = std::io::Read::read_to_string(&mut pipe, &mut stderr);
+            }
+            bail!("git {} failed: {}", args[0], stderr.trim());
+        }
+        Ok(stdout)
+    }
+
+    /// Trimmed stdout of a successful command
+    fn run(&self, args: &[&str]) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.output(args)?).trim().to_string())
+    }
+
+    /// Trimmed stdout, or `None` if the command failed
+    fn run_optional(&self, args: &[&str]) -> Option<String> {
+        self.run(args).ok().filter(|output| !output.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use summ_common::StopReason;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// A stopped session whose workspace is a repository with one commit and
+    /// then a modified, an untracked, and an ignored file
+    fn session_with_repo(temp_dir: &TempDir, mode: CheckpointMode) -> Session {
+        let workdir = temp_dir.path().join("session_abc");
+        let workspace = workdir.join("workspace");
+        fs::create_dir_all(workdir.join("runtime")).unwrap();
+        fs::create_dir_all(&workspace).unwrap();
+        git(&workspace, &["init", "--quiet", "--initial-branch=main"]);
+        fs::write(workspace.join("README.md"), "one\n").unwrap();
+        fs::write(workspace.join(".gitignore"), "target/\n").unwrap();
+        git(&workspace, &["add", "."]);
+        git(&workspace, &["commit", "--quiet", "-m", "initial"]);
+
+        fs::write(workspace.join("README.md"), "one\ntwo\n").unwrap();
+        fs::write(workspace.join("notes.txt"), "agent work\n").unwrap();
+        fs::create_dir_all(workspace.join("target")).unwrap();
+        fs::write(workspace.join("target/out.bin"), "build output").unwrap();
+
+        Session {
+            session_id: "session_abc".to_string(),
+            workdir,
+            status: SessionStatus::Stopped,
+            stop_reason: Some(StopReason::UserRequested { method: None }),
+            git_checkpoint: Some(mode),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        let mut session = Session {
+            status: SessionStatus::Idle,
+            git_checkpoint: Some(CheckpointMode::Commit),
+            ..Default::default()
+        };
+        assert!(!is_due(&session));
+        session.status = SessionStatus::Stopped;
+        assert!(is_due(&session));
+        session.checkpoint = Some(failed(CheckpointMode::Commit, "x".to_string()));
+        assert!(!is_due(&session));
+        session.checkpoint = None;
+        session.git_checkpoint = None;
+        assert!(!is_due(&session));
+    }
+
+    #[test]
+    fn test_commit_checkpoint_leaves_workspace_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = session_with_repo(&temp_dir, CheckpointMode::Commit);
+        let workspace = session.workdir.join("workspace");
+        let head = git(&workspace, &["rev-parse", "HEAD"]);
+        let status = git(&workspace, &["status", "--porcelain"]);
+
+        let checkpoint = take(&session, CheckpointMode::Commit);
+        assert_eq!(checkpoint.warning, None);
+        assert_eq!(checkpoint.branch.as_deref(), Some("summ/session_abc"));
+        let commit = checkpoint.commit.unwrap();
+        assert_eq!(git(&workspace, &["rev-parse", "summ/session_abc"]), commit);
+        assert_eq!(git(&workspace, &["rev-parse", &format!("{}^", commit)]), head);
+        assert_eq!(
+            git(&workspace, &["log", "-1", "--format=%s", &commit]),
+            "summ checkpoint: session_abc user_requested"
+        );
+        assert_eq!(git(&workspace, &["show", &format!("{}:notes.txt", commit)]), "agent work");
+        assert_eq!(git(&workspace, &["show", &format!("{}:README.md", commit)]), "one\ntwo");
+        // Ignored files stay out
+        let files = git(&workspace, &["ls-tree", "-r", "--name-only", &commit]);
+        assert!(!files.contains("target/"), "{}", files);
+
+        // HEAD, index, and working tree are as they were
+        assert_eq!(git(&workspace, &["rev-parse", "HEAD"]), head);
+        assert_eq!(git(&workspace, &["status", "--porcelain"]), status);
+        assert!(!session.workdir.join(SCRATCH_INDEX).exists());
+
+        // A second checkpoint builds on the branch
+        let again = take(&session, CheckpointMode::Commit).commit.unwrap();
+        assert_eq!(git(&workspace, &["rev-parse", &format!("{}^", again)]), commit);
+    }
+
+    #[test]
+    fn test_patch_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = session_with_repo(&temp_dir, CheckpointMode::Patch);
+
+        let checkpoint = take(&session, CheckpointMode::Patch);
+        assert_eq!(checkpoint.warning, None);
+        assert_eq!(checkpoint.commit, None);
+        let path = checkpoint.patch_path.unwrap();
+        assert_eq!(path, session.workdir.join(PATCH_FILE));
+        let patch = fs::read_to_string(path).unwrap();
+        assert!(patch.contains("+two"), "{}", patch);
+        assert!(patch.contains("b/notes.txt"), "{}", patch);
+        assert!(!patch.contains("out.bin"), "{}", patch);
+    }
+
+    #[test]
+    fn test_checkpoint_without_git_is_a_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let workdir = temp_dir.path().join("session_abc");
+        fs::create_dir_all(workdir.join("workspace")).unwrap();
+        let session = Session {
+            session_id: "session_abc".to_string(),
+            workdir,
+            status: SessionStatus::Stopped,
+            ..Default::default()
+        };
+
+        let checkpoint = take(&session, CheckpointMode::Commit);
+        assert_eq!(checkpoint.warning.as_deref(), Some("Workspace is not a git repository"));
+        assert_eq!(checkpoint.commit, None);
+    }
+}
@@ -0,0 +1,129 @@
+// summ-daemon/src/clock.rs
+// Ages of wall-clock timestamps that stay steady when NTP steps the clock
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Age of `then` at `now`; timestamps in the future are zero seconds old
+pub fn wall_age(then: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+    (now - then).to_std().unwrap_or_default()
+}
+
+/// When a timestamp was first seen, and how old the wall clock said it was
+struct Sighting {
+    timestamp: DateTime<Utc>,
+    at: Instant,
+    age: Duration,
+}
+
+/// Wall-clock timestamps that must survive restarts (hook reports, creation
+/// times) are read once through the wall clock. From then on their age grows
+/// with the monotonic clock, so a clock step after the first sighting
+/// neither ages them nor makes them younger.
+#[derive(Default)]
+pub struct AgeTracker {
+    seen: Mutex<HashMap<String, Sighting>>,
+}
+
+impl AgeTracker {
+    /// Age of the timestamp currently held under `key` (one per session and
+    /// purpose); a new timestamp under the key starts a new sighting
+    pub fn age(&self, key: &str, timestamp: DateTime<Utc>) -> Duration {
+        self.age_at(key, timestamp, Utc::now(), Instant::now())
+    }
+
+    fn age_at(&self, key: &str, timestamp: DateTime<Utc>, wall_now: DateTime<Utc>, now: Instant) -> Duration {
+        let mut seen = self.seen.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match seen.get(key) {
+            Some(sighting) if sighting.timestamp == timestamp => {
+                sighting.age + now.saturating_duration_since(sighting.at)
+            }
+            _ => {
+                let age = wall_age(timestamp, wall_now);
+                seen.insert(key.to_string(), Sighting { timestamp, at: now, age });
+                age
+            }
+        }
+    }
+
+    /// Drop the sightings of sessions `keep` turns down
+    fn retain(&self, keep: impl Fn(&str) -> bool) {
+        let mut seen = self.seen.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        seen.retain(|key, _| keep(key.rsplit_once(':').map_or(key.as_str(), |(session_id, _)| session_id)));
+    }
+}
+
+static TRACKER: LazyLock<AgeTracker> = LazyLock::new(AgeTracker::default);
+
+/// Age of a session's latest status report (or its creation, without one)
+pub fn status_age(session_id: &str, since: DateTime<Utc>) -> Duration {
+    TRACKER.age(&format!("{}:status", session_id), since)
+}
+
+/// Time since a session was created
+pub fn session_age(session_id: &str, created_at: DateTime<Utc>) -> Duration {
+    TRACKER.age(&format!("{}:created", session_id), created_at)
+}
+
+/// Forget sessions the daemon no longer tracks
+pub fn retain_sessions(keep: impl Fn(&str) -> bool) {
+    TRACKER.retain(keep);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_760_000_000 + seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn test_future_timestamps_are_fresh() {
+        assert_eq!(wall_age(at(60), at(0)), Duration::ZERO);
+        assert_eq!(wall_age(at(0), at(90)), Duration::from_secs(90));
+
+        // Written after the clock stepped back an hour: fresh, and ageing normally
+        let tracker = AgeTracker::default();
+        let start = Instant::now();
+        assert_eq!(tracker.age_at("s:status", at(3600), at(0), start), Duration::ZERO);
+        assert_eq!(
+            tracker.age_at("s:status", at(3600), at(10), start + Duration::from_secs(10)),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn test_clock_steps_after_sighting_are_ignored() {
+        let tracker = AgeTracker::default();
+        let start = Instant::now();
+        assert_eq!(tracker.age_at("s:status", at(-5), at(0), start), Duration::from_secs(5));
+
+        // The clock jumps an hour ahead, then an hour back: only real time counts
+        let later = start + Duration::from_secs(20);
+        assert_eq!(tracker.age_at("s:status", at(-5), at(3620), later), Duration::from_secs(25));
+        assert_eq!(tracker.age_at("s:status", at(-5), at(-3580), later), Duration::from_secs(25));
+
+        // A new report under the key is read through the wall clock again
+        assert_eq!(tracker.age_at("s:status", at(-3590), at(-3580), later), Duration::from_secs(10));
+        // Other keys are tracked on their own
+        assert_eq!(tracker.age_at("t:status", at(-5), at(3620), later), Duration::from_secs(3625));
+    }
+
+    #[test]
+    fn test_retain_forgets_other_sessions() {
+        let tracker = AgeTracker::default();
+        let start = Instant::now();
+        tracker.age_at("kept:status", at(-5), at(0), start);
+        tracker.age_at("kept:created", at(-5), at(0), start);
+        tracker.age_at("gone:status", at(-5), at(0), start);
+        tracker.age_at("gone:created", at(-5), at(0), start);
+
+        tracker.retain(|id| id == "kept");
+        let seen = tracker.seen.lock().unwrap();
+        let mut keys: Vec<&str> = seen.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["kept:created", "kept:status"]);
+    }
+}
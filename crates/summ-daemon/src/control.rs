@@ -0,0 +1,175 @@
+// summ-daemon/src/control.rs
+// Raw key and signal delivery to session panes, plus pause/resume
+use anyhow::{Context, Result};
+use std::fs;
+use summ_common::DaemonConfig;
+
+use crate::prompt::cli_name;
+
+/// Named tmux keys that clients are allowed to send
+const ALLOWED_KEY_NAMES: &[&str] = &[
+    "Enter", "Escape", "Tab", "BTab", "BSpace", "Space", "Up", "Down", "Left", "Right",
+    "Home", "End", "PageUp", "PageDown", "PPage", "NPage", "IC", "DC",
+    "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+];
+
+/// Signals that clients are allowed to send, by name
+const ALLOWED_SIGNALS: &[(&str, libc::c_int)] = &[
+    ("INT", libc::SIGINT),
+    ("TERM", libc::SIGTERM),
+    ("HUP", libc::SIGHUP),
+    ("QUIT", libc::SIGQUIT),
+];
+
+/// Check whether a tmux key name is on the allowlist.
+///
+/// Accepts named keys (`Enter`, `Escape`, `Up`, ...), control combinations
+/// (`C-c`) and single alphanumeric characters (`y`, `n`, `1`).
+pub fn is_allowed_key(key: &str) -> bool {
+    if ALLOWED_KEY_NAMES.contains(&key) {
+        return true;
+    }
+    if let Some(rest) = key.strip_prefix("C-") {
+        let mut chars = rest.chars();
+        return matches!((chars.next(), chars.next()), (Some(c), None) if c.is_ascii_lowercase());
+    }
+    let mut chars = key.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if c.is_ascii_alphanumeric())
+}
+
+/// Validate a list of keys, returning the first rejected one
+pub fn validate_keys(keys: &[String]) -> std::result::Result<(), String> {
+    if keys.is_empty() {
+        return Err("No keys given".to_string());
+    }
+    match keys.iter().find(|k| !is_allowed_key(k)) {
+        Some(key) => Err(format!("Key not allowed: {}", key)),
+        None => Ok(()),
+    }
+}
+
+/// Resolve a signal name such as `INT` or `SIGINT` (case-insensitive)
+pub fn parse_signal(name: &str) -> Option<libc::c_int> {
+    let upper = name.to_ascii_uppercase();
+    let bare = upper.strip_prefix("SIG").unwrap_or(&upper);
+    ALLOWED_SIGNALS
+        .iter()
+        .find(|(n, _)| *n == bare)
+        .map(|(_, sig)| *sig)
+}
+
+/// Names of all signals accepted by `parse_signal`
+pub fn allowed_signal_names() -> Vec<&'static str> {
+    ALLOWED_SIGNALS.iter().map(|(n, _)| *n).collect()
+}
+
+/// Find the foreground process group of the terminal a process is attached to.
+///
+/// Reads `tpgid` from `/proc/<pid>/stat`; returns None if unavailable.
+pub fn foreground_pgid(pid: u32) -> Option<i32> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name may contain spaces, so parse after the closing paren
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    // state ppid pgrp session tty_nr tpgid ...
+    let tpgid: i32 = fields.get(5)?.parse().ok()?;
+    (tpgid > 0).then_some(tpgid)
+}
+
+/// Send a signal to the foreground process group of a pane, falling back to the pane process
+pub fn signal_pane(pane_pid: u32, signal: libc::c_int) -> Result<()> {
+    let target = match foreground_pgid(pane_pid) {
+        Some(pgid) => -pgid,
+        None => i32::try_from(pane_pid).context("Invalid pane PID")?,
+    };
+    // SAFETY: kill(2) has no memory-safety preconditions
+    let rc = unsafe { libc::kill(target, signal) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error())
+            .context(format!("Failed to send signal {} to {}", signal, target));
+    }
+    Ok(())
+}
+
+/// Freeze the pane's foreground process group with SIGSTOP
+pub fn pause_pane(pane_pid: u32) -> Result<()> {
+    signal_pane(pane_pid, libc::SIGSTOP)
+}
+
+/// Continue the pane's foreground process group with SIGCONT
+pub fn resume_pane(pane_pid: u32) -> Result<()> {
+    signal_pane(pane_pid, libc::SIGCONT)
+}
+
+/// Text typed into a CLI to make it exit when its session is stopped:
+/// `quit_commands` for its name, else the built-in command (Claude Code exits
+/// on `/exit`); None when the CLI has none and is stopped by killing its
+/// tmux session
+pub fn quit_command(config: &DaemonConfig, cli: &str) -> Option<String> {
+    let name = cli_name(cli);
+    match config.quit_commands.get(name) {
+        Some(command) => Some(command.clone()).filter(|command| !command.trim().is_empty()),
+        None if name == "claude" => Some("/exit".to_string()),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_keys() {
+        assert!(is_allowed_key("Enter"));
+        assert!(is_allowed_key("Escape"));
+        assert!(is_allowed_key("C-c"));
+        assert!(is_allowed_key("y"));
+        assert!(is_allowed_key("1"));
+    }
+
+    #[test]
+    fn test_rejected_keys() {
+        assert!(!is_allowed_key("rm -rf /"));
+        assert!(!is_allowed_key("C-"));
+        assert!(!is_allowed_key("C-cc"));
+        assert!(!is_allowed_key("M-x"));
+        assert!(!is_allowed_key(";"));
+        assert!(!is_allowed_key(""));
+    }
+
+    #[test]
+    fn test_validate_keys() {
+        assert!(validate_keys(&["C-c".to_string(), "Enter".to_string()]).is_ok());
+        assert!(validate_keys(&[]).is_err());
+        let err = validate_keys(&["Enter".to_string(), "echo hi".to_string()]).unwrap_err();
+        assert!(err.contains("echo hi"));
+    }
+
+    #[test]
+    fn test_parse_signal() {
+        assert_eq!(parse_signal("INT"), Some(libc::SIGINT));
+        assert_eq!(parse_signal("sigterm"), Some(libc::SIGTERM));
+        assert_eq!(parse_signal("hup"), Some(libc::SIGHUP));
+        assert_eq!(parse_signal("KILL"), None);
+        assert_eq!(parse_signal("STOP"), None);
+    }
+
+    #[test]
+    fn test_foreground_pgid_of_self() {
+        // Only checks that parsing does not panic; the test runner may have no tty
+        let _ = foreground_pgid(std::process::id());
+        assert_eq!(foreground_pgid(0), None);
+    }
+
+    #[test]
+    fn test_quit_command() {
+        let mut config = DaemonConfig::with_base_dir(std::path::Path::new("/tmp/summ-test"));
+        assert_eq!(quit_command(&config, "/usr/bin/claude --resume").as_deref(), Some("/exit"));
+        assert_eq!(quit_command(&config, "aider"), None);
+
+        config.quit_commands.insert("aider".to_string(), "/quit".to_string());
+        config.quit_commands.insert("claude".to_string(), String::new());
+        assert_eq!(quit_command(&config, "aider --model x").as_deref(), Some("/quit"));
+        // An empty command turns the built-in one off
+        assert_eq!(quit_command(&config, "claude"), None);
+    }
+}
@@ -0,0 +1,214 @@
+// summ-daemon/src/disk.rs
+// Free-space watch on the filesystem holding the sessions directory
+use serde::Serialize;
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
+use summ_common::{DaemonConfig, DaemonError};
+use tracing::{debug, info, warn};
+
+/// How often free space is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A warning only clears once free space is this factor above its threshold,
+/// so it does not flap when usage hovers at the boundary
+const CLEAR_FACTOR: f64 = 1.25;
+
+/// Free and total bytes of a filesystem (free as available to unprivileged users)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskSpace {
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+}
+
+impl DiskSpace {
+    fn free_percent(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 100.0;
+        }
+        self.free_bytes as f64 / self.total_bytes as f64 * 100.0
+    }
+}
+
+/// statvfs the filesystem containing `path`
+pub fn disk_space(path: &Path) -> std::io::Result<DiskSpace> {
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let block_size = stat.f_frsize as u64;
+    Ok(DiskSpace {
+        free_bytes: stat.f_bavail as u64 * block_size,
+        total_bytes: stat.f_blocks as u64 * block_size,
+    })
+}
+
+/// How full the sessions filesystem is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiskLevel {
+    /// Above the warning threshold
+    #[default]
+    Ok,
+    /// Below `disk_warn`
+    Low,
+    /// Below `disk_refuse`; new sessions are refused
+    Critical,
+}
+
+/// Level for `space`, given the current level: entering a level uses the
+/// configured thresholds, staying in it uses them scaled by `CLEAR_FACTOR`
+pub fn next_level(current: DiskLevel, space: DiskSpace, config: &DaemonConfig) -> DiskLevel {
+    let factor = |level: DiskLevel| if current >= level { CLEAR_FACTOR } else { 1.0 };
+    let (free, total) = (space.free_bytes, space.total_bytes);
+    if config.disk_refuse.is_below(free, total, factor(DiskLevel::Critical)) {
+        DiskLevel::Critical
+    } else if config.disk_warn.is_below(free, total, factor(DiskLevel::Low)) {
+        DiskLevel::Low
+    } else {
+        DiskLevel::Ok
+    }
+}
+
+/// Daemon-level low-disk warning, reported by DaemonStatus
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiskWarning {
+    pub level: DiskLevel,
+    pub path: PathBuf,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+    pub message: String,
+}
+
+impl DiskWarning {
+    fn new(level: DiskLevel, path: &Path, space: DiskSpace) -> Self {
+        let mut message = format!(
+            "Only {:.1} GB ({:.1}%) free on the filesystem holding {}",
+            space.free_bytes as f64 / (1024.0 * 1024.0 * 1024.0),
+            space.free_percent(),
+            path.display()
+        );
+        if level == DiskLevel::Critical {
+            message.push_str("; new sessions are refused until space is freed");
+        }
+        Self {
+            level,
+            path: path.to_path_buf(),
+            free_bytes: space.free_bytes,
+            total_bytes: space.total_bytes,
+            message,
+        }
+    }
+}
+
+/// The current warning, set by `watch_disk`
+static DISK_WARNING: RwLock<Option<DiskWarning>> = RwLock::new(None);
+
+/// The current low-disk warning, if any
+pub fn current_warning() -> Option<DiskWarning> {
+    DISK_WARNING.read().ok().and_then(|warning| warning.clone())
+}
+
+/// Error for a Start request while the disk is below the refuse floor
+pub fn start_refusal() -> Option<DaemonError> {
+    refusal(current_warning().as_ref())
+}
+
+fn refusal(warning: Option<&DiskWarning>) -> Option<DaemonError> {
+    warning
+        .filter(|warning| warning.level == DiskLevel::Critical)
+        .map(|warning| DaemonError::e013(warning.message.clone()))
+}
+
+/// Check free space on the sessions filesystem every `CHECK_INTERVAL`,
+/// keeping `current_warning` up to date and logging level changes
+pub async fn watch_disk(config: DaemonConfig) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    let mut level = DiskLevel::Ok;
+
+    loop {
+        interval.tick().await;
+
+        let space = match disk_space(&config.sessions_dir) {
+            Ok(space) => space,
+            Err(e) => {
+                debug!("Failed to check free space on {}: {}", config.sessions_dir.display(), e);
+                continue;
+            }
+        };
+        let next = next_level(level, space, &config);
+        let warning = (next != DiskLevel::Ok).then(|| DiskWarning::new(next, &config.sessions_dir, space));
+
+        if next != level {
+            match &warning {
+                Some(warning) => warn!("Disk space {:?}: {}", next, warning.message),
+                None => info!("Disk space recovered on {}", config.sessions_dir.display()),
+            }
+            level = next;
+        }
+        if let Ok(mut current) = DISK_WARNING.write() {
+            *current = warning;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use summ_common::DiskThreshold;
+    use tempfile::TempDir;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn space(free_gb: u64) -> DiskSpace {
+        DiskSpace {
+            free_bytes: free_gb * GB,
+            total_bytes: 100 * GB,
+        }
+    }
+
+    #[test]
+    fn test_disk_space_of_temp_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let space = disk_space(temp_dir.path()).unwrap();
+        assert!(space.total_bytes > 0);
+        assert!(space.free_bytes <= space.total_bytes);
+        assert!(disk_space(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_next_level_with_hysteresis() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = DaemonConfig::with_base_dir(temp_dir.path());
+        config.disk_refuse = DiskThreshold { percent: None, mb: Some(2 * 1024) };
+
+        let mut level = DiskLevel::Ok;
+        let mut levels = Vec::new();
+        // Falling through warn (10%) and refuse (2GB), then recovering
+        for free_gb in [50, 11, 9, 11, 12, 13, 3, 1, 2, 3, 20] {
+            level = next_level(level, space(free_gb), &config);
+            levels.push(level);
+        }
+        use DiskLevel::*;
+        assert_eq!(
+            levels,
+            [Ok, Ok, Low, Low, Low, Ok, Low, Critical, Critical, Low, Ok]
+        );
+    }
+
+    #[test]
+    fn test_refusal_only_when_critical() {
+        let path = Path::new("/sessions");
+        assert!(refusal(None).is_none());
+        assert!(refusal(Some(&DiskWarning::new(DiskLevel::Low, path, space(5)))).is_none());
+
+        let error = refusal(Some(&DiskWarning::new(DiskLevel::Critical, path, space(1)))).unwrap();
+        assert_eq!(error.code.code(), "E013");
+        assert!(error.message.contains("1.0 GB (1.0%) free"), "{}", error.message);
+        assert!(error.message.contains("/sessions"));
+    }
+}
@@ -0,0 +1,177 @@
+// summ-daemon/src/ephemeral.rs
+// Throwaway sessions (`summ scratch`), removed from disk as soon as they end
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use summ_common::{LifecycleEvent, Session, SessionStatus};
+use tracing::{info, warn};
+
+use crate::checkpoint;
+use crate::lifecycle;
+
+/// Name prefix of ephemeral sessions started without a name
+pub const NAME_PREFIX: &str = "scratch-";
+
+/// `scratch-<n>`, numbered one past the highest such name among `sessions`
+pub fn next_name<'a>(sessions: impl IntoIterator<Item = &'a Session>) -> String {
+    let highest = sessions
+        .into_iter()
+        .filter_map(|session| session.name.strip_prefix(NAME_PREFIX)?.parse::<u64>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("{}{}", NAME_PREFIX, highest + 1)
+}
+
+/// Whether an ephemeral session has stopped or failed, with no checkpoint
+/// still to be taken of its workspace
+fn has_ended(session: &Session) -> bool {
+    session.ephemeral
+        && matches!(session.status, SessionStatus::Stopped | SessionStatus::Failed)
+        && !checkpoint::is_due(session)
+}
+
+/// Take the ephemeral sessions that have ended out of `sessions`, for
+/// `remove` to delete once the sessions lock is released
+pub fn take_ended(sessions: &mut HashMap<String, Session>) -> Vec<Session> {
+    let ended: Vec<String> = sessions
+        .values()
+        .filter(|session| has_ended(session))
+        .map(|session| session.session_id.clone())
+        .collect();
+    ended.iter().filter_map(|id| sessions.remove(id)).collect()
+}
+
+/// Delete a session's directory and terminal log. A failure is only warned
+/// about: the metadata left behind brings the session back on the next
+/// daemon start, which tries again.
+pub fn remove(session: &Session) {
+    let mut failed = false;
+    if let Err(e) = fs::remove_dir_all(&session.workdir) {
+        if e.kind() != ErrorKind::NotFound {
+            warn!("Failed to remove ephemeral session {}: {}", session.session_id, e);
+            failed = true;
+        }
+    }
+    if let Err(e) = fs::remove_file(&session.log_path) {
+        if e.kind() != ErrorKind::NotFound {
+            warn!("Failed to remove log of ephemeral session {}: {}", session.session_id, e);
+            failed = true;
+        }
+    }
+    lifecycle::fire(session, LifecycleEvent::Removed);
+    if !failed {
+        info!(
+            "Removed ephemeral session {} ({})",
+            session.session_id,
+            session
+                .stop_reason
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| session.status.to_string())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn session(id: &str, name: &str, status: SessionStatus, ephemeral: bool) -> Session {
+        Session {
+            session_id: id.to_string(),
+            name: name.to_string(),
+            status,
+            ephemeral,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_next_name() {
+        assert_eq!(next_name(&[]), "scratch-1");
+        let sessions = [
+            session("a", "scratch-2", SessionStatus::Idle, true),
+            session("b", "scratch-10", SessionStatus::Running, true),
+            session("c", "scratch-notes", SessionStatus::Running, false),
+            session("d", "build", SessionStatus::Running, false),
+        ];
+        assert_eq!(next_name(&sessions), "scratch-11");
+    }
+
+    #[test]
+    fn test_take_ended() {
+        let mut sessions: HashMap<String, Session> = [
+            session("stopped", "scratch-1", SessionStatus::Stopped, true),
+            session("failed", "scratch-2", SessionStatus::Failed, true),
+            session("idle", "scratch-3", SessionStatus::Idle, true),
+            session("kept", "build", SessionStatus::Stopped, false),
+            Session {
+                git_checkpoint: Some(summ_common::CheckpointMode::Patch),
+                ..session("checkpointing", "scratch-4", SessionStatus::Stopped, true)
+            },
+        ]
+        .into_iter()
+        .map(|session| (session.session_id.clone(), session))
+        .collect();
+
+        let mut ended: Vec<String> = take_ended(&mut sessions)
+            .into_iter()
+            .map(|session| session.session_id)
+            .collect();
+        ended.sort();
+        assert_eq!(ended, ["failed", "stopped"]);
+        let mut left: Vec<&String> = sessions.keys().collect();
+        left.sort();
+        assert_eq!(left, ["checkpointing", "idle", "kept"]);
+    }
+
+    #[test]
+    fn test_remove_deletes_directory_and_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = summ_common::DaemonConfig::with_base_dir(temp_dir.path());
+        let workdir = config.sessions_dir.join("scratch01");
+        crate::init::create_session_structure(&workdir).unwrap();
+        let log_path = config.session_log_path("scratch01").unwrap();
+        fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+        fs::write(&log_path, "output").unwrap();
+        let session = Session {
+            session_id: "scratch01".to_string(),
+            workdir: workdir.clone(),
+            log_path: log_path.clone(),
+            status: SessionStatus::Stopped,
+            ephemeral: true,
+            ..Default::default()
+        };
+
+        remove(&session);
+        assert!(!workdir.exists());
+        assert!(!log_path.exists());
+        // Already gone: nothing to do
+        remove(&session);
+    }
+
+    #[test]
+    fn test_remove_observer_keeps_shared_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = summ_common::DaemonConfig::with_base_dir(temp_dir.path());
+        let shared = temp_dir.path().join("primary/workspace");
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(shared.join("main.rs"), "fn main() {}").unwrap();
+        let workdir = config.sessions_dir.join("observer01");
+        fs::create_dir_all(workdir.join("runtime")).unwrap();
+        std::os::unix::fs::symlink(&shared, workdir.join("workspace")).unwrap();
+        let session = Session {
+            session_id: "observer01".to_string(),
+            workdir: workdir.clone(),
+            status: SessionStatus::Stopped,
+            ephemeral: true,
+            observer_of: Some("primary".to_string()),
+            ..Default::default()
+        };
+
+        remove(&session);
+        assert!(!workdir.exists());
+        assert!(shared.join("main.rs").exists());
+    }
+}
@@ -0,0 +1,285 @@
+// summ-daemon/src/evict.rs
+// Dropping long-ended sessions from memory; their meta.json on disk stays
+// authoritative and brings them back when a request names them
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use summ_common::{DaemonConfig, Namespace, Scope, Session, SessionStatus};
+use tracing::{debug, warn};
+
+use crate::checkpoint;
+use crate::clock;
+use crate::session::SessionExt;
+
+/// A restored session stays in memory at least this long, so requests that
+/// follow the one that brought it back find it there
+const RESTORE_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// Where an evicted session lives on disk
+#[derive(Debug, Clone)]
+struct Evicted {
+    namespace: Namespace,
+    workdir: PathBuf,
+}
+
+/// Sessions evicted from the daemon's map ("archived"). Only taken while the
+/// sessions lock is held, or with no lock held at all.
+#[derive(Debug, Default)]
+pub struct Archive {
+    evicted: Mutex<HashMap<String, Evicted>>,
+    /// When sessions were brought back from disk
+    restored: Mutex<HashMap<String, Instant>>,
+}
+
+impl Archive {
+    /// Number of archived sessions in each namespace
+    pub fn counts(&self) -> HashMap<Namespace, usize> {
+        let evicted = self.evicted.lock().unwrap_or_else(|e| e.into_inner());
+        let mut counts = HashMap::new();
+        for entry in evicted.values() {
+            *counts.entry(entry.namespace.clone()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Number of archived sessions in the namespaces of `scope`
+    pub fn count(&self, scope: &Scope) -> usize {
+        let evicted = self.evicted.lock().unwrap_or_else(|e| e.into_inner());
+        evicted.values().filter(|entry| scope.contains(&entry.namespace)).count()
+    }
+
+    /// Workspace directories of the archived sessions in `scope`
+    fn workdirs(&self, scope: &Scope) -> Vec<PathBuf> {
+        let evicted = self.evicted.lock().unwrap_or_else(|e| e.into_inner());
+        evicted
+            .values()
+            .filter(|entry| scope.contains(&entry.namespace))
+            .map(|entry| entry.workdir.clone())
+            .collect()
+    }
+
+    fn workdir(&self, session_id: &str) -> Option<PathBuf> {
+        let evicted = self.evicted.lock().unwrap_or_else(|e| e.into_inner());
+        evicted.get(session_id).map(|entry| entry.workdir.clone())
+    }
+}
+
+/// Whether a session is ended, not ephemeral (those are removed instead), and
+/// idle longer than `window` as of `now`
+fn is_stale(session: &Session, window: Duration, now: DateTime<Utc>) -> bool {
+    matches!(session.status, SessionStatus::Stopped | SessionStatus::Failed)
+        && !session.ephemeral
+        && !checkpoint::is_due(session)
+        && clock::wall_age(session.last_activity, now) > window
+}
+
+/// Move sessions that ended more than `archive_after_hours` ago (going by
+/// their last activity) out of `sessions`; returns how many. Sessions a
+/// pending start waits on or a session in memory observes stay, and so does
+/// one restored in the last few minutes.
+pub fn evict(sessions: &mut HashMap<String, Session>, archive: &Archive, config: &DaemonConfig) -> usize {
+    if config.archive_after_hours == 0 {
+        return 0;
+    }
+    let window = Duration::from_secs(config.archive_after_hours.saturating_mul(3600));
+    let now = Utc::now();
+    let referenced: HashSet<&str> = sessions
+        .values()
+        .flat_map(|session| {
+            let trigger = session
+                .start_after
+                .as_ref()
+                .filter(|_| session.status == SessionStatus::Pending)
+                .map(|trigger| trigger.session_id.as_str());
+            trigger.into_iter().chain(session.observer_of.as_deref())
+        })
+        .collect();
+
+    let mut restored = archive.restored.lock().unwrap_or_else(|e| e.into_inner());
+    restored.retain(|_, at| at.elapsed() < RESTORE_GRACE);
+    let stale: Vec<String> = sessions
+        .values()
+        .filter(|session| is_stale(session, window, now))
+        .map(|session| session.session_id.clone())
+        .filter(|id| !referenced.contains(id.as_str()) && !restored.contains_key(id))
+        .collect();
+
+    let mut evicted = archive.evicted.lock().unwrap_or_else(|e| e.into_inner());
+    for id in &stale {
+        if let Some(session) = sessions.remove(id) {
+            debug!("Archived session {} out of memory", id);
+            evicted.insert(
+                session.session_id,
+                Evicted {
+                    namespace: session.namespace,
+                    workdir: session.workdir,
+                },
+            );
+        }
+    }
+    stale.len()
+}
+
+/// Read an archived session's metadata, unless it went missing or no longer
+/// belongs to that session
+fn load(workdir: PathBuf, session_id: &str) -> Option<Session> {
+    match Session::load_metadata(&workdir) {
+        Ok(session) if session.session_id == session_id => Some(session),
+        Ok(session) => {
+            warn!("{} now holds session {}, not {}", workdir.display(), session.session_id, session_id);
+            None
+        }
+        Err(e) => {
+            warn!("Failed to load archived session {}: {:#}", session_id, e);
+            None
+        }
+    }
+}
+
+/// Bring archived sessions named by a request back into memory. The
+/// metadata is read before the sessions lock is taken, and a session is
+/// only put back if it is still archived by then.
+pub async fn restore(
+    sessions: &tokio::sync::RwLock<HashMap<String, Session>>,
+    archive: &Archive,
+    session_ids: &[&str],
+) {
+    let wanted: Vec<(String, PathBuf)> = session_ids
+        .iter()
+        .filter_map(|id| archive.workdir(id).map(|workdir| (id.to_string(), workdir)))
+        .collect();
+    if wanted.is_empty() {
+        return;
+    }
+    let loaded = tokio::task::spawn_blocking(move || {
+        wanted
+            .into_iter()
+            .filter_map(|(id, workdir)| load(workdir, &id))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    let mut sessions = sessions.write().await;
+    let mut evicted = archive.evicted.lock().unwrap_or_else(|e| e.into_inner());
+    let mut restored = archive.restored.lock().unwrap_or_else(|e| e.into_inner());
+    for session in loaded {
+        if evicted.remove(&session.session_id).is_none() {
+            continue;
+        }
+        debug!("Restored archived session {} from disk", session.session_id);
+        restored.insert(session.session_id.clone(), Instant::now());
+        sessions.entry(session.session_id.clone()).or_insert(session);
+    }
+}
+
+/// Read the archived sessions of `scope` from disk, for a listing; they stay
+/// archived
+pub fn scan(archive: &Archive, scope: &Scope) -> Vec<Session> {
+    archive
+        .workdirs(scope)
+        .into_iter()
+        .filter_map(|workdir| Session::load_metadata(&workdir).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use tokio::sync::RwLock;
+
+    fn session(config: &DaemonConfig, id: &str, status: SessionStatus, hours_ago: i64) -> Session {
+        let workdir = config.sessions_dir.join(id);
+        std::fs::create_dir_all(&workdir).unwrap();
+        let session = Session {
+            session_id: id.to_string(),
+            tmux_session: format!("summ-{}", id),
+            workdir,
+            status,
+            last_activity: Utc::now() - chrono::Duration::hours(hours_ago),
+            ..Default::default()
+        };
+        session.save_metadata().unwrap();
+        session
+    }
+
+    fn map(sessions: Vec<Session>) -> HashMap<String, Session> {
+        sessions.into_iter().map(|session| (session.session_id.clone(), session)).collect()
+    }
+
+    #[test]
+    fn test_evict_only_long_ended_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let ephemeral = Session {
+            ephemeral: true,
+            ..session(&config, "scratch", SessionStatus::Stopped, 48)
+        };
+        let observed = session(&config, "observed", SessionStatus::Stopped, 48);
+        let observer = Session {
+            observer_of: Some("observed".to_string()),
+            ..session(&config, "observer", SessionStatus::Idle, 48)
+        };
+        let mut sessions = map(vec![
+            session(&config, "old", SessionStatus::Stopped, 48),
+            session(&config, "failed", SessionStatus::Failed, 30),
+            session(&config, "recent", SessionStatus::Stopped, 1),
+            session(&config, "running", SessionStatus::Running, 48),
+            ephemeral,
+            observed,
+            observer,
+        ]);
+        let archive = Archive::default();
+
+        assert_eq!(evict(&mut sessions, &archive, &config), 2);
+        let mut left: Vec<&str> = sessions.keys().map(String::as_str).collect();
+        left.sort();
+        assert_eq!(left, ["observed", "observer", "recent", "running", "scratch"]);
+        assert_eq!(archive.count(&Scope::default()), 2);
+        let mut scanned: Vec<String> =
+            scan(&archive, &Scope::default()).into_iter().map(|session| session.session_id).collect();
+        scanned.sort();
+        assert_eq!(scanned, ["failed", "old"]);
+
+        let off = DaemonConfig {
+            archive_after_hours: 0,
+            ..config
+        };
+        assert_eq!(evict(&mut sessions, &archive, &off), 0);
+    }
+
+    #[test]
+    fn test_is_stale_by_wall_age() {
+        let window = Duration::from_secs(24 * 3600);
+        let now = Utc::now();
+        let ended = |hours_ago: i64| Session {
+            status: SessionStatus::Stopped,
+            last_activity: now - chrono::Duration::hours(hours_ago),
+            ..Default::default()
+        };
+        assert!(is_stale(&ended(25), window, now));
+        assert!(!is_stale(&ended(23), window, now));
+        // Active after a clock step back: in the future, so not ended long ago
+        assert!(!is_stale(&ended(-48), window, now));
+    }
+
+    #[tokio::test]
+    async fn test_restore_brings_sessions_back_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let archive = Archive::default();
+        let mut map = map(vec![session(&config, "old", SessionStatus::Stopped, 48)]);
+        evict(&mut map, &archive, &config);
+        let sessions = RwLock::new(map);
+
+        restore(&sessions, &archive, &["old", "unknown"]).await;
+        assert_eq!(sessions.read().await["old"].status, SessionStatus::Stopped);
+        assert_eq!(archive.count(&Scope::default()), 0);
+
+        // Freshly restored, so not evicted again right away
+        assert_eq!(evict(&mut *sessions.write().await, &archive, &config), 0);
+    }
+}
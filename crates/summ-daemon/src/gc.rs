@@ -0,0 +1,336 @@
+// summ-daemon/src/gc.rs
+// Removing terminal logs and session directories that no session owns (`summ gc`)
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use summ_common::{DaemonConfig, GcCategory, GcReport, Session, NAMESPACES_DIR};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::recovery::{SystemTmux, TmuxProbe};
+
+/// A session directory without metadata younger than this is left alone: a
+/// start may still be creating it
+pub const PARTIAL_DIR_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// How often the daemon collects garbage on its own
+const INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// The periodic run leaves partial directories for a week, so a failed start
+/// can still be looked into
+const PERIODIC_PARTIAL_DIR_MIN_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A session directory, with the ID it is named after
+struct SessionDir {
+    session_id: String,
+    path: PathBuf,
+}
+
+/// Session directories under the sessions directory and its namespaces.
+/// Entries that are not directories, or whose name could not be a session
+/// ID, are never considered.
+fn session_dirs(config: &DaemonConfig) -> Vec<SessionDir> {
+    let mut parents = vec![config.sessions_dir.clone()];
+    if let Ok(namespaces) = fs::read_dir(config.sessions_dir.join(NAMESPACES_DIR)) {
+        parents.extend(
+            namespaces
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+                .map(|entry| entry.path()),
+        );
+    }
+
+    let mut dirs = Vec::new();
+    for parent in parents {
+        let Ok(entries) = fs::read_dir(&parent) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let session_id = entry.file_name().to_string_lossy().into_owned();
+            if !entry.file_type().is_ok_and(|file_type| file_type.is_dir())
+                || (parent == config.sessions_dir && session_id == NAMESPACES_DIR)
+                || summ_common::validate_session_id(&session_id).is_err()
+            {
+                continue;
+            }
+            dirs.push(SessionDir {
+                session_id,
+                path: entry.path(),
+            });
+        }
+    }
+    dirs
+}
+
+/// Time since `path` or, for a directory, any of its direct entries last changed
+fn age(path: &Path) -> Duration {
+    let mut newest = fs::symlink_metadata(path).and_then(|metadata| metadata.modified()).ok();
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let modified = entry.metadata().and_then(|metadata| metadata.modified()).ok();
+            newest = newest.max(modified);
+        }
+    }
+    newest
+        .and_then(|newest| SystemTime::now().duration_since(newest).ok())
+        .unwrap_or(Duration::ZERO)
+}
+
+/// Bytes taken by a file or a directory tree
+fn size(path: &Path) -> u64 {
+    crate::init::measure_init_source(path, u64::MAX).map_or(0, |size| size.bytes)
+}
+
+/// Delete a log or directory, or only count it on a dry run
+fn remove(category: &mut GcCategory, path: PathBuf, dry_run: bool) {
+    let bytes = size(&path);
+    if !dry_run {
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        if let Err(e) = removed {
+            warn!("Failed to remove {}: {}", path.display(), e);
+            return;
+        }
+    }
+    category.count += 1;
+    category.bytes += bytes;
+    category.paths.push(path);
+}
+
+/// Delete what no session owns: session directories without metadata and
+/// without a tmux session, once they are `partial_dir_min_age` old, and logs
+/// of sessions that no longer exist, once they are past the retention window.
+///
+/// A session owns its files when the daemon holds it (`known`), when its
+/// directory has a meta.json (it would be recovered on the next start), or
+/// when a tmux session of its ID is alive. None of those is ever touched.
+pub fn collect(
+    config: &DaemonConfig,
+    known: &HashSet<String>,
+    tmux: &impl TmuxProbe,
+    dry_run: bool,
+    partial_dir_min_age: Duration,
+) -> GcReport {
+    let dirs = session_dirs(config);
+    let mut owners: HashSet<String> = known.clone();
+    owners.extend(
+        dirs.iter()
+            .filter(|dir| dir.path.join("meta.json").exists())
+            .map(|dir| dir.session_id.clone()),
+    );
+    for tmux_session in tmux.summ_sessions() {
+        owners.extend(tmux_session.name.strip_prefix("summ-").map(str::to_string));
+        owners.extend(tmux_session.session_id);
+    }
+
+    let mut report = GcReport {
+        dry_run,
+        ..Default::default()
+    };
+    for dir in dirs {
+        if owners.contains(&dir.session_id) {
+            continue;
+        }
+        if age(&dir.path) < partial_dir_min_age {
+            // Still being created, maybe; its log stays with it
+            owners.insert(dir.session_id);
+            continue;
+        }
+        remove(&mut report.partial_session_dirs, dir.path, dry_run);
+    }
+
+    let retention = config.cleanup_retention();
+    let mut logs: Vec<PathBuf> = fs::read_dir(&config.logs_dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    logs.sort();
+    for log in logs {
+        let Some(session_id) = log
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".log"))
+        else {
+            continue;
+        };
+        if summ_common::validate_session_id(session_id).is_err()
+            || owners.contains(session_id)
+            || !log.is_file()
+            || age(&log) < retention
+        {
+            continue;
+        }
+        remove(&mut report.orphaned_logs, log, dry_run);
+    }
+    report
+}
+
+/// Collect garbage every few hours, conservatively: partial directories are
+/// kept for a week. Started once recovery is done, so every session on disk
+/// is known.
+pub async fn watch(sessions: Arc<RwLock<HashMap<String, Session>>>, config: DaemonConfig) {
+    loop {
+        tokio::time::sleep(INTERVAL).await;
+        let known: HashSet<String> = sessions.read().await.keys().cloned().collect();
+        let config = config.clone();
+        let collected = tokio::task::spawn_blocking(move || {
+            collect(&config, &known, &SystemTmux, false, PERIODIC_PARTIAL_DIR_MIN_AGE)
+        })
+        .await;
+        match collected {
+            Ok(report) if report.orphaned_logs.count + report.partial_session_dirs.count > 0 => info!(
+                "Removed {} orphaned logs ({} bytes) and {} partial session directories ({} bytes)",
+                report.orphaned_logs.count,
+                report.orphaned_logs.bytes,
+                report.partial_session_dirs.count,
+                report.partial_session_dirs.bytes
+            ),
+            Ok(_) => {}
+            Err(e) => warn!("Garbage collection failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::SummTmuxSession;
+    use std::fs::File;
+    use tempfile::TempDir;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Live tmux sessions, by name
+    struct Tmux(Vec<&'static str>);
+
+    impl TmuxProbe for Tmux {
+        fn summ_sessions(&self) -> Vec<SummTmuxSession> {
+            self.0
+                .iter()
+                .map(|name| SummTmuxSession {
+                    name: name.to_string(),
+                    session_id: None,
+                })
+                .collect()
+        }
+
+        fn pane_pid(&self, _tmux_session: &str) -> Option<u32> {
+            None
+        }
+
+        fn session_path(&self, _tmux_session: &str) -> Option<PathBuf> {
+            None
+        }
+
+        fn adopt(&self, _tmux_session: &str, _session_id: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Set the modification time of `path` and its direct entries `ago` back
+    fn backdate(path: &Path, ago: Duration) {
+        let then = SystemTime::now() - ago;
+        let mut paths = vec![path.to_path_buf()];
+        if path.is_dir() {
+            paths.extend(fs::read_dir(path).unwrap().flatten().map(|entry| entry.path()));
+        }
+        for path in paths {
+            File::open(&path).unwrap().set_modified(then).unwrap();
+        }
+    }
+
+    /// A session directory, with or without meta.json, and its log
+    fn session(config: &DaemonConfig, parent: &Path, id: &str, meta: bool, ago: Duration) -> PathBuf {
+        let dir = parent.join(id);
+        crate::init::create_session_structure(&dir).unwrap();
+        fs::write(dir.join("workspace").join("notes.txt"), "notes").unwrap();
+        if meta {
+            fs::write(dir.join("meta.json"), "{}").unwrap();
+        }
+        backdate(&dir, ago);
+        log(config, id, ago);
+        dir
+    }
+
+    fn log(config: &DaemonConfig, id: &str, ago: Duration) -> PathBuf {
+        let path = config.session_log_path(id).unwrap();
+        fs::write(&path, "output").unwrap();
+        backdate(&path, ago);
+        path
+    }
+
+    #[test]
+    fn test_collect_never_touches_owned_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        config.ensure_directories().unwrap();
+        let sessions_dir = config.sessions_dir.clone();
+        let team = sessions_dir.join(NAMESPACES_DIR).join("team");
+        let old = 30 * DAY;
+
+        // Owned: held by the daemon, recoverable from meta.json, alive in tmux,
+        // or too young to tell
+        let held = session(&config, &sessions_dir, "held01", false, old);
+        let recoverable = session(&config, &team, "recoverable01", true, old);
+        let in_tmux = session(&config, &sessions_dir, "tmux01", false, old);
+        let young = session(&config, &sessions_dir, "young01", false, Duration::ZERO);
+        let fresh_log = log(&config, "fresh01", Duration::ZERO);
+        let unrelated = config.logs_dir.join("daemon.txt");
+        fs::write(&unrelated, "daemon").unwrap();
+        // Not owned
+        let partial = session(&config, &sessions_dir, "partial01", false, old);
+        let partial_in_namespace = session(&config, &team, "partial02", false, old);
+        let orphan_log = log(&config, "gone01", old);
+
+        let known: HashSet<String> = ["held01".to_string()].into();
+        let tmux = Tmux(vec!["summ-tmux01"]);
+        let report = collect(&config, &known, &tmux, false, PARTIAL_DIR_MIN_AGE);
+
+        assert_eq!(report.partial_session_dirs.count, 2);
+        assert_eq!(report.partial_session_dirs.paths, [partial.clone(), partial_in_namespace.clone()]);
+        assert!(report.partial_session_dirs.bytes >= 10);
+        // The partial directories' own logs go with them
+        assert_eq!(report.orphaned_logs.count, 3);
+        assert_eq!(
+            report.orphaned_logs.paths,
+            [orphan_log.clone(), log_path(&config, "partial01"), log_path(&config, "partial02")]
+        );
+        assert!(!partial.exists() && !partial_in_namespace.exists() && !orphan_log.exists());
+
+        for kept in [&held, &recoverable, &in_tmux, &young, &fresh_log, &unrelated] {
+            assert!(kept.exists(), "{} was removed", kept.display());
+        }
+        for id in ["held01", "recoverable01", "tmux01", "young01"] {
+            assert!(log_path(&config, id).exists(), "log of {} was removed", id);
+        }
+        assert!(team.exists());
+    }
+
+    fn log_path(config: &DaemonConfig, id: &str) -> PathBuf {
+        config.session_log_path(id).unwrap()
+    }
+
+    #[test]
+    fn test_dry_run_deletes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        config.ensure_directories().unwrap();
+        let partial = session(&config, &config.sessions_dir, "partial01", false, 30 * DAY);
+
+        let report = collect(&config, &HashSet::new(), &Tmux(Vec::new()), true, PARTIAL_DIR_MIN_AGE);
+        assert!(report.dry_run);
+        assert_eq!(report.partial_session_dirs.count, 1);
+        assert_eq!(report.orphaned_logs.count, 1);
+        assert!(partial.exists());
+        assert!(log_path(&config, "partial01").exists());
+
+        // The periodic run waits longer for partial directories
+        let report = collect(&config, &HashSet::new(), &Tmux(Vec::new()), true, PERIODIC_PARTIAL_DIR_MIN_AGE * 10);
+        assert_eq!(report.partial_session_dirs.count, 0);
+        assert_eq!(report.orphaned_logs.count, 0);
+    }
+}
@@ -0,0 +1,274 @@
+// summ-daemon/src/health.rs
+// Cached internal health, kept up to date by background tasks and read by Healthz
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, AtomicU8, Ordering};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use summ_common::{DaemonConfig, HealthReport, HealthStatus};
+use tracing::{info, warn};
+
+use crate::disk::{self, DiskLevel, DiskWarning};
+use crate::storage::{self, StorageStatus};
+
+/// How often the sessions directory is probed for writes
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// File created and removed by the write probe
+const PROBE_FILE: &str = ".healthz-probe";
+
+/// A monitor heartbeat older than this means the monitor is stuck (it ticks every 5s)
+const MONITOR_STALE_SECONDS: u64 = 30;
+
+/// Monotonic time in milliseconds of the last completed monitor tick; 0 until
+/// the first. The wall clock would make the monitor look stuck (and restart
+/// it) whenever NTP steps the clock forward.
+static MONITOR_HEARTBEAT_MS: AtomicI64 = AtomicI64::new(0);
+
+/// Origin of `monotonic_ms`
+static STARTED: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+/// Milliseconds on the monotonic clock, never 0
+fn monotonic_ms() -> i64 {
+    STARTED.elapsed().as_millis() as i64 + 1
+}
+
+/// Cached boolean checks: `UNKNOWN` until the check first runs
+const UNKNOWN: u8 = 0;
+const PASSING: u8 = 1;
+const FAILING: u8 = 2;
+
+static TMUX_REACHABLE: AtomicU8 = AtomicU8::new(UNKNOWN);
+static SESSIONS_DIR_WRITABLE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+fn store(check: &AtomicU8, passing: bool) {
+    check.store(if passing { PASSING } else { FAILING }, Ordering::Relaxed);
+}
+
+fn load(check: &AtomicU8) -> Option<bool> {
+    match check.load(Ordering::Relaxed) {
+        UNKNOWN => None,
+        value => Some(value == PASSING),
+    }
+}
+
+/// Record a completed monitor tick and whether tmux answered during it
+pub fn record_monitor_tick(tmux_reachable: bool) {
+    MONITOR_HEARTBEAT_MS.store(monotonic_ms(), Ordering::Relaxed);
+    store(&TMUX_REACHABLE, tmux_reachable);
+}
+
+/// Seconds since the last completed monitor tick, if there was one
+pub fn monitor_heartbeat_age() -> Option<u64> {
+    heartbeat_age(
+        MONITOR_HEARTBEAT_MS.load(Ordering::Relaxed),
+        monotonic_ms(),
+    )
+}
+
+fn heartbeat_age(heartbeat_ms: i64, now_ms: i64) -> Option<u64> {
+    (heartbeat_ms > 0).then(|| (now_ms - heartbeat_ms).max(0) as u64 / 1000)
+}
+
+/// Create and remove a file in the sessions directory
+fn probe_writable(sessions_dir: &Path) -> std::io::Result<()> {
+    let probe = sessions_dir.join(PROBE_FILE);
+    std::fs::write(&probe, b"ok")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Probe the sessions directory for writes every `PROBE_INTERVAL`
+pub async fn watch_sessions_dir(config: DaemonConfig) {
+    let mut interval = tokio::time::interval(PROBE_INTERVAL);
+    let mut writable = None;
+
+    loop {
+        interval.tick().await;
+
+        let sessions_dir = config.sessions_dir.clone();
+        let result = tokio::task::spawn_blocking(move || probe_writable(&sessions_dir))
+            .await
+            .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+        // A successful probe also ends a storage-unavailable period
+        storage::record_probe(&result);
+        let now_writable = result.is_ok();
+        if writable != Some(now_writable) {
+            match result {
+                Ok(()) if writable.is_some() => {
+                    info!("Sessions directory {} is writable again", config.sessions_dir.display())
+                }
+                Ok(()) => {}
+                Err(e) => warn!(
+                    "Sessions directory {} is not writable: {}",
+                    config.sessions_dir.display(),
+                    e
+                ),
+            }
+            writable = Some(now_writable);
+        }
+        store(&SESSIONS_DIR_WRITABLE, now_writable);
+    }
+}
+
+/// Inputs of a health report, as cached by the background tasks
+struct HealthInputs {
+    tmux_reachable: Option<bool>,
+    sessions_dir_writable: Option<bool>,
+    monitor_heartbeat_age: Option<u64>,
+    disk_warning: Option<DiskWarning>,
+    storage: StorageStatus,
+}
+
+/// Current health, from cached state only
+pub fn current_report() -> HealthReport {
+    evaluate(HealthInputs {
+        tmux_reachable: load(&TMUX_REACHABLE),
+        sessions_dir_writable: load(&SESSIONS_DIR_WRITABLE),
+        monitor_heartbeat_age: monitor_heartbeat_age(),
+        disk_warning: disk::current_warning(),
+        storage: storage::status(),
+    })
+}
+
+fn evaluate(inputs: HealthInputs) -> HealthReport {
+    let disk_level = inputs
+        .disk_warning
+        .as_ref()
+        .map_or(DiskLevel::Ok, |warning| warning.level);
+    let mut report = HealthReport {
+        tmux_reachable: inputs.tmux_reachable,
+        sessions_dir_writable: inputs.sessions_dir_writable,
+        monitor_heartbeat_age_seconds: inputs.monitor_heartbeat_age,
+        disk: serde_json::to_value(disk_level)
+            .ok()
+            .and_then(|level| level.as_str().map(str::to_string)),
+        ..Default::default()
+    };
+
+    if inputs.tmux_reachable == Some(false) {
+        report.add_problem(HealthStatus::Failing, "tmux is not reachable");
+    }
+    if inputs.sessions_dir_writable == Some(false) {
+        report.add_problem(HealthStatus::Failing, "Sessions directory is not writable");
+    }
+    if !inputs.storage.available {
+        report.add_problem(
+            HealthStatus::Failing,
+            format!(
+                "Storage unavailable: {} consecutive writes failed",
+                inputs.storage.consecutive_failures
+            ),
+        );
+    }
+    match inputs.monitor_heartbeat_age {
+        None => report.add_problem(HealthStatus::Degraded, "Session monitor has not run yet"),
+        Some(age) if age > MONITOR_STALE_SECONDS => report.add_problem(
+            HealthStatus::Degraded,
+            format!("Session monitor last ran {}s ago", age),
+        ),
+        Some(_) => {}
+    }
+    if let Some(warning) = inputs.disk_warning {
+        let status = match warning.level {
+            DiskLevel::Critical => HealthStatus::Failing,
+            _ => HealthStatus::Degraded,
+        };
+        report.add_problem(status, warning.message);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn healthy() -> HealthInputs {
+        HealthInputs {
+            tmux_reachable: Some(true),
+            sessions_dir_writable: Some(true),
+            monitor_heartbeat_age: Some(3),
+            disk_warning: None,
+            storage: StorageStatus { available: true, consecutive_failures: 0 },
+        }
+    }
+
+    #[test]
+    fn test_evaluate_healthy() {
+        let report = evaluate(healthy());
+        assert_eq!(report.status, HealthStatus::Ok);
+        assert!(report.reasons.is_empty());
+        assert_eq!(report.disk.as_deref(), Some("ok"));
+    }
+
+    #[test]
+    fn test_evaluate_problems() {
+        let report = evaluate(HealthInputs {
+            monitor_heartbeat_age: Some(95),
+            ..healthy()
+        });
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.reasons, ["Session monitor last ran 95s ago"]);
+
+        let report = evaluate(HealthInputs {
+            tmux_reachable: None,
+            sessions_dir_writable: None,
+            monitor_heartbeat_age: None,
+            disk_warning: None,
+            storage: StorageStatus { available: true, consecutive_failures: 1 },
+        });
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.reasons, ["Session monitor has not run yet"]);
+
+        let report = evaluate(HealthInputs {
+            tmux_reachable: Some(false),
+            sessions_dir_writable: Some(false),
+            ..healthy()
+        });
+        assert_eq!(report.status, HealthStatus::Failing);
+        assert_eq!(report.reasons.len(), 2);
+
+        let report = evaluate(HealthInputs {
+            storage: StorageStatus { available: false, consecutive_failures: 3 },
+            ..healthy()
+        });
+        assert_eq!(report.status, HealthStatus::Failing);
+        assert_eq!(report.reasons, ["Storage unavailable: 3 consecutive writes failed"]);
+
+        // A low disk degrades, a critical one fails
+        let warning = DiskWarning {
+            level: DiskLevel::Low,
+            path: "/sessions".into(),
+            free_bytes: 1,
+            total_bytes: 100,
+            message: "Only 0.0 GB (1.0%) free".to_string(),
+        };
+        let report = evaluate(HealthInputs {
+            disk_warning: Some(warning.clone()),
+            ..healthy()
+        });
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert_eq!(report.disk.as_deref(), Some("low"));
+
+        let report = evaluate(HealthInputs {
+            disk_warning: Some(DiskWarning {
+                level: DiskLevel::Critical,
+                ..warning
+            }),
+            ..healthy()
+        });
+        assert_eq!(report.status, HealthStatus::Failing);
+        assert_eq!(report.disk.as_deref(), Some("critical"));
+    }
+
+    #[test]
+    fn test_heartbeat_age_and_probe() {
+        assert_eq!(heartbeat_age(0, 10_000), None);
+        assert_eq!(heartbeat_age(4_000, 10_500), Some(6));
+        assert_eq!(heartbeat_age(11_000, 10_000), Some(0));
+
+        let temp_dir = TempDir::new().unwrap();
+        probe_writable(temp_dir.path()).unwrap();
+        assert!(!temp_dir.path().join(PROBE_FILE).exists());
+        assert!(probe_writable(&temp_dir.path().join("missing")).is_err());
+    }
+}
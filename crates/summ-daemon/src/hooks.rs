@@ -1,74 +1,52 @@
 // summ-daemon/src/hooks.rs
 // Claude Code Hook integration for status reporting
 use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
-use std::os::unix::fs::PermissionsExt;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
-
-/// The summ-hook script content
-pub const SUMM_HOOK_SCRIPT: &str = r#"#!/bin/bash
-# summ-hook: Claude Code Hook handler script
-# Usage: summ-hook <event> [args...]
-
-set -e
-
-EVENT="$1"
-shift
-RUNTIME_DIR="${SUMM_RUNTIME_DIR:-$PWD/../runtime}"
-STATUS_FILE="$RUNTIME_DIR/status.json"
-
-# From stdin read Hook input (JSON)
-INPUT=$(cat)
-
-# Extract session_id (from environment variable or input)
-SESSION_ID="${SUMM_SESSION_ID:-unknown}"
-
-# Ensure runtime directory exists
-mkdir -p "$(dirname "$STATUS_FILE")"
-
-write_status() {
-    local state="$1"
-    local message="$2"
-
-    cat > "$STATUS_FILE" << EOF
-{
-  "state": "$state",
-  "message": "$message",
-  "event": "$EVENT",
-  "timestamp": "$(date -Iseconds)"
-}
-EOF
+use std::time::Duration;
+use summ_common::assets::{self, HOOK_ASSETS_VERSION};
+use summ_common::hook_settings::{self, hook_kind};
+use summ_common::{CliState, CliStatus, DaemonConfig, HookHealth, HookInfo, HookKind, HookState, Session, SessionStatus};
+
+use crate::events;
+use crate::session::SessionExt;
+use crate::storage;
+use crate::tmux::TmuxManager;
+
+/// Terminal output (lowercased) showing that the CLI rejected or failed to run its hooks
+const HOOK_ERROR_PATTERNS: &[&str] = &[
+    "settings error",
+    "invalid settings",
+    "settings.local.json",
+    "hook error",
+    "hook failed",
+    "summ-hook: command not found",
+    "summ-hook: no such file",
+];
+
+/// Pane lines scanned for hook errors
+const HOOK_CHECK_LINES: i32 = 50;
+
+/// Bytes read from the end of the session log when the pane cannot be captured
+const HOOK_CHECK_LOG_BYTES: u64 = 16 * 1024;
+
+/// Result of refreshing hook assets
+#[derive(Debug, Default, Serialize)]
+pub struct HookSyncReport {
+    /// Whether the summ-hook script was (re)installed
+    pub script_updated: bool,
+    /// Sessions whose workspace hook settings were refreshed
+    pub sessions_updated: Vec<String>,
 }
 
-case "$EVENT" in
-    session-start)
-        write_status "idle" "Session started, ready for tasks"
-        ;;
-
-    stop)
-        # Claude main agent completed response
-        write_status "idle" "Task completed"
-        ;;
-
-    subagent-stop)
-        # Subagent completed
-        write_status "idle" "Subagent task completed"
-        ;;
-
-    session-end)
-        # Session ended
-        REASON=$(echo "$INPUT" | jq -r '.reason // "unknown"' 2>/dev/null || echo "unknown")
-        write_status "stopped" "Session ended: $REASON"
-        ;;
-
-    *)
-        echo "Unknown event: $EVENT" >&2
-        exit 1
-        ;;
-esac
-
-exit 0
-"#;
+/// Read the asset version recorded in a workspace's deployed hook settings
+pub fn deployed_hooks_version(workspace_dir: &Path) -> Option<u32> {
+    let content = fs::read_to_string(hook_settings::claude_settings_path(workspace_dir)).ok()?;
+    hook_settings::deployed_version(&serde_json::from_str(&content).ok()?)
+}
 
 /// Deploy Claude Code hooks to the workspace directory
 pub fn deploy_claude_code_hooks(
@@ -80,47 +58,14 @@ pub fn deploy_claude_code_hooks(
     fs::create_dir_all(&claude_dir)
         .context(format!("Failed to create .claude directory: {}", claude_dir.display()))?;
 
-    // Build the hook command with environment variables
-    let hook_base = format!(
-        "SUMM_SESSION_ID={} SUMM_RUNTIME_DIR={} ~/.summ-daemon/bin/summ-hook",
-        session_id,
-        runtime_dir.display()
-    );
-
-    // Create the hooks configuration
-    let hooks_config = serde_json::json!({
-        "hooks": {
-            "SessionStart": [{
-                "hooks": [{
-                    "type": "command",
-                    "command": format!("{} session-start", hook_base)
-                }]
-            }],
-            "Stop": [{
-                "hooks": [{
-                    "type": "command",
-                    "command": format!("{} stop", hook_base)
-                }]
-            }],
-            "SubagentStop": [{
-                "hooks": [{
-                    "type": "command",
-                    "command": format!("{} subagent-stop", hook_base)
-                }]
-            }],
-            "SessionEnd": [{
-                "hooks": [{
-                    "type": "command",
-                    "command": format!("{} session-end", hook_base)
-                }]
-            }]
-        }
-    });
+    // Keep settings that came with the workspace (e.g. from the init source)
+    let settings_path = hook_settings::claude_settings_path(workspace_dir);
+    let existing = fs::read_to_string(&settings_path).ok();
+    let settings = hook_settings::claude_settings(existing.as_deref(), session_id, runtime_dir);
 
-    let settings_path = claude_dir.join("settings.local.json");
     fs::write(
         &settings_path,
-        serde_json::to_string_pretty(&hooks_config)?.as_bytes(),
+        serde_json::to_string_pretty(&settings)?.as_bytes(),
     )
     .context(format!(
         "Failed to write Claude Code hooks: {}",
@@ -132,57 +77,224 @@ pub fn deploy_claude_code_hooks(
     Ok(())
 }
 
-/// Deploy hooks for other CLI tools (placeholder for future expansion)
+/// Whether hooks are deployed for this CLI
+fn cli_supports_hooks(cli: &str) -> bool {
+    hook_kind(cli) == HookKind::Claude
+}
+
+/// Deploy the hooks of a session's CLI, if it has any, and describe what was deployed
+#[tracing::instrument(skip_all, fields(cli = %cli))]
 pub fn deploy_cli_hooks(
     workspace_dir: &Path,
     cli: &str,
     session_id: &str,
     runtime_dir: &Path,
-) -> Result<()> {
-    if cli.contains("claude") {
-        deploy_claude_code_hooks(workspace_dir, session_id, runtime_dir)?;
-    } else if cli.contains("aider") {
+) -> Result<HookInfo> {
+    let kind = hook_kind(cli);
+    match kind {
+        HookKind::Claude => deploy_claude_code_hooks(workspace_dir, session_id, runtime_dir)?,
         // aider doesn't currently support hooks, log info
-        tracing::info!(
+        HookKind::AiderHeuristic => tracing::info!(
             "CLI '{}' does not support hooks, status detection will be limited",
             cli
-        );
+        ),
+        // Extend here for other CLI tools in the future
+        HookKind::None => {}
     }
-    // Extend here for other CLI tools in the future
-    Ok(())
+    let deployed = kind == HookKind::Claude;
+    Ok(HookInfo {
+        kind,
+        state: if deployed { HookState::Deployed } else { HookState::None },
+        deployed_at: deployed.then(chrono::Utc::now),
+        ..Default::default()
+    })
 }
 
-/// Install the summ-hook script to ~/.summ-daemon/bin/
-pub fn install_hook_script(base_dir: &Path) -> Result<()> {
-    let bin_dir = base_dir.join("bin");
-    fs::create_dir_all(&bin_dir)
-        .context(format!("Failed to create bin directory: {}", bin_dir.display()))?;
-
-    let hook_script = bin_dir.join("summ-hook");
-    fs::write(&hook_script, SUMM_HOOK_SCRIPT).context(format!(
-        "Failed to write hook script: {}",
-        hook_script.display()
-    ))?;
+/// Time after creation a session's hooks have to report, counted from when
+/// creation finished
+fn report_window(session: &Session, config: &DaemonConfig) -> Duration {
+    let creation_ms = session.creation_timings.as_ref().map_or(0, |t| t.total_ms);
+    Duration::from_millis(config.hook_check_seconds.saturating_mul(1000).saturating_add(creation_ms))
+}
 
-    // Make the script executable
-    #[cfg(unix)]
+/// State of a session's hooks given its latest report:
+/// - CLIs without hooks are `none`
+/// - hooks that never reported are `deployed` until the post-start check
+///   window has passed, then `silent`
+/// - a busy report older than `hook_silent_seconds` (never less than the
+///   stale-report age, before which the daemon still trusts the report) is
+///   `silent`: Claude Code reports when a task ends, so a long silence while
+///   busy means the hooks stopped firing or the CLI hung
+/// - anything else is `ok`; an idle or waiting CLI has nothing to report
+fn hook_state(session: &Session, kind: HookKind, report: Option<&CliStatus>, config: &DaemonConfig) -> HookState {
+    if kind != HookKind::Claude {
+        return HookState::None;
+    }
+    let Some(report) = report else {
+        let age = crate::clock::session_age(&session.session_id, session.created_at);
+        return if age < report_window(session, config) {
+            HookState::Deployed
+        } else {
+            HookState::Silent
+        };
+    };
+    let silent_after = Duration::from_secs(config.hook_silent_seconds).max(crate::session::STALE_REPORT);
+    if report.state == CliState::Busy
+        && crate::clock::status_age(&session.session_id, report.timestamp) > silent_after
     {
-        let mut perms = fs::metadata(&hook_script)
-            .context(format!("Failed to get metadata: {}", hook_script.display()))?
-            .permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(&hook_script, perms)
-            .context(format!("Failed to set permissions: {}", hook_script.display()))?;
+        HookState::Silent
+    } else {
+        HookState::Ok
     }
+}
 
-    tracing::info!("Installed summ-hook script to {}", hook_script.display());
+/// Record a live session's latest hook report in its `hooks` and recompute
+/// their state, persisting any change; returns whether anything changed.
+/// Sessions started before `hooks` was recorded get their kind from the CLI.
+pub fn record_report(session: &mut Session, report: Option<&CliStatus>, config: &DaemonConfig) -> bool {
+    let mut hooks = session.hooks.clone().unwrap_or_else(|| HookInfo {
+        kind: hook_kind(&session.cli),
+        ..Default::default()
+    });
+    if let Some(report) = report.filter(|report| hooks.last_event_at != Some(report.timestamp)) {
+        hooks.last_event_at = Some(report.timestamp);
+        hooks.last_event = report.event.clone();
+        events::record_hook(session, report);
+    }
+    hooks.state = hook_state(session, hooks.kind, report, config);
+    if session.hooks.as_ref() == Some(&hooks) {
+        return false;
+    }
+    if hooks.state == HookState::Silent
+        && session.hooks.as_ref().is_some_and(|previous| previous.state != HookState::Silent)
+    {
+        tracing::warn!("Session {} hooks have gone silent while it is busy", session.session_id);
+    }
+    session.hooks = Some(hooks);
+    storage::save(session).ok();
+    true
+}
+
+/// Classify a session whose hooks have not reported from its recent terminal output
+fn classify_hook_output(output: &str) -> HookHealth {
+    let output = output.to_lowercase();
+    if HOOK_ERROR_PATTERNS.iter().any(|pattern| output.contains(pattern)) {
+        HookHealth::Broken
+    } else {
+        HookHealth::Missing
+    }
+}
+
+/// Last `max_bytes` of a file, or an empty string if it cannot be read
+fn read_tail(path: &Path, max_bytes: u64) -> String {
+    let Ok(mut file) = fs::File::open(path) else {
+        return String::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut buf = Vec::new();
+    if file.seek(SeekFrom::Start(len.saturating_sub(max_bytes))).is_err()
+        || file.read_to_end(&mut buf).is_err()
+    {
+        return String::new();
+    }
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+/// Post-start hook check for a live session.
+///
+/// A session is `ok` once its hooks have written `runtime/status.json`. Without a
+/// report, it is judged `hook_check_seconds` after creation finished: `broken` if
+/// the pane (or the session log) shows hook errors, `missing` otherwise. Flagged
+/// sessions are only re-checked for a late report. Returns `None` when there is
+/// nothing to record yet.
+pub fn check_hook_health(session: &Session, config: &DaemonConfig) -> Option<HookHealth> {
+    if !cli_supports_hooks(&session.cli) || session.hook_health == Some(HookHealth::Ok) {
+        return None;
+    }
+    if session.workdir.join("runtime/status.json").exists() {
+        return Some(HookHealth::Ok);
+    }
+    if session.hook_health.is_some() {
+        return None;
+    }
 
+    let elapsed = crate::clock::session_age(&session.session_id, session.created_at);
+    if elapsed < report_window(session, config) {
+        return None;
+    }
+
+    let output = TmuxManager::capture_pane(&session.tmux_session, HOOK_CHECK_LINES)
+        .ok()
+        .filter(|output| !output.trim().is_empty())
+        .unwrap_or_else(|| read_tail(&session.log_path, HOOK_CHECK_LOG_BYTES));
+    Some(classify_hook_output(&output))
+}
+
+/// Install the summ-hook script to ~/.summ-daemon/bin/
+pub fn install_hook_script(base_dir: &Path) -> Result<()> {
+    let hook_script = assets::install_hook_script(base_dir)?;
+    tracing::info!("Installed summ-hook script to {}", hook_script.display());
     Ok(())
 }
 
+/// Refresh out-of-date hook assets: the installed script and the settings
+/// deployed into each live session's workspace.
+///
+/// With `force`, everything is rewritten regardless of the recorded version.
+pub fn sync_hooks(
+    base_dir: &Path,
+    sessions: &HashMap<String, Session>,
+    force: bool,
+) -> Result<HookSyncReport> {
+    let mut report = HookSyncReport::default();
+
+    let script_path = assets::hook_script_path(base_dir);
+    if force || assets::installed_hook_version(&script_path) != Some(HOOK_ASSETS_VERSION) {
+        install_hook_script(base_dir)?;
+        report.script_updated = true;
+    }
+
+    for session in sessions.values() {
+        let live = !matches!(
+            session.status,
+            SessionStatus::Stopped
+                | SessionStatus::Starting
+                | SessionStatus::Pending
+                | SessionStatus::Failed
+        );
+        if !live || !cli_supports_hooks(&session.cli) {
+            continue;
+        }
+
+        let hooks_dir = session.hooks_dir();
+        if !force && deployed_hooks_version(&hooks_dir) == Some(HOOK_ASSETS_VERSION) {
+            continue;
+        }
+
+        let runtime_dir = session.workdir.join("runtime");
+        match deploy_claude_code_hooks(&hooks_dir, &session.session_id, &runtime_dir) {
+            Ok(()) => {
+                tracing::info!("Refreshed hook settings for session {}", session.session_id);
+                report.sessions_updated.push(session.session_id.clone());
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to refresh hook settings for session {}: {}",
+                    session.session_id,
+                    e
+                );
+            }
+        }
+    }
+
+    report.sessions_updated.sort();
+    Ok(report)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use summ_common::assets::SUMM_HOOK_SCRIPT;
     use tempfile::TempDir;
 
     #[test]
@@ -192,6 +304,7 @@ mod tests {
         assert!(SUMM_HOOK_SCRIPT.contains("session-start"));
         assert!(SUMM_HOOK_SCRIPT.contains("stop"));
         assert!(SUMM_HOOK_SCRIPT.contains("subagent-stop"));
+        assert!(SUMM_HOOK_SCRIPT.contains("notification"));
         assert!(SUMM_HOOK_SCRIPT.contains("session-end"));
         assert!(SUMM_HOOK_SCRIPT.contains("write_status"));
     }
@@ -230,6 +343,7 @@ mod tests {
         assert!(content.contains("SessionStart"));
         assert!(content.contains("Stop"));
         assert!(content.contains("SubagentStop"));
+        assert!(content.contains("Notification"));
         assert!(content.contains("SessionEnd"));
         assert!(content.contains("summ-hook"));
     }
@@ -265,4 +379,196 @@ mod tests {
         let settings_path = workspace_dir.join(".claude/settings.local.json");
         assert!(!settings_path.exists());
     }
+
+    #[test]
+    fn test_deploy_merges_existing_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_dir = temp_dir.path().join("workspace");
+        let runtime_dir = temp_dir.path().join("runtime");
+        let claude_dir = workspace_dir.join(".claude");
+        fs::create_dir_all(&claude_dir).unwrap();
+
+        // A project-provided setting, a user hook and an outdated summ hook
+        let existing = serde_json::json!({
+            "permissions": {"allow": ["Bash(ls)"]},
+            "hooks": {
+                "Stop": [
+                    {"hooks": [{"type": "command", "command": "notify-send done"}]},
+                    {"hooks": [{"type": "command", "command": "SUMM_SESSION_ID=old ~/.summ-daemon/bin/summ-hook stop"}]}
+                ]
+            }
+        });
+        fs::write(
+            claude_dir.join("settings.local.json"),
+            serde_json::to_string(&existing).unwrap(),
+        )
+        .unwrap();
+
+        deploy_claude_code_hooks(&workspace_dir, "s1", &runtime_dir).unwrap();
+
+        let content = fs::read_to_string(claude_dir.join("settings.local.json")).unwrap();
+        let settings: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(settings["permissions"]["allow"][0], "Bash(ls)");
+
+        let stop = settings["hooks"]["Stop"].as_array().unwrap();
+        assert_eq!(stop.len(), 2);
+        assert_eq!(stop[0]["hooks"][0]["command"], "notify-send done");
+        assert!(!content.contains("SUMM_SESSION_ID=old"));
+        assert_eq!(deployed_hooks_version(&workspace_dir), Some(HOOK_ASSETS_VERSION));
+    }
+
+    #[test]
+    fn test_check_hook_health() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        fs::create_dir_all(&config.logs_dir).unwrap();
+
+        let workdir = temp_dir.path().join("sessions/hooks01");
+        fs::create_dir_all(workdir.join("runtime")).unwrap();
+        let mut session = Session {
+            session_id: "hooks01".to_string(),
+            tmux_session: "summ-test-no-such-session".to_string(),
+            cli: "claude".to_string(),
+            workdir: workdir.clone(),
+            log_path: config.session_log_path("hooks01").unwrap(),
+            created_at: chrono::Utc::now(),
+            ..Default::default()
+        };
+
+        // Still inside the check window
+        assert_eq!(check_hook_health(&session, &config), None);
+
+        session.created_at = chrono::Utc::now() - chrono::Duration::seconds(60);
+        assert_eq!(check_hook_health(&session, &config), Some(HookHealth::Missing));
+
+        fs::write(
+            config.session_log_path("hooks01").unwrap(),
+            "Settings Error\n .claude/settings.local.json: Invalid JSON\n",
+        )
+        .unwrap();
+        assert_eq!(check_hook_health(&session, &config), Some(HookHealth::Broken));
+
+        // Once flagged, only a late report changes the verdict
+        session.hook_health = Some(HookHealth::Broken);
+        assert_eq!(check_hook_health(&session, &config), None);
+        fs::write(workdir.join("runtime/status.json"), "{}").unwrap();
+        assert_eq!(check_hook_health(&session, &config), Some(HookHealth::Ok));
+
+        session.hook_health = Some(HookHealth::Ok);
+        assert_eq!(check_hook_health(&session, &config), None);
+
+        // CLIs without hooks are never flagged
+        let aider = Session { cli: "aider".to_string(), hook_health: None, ..session };
+        assert_eq!(check_hook_health(&aider, &config), None);
+    }
+
+    #[test]
+    fn test_record_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let workdir = temp_dir.path().join("sessions/hooks02");
+        fs::create_dir_all(&workdir).unwrap();
+        let mut session = Session {
+            session_id: "hooks02".to_string(),
+            cli: "claude".to_string(),
+            workdir,
+            created_at: chrono::Utc::now(),
+            hooks: Some(deploy_cli_hooks(temp_dir.path(), "claude", "hooks02", temp_dir.path()).unwrap()),
+            ..Default::default()
+        };
+        let report = |state: CliState, event: &str, seconds_ago: i64| CliStatus {
+            state,
+            message: None,
+            event: Some(event.to_string()),
+            timestamp: chrono::Utc::now() - chrono::Duration::seconds(seconds_ago),
+            transcript_path: None,
+        };
+        let state = |session: &Session| session.hooks.as_ref().unwrap().state;
+
+        // Waiting for the first report, then silent once the check window passed
+        assert!(!record_report(&mut session, None, &config));
+        assert_eq!(state(&session), HookState::Deployed);
+        session.created_at -= chrono::Duration::seconds(60);
+        assert!(record_report(&mut session, None, &config));
+        assert_eq!(state(&session), HookState::Silent);
+
+        let busy = report(CliState::Busy, "user-prompt-submit", 60);
+        assert!(record_report(&mut session, Some(&busy), &config));
+        let hooks = session.hooks.clone().unwrap();
+        assert_eq!(hooks.state, HookState::Ok);
+        assert_eq!(hooks.last_event_at, Some(busy.timestamp));
+        assert_eq!(hooks.last_event.as_deref(), Some("user-prompt-submit"));
+        assert!(!record_report(&mut session, Some(&busy), &config));
+
+        // Busy for longer than hook_silent_seconds without a report
+        let busy = report(CliState::Busy, "user-prompt-submit", 2 * 60 * 60);
+        record_report(&mut session, Some(&busy), &config);
+        assert_eq!(state(&session), HookState::Silent);
+        // An idle CLI has nothing to report, however long ago it went idle
+        let idle = report(CliState::Idle, "stop", 2 * 60 * 60);
+        record_report(&mut session, Some(&idle), &config);
+        assert_eq!(state(&session), HookState::Ok);
+
+        // The threshold never drops below the stale-report age
+        let config = DaemonConfig { hook_silent_seconds: 10, ..config };
+        let busy = report(CliState::Busy, "user-prompt-submit", 90);
+        record_report(&mut session, Some(&busy), &config);
+        assert_eq!(state(&session), HookState::Ok);
+
+        // Sessions without hooks, including ones recorded before `hooks` existed
+        let mut aider = Session { cli: "aider".to_string(), hooks: None, ..session };
+        assert!(record_report(&mut aider, None, &config));
+        assert_eq!(aider.hooks.as_ref().unwrap().kind, HookKind::AiderHeuristic);
+        assert_eq!(state(&aider), HookState::None);
+    }
+
+    #[test]
+    fn test_sync_hooks_refreshes_outdated_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path();
+
+        let make_session = |id: &str, status: SessionStatus| {
+            let workdir = base_dir.join("sessions").join(id);
+            fs::create_dir_all(workdir.join("workspace")).unwrap();
+            Session {
+                session_id: id.to_string(),
+                cli: "claude".to_string(),
+                workdir,
+                status,
+                ..Default::default()
+            }
+        };
+
+        let outdated = make_session("outdated", SessionStatus::Idle);
+        let current = make_session("current", SessionStatus::Running);
+        let stopped = make_session("stopped", SessionStatus::Stopped);
+        deploy_claude_code_hooks(
+            &current.workdir.join("workspace"),
+            "current",
+            &current.workdir.join("runtime"),
+        )
+        .unwrap();
+
+        let sessions: HashMap<String, Session> = [outdated, current, stopped]
+            .into_iter()
+            .map(|s| (s.session_id.clone(), s))
+            .collect();
+
+        let report = sync_hooks(base_dir, &sessions, false).unwrap();
+        assert!(report.script_updated);
+        assert_eq!(report.sessions_updated, vec!["outdated".to_string()]);
+
+        // Everything is current now
+        let report = sync_hooks(base_dir, &sessions, false).unwrap();
+        assert!(!report.script_updated);
+        assert!(report.sessions_updated.is_empty());
+
+        // Force rewrites live sessions but still skips stopped ones
+        let report = sync_hooks(base_dir, &sessions, true).unwrap();
+        assert!(report.script_updated);
+        assert_eq!(
+            report.sessions_updated,
+            vec!["current".to_string(), "outdated".to_string()]
+        );
+    }
 }
@@ -0,0 +1,94 @@
+// summ-daemon/src/idle.rs
+// Stopping sessions whose CLI has sat idle for longer than their idle timeout
+use summ_common::{LifecycleEvent, Session, SessionStatus, StopReason, TimelineKind};
+use tracing::{info, warn};
+
+use crate::events;
+use crate::lifecycle;
+use crate::session::SessionExt;
+use crate::storage;
+use crate::tmux::TmuxManager;
+
+/// Whether the session has an idle timeout and its CLI has been idle for all of it
+pub fn is_expired(session: &Session) -> bool {
+    session.idle_timeout_seconds.is_some_and(|timeout| {
+        session.status == SessionStatus::Idle && session.status_age().as_secs() >= timeout
+    })
+}
+
+/// Stop a session whose idle timeout expired
+pub fn stop(session: &mut Session) {
+    info!(
+        "Session {} was idle for {}s; stopping it",
+        session.session_id,
+        session.idle_timeout_seconds.unwrap_or_default()
+    );
+    if let Err(e) = TmuxManager::kill_session(&session.tmux_session) {
+        warn!("Failed to kill tmux session of idle session {}: {}", session.session_id, e);
+    }
+
+    session.status = SessionStatus::Stopped;
+    session.pid = None;
+    session.current_command = None;
+    session.needs_attention = false;
+    session.waiting_for_input = None;
+    let reason = StopReason::IdleTimeout;
+    events::record(session, TimelineKind::Stopped, format!("stopped: {}", reason));
+    session.stop_reason = Some(reason);
+    storage::save(session).ok();
+    lifecycle::fire(session, LifecycleEvent::Stopped);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn idle_session(temp_dir: &TempDir, id: &str, idle_for: chrono::Duration) -> Session {
+        let workdir = temp_dir.path().join(id);
+        crate::init::create_session_structure(&workdir).unwrap();
+        Session {
+            session_id: id.to_string(),
+            tmux_session: format!("summ-{}", id),
+            workdir,
+            status: SessionStatus::Idle,
+            created_at: Utc::now() - idle_for,
+            idle_timeout_seconds: Some(30 * 60),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(is_expired(&idle_session(&temp_dir, "idle01", chrono::Duration::hours(1))));
+        assert!(!is_expired(&idle_session(&temp_dir, "idle02", chrono::Duration::minutes(5))));
+
+        let busy = Session {
+            status: SessionStatus::Running,
+            ..idle_session(&temp_dir, "idle03", chrono::Duration::hours(1))
+        };
+        assert!(!is_expired(&busy));
+
+        let no_timeout = Session {
+            idle_timeout_seconds: None,
+            ..idle_session(&temp_dir, "idle04", chrono::Duration::hours(1))
+        };
+        assert!(!is_expired(&no_timeout));
+    }
+
+    #[test]
+    fn test_stop_records_idle_timeout() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = idle_session(&temp_dir, "idle05", chrono::Duration::hours(1));
+        stop(&mut session);
+
+        assert_eq!(session.status, SessionStatus::Stopped);
+        assert_eq!(session.stop_reason, Some(StopReason::IdleTimeout));
+        let saved = Session::load_metadata(&session.workdir).unwrap();
+        assert_eq!(saved.stop_reason, Some(StopReason::IdleTimeout));
+        let journal = events::read(&session);
+        assert_eq!(journal.last().unwrap().detail, "stopped: idle timeout");
+    }
+}
@@ -1,14 +1,98 @@
 // summ-daemon/src/init.rs
 // Initialization functions for session workdir setup
+use crate::archive::{self, ArchiveFormat};
+use crate::workspace_paths::{PathChecker, Placement};
 use anyhow::{Context, Result};
-use compress_tools::Ownership;
 use std::fs;
+use sha2::{Digest, Sha256};
 use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use summ_common::{DaemonConfig, DaemonError, InitManifest, InitPathIssue, InitPathPolicy};
+use tokio::time::Instant;
+
+/// File in a session's runtime directory recording what its workspace started from
+pub const INIT_MANIFEST_FILE: &str = "init_manifest.json";
+
+/// Initialize `workdir` from `init_path`, giving up at `deadline`, and describe
+/// the result in an `InitManifest`. An empty `init_path` (an ephemeral session
+/// started without a source) leaves the workdir empty.
+///
+/// The copy or extraction (and hashing) runs on a blocking thread. When the deadline
+/// passes this returns an `E012` error right away; the partial workdir is removed as
+/// soon as the copy stops (at its next file) or the archive extraction finishes.
+#[tracing::instrument(skip_all, fields(init = %init_path.display()))]
+pub async fn initialize_workdir_until(
+    workdir: &Path,
+    init_path: &Path,
+    deadline: Option<Instant>,
+    config: &DaemonConfig,
+) -> Result<InitManifest> {
+    if init_path.as_os_str().is_empty() {
+        fs::create_dir_all(workdir)
+            .with_context(|| format!("Failed to create {}", workdir.display()))?;
+        return Ok(InitManifest {
+            source: PathBuf::new(),
+            kind: "empty".to_string(),
+            sha256: None,
+            file_count: 0,
+            total_bytes: 0,
+            path_issues: Vec::new(),
+        });
+    }
 
-/// Initialize a workdir from a source (directory, zip, or tar.gz)
-pub fn initialize_workdir(workdir: &Path, init_path: &Path) -> Result<()> {
+    // Set on timeout (to stop the copy) and when the blocking work ends;
+    // whichever side sets it second after a timeout removes the partial workdir
+    let finished = Arc::new(AtomicBool::new(false));
+    let task = tokio::task::spawn_blocking({
+        let finished = finished.clone();
+        let workdir = workdir.to_path_buf();
+        let init_path = init_path.to_path_buf();
+        let config = config.clone();
+        move || {
+            let result = initialize_workdir(&workdir, &init_path, &finished, config.init_path_policy)
+                .and_then(|path_issues| {
+                    let manifest = build_manifest(&workdir, &init_path, &config)?;
+                    Ok(InitManifest { path_issues, ..manifest })
+                });
+            if finished.swap(true, Ordering::SeqCst) {
+                let _ = fs::remove_dir_all(&workdir);
+            }
+            result
+        }
+    });
+
+    let joined = match deadline {
+        Some(deadline) => match tokio::time::timeout_at(deadline, task).await {
+            Ok(joined) => joined,
+            Err(_) => {
+                if finished.swap(true, Ordering::SeqCst) {
+                    let _ = fs::remove_dir_all(workdir);
+                }
+                return Err(DaemonError::e012(format!(
+                    "Deadline exceeded while initializing from {}",
+                    init_path.display()
+                ))
+                .into());
+            }
+        },
+        None => task.await,
+    };
+    joined.context("Workspace initialization task failed")?
+}
+
+/// Initialize a workdir from a source (directory, zip, or tar.gz), returning
+/// the entries `policy` left out or renamed.
+///
+/// A directory copy stops with an error once `cancel` is set. A failed
+/// initialization removes what it wrote, so no session runs in half a workspace.
+pub fn initialize_workdir(
+    workdir: &Path,
+    init_path: &Path,
+    cancel: &AtomicBool,
+    policy: InitPathPolicy,
+) -> Result<Vec<InitPathIssue>> {
     if !init_path.exists() {
         anyhow::bail!(
             "Initialization source not found: {}",
@@ -16,102 +100,290 @@ pub fn initialize_workdir(workdir: &Path, init_path: &Path) -> Result<()> {
         );
     }
 
-    if init_path.is_dir() {
-        copy_dir_contents(init_path, workdir)?;
-    } else if init_path.extension().map_or(false, |e| e == "zip") {
-        extract_zip(init_path, workdir)?;
-    } else if init_path.to_string_lossy().ends_with(".tar.gz")
-        || init_path.to_string_lossy().ends_with(".tgz")
-    {
-        extract_tar_gz(init_path, workdir)?;
+    let mut checker = PathChecker::new(workdir, policy);
+    let result = if init_path.is_dir() {
+        copy_dir_contents(init_path, workdir, &mut checker, cancel)
+    } else if let Some(format) = ArchiveFormat::from_path(init_path) {
+        match format {
+            ArchiveFormat::Zip => extract_zip(init_path, workdir, &mut checker),
+            ArchiveFormat::TarGz => extract_tar_gz(init_path, workdir, &mut checker),
+        }
     } else {
         anyhow::bail!(
-            "Unsupported initialization source: {}. Expected directory, .zip, or .tar.gz",
-            init_path.display()
+            "Unsupported initialization source: {}. Expected directory, .zip, or .tar.gz \
+             (archives are read with the {} backend)",
+            init_path.display(),
+            archive::BACKEND
         );
+    };
+    let result = result.and_then(|()| Ok(checker.finish(init_path)?));
+    match &result {
+        Ok(issues) if !issues.is_empty() => tracing::warn!(
+            "{} entries of {} were left out of the workspace or renamed (init_path_policy)",
+            issues.len(),
+            init_path.display()
+        ),
+        Ok(_) => {}
+        Err(_) => {
+            let _ = fs::remove_dir_all(workdir);
+        }
     }
-    Ok(())
+    result
+}
+
+/// Entries looked at before a size measurement gives up
+const MAX_MEASURE_ENTRIES: usize = 100_000;
+
+/// Measured size of an init source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitSize {
+    /// Bytes counted
+    pub bytes: u64,
+    /// False when the measurement stopped early and `bytes` is a lower bound
+    pub complete: bool,
+}
+
+impl std::fmt::Display for InitSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const MB: f64 = 1024.0 * 1024.0;
+        if !self.complete {
+            write!(f, "at least ")?;
+        }
+        match self.bytes as f64 / MB {
+            mb if mb >= 1024.0 => write!(f, "{:.1} GB", mb / 1024.0),
+            mb => write!(f, "{:.1} MB", mb),
+        }
+    }
+}
+
+/// Size of a directory's files or an archive's uncompressed entries.
+///
+/// The measurement stops (as a lower bound) once it exceeds `limit` bytes or has
+/// looked at `MAX_MEASURE_ENTRIES` entries, so it stays fast for huge sources.
+/// Archives that cannot be listed report their own file size as a lower bound.
+pub fn measure_init_source(init_path: &Path, limit: u64) -> Option<InitSize> {
+    if init_path.is_dir() {
+        Some(measure_dir(init_path, limit))
+    } else if init_path.is_file() {
+        measure_archive(init_path, limit).or_else(|| {
+            let bytes = fs::metadata(init_path).ok()?.len();
+            Some(InitSize { bytes, complete: false })
+        })
+    } else {
+        None
+    }
+}
+
+fn measure_dir(root: &Path, limit: u64) -> InitSize {
+    let mut size = InitSize { bytes: 0, complete: true };
+    let mut pending = vec![root.to_path_buf()];
+    let mut entries = 0;
+
+    while let Some(dir) = pending.pop() {
+        let Ok(read_dir) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            entries += 1;
+            if entries > MAX_MEASURE_ENTRIES || size.bytes > limit {
+                size.complete = false;
+                return size;
+            }
+            // Symlinks are not copied, so they are not counted either
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => pending.push(entry.path()),
+                Ok(file_type) if file_type.is_file() => {
+                    size.bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                }
+                _ => {}
+            }
+        }
+    }
+    size
+}
+
+fn measure_archive(archive_path: &Path, limit: u64) -> Option<InitSize> {
+    let (bytes, complete) = archive::measure(archive_path, limit, MAX_MEASURE_ENTRIES)?;
+    Some(InitSize { bytes, complete })
+}
+
+/// Refuse an init source larger than `max_init_size_mb` (0 disables the check) with
+/// an `E010` stating the measured size and the override
+pub fn check_init_size(init_path: &Path, max_init_size_mb: u64) -> Result<(), DaemonError> {
+    if max_init_size_mb == 0 {
+        return Ok(());
+    }
+    let limit = max_init_size_mb.saturating_mul(1024 * 1024);
+    match measure_init_source(init_path, limit) {
+        Some(size) if size.bytes > limit => Err(DaemonError::e010(format!(
+            "Initialization source {} is {}, over the {} MB limit (max_init_size_mb); \
+             pass --allow-large-init to start anyway",
+            init_path.display(),
+            size,
+            max_init_size_mb
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Kind of init source, as dispatched by `initialize_workdir`
+fn source_kind(init_path: &Path) -> &'static str {
+    if init_path.is_dir() {
+        "directory"
+    } else if init_path.extension().is_some_and(|e| e == "zip") {
+        "zip"
+    } else {
+        "tar.gz"
+    }
+}
+
+/// Files under `root` as (relative path, size), sorted by path; symlinks are skipped
+fn workspace_files(root: &Path) -> Result<Vec<(String, PathBuf, u64)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        {
+            let entry = entry.context("Failed to read directory entry")?;
+            let file_type = entry.file_type().context("Failed to get file type")?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().into_owned();
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                files.push((relative, path, size));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to hash {}", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
 }
 
-/// Copy directory contents recursively from source to destination
-pub fn copy_dir_contents(source: &Path, destination: &Path) -> Result<()> {
+/// Describe a freshly initialized `workspace` and where it came from.
+///
+/// Archives are hashed as files and directories by their copied files, when
+/// `hash_init` is on; directories over `hash_init_max_dir_mb` are not hashed.
+pub fn build_manifest(
+    workspace: &Path,
+    init_path: &Path,
+    config: &DaemonConfig,
+) -> Result<InitManifest> {
+    let files = workspace_files(workspace)?;
+    let total_bytes = files.iter().map(|(_, _, size)| size).sum();
+    let kind = source_kind(init_path);
+
+    let sha256 = match kind {
+        _ if !config.hash_init => None,
+        "directory" if total_bytes > config.hash_init_max_dir_mb.saturating_mul(1024 * 1024) => None,
+        "directory" => {
+            let mut hasher = Sha256::new();
+            for (relative, path, size) in &files {
+                hasher.update(relative.as_bytes());
+                hasher.update([0]);
+                hasher.update(size.to_le_bytes());
+                let mut file = File::open(path)
+                    .with_context(|| format!("Failed to open {}", path.display()))?;
+                std::io::copy(&mut file, &mut hasher)
+                    .with_context(|| format!("Failed to hash {}", path.display()))?;
+            }
+            Some(format!("{:x}", hasher.finalize()))
+        }
+        _ => Some(sha256_file(init_path)?),
+    };
+
+    Ok(InitManifest {
+        source: fs::canonicalize(init_path).unwrap_or_else(|_| init_path.to_path_buf()),
+        kind: kind.to_string(),
+        sha256,
+        file_count: files.len() as u64,
+        total_bytes,
+        path_issues: Vec::new(),
+    })
+}
+
+/// Copy directory contents recursively from source to destination, where
+/// `checker` places them, checking `cancel` before every entry
+pub fn copy_dir_contents(
+    source: &Path,
+    destination: &Path,
+    checker: &mut PathChecker,
+    cancel: &AtomicBool,
+) -> Result<()> {
     if !source.exists() {
         anyhow::bail!("Source directory does not exist: {}", source.display());
     }
+    copy_tree(source, Path::new(""), destination, checker, cancel)
+}
+
+/// Copy the directory `relative` of `source` and everything below it
+fn copy_tree(
+    source: &Path,
+    relative: &Path,
+    destination: &Path,
+    checker: &mut PathChecker,
+    cancel: &AtomicBool,
+) -> Result<()> {
+    let dir = source.join(relative);
 
     // Create destination directory if it doesn't exist
     fs::create_dir_all(destination)
         .context(format!("Failed to create directory: {}", destination.display()))?;
 
     // Iterate through entries in source directory
-    for entry in fs::read_dir(source)
-        .context(format!("Failed to read directory: {}", source.display()))?
+    for entry in fs::read_dir(&dir)
+        .context(format!("Failed to read directory: {}", dir.display()))?
     {
+        if cancel.load(Ordering::SeqCst) {
+            anyhow::bail!("Workspace initialization cancelled");
+        }
         let entry = entry.context("Failed to read directory entry")?;
         let file_type = entry.file_type().context("Failed to get file type")?;
+        // Skip symlinks and other special files
+        if !file_type.is_dir() && !file_type.is_file() {
+            continue;
+        }
         let src_path = entry.path();
-        let dest_path = destination.join(entry.file_name());
+        let entry_relative = relative.join(entry.file_name());
+        let Placement::Write(target) = checker.place(&entry_relative) else {
+            continue;
+        };
+        let dest_path = destination.join(target);
 
         if file_type.is_dir() {
-            copy_dir_contents(&src_path, &dest_path)?;
-        } else if file_type.is_file() {
+            fs::create_dir_all(&dest_path)
+                .context(format!("Failed to create directory: {}", dest_path.display()))?;
+            copy_tree(source, &entry_relative, destination, checker, cancel)?;
+        } else {
             fs::copy(&src_path, &dest_path).context(format!(
                 "Failed to copy file from {} to {}",
                 src_path.display(),
                 dest_path.display()
             ))?;
         }
-        // Skip symlinks and other special files
     }
 
     Ok(())
 }
 
 /// Extract a ZIP archive to the destination directory
-pub fn extract_zip(archive_path: &Path, destination: &Path) -> Result<()> {
-    if !archive_path.exists() {
-        anyhow::bail!("Archive not found: {}", archive_path.display());
-    }
-
-    fs::create_dir_all(destination)
-        .context(format!("Failed to create directory: {}", destination.display()))?;
-
-    let file = File::open(archive_path).context(format!(
-        "Failed to open archive: {}",
-        archive_path.display()
-    ))?;
-    let mut reader = BufReader::new(file);
-
-    compress_tools::uncompress_archive(&mut reader, destination, Ownership::Ignore).context(format!(
-        "Failed to extract ZIP archive: {}",
-        archive_path.display()
-    ))?;
-
-    Ok(())
+pub fn extract_zip(archive_path: &Path, destination: &Path, checker: &mut PathChecker) -> Result<()> {
+    archive::extract(archive_path, destination, ArchiveFormat::Zip, checker)
 }
 
 /// Extract a tar.gz archive to the destination directory
-pub fn extract_tar_gz(archive_path: &Path, destination: &Path) -> Result<()> {
-    if !archive_path.exists() {
-        anyhow::bail!("Archive not found: {}", archive_path.display());
-    }
-
-    fs::create_dir_all(destination)
-        .context(format!("Failed to create directory: {}", destination.display()))?;
-
-    let file = File::open(archive_path).context(format!(
-        "Failed to open archive: {}",
-        archive_path.display()
-    ))?;
-    let mut reader = BufReader::new(file);
-
-    compress_tools::uncompress_archive(&mut reader, destination, Ownership::Ignore)
-        .context(format!(
-            "Failed to extract tar.gz archive: {}",
-            archive_path.display()
-        ))?;
-
-    Ok(())
+pub fn extract_tar_gz(archive_path: &Path, destination: &Path, checker: &mut PathChecker) -> Result<()> {
+    archive::extract(archive_path, destination, ArchiveFormat::TarGz, checker)
 }
 
 /// Create the session structure with workspace and runtime directories
@@ -136,8 +408,18 @@ pub fn create_session_structure(session_dir: &Path) -> Result<()> {
 mod tests {
     use super::*;
     use std::io::Write;
+    use summ_common::InitPathProblem;
     use tempfile::TempDir;
 
+    fn copy_all(source: &Path, destination: &Path, cancelled: bool) -> Result<()> {
+        let mut checker = PathChecker::new(destination, InitPathPolicy::Fail);
+        copy_dir_contents(source, destination, &mut checker, &AtomicBool::new(cancelled))
+    }
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
     #[test]
     fn test_copy_dir_contents() {
         let source_dir = TempDir::new().unwrap();
@@ -153,7 +435,7 @@ mod tests {
         File::create(&file2).unwrap().write_all(b"content2").unwrap();
 
         // Copy
-        let result = copy_dir_contents(source_dir.path(), dest_dir.path());
+        let result = copy_all(source_dir.path(), dest_dir.path(), false);
         assert!(result.is_ok());
 
         // Verify
@@ -171,7 +453,7 @@ mod tests {
         let dest_dir = TempDir::new().unwrap();
 
         // Empty directory copy should succeed
-        let result = copy_dir_contents(source_dir.path(), dest_dir.path());
+        let result = copy_all(source_dir.path(), dest_dir.path(), false);
         assert!(result.is_ok());
     }
 
@@ -198,7 +480,7 @@ mod tests {
         File::create(&test_file).unwrap().write_all(b"hello").unwrap();
 
         // Initialize from directory
-        let result = initialize_workdir(dest_dir.path(), source_dir.path());
+        let result = initialize_workdir(dest_dir.path(), source_dir.path(), &AtomicBool::new(false), InitPathPolicy::Fail);
         assert!(result.is_ok());
 
         // Verify content copied
@@ -213,7 +495,7 @@ mod tests {
         let nonexistent = temp_dir.path().join("does_not_exist");
         let dest_dir = TempDir::new().unwrap();
 
-        let result = initialize_workdir(dest_dir.path(), &nonexistent);
+        let result = initialize_workdir(dest_dir.path(), &nonexistent, &AtomicBool::new(false), InitPathPolicy::Fail);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
@@ -224,7 +506,7 @@ mod tests {
         let fake_zip = temp_dir.path().join("nonexistent.zip");
         let dest_dir = TempDir::new().unwrap();
 
-        let result = extract_zip(&fake_zip, dest_dir.path());
+        let result = extract_zip(&fake_zip, dest_dir.path(), &mut PathChecker::new(dest_dir.path(), InitPathPolicy::Fail));
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
@@ -235,7 +517,7 @@ mod tests {
         let fake_tar = temp_dir.path().join("nonexistent.tar.gz");
         let dest_dir = TempDir::new().unwrap();
 
-        let result = extract_tar_gz(&fake_tar, dest_dir.path());
+        let result = extract_tar_gz(&fake_tar, dest_dir.path(), &mut PathChecker::new(dest_dir.path(), InitPathPolicy::Fail));
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
@@ -248,8 +530,289 @@ mod tests {
 
         File::create(&fake_archive).unwrap().write_all(b"content").unwrap();
 
-        let result = initialize_workdir(dest_dir.path(), &fake_archive);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Unsupported"));
+        let result = initialize_workdir(dest_dir.path(), &fake_archive, &AtomicBool::new(false), InitPathPolicy::Fail);
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("Unsupported"));
+        assert!(message.contains(&format!("the {} backend", archive::BACKEND)), "{}", message);
+    }
+
+    fn source_with_files(count: usize) -> TempDir {
+        let source_dir = TempDir::new().unwrap();
+        for i in 0..count {
+            fs::write(source_dir.path().join(format!("file{}.txt", i)), "content").unwrap();
+        }
+        source_dir
+    }
+
+    #[test]
+    fn test_copy_dir_contents_cancelled() {
+        let source_dir = source_with_files(3);
+        let dest_dir = TempDir::new().unwrap();
+
+        let result = copy_all(source_dir.path(), dest_dir.path(), true);
+        assert!(result.unwrap_err().to_string().contains("cancelled"));
+        assert_eq!(fs::read_dir(dest_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_measure_init_source_directory() {
+        let source_dir = TempDir::new().unwrap();
+        fs::create_dir_all(source_dir.path().join("a/b")).unwrap();
+        fs::write(source_dir.path().join("top.txt"), vec![0u8; 1000]).unwrap();
+        fs::write(source_dir.path().join("a/b/deep.txt"), vec![0u8; 500]).unwrap();
+        std::os::unix::fs::symlink("/", source_dir.path().join("root-link")).unwrap();
+
+        let size = measure_init_source(source_dir.path(), u64::MAX).unwrap();
+        assert_eq!(size, InitSize { bytes: 1500, complete: true });
+
+        // Stops early once over the limit
+        let size = measure_init_source(source_dir.path(), 100).unwrap();
+        assert!(!size.complete);
+        assert!(size.bytes > 100);
+
+        assert_eq!(measure_init_source(&source_dir.path().join("missing"), 100), None);
+    }
+
+    #[test]
+    fn test_check_init_size() {
+        let source_dir = TempDir::new().unwrap();
+        fs::write(source_dir.path().join("big.bin"), vec![0u8; 3 * 1024 * 1024]).unwrap();
+
+        assert!(check_init_size(source_dir.path(), 4).is_ok());
+        assert!(check_init_size(source_dir.path(), 0).is_ok());
+        let error = check_init_size(source_dir.path(), 2).unwrap_err();
+        assert_eq!(error.code.code(), "E010");
+        assert!(error.message.contains("is 3.0 MB, over the 2 MB limit"), "{}", error.message);
+        assert!(error.message.contains("--allow-large-init"));
+
+        // An archive that cannot be listed is measured by its own size, as a lower bound
+        let archive = source_dir.path().join("source.tar.gz");
+        fs::write(&archive, vec![0xabu8; 3 * 1024 * 1024]).unwrap();
+        let error = check_init_size(&archive, 2).unwrap_err();
+        assert!(error.message.contains("is at least 3.0 MB"), "{}", error.message);
+    }
+
+    #[test]
+    fn test_build_manifest_for_directory() {
+        let source_dir = TempDir::new().unwrap();
+        fs::create_dir_all(source_dir.path().join("src")).unwrap();
+        fs::write(source_dir.path().join("README.md"), "hello\n").unwrap();
+        fs::write(source_dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = DaemonConfig::with_base_dir(temp_dir.path());
+
+        let manifest = build_manifest(source_dir.path(), source_dir.path(), &config).unwrap();
+        assert_eq!(manifest.kind, "directory");
+        assert_eq!(manifest.source, fs::canonicalize(source_dir.path()).unwrap());
+        assert_eq!(manifest.file_count, 2);
+        assert_eq!(manifest.total_bytes, 6 + 13);
+        let sha256 = manifest.sha256.unwrap();
+        assert_eq!(sha256.len(), 64);
+
+        // Same files, same hash; a changed file changes it
+        let copy = TempDir::new().unwrap();
+        copy_all(source_dir.path(), copy.path(), false).unwrap();
+        let copied = build_manifest(copy.path(), copy.path(), &config).unwrap();
+        assert_eq!(copied.sha256.as_deref(), Some(sha256.as_str()));
+        fs::write(copy.path().join("src/main.rs"), "fn main() { }\n").unwrap();
+        let changed = build_manifest(copy.path(), copy.path(), &config).unwrap();
+        assert_ne!(changed.sha256.as_deref(), Some(sha256.as_str()));
+
+        // Hashing is skipped when disabled or for directories over the size threshold
+        config.hash_init_max_dir_mb = 0;
+        assert_eq!(build_manifest(source_dir.path(), source_dir.path(), &config).unwrap().sha256, None);
+        config.hash_init_max_dir_mb = 512;
+        config.hash_init = false;
+        assert_eq!(build_manifest(source_dir.path(), source_dir.path(), &config).unwrap().sha256, None);
+    }
+
+    #[test]
+    fn test_build_manifest_hashes_archive_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("repo.tar.gz");
+        fs::write(&archive, "abc").unwrap();
+        let workspace = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace).unwrap();
+        fs::write(workspace.join("extracted.txt"), "data").unwrap();
+
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let manifest = build_manifest(&workspace, &archive, &config).unwrap();
+        assert_eq!(manifest.kind, "tar.gz");
+        assert_eq!(
+            manifest.sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(manifest.file_count, 1);
+        assert_eq!(manifest.total_bytes, 4);
+    }
+
+    #[tokio::test]
+    async fn test_initialize_workdir_until_within_deadline() {
+        let source_dir = source_with_files(3);
+        let temp_dir = TempDir::new().unwrap();
+        let session_dir = temp_dir.path().join("session_001");
+        create_session_structure(&session_dir).unwrap();
+
+        let deadline = Instant::now() + std::time::Duration::from_secs(30);
+        let workspace_dir = session_dir.join("workspace");
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let manifest = initialize_workdir_until(&workspace_dir, source_dir.path(), Some(deadline), &config)
+            .await
+            .unwrap();
+        assert!(workspace_dir.join("file2.txt").exists());
+        assert_eq!(manifest.kind, "directory");
+        assert_eq!(manifest.file_count, 3);
+        assert!(manifest.sha256.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_initialize_workdir_until_empty_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_dir = temp_dir.path().join("session_001").join("workspace");
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let manifest = initialize_workdir_until(&workspace_dir, Path::new(""), None, &config)
+            .await
+            .unwrap();
+        assert!(workspace_dir.is_dir());
+        assert_eq!(fs::read_dir(&workspace_dir).unwrap().count(), 0);
+        assert_eq!(manifest.kind, "empty");
+        assert_eq!(manifest.file_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_initialize_workdir_until_deadline_cleans_up() {
+        let source_dir = source_with_files(2000);
+        let temp_dir = TempDir::new().unwrap();
+        let session_dir = temp_dir.path().join("session_001");
+        create_session_structure(&session_dir).unwrap();
+
+        let workspace_dir = session_dir.join("workspace");
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let err = initialize_workdir_until(&workspace_dir, source_dir.path(), Some(Instant::now()), &config)
+            .await
+            .unwrap_err();
+        let daemon_error = err.downcast_ref::<DaemonError>().unwrap();
+        assert_eq!(daemon_error.code.code(), "E012");
+
+        // The partial workspace disappears once the copy notices the cancellation
+        for _ in 0..500 {
+            if !workspace_dir.exists() {
+                assert!(session_dir.join("runtime").exists());
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("partial workspace was not removed");
+    }
+
+    /// Initialize a fresh workspace from `source` under `policy`
+    fn init_with(source: &Path, policy: InitPathPolicy) -> (TempDir, PathBuf, Result<Vec<InitPathIssue>>) {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_dir = temp_dir.path().join("workspace");
+        fs::create_dir(&workspace_dir).unwrap();
+        let result = initialize_workdir(&workspace_dir, source, &AtomicBool::new(false), policy);
+        (temp_dir, workspace_dir, result)
+    }
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut files: Vec<String> = workspace_files(dir)
+            .unwrap()
+            .into_iter()
+            .map(|(relative, _, _)| relative)
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn case_collision_dir() -> TempDir {
+        let source_dir = TempDir::new().unwrap();
+        fs::create_dir(source_dir.path().join("src")).unwrap();
+        fs::write(source_dir.path().join("README.md"), "upper\n").unwrap();
+        fs::write(source_dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        // Created after the others, which read_dir does not promise to keep;
+        // the second of each pair collides whichever order it lists them in
+        fs::write(source_dir.path().join("readme.md"), "lower\n").unwrap();
+        source_dir
+    }
+
+    #[test]
+    fn test_case_collisions_fail_without_a_workspace() {
+        let source_dir = case_collision_dir();
+        let sources = [
+            fixture("case_collision.zip"),
+            fixture("case_collision.tar.gz"),
+            source_dir.path().to_path_buf(),
+        ];
+        for source in &sources {
+            let (_temp_dir, workspace_dir, result) = init_with(source, InitPathPolicy::Fail);
+            let error = result.unwrap_err().downcast::<DaemonError>().unwrap();
+            assert_eq!(error.code.code(), "E004");
+            assert!(error.message.contains("differs only by case from"), "{}", error.message);
+            assert!(error.message.contains("init_path_policy is `fail`"), "{}", error.message);
+            assert!(!workspace_dir.exists(), "{}", source.display());
+        }
+    }
+
+    #[test]
+    fn test_case_collisions_skipped_or_renamed() {
+        for name in ["case_collision.zip", "case_collision.tar.gz"] {
+            let (_temp_dir, workspace_dir, result) = init_with(&fixture(name), InitPathPolicy::WarnAndSkip);
+            let issues = result.unwrap();
+            assert_eq!(entries(&workspace_dir), ["README.md", "src/main.rs"], "{}", name);
+            let skipped: Vec<&str> = issues.iter().map(|issue| issue.entry.as_str()).collect();
+            assert_eq!(skipped, ["readme.md", "Src"], "{}", name);
+            assert!(issues.iter().all(|issue| issue.renamed_to.is_none()));
+
+            let (_temp_dir, workspace_dir, result) = init_with(&fixture(name), InitPathPolicy::Rename);
+            let issues = result.unwrap();
+            assert_eq!(
+                entries(&workspace_dir),
+                ["README.md", "Src~2/lib.rs", "readme~2.md", "src/main.rs"],
+                "{}",
+                name
+            );
+            assert_eq!(fs::read_to_string(workspace_dir.join("readme~2.md")).unwrap(), "lower\n");
+            assert_eq!(issues.len(), 2, "{}", name);
+            assert_eq!(issues[0].collides_with.as_deref(), Some("README.md"));
+        }
+
+        let source_dir = case_collision_dir();
+        let (_temp_dir, workspace_dir, result) = init_with(source_dir.path(), InitPathPolicy::Rename);
+        assert_eq!(result.unwrap().len(), 1);
+        assert_eq!(entries(&workspace_dir).len(), 3);
+    }
+
+    #[test]
+    fn test_too_long_paths_by_policy() {
+        for name in ["long_path.zip", "long_path.tar.gz"] {
+            let (_temp_dir, workspace_dir, result) = init_with(&fixture(name), InitPathPolicy::Fail);
+            let error = result.unwrap_err().downcast::<DaemonError>().unwrap();
+            assert_eq!(error.code.code(), "E004");
+            assert!(error.message.contains("is too long for the workspace's filesystem"), "{}", error.message);
+            assert!(!workspace_dir.exists(), "{}", name);
+
+            for policy in [InitPathPolicy::WarnAndSkip, InitPathPolicy::Rename] {
+                let (_temp_dir, workspace_dir, result) = init_with(&fixture(name), policy);
+                let issues = result.unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+                assert_eq!(entries(&workspace_dir), ["ok.txt"], "{}", name);
+                assert_eq!(issues.len(), 1);
+                assert_eq!(issues[0].problem, InitPathProblem::TooLong);
+                assert!(issues[0].entry.starts_with("node_modules/a-very-long"), "{}", issues[0].entry);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initialize_workdir_until_records_path_issues() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_dir = temp_dir.path().join("session_001").join("workspace");
+        let mut config = DaemonConfig::with_base_dir(temp_dir.path());
+        config.init_path_policy = InitPathPolicy::Rename;
+        let manifest = initialize_workdir_until(&workspace_dir, &fixture("case_collision.tar.gz"), None, &config)
+            .await
+            .unwrap();
+        assert_eq!(manifest.file_count, 4);
+        assert_eq!(manifest.path_issues.len(), 2);
+        assert_eq!(manifest.path_issues[1].renamed_to.as_deref(), Some("Src~2"));
     }
 }
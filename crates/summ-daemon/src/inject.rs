@@ -0,0 +1,156 @@
+// summ-daemon/src/inject.rs
+// Message injection with optional pacing and bracketed paste
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+use crate::tmux::TmuxManager;
+
+/// Upper bound on how long a single paced injection may take
+pub const MAX_INJECT_DURATION: Duration = Duration::from_secs(300);
+
+/// Escape sequence that opens a bracketed paste
+const BRACKETED_PASTE_START: &str = "\x1b[200~";
+/// Escape sequence that closes a bracketed paste
+const BRACKETED_PASTE_END: &str = "\x1b[201~";
+
+/// Options controlling how a message is delivered to the tmux pane
+#[derive(Debug, Clone, Default)]
+pub struct InjectOptions {
+    /// Delay between individual characters (simulated typing)
+    pub typing_delay: Option<Duration>,
+    /// Delay between lines
+    pub line_delay: Option<Duration>,
+    /// Wrap the payload in bracketed paste escape sequences
+    pub bracketed_paste: bool,
+}
+
+impl InjectOptions {
+    /// Build options from the millisecond values carried by the Inject request
+    pub fn from_millis(
+        typing_delay_ms: Option<u64>,
+        line_delay_ms: Option<u64>,
+        bracketed_paste: bool,
+    ) -> Self {
+        Self {
+            typing_delay: typing_delay_ms.filter(|ms| *ms > 0).map(Duration::from_millis),
+            line_delay: line_delay_ms.filter(|ms| *ms > 0).map(Duration::from_millis),
+            bracketed_paste,
+        }
+    }
+
+    /// Whether the message has to be sent in several paced chunks
+    pub fn is_paced(&self) -> bool {
+        self.typing_delay.is_some() || self.line_delay.is_some()
+    }
+
+    /// Estimate the total time spent sleeping while injecting `message`
+    pub fn estimated_duration(&self, message: &str) -> Duration {
+        let mut total = Duration::ZERO;
+        if let Some(delay) = self.typing_delay {
+            total += delay.saturating_mul(message.chars().count() as u32);
+        }
+        if let Some(delay) = self.line_delay {
+            let breaks = message.lines().count().saturating_sub(1);
+            total += delay.saturating_mul(breaks as u32);
+        }
+        total
+    }
+}
+
+/// Inject a message into a tmux session the daemon created, honoring the
+/// pacing options
+pub async fn inject_message(tmux_session: &str, message: &str, options: &InjectOptions) -> Result<()> {
+    if !options.is_paced() && !options.bracketed_paste {
+        TmuxManager::ensure_owned(tmux_session)?;
+        return TmuxManager::send_keys(tmux_session, message, true);
+    }
+
+    let estimated = options.estimated_duration(message);
+    if estimated > MAX_INJECT_DURATION {
+        anyhow::bail!(
+            "Injection would take about {}s, exceeding the maximum of {}s",
+            estimated.as_secs(),
+            MAX_INJECT_DURATION.as_secs()
+        );
+    }
+
+    TmuxManager::ensure_owned(tmux_session)?;
+    tokio::time::timeout(MAX_INJECT_DURATION, send_paced(tmux_session, message, options))
+        .await
+        .context("Injection exceeded the maximum allowed duration")?
+}
+
+/// Send the message chunk by chunk, sleeping between chunks as configured
+async fn send_paced(tmux_session: &str, message: &str, options: &InjectOptions) -> Result<()> {
+    if options.bracketed_paste {
+        TmuxManager::send_literal(tmux_session, BRACKETED_PASTE_START)?;
+    }
+
+    let lines = split_lines(message);
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            if let Some(delay) = options.line_delay {
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        match options.typing_delay {
+            Some(delay) => {
+                for (pos, ch) in line.chars().enumerate() {
+                    if pos > 0 {
+                        tokio::time::sleep(delay).await;
+                    }
+                    TmuxManager::send_literal(tmux_session, ch.encode_utf8(&mut [0u8; 4]))?;
+                }
+            }
+            None => TmuxManager::send_literal(tmux_session, line)?,
+        }
+    }
+
+    if options.bracketed_paste {
+        TmuxManager::send_literal(tmux_session, BRACKETED_PASTE_END)?;
+    }
+
+    TmuxManager::send_key_names(tmux_session, &["Enter"])
+}
+
+/// Split a message into lines, keeping each line's trailing newline
+fn split_lines(message: &str) -> Vec<&str> {
+    message.split_inclusive('\n').collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_millis_ignores_zero() {
+        let options = InjectOptions::from_millis(Some(0), Some(0), false);
+        assert!(!options.is_paced());
+    }
+
+    #[test]
+    fn test_estimated_duration() {
+        let options = InjectOptions::from_millis(Some(10), Some(100), false);
+        // 7 chars * 10ms + 2 line breaks * 100ms
+        assert_eq!(
+            options.estimated_duration("ab\ncd\ne"),
+            Duration::from_millis(270)
+        );
+    }
+
+    #[test]
+    fn test_split_lines_keeps_newlines() {
+        assert_eq!(split_lines("a\nb\n"), vec!["a\n", "b\n"]);
+        assert_eq!(split_lines("single"), vec!["single"]);
+    }
+
+    #[tokio::test]
+    async fn test_inject_rejects_excessive_duration() {
+        let options = InjectOptions::from_millis(Some(1000), None, false);
+        let message = "x".repeat(1000);
+        let result = inject_message("summ-nonexistent", &message, &options).await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("exceeding the maximum"));
+    }
+}
@@ -1,86 +1,68 @@
 // summ-daemon/src/ipc.rs
 // IPC protocol handler for Unix socket communication
 use anyhow::{Context, Result};
-use summ_common::{Request, Response};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use summ_common::ipc::{read_message, write_message, FrameInfo};
+use summ_common::{Request, Response, Scope};
 use tokio::net::UnixStream;
 
-const MAX_REQUEST_SIZE: usize = 16 * 1024 * 1024; // 16MB
-
-/// Read a length-prefixed JSON request from the stream
-pub async fn read_request(stream: &mut UnixStream) -> Result<Request> {
-    // Read the 4-byte length prefix (big-endian u32)
-    let mut len_buf = [0u8; 4];
-    stream
-        .read_exact(&mut len_buf)
-        .await
-        .context("Failed to read request length")?;
-
-    let len = u32::from_be_bytes(len_buf) as usize;
-
-    // Validate length
-    if len > MAX_REQUEST_SIZE {
-        anyhow::bail!(
-            "Request size {} exceeds maximum allowed size of {}",
-            len,
-            MAX_REQUEST_SIZE
-        );
-    }
-
-    if len == 0 {
-        anyhow::bail!("Received empty request");
-    }
-
-    // Read the JSON payload
-    let mut buf = vec![0u8; len];
-    stream
-        .read_exact(&mut buf)
-        .await
-        .context("Failed to read request payload")?;
-
-    // Parse JSON
-    let request: Request = serde_json::from_slice(&buf)
-        .context("Failed to parse request JSON")?;
-
-    Ok(request)
+use crate::warnings;
+
+/// How long a client has to send its complete request
+pub const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Request fields ignored because this daemon does not know them
+pub static UNKNOWN_REQUEST_FIELDS: AtomicU64 = AtomicU64::new(0);
+
+/// Read a length-prefixed request, returning its scope and how it was framed.
+///
+/// Fails if the request is not complete within [`REQUEST_READ_TIMEOUT`], so a
+/// stalled client cannot hold its connection task forever.
+pub async fn read_request(stream: &mut UnixStream) -> Result<(Request, Scope, FrameInfo)> {
+    let (raw, frame): (serde_json::Value, FrameInfo) =
+        tokio::time::timeout(REQUEST_READ_TIMEOUT, read_message(stream))
+            .await
+            .map_err(|_| anyhow::anyhow!("Timed out waiting for a complete request"))??;
+    let request = Request::deserialize(&raw).context("Failed to parse request")?;
+    let scope = Scope::deserialize(&raw).context("Failed to parse request scope")?;
+    record_unknown_fields(&request, &raw);
+    Ok((request, scope, frame))
 }
 
-/// Write a length-prefixed JSON response to the stream
-pub async fn write_response(stream: &mut UnixStream, response: &Response) -> Result<()> {
-    // Serialize response to JSON
-    let json_bytes = serde_json::to_vec(response)
-        .context("Failed to serialize response")?;
-
-    // Validate response size
-    if json_bytes.len() > MAX_REQUEST_SIZE {
-        anyhow::bail!(
-            "Response size {} exceeds maximum allowed size of {}",
-            json_bytes.len(),
-            MAX_REQUEST_SIZE
-        );
+/// Fields newer clients send that this daemon ignores are tolerated, but
+/// counted and logged: they usually mean the client is newer than the daemon
+fn record_unknown_fields(request: &Request, raw: &serde_json::Value) {
+    let unknown = request.unknown_fields(raw);
+    if unknown.is_empty() {
+        return;
     }
+    UNKNOWN_REQUEST_FIELDS.fetch_add(unknown.len() as u64, Ordering::Relaxed);
+    warnings::warn(
+        "unknown_request_fields",
+        None,
+        format!(
+            "Ignored unknown fields in a {} request: {} (is the client newer than the daemon?)",
+            request.kind(),
+            unknown.join(", ")
+        ),
+    );
+}
 
-    // Write length prefix (big-endian u32)
-    let len = json_bytes.len() as u32;
-    let len_buf = len.to_be_bytes();
-    stream
-        .write_all(&len_buf)
-        .await
-        .context("Failed to write response length")?;
-
-    // Write JSON payload
-    stream
-        .write_all(&json_bytes)
-        .await
-        .context("Failed to write response payload")?;
-
-    // Flush to ensure data is sent
-    stream
-        .flush()
-        .await
-        .context("Failed to flush response")?;
-
-    Ok(())
+/// Write a length-prefixed response framed like the request it answers
+pub async fn write_response(
+    stream: &mut UnixStream,
+    response: &Response,
+    request_frame: &FrameInfo,
+) -> Result<()> {
+    write_message(
+        stream,
+        response,
+        request_frame.format,
+        request_frame.accepts_compression,
+    )
+    .await
 }
 
 #[cfg(test)]
@@ -88,6 +70,7 @@ mod tests {
     use super::*;
     use summ_common::{Request, Response};
     use std::path::PathBuf;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
     use tokio::net::{UnixListener, UnixStream};
     use tokio::task;
 
@@ -97,6 +80,22 @@ mod tests {
             cli: "claude".to_string(),
             init: PathBuf::from("/path/to/init"),
             name: Some("test-session".to_string()),
+            protected: false,
+            deadline_ms: None,
+            background: false,
+            allow_large_init: false,
+            git_checkpoint: None,
+            secrets: Vec::new(),
+            workdir_subpath: None,
+            hooks_in_subpath: false,
+            ephemeral: false,
+            idle_timeout_seconds: None,
+            init_from_session: None,
+            after: None,
+            observer_of: None,
+            busy_alert_minutes: None,
+            inject_rate_limit: None,
+            no_inject_history: false,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -127,8 +126,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_max_request_size() {
-        // MAX_REQUEST_SIZE should be 16MB
-        assert_eq!(MAX_REQUEST_SIZE, 16 * 1024 * 1024);
+        // MAX_FRAME_SIZE should be 16MB
+        assert_eq!(summ_common::ipc::MAX_FRAME_SIZE, 16 * 1024 * 1024);
     }
 
     #[tokio::test]
@@ -142,13 +141,13 @@ mod tests {
         // Spawn server task
         let server_handle = task::spawn(async move {
             let mut stream = listener.accept().await.unwrap().0;
-            let request = read_request(&mut stream).await.unwrap();
+            let (request, _, frame) = read_request(&mut stream).await.unwrap();
             match request {
                 Request::DaemonStatus => {
                     let response = Response::Success {
                         data: serde_json::json!({"running": true, "version": "0.1.0"}),
                     };
-                    write_response(&mut stream, &response).await.unwrap();
+                    write_response(&mut stream, &response, &frame).await.unwrap();
                 }
                 _ => panic!("Unexpected request"),
             }
@@ -209,4 +208,38 @@ mod tests {
 
         server_handle.await.unwrap();
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_read_request_times_out_on_stalled_client() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let socket_path = temp_dir.path().join("test.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        // Announce a 100 byte frame, send part of it, then go quiet
+        let mut client = UnixStream::connect(&socket_path).await.unwrap();
+        client.write_all(&100u32.to_be_bytes()).await.unwrap();
+        client.write_all(b"{\"type\":").await.unwrap();
+
+        let mut stream = listener.accept().await.unwrap().0;
+        let err = read_request(&mut stream).await.unwrap_err();
+        assert!(err.to_string().contains("Timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_request_fields_are_tolerated_and_counted() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let socket_path = temp_dir.path().join("test.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        let mut client = UnixStream::connect(&socket_path).await.unwrap();
+        let payload = br#"{"type":"Status","session_id":"session_abc","verbose":true}"#;
+        client.write_all(&(payload.len() as u32).to_be_bytes()).await.unwrap();
+        client.write_all(payload).await.unwrap();
+
+        let before = UNKNOWN_REQUEST_FIELDS.load(Ordering::Relaxed);
+        let mut stream = listener.accept().await.unwrap().0;
+        let (request, _, _) = read_request(&mut stream).await.unwrap();
+        assert!(matches!(request, Request::Status { session_id } if session_id == "session_abc"));
+        assert!(UNKNOWN_REQUEST_FIELDS.load(Ordering::Relaxed) > before);
+    }
 }
@@ -0,0 +1,249 @@
+// summ-daemon/src/lifecycle.rs
+// Run the user's `lifecycle_hooks` commands on session lifecycle events
+use std::collections::HashSet;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use summ_common::protocol::HookRun;
+use summ_common::{DaemonConfig, LifecycleEvent, LifecycleHook, Session, SessionInfoRef};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
+
+use crate::warnings;
+
+/// Hook commands running at once; further runs wait for one to finish
+const MAX_CONCURRENT_RUNS: usize = 4;
+
+/// Bytes of a command's stdout and stderr that are kept
+const MAX_OUTPUT_BYTES: usize = 4096;
+
+static HOOKS: OnceLock<Vec<LifecycleHook>> = OnceLock::new();
+static RUN_SLOTS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_RUNS);
+
+/// Hook runs that could not start, timed out or exited non-zero
+pub static FAILURES: AtomicU64 = AtomicU64::new(0);
+
+/// Set the daemon-wide hooks from the config
+pub fn init(config: &DaemonConfig) {
+    let mut names = HashSet::new();
+    for hook in &config.lifecycle_hooks {
+        if !names.insert(hook.name.as_str()) {
+            warn!("Lifecycle hook {:?} is configured more than once", hook.name);
+        }
+    }
+    let enabled = config.lifecycle_hooks.iter().filter(|hook| hook.enabled).count();
+    if enabled > 0 {
+        info!("{} lifecycle hook(s) enabled", enabled);
+    }
+    HOOKS.set(config.lifecycle_hooks.clone()).ok();
+}
+
+/// Run the hooks configured for `event` in the background, without waiting
+/// for them
+pub fn fire(session: &Session, event: LifecycleEvent) {
+    let Some(hooks) = HOOKS.get() else {
+        return;
+    };
+    let matching: Vec<&LifecycleHook> = hooks.iter().filter(|hook| hook.runs_on(event)).collect();
+    if matching.is_empty() {
+        return;
+    }
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let payload = match serde_json::to_vec(&SessionInfoRef::from(session)) {
+        Ok(payload) => payload,
+        Err(e) => {
+            warn!("Failed to serialize session {} for lifecycle hooks: {}", session.session_id, e);
+            return;
+        }
+    };
+    for hook in matching {
+        let hook = hook.clone();
+        let payload = payload.clone();
+        let session_id = session.session_id.clone();
+        let session_name = session.name.clone();
+        runtime.spawn(async move {
+            // The semaphore is never closed
+            let _slot = RUN_SLOTS.acquire().await.ok();
+            let run = run(&hook, event, &session_id, &session_name, &payload).await;
+            report(&run, &session_id);
+        });
+    }
+}
+
+/// Run one hook for one event and wait for it, killing it past its timeout
+pub async fn run(
+    hook: &LifecycleHook,
+    event: LifecycleEvent,
+    session_id: &str,
+    session_name: &str,
+    payload: &[u8],
+) -> HookRun {
+    let started = Instant::now();
+    let mut outcome = HookRun {
+        name: hook.name.clone(),
+        event,
+        exit_code: None,
+        timed_out: false,
+        duration_ms: 0,
+        stdout: String::new(),
+        stderr: String::new(),
+    };
+
+    let spawned = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(&hook.command)
+        .env("SUMM_SESSION_ID", session_id)
+        .env("SUMM_SESSION_NAME", session_name)
+        .env("SUMM_EVENT", event.to_string())
+        .env("SUMM_HOOK_NAME", &hook.name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            outcome.stderr = format!("failed to start: {}", e);
+            return outcome;
+        }
+    };
+
+    let stdin = child.stdin.take();
+    let finished = async move {
+        // A command that does not read its stdin closes it early
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(payload).await.ok();
+        }
+        child.wait_with_output().await
+    };
+    match tokio::time::timeout(Duration::from_secs(hook.timeout_seconds), finished).await {
+        Ok(Ok(output)) => {
+            outcome.exit_code = output.status.code();
+            outcome.stdout = truncated(&output.stdout);
+            outcome.stderr = truncated(&output.stderr);
+        }
+        Ok(Err(e)) => outcome.stderr = format!("failed to wait for the command: {}", e),
+        // Dropping the child kills it
+        Err(_) => outcome.timed_out = true,
+    }
+    outcome.duration_ms = started.elapsed().as_millis() as u64;
+    outcome
+}
+
+/// Whether the run went well: started, finished in time and exited 0
+pub fn succeeded(run: &HookRun) -> bool {
+    run.exit_code == Some(0) && !run.timed_out
+}
+
+/// Log a background run, and warn about and count a failed one
+fn report(run: &HookRun, session_id: &str) {
+    let output = [("stdout", &run.stdout), ("stderr", &run.stderr)]
+        .iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(stream, text)| format!("\n{}: {}", stream, text.trim_end()))
+        .collect::<String>();
+    if succeeded(run) {
+        info!(
+            "Lifecycle hook {} ran for session {} ({}) in {}ms{}",
+            run.name, session_id, run.event, run.duration_ms, output
+        );
+        return;
+    }
+    FAILURES.fetch_add(1, Ordering::Relaxed);
+    let failure = if run.timed_out {
+        "timed out".to_string()
+    } else {
+        match run.exit_code {
+            Some(code) => format!("exited with {}", code),
+            None => "did not finish".to_string(),
+        }
+    };
+    warnings::warn(
+        "lifecycle_hook",
+        Some(session_id),
+        format!(
+            "Lifecycle hook {} {} for session {} ({}){}",
+            run.name, failure, session_id, run.event, output
+        ),
+    );
+}
+
+/// The start of a command's output, as text
+fn truncated(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_OUTPUT_BYTES)]).into_owned();
+    if bytes.len() > MAX_OUTPUT_BYTES {
+        format!("{}… ({} bytes)", text, bytes.len())
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(command: &str, timeout_seconds: u64) -> LifecycleHook {
+        LifecycleHook {
+            name: "test".to_string(),
+            events: vec![LifecycleEvent::Stopped],
+            command: command.to_string(),
+            enabled: true,
+            timeout_seconds,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_passes_session_and_event() {
+        let echo = hook(r#"echo "$SUMM_EVENT $SUMM_SESSION_ID $SUMM_HOOK_NAME"; cat; echo oops >&2"#, 5);
+        let run = run(&echo, LifecycleEvent::Stopped, "s1", "demo", br#"{"session_id":"s1"}"#).await;
+        assert!(succeeded(&run));
+        assert_eq!(run.stdout, "stopped s1 test\n{\"session_id\":\"s1\"}");
+        assert_eq!(run.stderr, "oops\n");
+
+        let run = super::run(&hook("exit 3", 5), LifecycleEvent::Stopped, "s1", "demo", b"{}").await;
+        assert_eq!(run.exit_code, Some(3));
+        assert!(!succeeded(&run));
+    }
+
+    #[tokio::test]
+    async fn test_run_kills_commands_past_their_timeout() {
+        let started = Instant::now();
+        let run = run(&hook("sleep 30", 1), LifecycleEvent::Stopped, "s1", "demo", b"{}").await;
+        assert!(run.timed_out);
+        assert_eq!(run.exit_code, None);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_runs_on_and_config() {
+        let config: DaemonConfig = serde_json::from_value(serde_json::json!({
+            "lifecycle_hooks": [
+                {"name": "audit", "command": "logger summ"},
+                {"name": "slack", "events": ["failed", "stopped"], "command": "notify", "enabled": false}
+            ]
+        }))
+        .unwrap();
+        let [audit, slack] = config.lifecycle_hooks.as_slice() else {
+            panic!("expected two hooks");
+        };
+        assert_eq!(audit.timeout_seconds, 30);
+        assert!(audit.runs_on(LifecycleEvent::Created));
+        assert!(!slack.runs_on(LifecycleEvent::Failed));
+
+        let slack = LifecycleHook { enabled: true, ..slack.clone() };
+        assert!(slack.runs_on(LifecycleEvent::Failed));
+        assert!(!slack.runs_on(LifecycleEvent::Idle));
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!(truncated(b"short"), "short");
+        let long = vec![b'x'; MAX_OUTPUT_BYTES + 1];
+        assert!(truncated(&long).ends_with(&format!("… ({} bytes)", MAX_OUTPUT_BYTES + 1)));
+    }
+}
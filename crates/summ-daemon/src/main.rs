@@ -1,16 +1,57 @@
+mod archive;
+mod attention;
+mod browse;
+mod busy;
+mod checkpoint;
+mod clock;
+mod control;
+mod disk;
+mod environment;
+mod ephemeral;
+mod events;
+mod evict;
+mod gc;
+mod health;
+mod history;
 mod handler;
 mod hooks;
+mod idle;
 mod init;
+mod inject;
 mod ipc;
+mod lifecycle;
+mod migrate;
+#[cfg(feature = "otel")]
+mod otel;
+mod peer;
+mod pending;
+mod prompt;
+mod permissions;
 mod recovery;
+mod redact;
 mod server;
 mod session;
+mod shutdown;
+mod storage;
+mod task;
+mod throttle;
 mod tmux;
+mod upload;
+mod usage;
+mod warnings;
+mod workspace_paths;
 
 use anyhow::Result;
+use summ_common::DaemonConfig;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
 /// Initialize logging with proper defaults and environment variable support
-fn init_logging() {
+///
+/// With the `otel` feature and an `otel` config block, spans are also exported over OTLP.
+fn init_logging(config: &DaemonConfig) {
     // Set default log level based on RUST_LOG env var, defaulting to info
     // Users can set RUST_LOG=debug, RUST_LOG=summ_daemon=trace, etc.
     let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
@@ -20,23 +61,56 @@ fn init_logging() {
                 .add_directive("summ_common=info".parse().unwrap())
         });
 
-    // Configure the subscriber with pretty formatting for development
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
+    // Configure the log output with pretty formatting for development.
+    // Spans are for the exporter only; keep them out of the log lines.
+    let fmt_layer = tracing_subscriber::fmt::layer()
         .with_target(true)
         .with_thread_ids(false)
         .with_file(false)
         .with_line_number(false)
         .compact()
+        .with_filter(filter_fn(|metadata| metadata.is_event()));
+
+    #[cfg(feature = "otel")]
+    let (otel_layer, otel_error) = match config.otel.as_ref().map(otel::layer) {
+        Some(Ok(layer)) => (Some(layer), None),
+        Some(Err(e)) => (None, Some(e)),
+        None => (None, None),
+    };
+    #[cfg(not(feature = "otel"))]
+    let otel_layer: Option<tracing_subscriber::layer::Identity> = None;
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(fmt_layer)
+        .with(otel_layer)
         .init();
+
+    #[cfg(feature = "otel")]
+    match (&config.otel, otel_error) {
+        (_, Some(e)) => tracing::error!("Failed to set up OTLP export: {:#}", e),
+        (Some(otel), None) => tracing::info!("Exporting traces to {}", otel.endpoint),
+        (None, None) => {}
+    }
+    #[cfg(not(feature = "otel"))]
+    if config.otel.is_some() {
+        tracing::warn!("Ignoring `otel` config: summ-daemon was built without the otel feature");
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    if std::env::args().nth(1).as_deref() == Some("--dump-schema") {
+        println!("{}", serde_json::to_string_pretty(&summ_common::protocol_schema())?);
+        return Ok(());
+    }
+    let config = DaemonConfig::load()?;
+
     // Initialize logging
-    init_logging();
+    init_logging(&config);
+    redact::init(&config);
+    lifecycle::init(&config);
 
-    let config = summ_common::DaemonConfig::load()?;
     tracing::info!("SUMM Daemon starting...");
     tracing::debug!("Sessions directory: {:?}", config.sessions_dir);
     tracing::debug!("Logs directory: {:?}", config.logs_dir);
@@ -0,0 +1,133 @@
+// summ-daemon/src/migrate.rs
+// Schema migrations for session meta.json files
+use anyhow::Result;
+use serde_json::{Map, Value};
+use summ_common::SESSION_SCHEMA_VERSION;
+
+/// Read the schema version of a raw meta.json value (absent means version 1)
+pub fn schema_version(value: &Value) -> u32 {
+    value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Upgrade a raw meta.json value to the current schema.
+///
+/// Returns the upgraded value and whether any migration step ran.
+/// Files written by a newer daemon, and files that are not a JSON object,
+/// are rejected rather than guessed at.
+pub fn migrate_session(mut value: Value) -> Result<(Value, bool)> {
+    let mut version = schema_version(&value);
+    let Some(fields) = value.as_object_mut() else {
+        anyhow::bail!("Session metadata is not a JSON object");
+    };
+
+    if version > SESSION_SCHEMA_VERSION {
+        anyhow::bail!(
+            "Session metadata uses schema version {} but this daemon only understands up to {}. \
+             Upgrade summ-daemon to load it, or move the session directory aside",
+            version,
+            SESSION_SCHEMA_VERSION
+        );
+    }
+
+    let migrated = version < SESSION_SCHEMA_VERSION;
+    while version < SESSION_SCHEMA_VERSION {
+        match version {
+            1 => migrate_v1_to_v2(fields),
+            2 => migrate_v2_to_v3(fields),
+            _ => unreachable!("no migration defined from schema version {}", version),
+        }
+        version += 1;
+        fields.insert("schema_version".to_string(), Value::from(version));
+    }
+
+    Ok((value, migrated))
+}
+
+/// v2 added the `paused` flag and the explicit `schema_version` field
+fn migrate_v1_to_v2(fields: &mut Map<String, Value>) {
+    fields.entry("paused").or_insert(Value::Bool(false));
+}
+
+/// v3 added `created_by`; the creator of older sessions is not known
+fn migrate_v2_to_v3(fields: &mut Map<String, Value>) {
+    fields.entry("created_by").or_insert(Value::from("unknown"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use summ_common::{Session, SessionStatus};
+
+    const META_V1: &str = include_str!("../tests/fixtures/meta_v1.json");
+    const META_V2: &str = include_str!("../tests/fixtures/meta_v2.json");
+    const META_V3: &str = include_str!("../tests/fixtures/meta_v3.json");
+
+    #[test]
+    fn test_schema_version_defaults_to_1() {
+        let value: Value = serde_json::from_str(META_V1).unwrap();
+        assert_eq!(schema_version(&value), 1);
+        assert_eq!(schema_version(&serde_json::json!({"schema_version": 0})), 1);
+    }
+
+    #[test]
+    fn test_migrate_v1_fixture() {
+        let value: Value = serde_json::from_str(META_V1).unwrap();
+        let (value, migrated) = migrate_session(value).unwrap();
+        assert!(migrated);
+        assert_eq!(value["schema_version"], SESSION_SCHEMA_VERSION);
+
+        let session: Session = serde_json::from_value(value).unwrap();
+        assert_eq!(session.session_id, "session_v1");
+        assert_eq!(session.status, SessionStatus::Idle);
+        assert_eq!(session.pid, Some(4242));
+        assert!(!session.paused);
+        assert_eq!(session.created_by, "unknown");
+    }
+
+    #[test]
+    fn test_migrate_v2_fixture() {
+        let value: Value = serde_json::from_str(META_V2).unwrap();
+        let (value, migrated) = migrate_session(value).unwrap();
+        assert!(migrated);
+
+        let session: Session = serde_json::from_value(value).unwrap();
+        assert_eq!(session.schema_version, SESSION_SCHEMA_VERSION);
+        assert_eq!(session.status, SessionStatus::Paused);
+        assert!(session.paused);
+        assert_eq!(session.created_by, "unknown");
+        assert_eq!(session.created_by_uid, None);
+    }
+
+    #[test]
+    fn test_migrate_v3_fixture_is_current() {
+        let value: Value = serde_json::from_str(META_V3).unwrap();
+        let (value, migrated) = migrate_session(value).unwrap();
+        assert!(!migrated);
+
+        let session: Session = serde_json::from_value(value).unwrap();
+        assert_eq!(session.schema_version, 3);
+        assert_eq!(session.created_by, "alice");
+        assert_eq!(session.created_by_uid, Some(1000));
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_version() {
+        let mut value: Value = serde_json::from_str(META_V3).unwrap();
+        value["schema_version"] = Value::from(SESSION_SCHEMA_VERSION + 1);
+        let err = migrate_session(value).unwrap_err().to_string();
+        assert!(err.contains("Upgrade summ-daemon"));
+    }
+
+    #[test]
+    fn test_migrate_rejects_non_objects() {
+        for value in [serde_json::json!([]), serde_json::json!("x"), serde_json::json!(42), Value::Null] {
+            let err = migrate_session(value.clone()).unwrap_err().to_string();
+            assert_eq!(err, "Session metadata is not a JSON object", "{}", value);
+        }
+    }
+}
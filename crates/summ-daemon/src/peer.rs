@@ -0,0 +1,52 @@
+// summ-daemon/src/peer.rs
+// Identify the local user on the other end of an IPC connection
+use std::ffi::CStr;
+use tokio::net::UnixStream;
+
+/// Placeholder owner for sessions whose creator is not known
+pub const UNKNOWN_USER: &str = "unknown";
+
+/// uid of the connected client (SO_PEERCRED), if the platform reports it
+pub fn peer_uid(stream: &UnixStream) -> Option<u32> {
+    stream.peer_cred().ok().map(|cred| cred.uid())
+}
+
+/// Resolve a uid to its login name, falling back to the numeric uid
+pub fn username_for_uid(uid: u32) -> String {
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; 4096];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+
+    let rc = unsafe {
+        libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result)
+    };
+
+    if rc != 0 || result.is_null() || pwd.pw_name.is_null() {
+        return uid.to_string();
+    }
+
+    unsafe { CStr::from_ptr(pwd.pw_name) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_username_for_root() {
+        assert_eq!(username_for_uid(0), "root");
+    }
+
+    #[test]
+    fn test_username_for_unknown_uid_falls_back_to_number() {
+        assert_eq!(username_for_uid(4_000_000_000), "4000000000");
+    }
+
+    #[tokio::test]
+    async fn test_peer_uid_matches_current_user() {
+        let (a, _b) = UnixStream::pair().unwrap();
+        assert_eq!(peer_uid(&a), Some(summ_common::user::current_uid()));
+    }
+}
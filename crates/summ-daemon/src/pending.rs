@@ -0,0 +1,144 @@
+// summ-daemon/src/pending.rs
+// Starts held until another session reaches a status (`summ start --after`)
+use std::collections::HashMap;
+use summ_common::wait::{self, Progress};
+use summ_common::{Session, SessionStatus, StartTrigger};
+use tracing::info;
+
+use crate::session::SessionExt;
+use crate::storage;
+
+/// What a pending start should do about its trigger
+#[derive(Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// The trigger session reached the status: start now
+    Fire,
+    /// Not yet
+    Wait,
+    /// The trigger can no longer fire, for this reason
+    Fail(String),
+}
+
+/// Decide from the trigger session's status (`None` once it is gone)
+pub fn decide(trigger: &StartTrigger, current: Option<&SessionStatus>) -> Decision {
+    let Some(current) = current else {
+        return Decision::Fail(format!("Trigger session {} no longer exists", trigger.session_id));
+    };
+    match wait::progress(current, &trigger.status) {
+        Progress::Done => Decision::Fire,
+        Progress::Pending => Decision::Wait,
+        Progress::Unreachable => Decision::Fail(format!(
+            "Trigger session {} is {} and will not become {}",
+            trigger.session_id, current, trigger.status
+        )),
+    }
+}
+
+/// Move pending starts whose trigger fired to `Starting` and return them for
+/// launching; each fires at most once. Starts whose trigger can no longer
+/// fire are marked failed.
+pub fn take_due(sessions: &mut HashMap<String, Session>) -> Vec<Session> {
+    let decisions: Vec<(String, Decision)> = sessions
+        .values()
+        .filter(|session| session.status == SessionStatus::Pending)
+        .filter_map(|session| {
+            let trigger = session.start_after.as_ref()?;
+            let current = sessions.get(&trigger.session_id).map(|trigger| &trigger.status);
+            Some((session.session_id.clone(), decide(trigger, current)))
+        })
+        .collect();
+
+    let mut due = Vec::new();
+    for (session_id, decision) in decisions {
+        let Some(session) = sessions.get_mut(&session_id) else {
+            continue;
+        };
+        match decision {
+            Decision::Fire => {
+                info!("Trigger of pending session {} fired; starting it", session_id);
+                session.status = SessionStatus::Starting;
+                storage::save(session).ok();
+                due.push(session.clone());
+            }
+            Decision::Fail(reason) => session.fail_start(&anyhow::anyhow!(reason)),
+            Decision::Wait => {}
+        }
+    }
+    due
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn trigger(status: SessionStatus) -> StartTrigger {
+        StartTrigger {
+            session_id: "session_a".to_string(),
+            status,
+        }
+    }
+
+    fn session(temp_dir: &TempDir, id: &str, status: SessionStatus, start_after: Option<StartTrigger>) -> Session {
+        let workdir = temp_dir.path().join(id);
+        std::fs::create_dir_all(&workdir).unwrap();
+        Session {
+            session_id: id.to_string(),
+            status,
+            workdir,
+            start_after,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decide() {
+        let idle = trigger(SessionStatus::Idle);
+        assert_eq!(decide(&idle, Some(&SessionStatus::Idle)), Decision::Fire);
+        assert_eq!(decide(&idle, Some(&SessionStatus::Running)), Decision::Wait);
+        assert_eq!(decide(&idle, Some(&SessionStatus::Pending)), Decision::Wait);
+        assert_eq!(
+            decide(&idle, Some(&SessionStatus::Stopped)),
+            Decision::Fail("Trigger session session_a is stopped and will not become idle".to_string())
+        );
+        assert_eq!(
+            decide(&idle, None),
+            Decision::Fail("Trigger session session_a no longer exists".to_string())
+        );
+    }
+
+    #[test]
+    fn test_take_due() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut sessions: HashMap<String, Session> = [
+            session(&temp_dir, "session_a", SessionStatus::Idle, None),
+            session(&temp_dir, "session_b", SessionStatus::Pending, Some(trigger(SessionStatus::Idle))),
+            session(&temp_dir, "session_c", SessionStatus::Pending, Some(trigger(SessionStatus::Stopped))),
+            session(&temp_dir, "session_d", SessionStatus::Pending, Some(trigger(SessionStatus::Failed))),
+        ]
+        .into_iter()
+        .map(|session| (session.session_id.clone(), session))
+        .collect();
+
+        let due = take_due(&mut sessions);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].session_id, "session_b");
+        assert_eq!(sessions["session_b"].status, SessionStatus::Starting);
+        assert_eq!(
+            Session::load_metadata(&sessions["session_b"].workdir).unwrap().status,
+            SessionStatus::Starting
+        );
+        assert_eq!(sessions["session_c"].status, SessionStatus::Pending);
+        assert_eq!(sessions["session_d"].status, SessionStatus::Failed);
+        assert_eq!(
+            sessions["session_d"].start_error.as_deref(),
+            Some("Trigger session session_a is idle and will not become failed")
+        );
+
+        // Fired once: the started session is no longer pending
+        assert!(take_due(&mut sessions).is_empty());
+        sessions.remove("session_a");
+        assert!(take_due(&mut sessions).is_empty());
+        assert_eq!(sessions["session_c"].status, SessionStatus::Failed);
+    }
+}
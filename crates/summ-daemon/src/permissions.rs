@@ -0,0 +1,216 @@
+// summ-daemon/src/permissions.rs
+// Keep daemon data private to its user: workspaces and logs can hold secrets
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use summ_common::DaemonConfig;
+use tracing::{info, warn};
+
+/// Permission bits for group and other users
+const GROUP_OTHER: u32 = 0o077;
+
+/// Mode for log files the daemon creates
+const PRIVATE_FILE_MODE: u32 = 0o600;
+
+/// Paths tightened (or that could not be) by a sweep
+#[derive(Debug, Default, PartialEq)]
+pub struct PermissionReport {
+    pub tightened: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl PermissionReport {
+    fn check(&mut self, path: &Path) {
+        match make_private(path) {
+            Ok(true) => self.tightened.push(path.to_path_buf()),
+            Ok(false) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => self.failed.push((path.to_path_buf(), e.to_string())),
+        }
+    }
+
+    /// Log what was tightened and what could not be
+    fn log(&self) {
+        if !self.tightened.is_empty() {
+            info!(
+                "Removed group/other access from {} paths: {}",
+                self.tightened.len(),
+                summarize(&self.tightened)
+            );
+        }
+        for (path, error) in &self.failed {
+            warn!("Failed to restrict permissions of {}: {}", path.display(), error);
+        }
+    }
+}
+
+/// The first few paths, then a count of the rest
+fn summarize(paths: &[PathBuf]) -> String {
+    const SHOWN: usize = 5;
+    let mut summary = paths
+        .iter()
+        .take(SHOWN)
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if paths.len() > SHOWN {
+        summary.push_str(&format!(" and {} more", paths.len() - SHOWN));
+    }
+    summary
+}
+
+/// Remove group and other access from `path`; returns whether anything changed.
+/// Symlinks are left alone so a link cannot redirect the chmod elsewhere.
+fn make_private(path: &Path) -> io::Result<bool> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.file_type().is_symlink() {
+        return Ok(false);
+    }
+    let mode = metadata.permissions().mode();
+    if mode & GROUP_OTHER == 0 {
+        return Ok(false);
+    }
+    fs::set_permissions(path, fs::Permissions::from_mode(mode & !GROUP_OTHER))?;
+    Ok(true)
+}
+
+/// Startup sweep over the daemon's base, sessions, and logs directories, every
+/// session directory, every log file, and the socket. Skipped when
+/// `private_permissions` is off.
+pub fn secure_daemon_dirs(config: &DaemonConfig) -> PermissionReport {
+    let mut report = PermissionReport::default();
+    if !config.private_permissions {
+        return report;
+    }
+
+    report.check(&config.data_dir());
+    for dir in [&config.sessions_dir, &config.logs_dir] {
+        report.check(dir);
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            report.check(&entry.path());
+        }
+    }
+    report.check(&config.socket_path);
+
+    report.log();
+    report
+}
+
+/// Make a new session's directory private and create its log file with
+/// owner-only access, so the terminal log never exists in a readable state
+pub fn secure_session(config: &DaemonConfig, session_dir: &Path, log_path: &Path) {
+    if !config.private_permissions {
+        return;
+    }
+    let mut report = PermissionReport::default();
+    report.check(session_dir);
+    if let Err(e) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(PRIVATE_FILE_MODE)
+        .open(log_path)
+    {
+        report.failed.push((log_path.to_path_buf(), e.to_string()));
+    }
+    // An existing log file keeps its mode on open
+    report.check(log_path);
+    for (path, error) in &report.failed {
+        warn!("Failed to restrict permissions of {}: {}", path.display(), error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    fn set_mode(path: &Path, mode: u32) {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn test_secure_daemon_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join(".summ-daemon");
+        let config = DaemonConfig::with_base_dir(&base);
+        config.ensure_directories().unwrap();
+        let session_dir = config.sessions_dir.join("session_abc");
+        fs::create_dir_all(session_dir.join("workspace")).unwrap();
+        let log = config.logs_dir.join("session_abc.log");
+        fs::write(&log, "secret").unwrap();
+        let workspace_file = session_dir.join("workspace/notes.txt");
+        fs::write(&workspace_file, "shared on purpose").unwrap();
+
+        for dir in [&base, &config.sessions_dir, &session_dir] {
+            set_mode(dir, 0o755);
+        }
+        // Already private: left as it is
+        set_mode(&config.logs_dir, 0o700);
+        set_mode(&log, 0o640);
+        set_mode(&workspace_file, 0o644);
+
+        let report = secure_daemon_dirs(&config);
+        assert_eq!(
+            report.tightened,
+            vec![base.clone(), config.sessions_dir.clone(), session_dir.clone(), log.clone()]
+        );
+        assert!(report.failed.is_empty());
+        for dir in [&base, &config.sessions_dir, &session_dir] {
+            assert_eq!(mode(dir), 0o700);
+        }
+        assert_eq!(mode(&config.logs_dir), 0o700);
+        assert_eq!(mode(&log), 0o600);
+        // Files inside workspaces are the agent's business
+        assert_eq!(mode(&workspace_file), 0o644);
+
+        // Nothing left to do
+        assert_eq!(secure_daemon_dirs(&config), PermissionReport::default());
+    }
+
+    #[test]
+    fn test_secure_daemon_dirs_can_be_disabled() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = DaemonConfig::with_base_dir(temp_dir.path());
+        config.private_permissions = false;
+        config.ensure_directories().unwrap();
+        set_mode(&config.sessions_dir, 0o755);
+
+        assert_eq!(secure_daemon_dirs(&config), PermissionReport::default());
+        assert_eq!(mode(&config.sessions_dir), 0o755);
+    }
+
+    #[test]
+    fn test_secure_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        config.ensure_directories().unwrap();
+        let session_dir = config.sessions_dir.join("session_abc");
+        fs::create_dir_all(&session_dir).unwrap();
+        set_mode(&session_dir, 0o755);
+        let log = config.logs_dir.join("session_abc.log");
+
+        secure_session(&config, &session_dir, &log);
+        assert_eq!(mode(&session_dir), 0o700);
+        assert_eq!(mode(&log), 0o600);
+
+        // An existing, readable log is tightened too
+        set_mode(&log, 0o644);
+        secure_session(&config, &session_dir, &log);
+        assert_eq!(mode(&log), 0o600);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let paths: Vec<PathBuf> = (0..7).map(|i| PathBuf::from(format!("/p{}", i))).collect();
+        assert_eq!(summarize(&paths[..2]), "/p0, /p1");
+        assert_eq!(summarize(&paths), "/p0, /p1, /p2, /p3, /p4 and 2 more");
+    }
+}
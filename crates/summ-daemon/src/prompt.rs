@@ -0,0 +1,374 @@
+// summ-daemon/src/prompt.rs
+// Detect CLIs sitting on a permission or confirmation prompt by combining the
+// pane contents, hook reports, and the terminal bell
+use chrono::Utc;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use summ_common::{
+    AnswerKeys, CliState, DaemonConfig, InputPrompt, OptionKeys, PromptAnswer, PromptSource, Session,
+};
+use tracing::info;
+
+use crate::redact::redact;
+use crate::session::SessionExt;
+use crate::storage;
+use crate::tmux::TmuxManager;
+
+/// Built-in prompt patterns per CLI name, used unless `prompt_patterns` sets them
+const BUILTIN_PATTERNS: &[(&str, &[&str])] = &[(
+    "claude",
+    &[
+        // "Do you want to proceed?", "Do you want to make this edit to main.rs?"
+        r"^\W*Do you want to .+\?\W*$",
+        r"No, and tell Claude what to do differently",
+    ],
+)];
+
+/// Non-blank lines at the bottom of the pane that are checked; a prompt
+/// scrolled further up has been answered
+const TAIL_LINES: usize = 20;
+
+/// Lines of a matched prompt kept, starting at the matching line
+const MAX_PROMPT_LINES: usize = 8;
+
+/// Compiled prompt patterns per CLI name
+pub struct PromptMatcher {
+    patterns: HashMap<String, Vec<Regex>>,
+}
+
+impl PromptMatcher {
+    /// Built-in patterns overridden per CLI by `prompt_patterns`; patterns that
+    /// fail to compile are returned as errors and skipped
+    pub fn new(config: &DaemonConfig) -> (Self, Vec<String>) {
+        let mut sources: HashMap<String, Vec<String>> = BUILTIN_PATTERNS
+            .iter()
+            .map(|(cli, patterns)| {
+                (cli.to_string(), patterns.iter().map(|p| p.to_string()).collect())
+            })
+            .collect();
+        sources.extend(config.prompt_patterns.clone());
+
+        let mut errors = Vec::new();
+        let patterns = sources
+            .into_iter()
+            .map(|(cli, sources)| {
+                let compiled = sources
+                    .iter()
+                    .filter_map(|pattern| match Regex::new(pattern) {
+                        Ok(regex) => Some(regex),
+                        Err(e) => {
+                            errors.push(format!(
+                                "Invalid prompt pattern {:?} for {}: {}",
+                                pattern, cli, e
+                            ));
+                            None
+                        }
+                    })
+                    .collect();
+                (cli, compiled)
+            })
+            .collect();
+        (Self { patterns }, errors)
+    }
+
+    /// Patterns for a session's CLI; empty when it has none
+    fn patterns_for(&self, cli: &str) -> &[Regex] {
+        self.patterns
+            .get(cli_name(cli))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Keys `summ answer` sends to a CLI: `answer_keys` for its name, else the
+/// built-in keys (Claude Code's select menus start on "Yes" and close with Escape)
+fn answer_keys(config: &DaemonConfig, cli: &str) -> AnswerKeys {
+    let name = cli_name(cli);
+    match config.answer_keys.get(name) {
+        Some(keys) => keys.clone(),
+        None if name == "claude" => AnswerKeys {
+            yes: vec!["Enter".to_string()],
+            no: vec!["Escape".to_string()],
+            options: OptionKeys::Arrows,
+        },
+        None => AnswerKeys::default(),
+    }
+}
+
+/// What `summ answer` sends to a session's pane
+#[derive(Debug, PartialEq, Eq)]
+pub enum AnswerInput {
+    /// tmux key names
+    Keys(Vec<String>),
+    /// Text typed literally, then Enter
+    Text(String),
+}
+
+/// Turn an answer into the input for a session's CLI
+pub fn answer_input(
+    config: &DaemonConfig,
+    cli: &str,
+    answer: &PromptAnswer,
+) -> std::result::Result<AnswerInput, String> {
+    match answer {
+        PromptAnswer::Option(0) => Err("Options are numbered from 1".to_string()),
+        PromptAnswer::Text(text) if text.trim().is_empty() => Err("No answer text given".to_string()),
+        PromptAnswer::Text(text) => Ok(AnswerInput::Text(text.clone())),
+        _ => {
+            let keys = answer_keys(config, cli).keys_for(answer).unwrap_or_default();
+            crate::control::validate_keys(&keys)
+                .map_err(|reason| format!("answer_keys for {}: {}", cli_name(cli), reason))?;
+            Ok(AnswerInput::Keys(keys))
+        }
+    }
+}
+
+/// The program name of a CLI command, e.g. `claude` for `/usr/bin/claude --resume`
+pub fn cli_name(cli: &str) -> &str {
+    let program = cli.split_whitespace().next().unwrap_or_default();
+    Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(program)
+}
+
+/// The prompt at the bottom of `pane`, from the first matching line on
+fn find_prompt(patterns: &[Regex], pane: &str) -> Option<String> {
+    let lines: Vec<&str> = pane.lines().filter(|line| !line.trim().is_empty()).collect();
+    let tail = &lines[lines.len().saturating_sub(TAIL_LINES)..];
+    let start = tail
+        .iter()
+        .position(|line| patterns.iter().any(|pattern| pattern.is_match(line)))?;
+    let prompt: Vec<&str> = tail[start..]
+        .iter()
+        .take(MAX_PROMPT_LINES)
+        .map(|line| line.trim_end())
+        .collect();
+    Some(redact(&prompt.join("\n")).into_owned())
+}
+
+/// Combine the signals. `pane` is `None` when the pane was not checked (no
+/// patterns for the CLI, or no capture); a checked pane without a prompt
+/// overrides the hook and the bell, since a hook report outlives the prompt.
+fn decide(
+    pane: Option<Option<String>>,
+    hook_message: Option<String>,
+    bell: bool,
+) -> Option<(PromptSource, String)> {
+    match pane {
+        Some(Some(text)) => Some((PromptSource::Pane, text)),
+        Some(None) => None,
+        None => match hook_message {
+            Some(message) => Some((PromptSource::Hook, message)),
+            None => bell.then(|| (PromptSource::Bell, "The CLI rang the bell".to_string())),
+        },
+    }
+}
+
+/// Check whether a live session is waiting on a prompt
+pub fn detect(session: &Session, matcher: &PromptMatcher) -> Option<(PromptSource, String)> {
+    let patterns = matcher.patterns_for(&session.cli);
+    let pane = if patterns.is_empty() {
+        None
+    } else {
+        TmuxManager::capture_pane(&session.tmux_session, 0)
+            .ok()
+            .filter(|pane| !pane.trim().is_empty())
+            .map(|pane| find_prompt(patterns, &pane))
+    };
+    let hook_message = session
+        .read_cli_status()
+        .filter(|status| status.state == CliState::WaitingForInput)
+        .map(|status| status.message.unwrap_or_else(|| "Waiting for input".to_string()));
+    decide(pane, hook_message, session.needs_attention)
+}
+
+/// Store the detected prompt on the session; returns true when the session
+/// starts waiting
+pub fn record(session: &mut Session, detected: Option<(PromptSource, String)>) -> bool {
+    let waiting = detected.map(|(source, text)| InputPrompt {
+        since: session
+            .waiting_for_input
+            .as_ref()
+            .map(|prompt| prompt.since)
+            .unwrap_or_else(Utc::now),
+        source,
+        text,
+    });
+    if waiting == session.waiting_for_input {
+        return false;
+    }
+    let started = session.waiting_for_input.is_none() && waiting.is_some();
+    if started {
+        info!("Session {} is waiting for input", session.session_id);
+    }
+    session.waiting_for_input = waiting;
+    storage::save(session).ok();
+    started
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CLAUDE_PROMPT: &str = "\
+⏺ I'll run the tests.
+
+╭──────────────────────────────────────────────╮
+│ Bash command                                 │
+│                                              │
+│   cargo test --workspace                     │
+│                                              │
+│ Do you want to proceed?                      │
+│ ❯ 1. Yes                                     │
+│   2. Yes, and don't ask again for cargo test │
+│   3. No, and tell Claude what to do differently (esc) │
+╰──────────────────────────────────────────────╯
+";
+
+    fn claude() -> PromptMatcher {
+        PromptMatcher::new(&DaemonConfig::default()).0
+    }
+
+    #[test]
+    fn test_cli_name() {
+        assert_eq!(cli_name("claude"), "claude");
+        assert_eq!(cli_name("/usr/local/bin/claude --resume"), "claude");
+        assert_eq!(cli_name("  aider-chat --model x"), "aider-chat");
+        assert_eq!(cli_name(""), "");
+    }
+
+    #[test]
+    fn test_finds_claude_permission_prompt() {
+        let matcher = claude();
+        let prompt = find_prompt(matcher.patterns_for("claude"), CLAUDE_PROMPT).unwrap();
+        assert!(prompt.starts_with("│ Do you want to proceed?"));
+        assert!(prompt.contains("❯ 1. Yes"));
+        assert_eq!(prompt.lines().count(), 5);
+    }
+
+    #[test]
+    fn test_ignores_ordinary_output_and_old_prompts() {
+        let patterns = claude();
+        let patterns = patterns.patterns_for("claude");
+        for pane in [
+            "⏺ Do you want to proceed with the refactor? I can also split it.\n> ",
+            "Tests passed.\n> do you want to proceed?\n",
+            "",
+        ] {
+            assert_eq!(find_prompt(patterns, pane), None, "{:?}", pane);
+        }
+
+        // A prompt that scrolled above the checked tail was already answered
+        let scrolled = format!("{}{}", CLAUDE_PROMPT, "⏺ Running tests\n".repeat(TAIL_LINES));
+        assert_eq!(find_prompt(patterns, &scrolled), None);
+    }
+
+    #[test]
+    fn test_configured_patterns_replace_builtins() {
+        let config = DaemonConfig {
+            prompt_patterns: HashMap::from([
+                ("aider".to_string(), vec![r"\(Y\)es/\(N\)o".to_string()]),
+                ("claude".to_string(), vec![]),
+                ("broken".to_string(), vec!["(".to_string()]),
+            ]),
+            ..Default::default()
+        };
+        let (matcher, errors) = PromptMatcher::new(&config);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("broken"));
+
+        assert!(matcher.patterns_for("claude").is_empty());
+        assert!(matcher.patterns_for("codex").is_empty());
+        let aider = matcher.patterns_for("aider --model sonnet");
+        assert_eq!(
+            find_prompt(aider, "Add src/main.rs to the chat? (Y)es/(N)o [Yes]:").as_deref(),
+            Some("Add src/main.rs to the chat? (Y)es/(N)o [Yes]:")
+        );
+    }
+
+    #[test]
+    fn test_answer_input_per_cli() {
+        let config = DaemonConfig {
+            answer_keys: HashMap::from([(
+                "aider".to_string(),
+                AnswerKeys {
+                    yes: vec!["Y".to_string(), "Enter".to_string()],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let keys = |cli: &str, answer: PromptAnswer| match answer_input(&config, cli, &answer) {
+            Ok(AnswerInput::Keys(keys)) => keys,
+            other => panic!("{:?}", other),
+        };
+
+        assert_eq!(keys("claude", PromptAnswer::Yes), ["Enter"]);
+        assert_eq!(keys("claude --resume", PromptAnswer::No), ["Escape"]);
+        assert_eq!(keys("claude", PromptAnswer::Option(2)), ["Down", "Enter"]);
+        assert_eq!(keys("aider", PromptAnswer::Yes), ["Y", "Enter"]);
+        assert_eq!(keys("codex", PromptAnswer::No), ["n", "Enter"]);
+        assert_eq!(keys("codex", PromptAnswer::Option(3)), ["3", "Enter"]);
+        assert_eq!(
+            answer_input(&config, "claude", &PromptAnswer::Text("use rg".into())),
+            Ok(AnswerInput::Text("use rg".into()))
+        );
+
+        assert!(answer_input(&config, "claude", &PromptAnswer::Option(0)).is_err());
+        assert!(answer_input(&config, "claude", &PromptAnswer::Text(" ".into())).is_err());
+        let bad = DaemonConfig {
+            answer_keys: HashMap::from([(
+                "claude".to_string(),
+                AnswerKeys {
+                    yes: vec!["rm -rf".to_string()],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let error = answer_input(&bad, "claude", &PromptAnswer::Yes).unwrap_err();
+        assert!(error.contains("answer_keys for claude"), "{}", error);
+    }
+
+    #[test]
+    fn test_decide_combines_signals() {
+        let hook = || Some("Claude needs your permission to use Bash".to_string());
+
+        assert_eq!(
+            decide(Some(Some("Do you want to proceed?".into())), None, false),
+            Some((PromptSource::Pane, "Do you want to proceed?".into()))
+        );
+        // A checked pane without a prompt wins over a lingering hook report and the bell
+        assert_eq!(decide(Some(None), hook(), true), None);
+        assert_eq!(
+            decide(None, hook(), true),
+            Some((PromptSource::Hook, hook().unwrap()))
+        );
+        assert_eq!(decide(None, None, true).map(|(source, _)| source), Some(PromptSource::Bell));
+        assert_eq!(decide(None, None, false), None);
+    }
+
+    #[test]
+    fn test_record_keeps_since_and_reports_start_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let workdir = temp_dir.path().join("prompt001");
+        std::fs::create_dir_all(workdir.join("runtime")).unwrap();
+        let mut session = Session {
+            session_id: "prompt001".to_string(),
+            workdir,
+            ..Default::default()
+        };
+
+        assert!(record(&mut session, Some((PromptSource::Hook, "permission".into()))));
+        let since = session.waiting_for_input.as_ref().unwrap().since;
+        assert!(!record(&mut session, Some((PromptSource::Pane, "Do you want to proceed?".into()))));
+        let prompt = session.waiting_for_input.as_ref().unwrap();
+        assert_eq!((prompt.since, prompt.source), (since, PromptSource::Pane));
+
+        assert!(!record(&mut session, None));
+        assert!(session.waiting_for_input.is_none());
+    }
+}
@@ -1,87 +1,350 @@
 // summ-daemon/src/recovery.rs
 // Session recovery functionality for daemon restart
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use summ_common::{DaemonConfig, Session, SessionStatus};
-use tracing::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use summ_common::{DaemonConfig, Namespace, Session, SessionStatus, StopReason, NAMESPACES_DIR};
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
 use crate::session::SessionExt;
+use crate::storage;
+use crate::tmux::SummTmuxSession;
 
-/// Recover existing sessions from tmux and metadata files
-/// This should be called on daemon startup to reconnect to existing tmux sessions
-pub fn recover_sessions(config: &DaemonConfig) -> Result<HashMap<String, Session>> {
-    let mut sessions = HashMap::new();
+/// The tmux state that recovery reconciles metadata against
+pub trait TmuxProbe {
+    /// Live tmux sessions carrying `@summ_session_id`, and unmarked ones
+    /// named like summ sessions
+    fn summ_sessions(&self) -> Vec<SummTmuxSession>;
+    /// PID of a tmux session's pane, if it has one
+    fn pane_pid(&self, tmux_session: &str) -> Option<u32>;
+    /// Directory a tmux session was started in
+    fn session_path(&self, tmux_session: &str) -> Option<PathBuf>;
+    /// Mark an unmarked tmux session as summ session `session_id`'s
+    fn adopt(&self, tmux_session: &str, session_id: &str) -> Result<()>;
+}
+
+/// The tmux server the daemon runs against
+pub struct SystemTmux;
+
+impl TmuxProbe for SystemTmux {
+    fn summ_sessions(&self) -> Vec<SummTmuxSession> {
+        crate::tmux::TmuxManager::list_summ_sessions().unwrap_or_default()
+    }
+
+    fn pane_pid(&self, tmux_session: &str) -> Option<u32> {
+        crate::tmux::TmuxManager::get_pane_pid(tmux_session).ok().flatten()
+    }
+
+    fn session_path(&self, tmux_session: &str) -> Option<PathBuf> {
+        crate::tmux::TmuxManager::session_path(tmux_session)
+    }
+
+    fn adopt(&self, tmux_session: &str, session_id: &str) -> Result<()> {
+        crate::tmux::TmuxManager::adopt(tmux_session, session_id)
+    }
+}
+
+/// Sessions loaded per step of the background recovery; the map is updated
+/// (and progress reported) after every batch
+const RECOVERY_BATCH: usize = 64;
+
+/// Progress of the startup recovery, shared with the request handler
+#[derive(Debug)]
+pub struct RecoveryProgress {
+    done: AtomicBool,
+    loaded: AtomicUsize,
+    total: AtomicUsize,
+}
+
+/// Recovery progress as reported by DaemonStatus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RecoveryStatus {
+    pub recovering: bool,
+    pub loaded: usize,
+    pub total: usize,
+}
+
+impl RecoveryProgress {
+    /// Recovery that has not finished yet
+    pub fn new() -> Self {
+        Self {
+            done: AtomicBool::new(false),
+            loaded: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+        }
+    }
+
+    /// Recovery that is already over (nothing to wait for)
+    pub fn completed() -> Self {
+        let progress = Self::new();
+        progress.finish();
+        progress
+    }
+
+    pub fn finish(&self) {
+        self.done.store(true, Ordering::Release);
+    }
+
+    pub fn status(&self) -> RecoveryStatus {
+        RecoveryStatus {
+            recovering: !self.done.load(Ordering::Acquire),
+            loaded: self.loaded.load(Ordering::Relaxed),
+            total: self.total.load(Ordering::Relaxed),
+        }
+    }
 
-    // Get all summ- prefixed tmux sessions
-    let tmux_sessions = crate::tmux::TmuxManager::list_summ_sessions()
-        .unwrap_or_default();
-    let tmux_set: std::collections::HashSet<&str> =
-        tmux_sessions.iter().map(|s| s.as_str()).collect();
+    /// `(loaded, total)` while recovery is still running
+    pub fn in_progress(&self) -> Option<(usize, usize)> {
+        let status = self.status();
+        status.recovering.then_some((status.loaded, status.total))
+    }
+}
 
-    // Scan sessions directory for meta.json files
+/// Directories that hold a meta.json, with the namespace they belong to:
+/// those directly under the sessions directory are the default namespace's,
+/// those under `namespaces/<name>/` are that namespace's
+fn session_dirs(config: &DaemonConfig) -> Result<Vec<(Namespace, PathBuf)>> {
     let sessions_dir = &config.sessions_dir;
 
     if !sessions_dir.exists() {
         fs::create_dir_all(sessions_dir)
             .context("Failed to create sessions directory during recovery")?;
         info!("Created sessions directory during recovery");
-        return Ok(sessions);
+        return Ok(Vec::new());
     }
 
-    for entry in fs::read_dir(sessions_dir)
-        .context("Failed to read sessions directory during recovery")?
+    let mut dirs: Vec<(Namespace, PathBuf)> = namespace_session_dirs(sessions_dir)?
+        .into_iter()
+        .map(|dir| (Namespace::default(), dir))
+        .collect();
+
+    let namespaces_dir = sessions_dir.join(NAMESPACES_DIR);
+    if namespaces_dir.is_dir() {
+        for entry in fs::read_dir(&namespaces_dir)
+            .context("Failed to read namespaces directory during recovery")?
+        {
+            let entry = entry.context("Failed to read directory entry")?;
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let namespace = match Namespace::new(&entry.file_name().to_string_lossy()) {
+                Ok(namespace) if !namespace.is_default() => namespace,
+                Ok(_) => {
+                    warn!(
+                        "Skipping {}: the default namespace's sessions live in the sessions directory",
+                        entry.path().display()
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Skipping {} during recovery: {}", entry.path().display(), e.message);
+                    continue;
+                }
+            };
+            for dir in namespace_session_dirs(&entry.path())? {
+                dirs.push((namespace.clone(), dir));
+            }
+        }
+    }
+    Ok(dirs)
+}
+
+/// Session directories directly under `dir`
+fn namespace_session_dirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {} during recovery", dir.display()))?
     {
         let entry = entry.context("Failed to read directory entry")?;
         let entry_path = entry.path();
 
-        // Skip if not a directory
-        if !entry_path.is_dir() {
+        // Skip entries that are not directories or have no meta.json
+        if !entry_path.is_dir() || !entry_path.join("meta.json").exists() {
             continue;
         }
+        // Never adopt a directory whose name could not be a session ID
+        if let Err(e) = summ_common::validate_session_id(&entry.file_name().to_string_lossy()) {
+            warn!("Skipping {} during recovery: {}", entry_path.display(), e.message);
+            continue;
+        }
+        dirs.push(entry_path);
+    }
+    Ok(dirs)
+}
 
-        let meta_path = entry_path.join("meta.json");
+/// Live tmux sessions by name, with their `@summ_session_id`
+type LiveSessions = HashMap<String, Option<String>>;
 
-        // Skip directories without meta.json
-        if !meta_path.exists() {
-            continue;
+/// Whether the tmux session a session's metadata names is alive and is the
+/// one the daemon created for it: its `@summ_session_id` is the session's ID,
+/// or it is unmarked but was started in the session's workspace (created
+/// before the options existed), in which case it is marked now. Any other
+/// session of that name is left alone and the summ session is recovered as
+/// stopped.
+fn claim(session: &Session, live: &LiveSessions, tmux: &impl TmuxProbe) -> bool {
+    let workspace = session.cwd();
+    match live.get(&session.tmux_session) {
+        None => return false,
+        Some(Some(session_id)) if *session_id == session.session_id => return true,
+        Some(None) if tmux.session_path(&session.tmux_session).is_some_and(|path| path == workspace) => {
+            if let Err(e) = tmux.adopt(&session.tmux_session, &session.session_id) {
+                warn!("Failed to mark tmux session {}: {:#}", session.tmux_session, e);
+            }
+            info!("Adopted unmarked tmux session {} started in {}", session.tmux_session, workspace.display());
+            return true;
         }
+        Some(Some(session_id)) => warn!(
+            "tmux session {} belongs to session {}; treating session {} as stopped",
+            session.tmux_session, session_id, session.session_id
+        ),
+        Some(None) => warn!(
+            "tmux session {} was not created by summ; leaving it alone and treating session {} as stopped",
+            session.tmux_session, session.session_id
+        ),
+    }
+    false
+}
 
-        // Load session metadata
-        let mut session: Session = <Session as SessionExt>::load_metadata(&entry_path)
-            .with_context(|| format!("Failed to load metadata from {:?}", meta_path))?;
-
-        // Reconcile with tmux state
-        if tmux_set.contains(session.tmux_session.as_str()) {
-            // tmux session exists, recover as running
-            session.status = SessionStatus::Running;
-            session.pid = crate::tmux::TmuxManager::get_pane_pid(&session.tmux_session)
-                .ok()
-                .flatten();
-            info!(
-                "Recovered running session: {} (tmux: {})",
-                session.session_id, session.tmux_session
-            );
-        } else if session.status == SessionStatus::Running {
-            // meta shows running but tmux session is gone, update to stopped
-            session.status = SessionStatus::Stopped;
-            session.pid = None;
-            session.save_metadata().ok();
-            info!(
-                "Session {} marked as stopped (tmux session gone)",
-                session.session_id
+/// Load one session's metadata from its directory in `namespace` and
+/// reconcile it with tmux state
+fn recover_session(
+    namespace: &Namespace,
+    session_dir: &Path,
+    config: &DaemonConfig,
+    live: &LiveSessions,
+    tmux: &impl TmuxProbe,
+) -> Result<Session> {
+    let meta_path = session_dir.join("meta.json");
+    let mut session: Session = <Session as SessionExt>::load_metadata(session_dir)
+        .with_context(|| format!("Failed to load metadata from {:?}", meta_path))?;
+    summ_common::validate_session_id(&session.session_id)
+        .with_context(|| format!("Bad session ID in {:?}", meta_path))?;
+    // A session moved between namespace directories by hand would be
+    // reachable from one namespace while its files sit in another
+    if session.namespace != *namespace {
+        anyhow::bail!(
+            "{:?} is in namespace {} but its directory is in namespace {}",
+            meta_path,
+            session.namespace,
+            namespace
+        );
+    }
+
+    if let Some(log_path) = relocated_log(&session, config) {
+        info!("Session {} now logs to {}", session.session_id, log_path.display());
+        session.log_path = log_path;
+        storage::save(&session).ok();
+    }
+
+    let ended = matches!(
+        session.status,
+        SessionStatus::Stopped | SessionStatus::Failed | SessionStatus::Pending
+    );
+    if ended {
+        // A session that had ended (or never started) is never brought back,
+        // even by a tmux session someone created under its name
+        if live.contains_key(&session.tmux_session) {
+            warn!(
+                "tmux session {} exists but session {} is {}; leaving it alone",
+                session.tmux_session, session.session_id, session.status
             );
         }
+    } else if claim(&session, live, tmux) {
+        // tmux session exists, recover as running
+        session.status = SessionStatus::Running;
+        session.pid = tmux.pane_pid(&session.tmux_session);
+        if session.paused {
+            recover_paused(&mut session, config);
+        }
+        info!(
+            "Recovered {:?} session: {} (tmux: {})",
+            session.status, session.session_id, session.tmux_session
+        );
+    } else if matches!(session.status, SessionStatus::Running | SessionStatus::Paused) {
+        // meta shows running but tmux session is gone, update to stopped
+        session.status = SessionStatus::Stopped;
+        session.paused = false;
+        session.pid = None;
+        // The daemon was not watching, so only a hook report tells us why
+        session.stop_reason = Some(session.reported_exit().unwrap_or(StopReason::Unknown));
+        storage::save(&session).ok();
+        info!(
+            "Session {} marked as stopped (tmux session gone)",
+            session.session_id
+        );
+    } else if session.status == SessionStatus::Starting {
+        // A background start was cut short by the daemon going down
+        session.fail_start(&anyhow::anyhow!(
+            "Daemon restarted before the session finished starting"
+        ));
+    }
+    Ok(session)
+}
+
+/// Recover existing sessions from tmux and metadata files into `sessions`.
+///
+/// Runs on daemon startup while requests are already served: sessions are loaded
+/// off the runtime a batch at a time and inserted as they come, with `progress`
+/// tracking how far it got. A session whose metadata cannot be read is skipped.
+/// The caller finishes `progress` once any follow-up work is done.
+pub async fn recover_sessions_into<T: TmuxProbe + Send + Sync + 'static>(
+    config: &DaemonConfig,
+    tmux: T,
+    sessions: &RwLock<HashMap<String, Session>>,
+    progress: &RecoveryProgress,
+) -> Result<()> {
+    let tmux = Arc::new(tmux);
+    let scan_config = config.clone();
+    let scan_tmux = tmux.clone();
+    let (tmux_sessions, dirs) = tokio::task::spawn_blocking(move || {
+        (scan_tmux.summ_sessions(), session_dirs(&scan_config))
+    })
+    .await?;
+    let dirs = dirs?;
+    let live: Arc<LiveSessions> = Arc::new(
+        tmux_sessions.iter().map(|tmux| (tmux.name.clone(), tmux.session_id.clone())).collect(),
+    );
+    progress.total.store(dirs.len(), Ordering::Relaxed);
 
-        sessions.insert(session.session_id.clone(), session);
+    for batch in dirs.chunks(RECOVERY_BATCH) {
+        let batch = batch.to_vec();
+        let (config, live, tmux) = (config.clone(), live.clone(), tmux.clone());
+        let recovered = tokio::task::spawn_blocking(move || {
+            batch
+                .iter()
+                .map(|(namespace, dir)| recover_session(namespace, dir, &config, &live, &*tmux))
+                .collect::<Vec<_>>()
+        })
+        .await?;
+
+        let count = recovered.len();
+        let mut sessions = sessions.write().await;
+        for result in recovered {
+            match result {
+                Ok(session) => {
+                    sessions.insert(session.session_id.clone(), session);
+                }
+                Err(e) => error!("Skipping session during recovery: {:#}", e),
+            }
+        }
+        progress.loaded.fetch_add(count, Ordering::Relaxed);
     }
 
-    // Check for orphan tmux sessions (without meta.json)
-    for tmux_name in &tmux_sessions {
-        let session_id = tmux_name.strip_prefix("summ-").unwrap_or(tmux_name);
+    // Check for orphan tmux sessions (without meta.json); unmarked ones with
+    // a summ-like name are a user's, not orphans
+    let sessions = sessions.read().await;
+    for tmux_session in &tmux_sessions {
+        let Some(session_id) = &tmux_session.session_id else {
+            continue;
+        };
         if !sessions.contains_key(session_id) {
             warn!(
-                "Found orphan tmux session {} without meta.json, consider manual cleanup",
-                tmux_name
+                "Found orphan tmux session {} (session {}) without meta.json, consider manual cleanup",
+                tmux_session.name, session_id
             );
         }
     }
@@ -90,8 +353,58 @@ pub fn recover_sessions(config: &DaemonConfig) -> Result<HashMap<String, Session
         "Recovered {} sessions from disk and tmux",
         sessions.len()
     );
+    let mut per_namespace: BTreeMap<&Namespace, usize> = BTreeMap::new();
+    for session in sessions.values() {
+        *per_namespace.entry(&session.namespace).or_default() += 1;
+    }
+    if per_namespace.keys().any(|namespace| !namespace.is_default()) {
+        for (namespace, count) in per_namespace {
+            info!("Namespace {}: {} sessions", namespace, count);
+        }
+    }
+
+    Ok(())
+}
+
+/// Where a session's terminal log is to be found when not at its recorded
+/// `log_path`: metadata written before the path was recorded gets the path
+/// under the current `logs_dir`, and so does a session whose recorded log is
+/// gone but which has one there (`logs_dir` was moved). `None` keeps the
+/// recorded path.
+fn relocated_log(session: &Session, config: &DaemonConfig) -> Option<PathBuf> {
+    let recorded = &session.log_path;
+    if !recorded.as_os_str().is_empty() && recorded.exists() {
+        return None;
+    }
+    let current = config.session_log_path(&session.session_id).ok()?;
+    if current == *recorded {
+        return None;
+    }
+    (recorded.as_os_str().is_empty() || current.exists()).then_some(current)
+}
 
-    Ok(sessions)
+/// Keep a paused session paused, or resume it when the config asks for it
+fn recover_paused(session: &mut Session, config: &DaemonConfig) {
+    if !config.resume_paused_on_recover {
+        session.status = SessionStatus::Paused;
+        return;
+    }
+
+    match session.pid.map(crate::control::resume_pane) {
+        Some(Ok(())) => {
+            session.paused = false;
+            storage::save(session).ok();
+            info!("Resumed paused session {} on recovery", session.session_id);
+        }
+        Some(Err(e)) => {
+            warn!("Failed to resume session {}: {}", session.session_id, e);
+            session.status = SessionStatus::Paused;
+        }
+        None => {
+            warn!("No process found to resume for session {}", session.session_id);
+            session.status = SessionStatus::Paused;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -99,8 +412,23 @@ mod tests {
     use super::*;
     use std::fs::{self, File};
     use std::io::Write;
+    use summ_common::IdFormat;
     use tempfile::TempDir;
 
+    /// Run recovery to completion against `tmux`
+    async fn recover_with(
+        config: &DaemonConfig,
+        tmux: impl TmuxProbe + Send + Sync + 'static,
+    ) -> Result<HashMap<String, Session>> {
+        let sessions = RwLock::new(HashMap::new());
+        recover_sessions_into(config, tmux, &sessions, &RecoveryProgress::new()).await?;
+        Ok(sessions.into_inner())
+    }
+
+    async fn recover_sessions(config: &DaemonConfig) -> Result<HashMap<String, Session>> {
+        recover_with(config, SystemTmux).await
+    }
+
     fn create_test_session_meta(session_dir: &std::path::Path, session_id: &str) -> Result<()> {
         let meta_path = session_dir.join("meta.json");
         let session = serde_json::json!({
@@ -119,37 +447,25 @@ mod tests {
         Ok(())
     }
 
-    #[test]
-    fn test_recover_from_empty_directory() {
+    #[tokio::test]
+    async fn test_recover_from_empty_directory() {
         let temp_dir = TempDir::new().unwrap();
-        let config = DaemonConfig {
-            sessions_dir: temp_dir.path().join("sessions"),
-            logs_dir: temp_dir.path().join("logs"),
-            socket_path: temp_dir.path().join("daemon.sock"),
-            cleanup_retention_hours: 24,
-            tmux_prefix: "summ-".to_string(),
-        };
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
 
         // Create empty sessions directory
         fs::create_dir_all(&config.sessions_dir).unwrap();
 
-        let result = recover_sessions(&config);
+        let result = recover_sessions(&config).await;
         assert!(result.is_ok());
 
         let sessions = result.unwrap();
         assert_eq!(sessions.len(), 0);
     }
 
-    #[test]
-    fn test_recover_skips_non_session_dirs() {
+    #[tokio::test]
+    async fn test_recover_skips_non_session_dirs() {
         let temp_dir = TempDir::new().unwrap();
-        let config = DaemonConfig {
-            sessions_dir: temp_dir.path().join("sessions"),
-            logs_dir: temp_dir.path().join("logs"),
-            socket_path: temp_dir.path().join("daemon.sock"),
-            cleanup_retention_hours: 24,
-            tmux_prefix: "summ-".to_string(),
-        };
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
 
         // Create sessions directory with various entries
         fs::create_dir_all(&config.sessions_dir).unwrap();
@@ -162,23 +478,76 @@ mod tests {
         let random_file = config.sessions_dir.join("random_file.txt");
         File::create(&random_file).unwrap().write_all(b"content").unwrap();
 
-        let result = recover_sessions(&config);
+        let result = recover_sessions(&config).await;
         assert!(result.is_ok());
 
         let sessions = result.unwrap();
         assert_eq!(sessions.len(), 0);
     }
 
-    #[test]
-    fn test_recover_loads_valid_session() {
+    #[tokio::test]
+    async fn test_recover_skips_invalid_session_ids() {
         let temp_dir = TempDir::new().unwrap();
-        let config = DaemonConfig {
-            sessions_dir: temp_dir.path().join("sessions"),
-            logs_dir: temp_dir.path().join("logs"),
-            socket_path: temp_dir.path().join("daemon.sock"),
-            cleanup_retention_hours: 24,
-            tmux_prefix: "summ-".to_string(),
-        };
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+
+        // A directory name that is not a session ID
+        let dotted = config.sessions_dir.join("session.bak");
+        fs::create_dir_all(&dotted).unwrap();
+        create_test_session_meta(&dotted, "session_bak").unwrap();
+
+        // A valid directory whose meta.json claims a path-like ID
+        let forged = config.sessions_dir.join("session_forged");
+        fs::create_dir_all(&forged).unwrap();
+        create_test_session_meta(&forged, "../../etc").unwrap();
+
+        let valid = config.sessions_dir.join("session_ok");
+        fs::create_dir_all(&valid).unwrap();
+        create_test_session_meta(&valid, "session_ok").unwrap();
+
+        let sessions = recover_sessions(&config).await.unwrap();
+        assert_eq!(sessions.keys().collect::<Vec<_>>(), vec!["session_ok"]);
+    }
+
+    #[tokio::test]
+    async fn test_recover_namespaces() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let work = Namespace::new("work").unwrap();
+
+        let default_dir = config.sessions_dir.join("session_default");
+        fs::create_dir_all(&default_dir).unwrap();
+        create_test_session_meta(&default_dir, "session_default").unwrap();
+
+        let work_dir = work.sessions_dir(&config.sessions_dir).join("session_work");
+        fs::create_dir_all(&work_dir).unwrap();
+        create_test_session_meta(&work_dir, "session_work").unwrap();
+        let meta = work_dir.join("meta.json");
+        let mut raw: serde_json::Value = serde_json::from_str(&fs::read_to_string(&meta).unwrap()).unwrap();
+        raw["namespace"] = "work".into();
+        fs::write(&meta, raw.to_string()).unwrap();
+
+        // Metadata of the default namespace in the work namespace's directory
+        let moved = work.sessions_dir(&config.sessions_dir).join("session_moved");
+        fs::create_dir_all(&moved).unwrap();
+        create_test_session_meta(&moved, "session_moved").unwrap();
+
+        // Not a namespace name
+        let bad = config.sessions_dir.join(NAMESPACES_DIR).join("Bad Name").join("session_bad");
+        fs::create_dir_all(&bad).unwrap();
+        create_test_session_meta(&bad, "session_bad").unwrap();
+
+        let sessions = recover_sessions(&config).await.unwrap();
+        let mut ids: Vec<&String> = sessions.keys().collect();
+        ids.sort();
+        assert_eq!(ids, vec!["session_default", "session_work"]);
+        assert!(sessions["session_default"].namespace.is_default());
+        assert_eq!(sessions["session_work"].namespace, work);
+    }
+
+    #[tokio::test]
+    async fn test_recover_loads_valid_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
 
         fs::create_dir_all(&config.sessions_dir).unwrap();
 
@@ -202,7 +571,7 @@ mod tests {
         let meta_path = session_dir.join("meta.json");
         File::create(&meta_path).unwrap().write_all(meta_content.as_bytes()).unwrap();
 
-        let result = recover_sessions(&config);
+        let result = recover_sessions(&config).await;
         assert!(result.is_ok());
 
         let sessions = result.unwrap();
@@ -216,19 +585,16 @@ mod tests {
         // by the recovery logic when tmux_set doesn't contain the session
     }
 
-    #[test]
-    fn test_recover_creates_directory_if_missing() {
+    #[tokio::test]
+    async fn test_recover_creates_directory_if_missing() {
         let temp_dir = TempDir::new().unwrap();
         let config = DaemonConfig {
             sessions_dir: temp_dir.path().join("nonexistent_sessions"),
-            logs_dir: temp_dir.path().join("logs"),
-            socket_path: temp_dir.path().join("daemon.sock"),
-            cleanup_retention_hours: 24,
-            tmux_prefix: "summ-".to_string(),
+            ..DaemonConfig::with_base_dir(temp_dir.path())
         };
 
         // Don't create the sessions directory - let recovery do it
-        let result = recover_sessions(&config);
+        let result = recover_sessions(&config).await;
         assert!(result.is_ok());
 
         // Directory should have been created
@@ -237,4 +603,617 @@ mod tests {
         let sessions = result.unwrap();
         assert_eq!(sessions.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_recover_paused_session_without_tmux_is_stopped() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+
+        let session_dir = config.sessions_dir.join("session_paused01");
+        fs::create_dir_all(&session_dir).unwrap();
+        create_test_session_meta(&session_dir, "session_paused01").unwrap();
+
+        // Mark the stored session as paused
+        let meta_path = session_dir.join("meta.json");
+        let mut meta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&meta_path).unwrap()).unwrap();
+        meta["status"] = serde_json::json!("paused");
+        meta["paused"] = serde_json::json!(true);
+        fs::write(&meta_path, meta.to_string()).unwrap();
+
+        let sessions = recover_sessions(&config).await.unwrap();
+        let session = &sessions["session_paused01"];
+        assert_eq!(session.status, SessionStatus::Stopped);
+        assert!(!session.paused);
+        assert_eq!(session.stop_reason, Some(StopReason::Unknown));
+
+        // The cleared flag is persisted
+        let reloaded = <Session as SessionExt>::load_metadata(&session_dir).unwrap();
+        assert!(!reloaded.paused);
+        assert_eq!(reloaded.stop_reason, Some(StopReason::Unknown));
+    }
+
+    #[tokio::test]
+    async fn test_recover_interrupted_start_is_failed() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+
+        let session_dir = config.sessions_dir.join("session_start01");
+        fs::create_dir_all(&session_dir).unwrap();
+        create_test_session_meta(&session_dir, "session_start01").unwrap();
+
+        let meta_path = session_dir.join("meta.json");
+        let mut meta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&meta_path).unwrap()).unwrap();
+        meta["status"] = serde_json::json!("starting");
+        fs::write(&meta_path, meta.to_string()).unwrap();
+
+        let sessions = recover_sessions(&config).await.unwrap();
+        let session = &sessions["session_start01"];
+        assert_eq!(session.status, SessionStatus::Failed);
+        assert!(session.start_error.as_deref().unwrap().contains("Daemon restarted"));
+
+        let reloaded = <Session as SessionExt>::load_metadata(&session_dir).unwrap();
+        assert_eq!(reloaded.status, SessionStatus::Failed);
+    }
+
+    /// A tmux session the daemon created for the session its name ends in
+    fn created(tmux_session: &str) -> SummTmuxSession {
+        SummTmuxSession {
+            name: tmux_session.to_string(),
+            session_id: tmux_session.strip_prefix("summ-").map(str::to_string),
+        }
+    }
+
+    /// tmux stand-in where every listed session is alive and PID lookups are slow
+    struct SlowTmux(Vec<String>);
+
+    impl TmuxProbe for SlowTmux {
+        fn summ_sessions(&self) -> Vec<SummTmuxSession> {
+            self.0.iter().map(|name| created(name)).collect()
+        }
+
+        fn pane_pid(&self, _tmux_session: &str) -> Option<u32> {
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            Some(4242)
+        }
+
+        fn session_path(&self, _tmux_session: &str) -> Option<PathBuf> {
+            None
+        }
+
+        fn adopt(&self, _tmux_session: &str, _session_id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recovery_fills_the_map_incrementally() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let ids: Vec<String> = (0..RECOVERY_BATCH * 2 + 1)
+            .map(|i| format!("session_{:03}", i))
+            .collect();
+        for id in &ids {
+            let session_dir = config.sessions_dir.join(id);
+            fs::create_dir_all(&session_dir).unwrap();
+            create_test_session_meta(&session_dir, id).unwrap();
+        }
+
+        let sessions = Arc::new(RwLock::new(HashMap::new()));
+        let progress = Arc::new(RecoveryProgress::new());
+        let tmux = SlowTmux(ids.iter().map(|id| format!("summ-{}", id)).collect());
+        let recovery = tokio::spawn({
+            let (config, sessions, progress) = (config.clone(), sessions.clone(), progress.clone());
+            async move { recover_sessions_into(&config, tmux, &sessions, &progress).await }
+        });
+
+        // Between batches the map holds exactly the sessions counted as loaded
+        let mut partial = Vec::new();
+        while !recovery.is_finished() {
+            {
+                let sessions = sessions.read().await;
+                let (loaded, total) = progress.in_progress().unwrap();
+                assert_eq!(sessions.len(), loaded);
+                if loaded > 0 && loaded < total {
+                    partial.push(loaded);
+                }
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        recovery.await.unwrap().unwrap();
+
+        assert!(!partial.is_empty(), "never saw a partially recovered map");
+        assert!(partial.iter().all(|loaded| loaded % RECOVERY_BATCH == 0));
+        assert_eq!(
+            progress.status(),
+            RecoveryStatus { recovering: true, loaded: ids.len(), total: ids.len() }
+        );
+        let sessions = sessions.read().await;
+        assert_eq!(sessions.len(), ids.len());
+        assert!(sessions.values().all(|s| s.pid == Some(4242)));
+
+        progress.finish();
+        assert_eq!(progress.in_progress(), None);
+    }
+
+    /// tmux stand-in: the listed sessions are alive with the given pane PIDs
+    struct FakeTmux(HashMap<String, u32>);
+
+    impl TmuxProbe for FakeTmux {
+        fn summ_sessions(&self) -> Vec<SummTmuxSession> {
+            self.0.keys().map(|name| created(name)).collect()
+        }
+
+        fn pane_pid(&self, tmux_session: &str) -> Option<u32> {
+            self.0.get(tmux_session).copied()
+        }
+
+        fn session_path(&self, _tmux_session: &str) -> Option<PathBuf> {
+            None
+        }
+
+        fn adopt(&self, _tmux_session: &str, _session_id: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// tmux stand-in with live sessions that may lack the identity options
+    struct MarkerTmux {
+        sessions: Vec<(SummTmuxSession, PathBuf)>,
+        adopted: Arc<std::sync::Mutex<Vec<(String, String)>>>,
+    }
+
+    impl TmuxProbe for MarkerTmux {
+        fn summ_sessions(&self) -> Vec<SummTmuxSession> {
+            self.sessions.iter().map(|(tmux, _)| tmux.clone()).collect()
+        }
+
+        fn pane_pid(&self, _tmux_session: &str) -> Option<u32> {
+            Some(4242)
+        }
+
+        fn session_path(&self, tmux_session: &str) -> Option<PathBuf> {
+            self.sessions.iter().find(|(tmux, _)| tmux.name == tmux_session).map(|(_, path)| path.clone())
+        }
+
+        fn adopt(&self, tmux_session: &str, session_id: &str) -> Result<()> {
+            self.adopted.lock().unwrap().push((tmux_session.to_string(), session_id.to_string()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recover_claims_tmux_sessions_by_their_options() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let mut tmux_sessions = Vec::new();
+        for (id, option) in [
+            ("session_marked", Some("session_marked")),
+            ("session_legacy", None),
+            ("session_foreign", None),
+            ("session_swapped", Some("session_other")),
+        ] {
+            let session_dir = config.sessions_dir.join(id);
+            fs::create_dir_all(&session_dir).unwrap();
+            create_test_session_meta(&session_dir, id).unwrap();
+            let path = match id {
+                "session_foreign" => PathBuf::from("/home/user/project"),
+                _ => session_dir.join("workspace"),
+            };
+            let tmux = SummTmuxSession {
+                name: format!("summ-{}", id),
+                session_id: option.map(str::to_string),
+            };
+            tmux_sessions.push((tmux, path));
+        }
+        // A user's session sharing the prefix is not an orphan to clean up
+        tmux_sessions.push((
+            SummTmuxSession { name: "summ-notes".to_string(), session_id: None },
+            PathBuf::from("/home/user"),
+        ));
+        let adopted = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let tmux = MarkerTmux { sessions: tmux_sessions, adopted: adopted.clone() };
+
+        let sessions = recover_with(&config, tmux).await.unwrap();
+        assert_eq!(sessions["session_marked"].status, SessionStatus::Running);
+        assert_eq!(sessions["session_marked"].pid, Some(4242));
+        // Created before the options: started in its workspace, so adopted and marked
+        assert_eq!(sessions["session_legacy"].status, SessionStatus::Running);
+        assert_eq!(
+            *adopted.lock().unwrap(),
+            vec![("summ-session_legacy".to_string(), "session_legacy".to_string())]
+        );
+        // Same name, but a user's session or another summ session's: left alone
+        for id in ["session_foreign", "session_swapped"] {
+            assert_eq!(sessions[id].status, SessionStatus::Stopped, "{}", id);
+            assert_eq!(sessions[id].pid, None, "{}", id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recover_never_revives_stopped_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let session_dir = config.sessions_dir.join("session_killed");
+        fs::create_dir_all(&session_dir).unwrap();
+        create_test_session_meta(&session_dir, "session_killed").unwrap();
+        let mut meta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(session_dir.join("meta.json")).unwrap()).unwrap();
+        meta["status"] = "stopped".into();
+        meta["stop_reason"] = serde_json::json!({ "kind": "external_kill" });
+        fs::write(session_dir.join("meta.json"), meta.to_string()).unwrap();
+
+        // The user recreated a tmux session under its name, in its workspace
+        let adopted = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let tmux = MarkerTmux {
+            sessions: vec![(
+                SummTmuxSession { name: "summ-session_killed".to_string(), session_id: None },
+                session_dir.join("workspace"),
+            )],
+            adopted: adopted.clone(),
+        };
+
+        let sessions = recover_with(&config, tmux).await.unwrap();
+        let session = &sessions["session_killed"];
+        assert_eq!(session.status, SessionStatus::Stopped);
+        assert_eq!(session.stop_reason, Some(StopReason::ExternalKill));
+        assert_eq!(session.pid, None);
+        assert!(adopted.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recover_relocates_logs() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        fs::create_dir_all(&config.logs_dir).unwrap();
+        let elsewhere = temp_dir.path().join("old-logs");
+        fs::create_dir_all(&elsewhere).unwrap();
+        for (id, log_path) in [
+            ("session_legacy", None),
+            ("session_moved", Some(elsewhere.join("session_moved.log"))),
+            ("session_kept", Some(elsewhere.join("session_kept.log"))),
+            ("session_lost", Some(elsewhere.join("session_lost.log"))),
+        ] {
+            let session_dir = config.sessions_dir.join(id);
+            fs::create_dir_all(&session_dir).unwrap();
+            create_test_session_meta(&session_dir, id).unwrap();
+            if let Some(log_path) = log_path {
+                let meta_path = session_dir.join("meta.json");
+                let mut meta: serde_json::Value = serde_json::from_slice(&fs::read(&meta_path).unwrap()).unwrap();
+                meta["log_path"] = serde_json::json!(log_path);
+                fs::write(&meta_path, meta.to_string()).unwrap();
+            }
+        }
+        // The moved log now sits in logs_dir; the kept one is still where it was
+        fs::write(config.session_log_path("session_moved").unwrap(), "output").unwrap();
+        fs::write(elsewhere.join("session_kept.log"), "output").unwrap();
+
+        let sessions = recover_with(&config, FakeTmux(HashMap::new())).await.unwrap();
+        assert_eq!(sessions["session_legacy"].log_path, config.session_log_path("session_legacy").unwrap());
+        assert_eq!(sessions["session_moved"].log_path, config.session_log_path("session_moved").unwrap());
+        assert_eq!(sessions["session_kept"].log_path, elsewhere.join("session_kept.log"));
+        assert_eq!(sessions["session_lost"].log_path, elsewhere.join("session_lost.log"));
+
+        // The fixed paths are saved
+        let reloaded = <Session as SessionExt>::load_metadata(&config.sessions_dir.join("session_legacy")).unwrap();
+        assert_eq!(reloaded.log_path, config.session_log_path("session_legacy").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_recover_accepts_every_id_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = DaemonConfig::with_base_dir(temp_dir.path());
+        let mut live = HashMap::new();
+        let mut ids = Vec::new();
+        for (pid, format) in [IdFormat::UuidShort, IdFormat::Ulid, IdFormat::Timestamp].into_iter().enumerate() {
+            config.id_format = format;
+            let session = launched_session(&config, &format!("{:?}", format), 200 + pid as u32);
+            live.insert(session.tmux_session.clone(), 200 + pid as u32);
+            ids.push(session.session_id);
+        }
+
+        let sessions = recover_with(&config, FakeTmux(live)).await.unwrap();
+        assert_eq!(sessions.len(), ids.len());
+        for id in &ids {
+            assert_eq!(sessions[id].status, SessionStatus::Running, "{}", id);
+        }
+    }
+
+    /// A session in the state `launch` leaves it in, without a real tmux session
+    fn launched_session(config: &DaemonConfig, name: &str, pid: u32) -> Session {
+        let mut session = Session::prepare(
+            "claude",
+            std::path::Path::new("/tmp/init"),
+            crate::session::StartOptions {
+                name: Some(name.to_string()),
+                ..Default::default()
+            },
+            Some(1000),
+            &HashMap::new(),
+            config,
+        )
+        .unwrap();
+        crate::init::create_session_structure(&session.workdir).unwrap();
+        session.status = SessionStatus::Running;
+        session.pid = Some(pid);
+        session.creation_timings = Some(summ_common::CreationTimings {
+            init_ms: 120,
+            hooks_ms: 4,
+            tmux_ms: 35,
+            logging_ms: 2,
+            total_ms: 161,
+        });
+        session.hook_health = Some(summ_common::HookHealth::Ok);
+        session.hooks = Some(summ_common::HookInfo {
+            kind: summ_common::HookKind::Claude,
+            state: summ_common::HookState::Ok,
+            deployed_at: Some(session.created_at),
+            last_event_at: Some(session.created_at),
+            last_event: Some("session-start".to_string()),
+        });
+        session.init_manifest = Some(summ_common::InitManifest {
+            source: "/tmp/init".into(),
+            kind: "directory".to_string(),
+            sha256: Some("ab".repeat(32)),
+            file_count: 3,
+            total_bytes: 4096,
+            path_issues: vec![summ_common::InitPathIssue {
+                entry: "readme.md".to_string(),
+                problem: summ_common::InitPathProblem::CaseCollision,
+                collides_with: Some("README.md".to_string()),
+                renamed_to: Some("readme~2.md".to_string()),
+            }],
+        });
+        session.save_metadata().unwrap();
+        session
+    }
+
+    /// Every field `meta.json` carries across the sessions built by
+    /// `test_restart_round_trip`. A new Session field must be added here and given
+    /// a non-default value in that test, so its persistence is checked.
+    const PERSISTED_FIELDS: &[&str] = &[
+        "schema_version",
+        "session_id",
+        "namespace",
+        "tmux_session",
+        "name",
+        "cli",
+        "workdir",
+        "log_path",
+        "init_source",
+        "status",
+        "pid",
+        "created_at",
+        "last_activity",
+        "paused",
+        "needs_attention",
+        "waiting_for_input",
+        "current_task",
+        "created_by",
+        "created_by_uid",
+        "protected",
+        "stop_reason",
+        "creation_timings",
+        "start_error",
+        "hook_health",
+        "hooks",
+        "note",
+        "tokens_in",
+        "tokens_out",
+        "estimated_cost",
+        "init_manifest",
+        "git_checkpoint",
+        "checkpoint",
+        "secrets",
+        "start_after",
+        "workdir_subpath",
+        "hooks_in_subpath",
+        "ephemeral",
+        "idle_timeout_seconds",
+        "busy_since",
+        "busy_alerted",
+        "busy_alert_minutes",
+        "inject_rate_limit",
+        "throttled_at",
+        "no_inject_history",
+        "observer_of",
+    ];
+
+    #[tokio::test]
+    async fn test_restart_round_trip() {
+        use crate::handler::Handler;
+        use std::sync::Arc;
+        use summ_common::{Request, Response};
+        use tokio::sync::RwLock;
+
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        config.ensure_directories().unwrap();
+
+        // Sessions in every state, changed through the handler where tmux is not needed
+        let protected = launched_session(&config, "protected", 101);
+        let mut paused = launched_session(&config, "paused", 102);
+        paused.status = SessionStatus::Paused;
+        paused.paused = true;
+        paused.hook_health = Some(summ_common::HookHealth::Broken);
+        paused.current_task = Some("Fix the flaky retry test".to_string());
+        paused.busy_since = Some(chrono::Utc::now() - chrono::Duration::minutes(42));
+        paused.busy_alerted = true;
+        paused.busy_alert_minutes = Some(30);
+        paused.inject_rate_limit = Some(5);
+        paused.throttled_at = Some(chrono::Utc::now() - chrono::Duration::minutes(3));
+        paused.no_inject_history = true;
+        paused.secrets = vec!["OPENAI_API_KEY".to_string()];
+        paused.start_after = Some(summ_common::StartTrigger {
+            session_id: "session_earlier".to_string(),
+            status: SessionStatus::Idle,
+        });
+        paused.save_metadata().unwrap();
+        let mut idle = launched_session(&config, "idle", 103);
+        idle.status = SessionStatus::Idle;
+        idle.needs_attention = true;
+        idle.waiting_for_input = Some(summ_common::InputPrompt {
+            since: chrono::Utc::now(),
+            source: summ_common::PromptSource::Pane,
+            text: "Do you want to proceed?".to_string(),
+        });
+        idle.tokens_in = Some(12_345);
+        idle.tokens_out = Some(678);
+        idle.estimated_cost = Some(0.0472);
+        idle.workdir_subpath = Some(PathBuf::from("services/api"));
+        idle.hooks_in_subpath = true;
+        idle.ephemeral = true;
+        idle.idle_timeout_seconds = Some(1800);
+        idle.save_metadata().unwrap();
+        let mut stopped = launched_session(&config, "stopped", 104);
+        stopped.git_checkpoint = Some(summ_common::CheckpointMode::Commit);
+        stopped.observer_of = Some("session_primary".to_string());
+        stopped.save_metadata().unwrap();
+
+        let sessions = Arc::new(RwLock::new(
+            [&protected, &paused, &idle, &stopped]
+                .into_iter()
+                .map(|s| (s.session_id.clone(), s.clone()))
+                .collect::<HashMap<_, _>>(),
+        ));
+        let handler = Handler::new(sessions.clone(), Arc::new(config.clone()));
+        let succeed = |response: Response| match response {
+            Response::Success { data } => data,
+            Response::Error { code, message, .. } => panic!("{}: {}", code, message),
+            other => panic!("Expected Success response, got {:?}", other),
+        };
+
+        succeed(
+            handler
+                .handle(
+                    Request::SetProtected {
+                        session_id: protected.session_id.clone(),
+                        protected: true,
+                    },
+                    None,
+                )
+                .await
+                .unwrap(),
+        );
+        succeed(
+            handler
+                .handle(
+                    Request::SetNote {
+                        session_id: protected.session_id.clone(),
+                        note: Some("release branch".to_string()),
+                    },
+                    None,
+                )
+                .await
+                .unwrap(),
+        );
+        succeed(
+            handler
+                .handle(
+                    Request::Stop {
+                        session_id: stopped.session_id.clone(),
+                        override_protection: false,
+                        stop_observers: false,
+                    },
+                    None,
+                )
+                .await
+                .unwrap(),
+        );
+        // As the monitor records it after the stop
+        if let Some(session) = sessions.write().await.get_mut(&stopped.session_id) {
+            session.checkpoint = Some(summ_common::Checkpoint {
+                mode: summ_common::CheckpointMode::Commit,
+                created_at: chrono::Utc::now(),
+                commit: Some("1a2b3c4d".repeat(5)),
+                branch: Some(format!("summ/{}", session.session_id)),
+                patch_path: None,
+                warning: None,
+            });
+            session.save_metadata().unwrap();
+        }
+
+        // An unsupported init source makes the background start fail; it is
+        // started in another namespace so its directory is found there too
+        let init = temp_dir.path().join("init.txt");
+        fs::write(&init, "not a directory or archive").unwrap();
+        let started = succeed(
+            handler
+                .handle_scoped(
+                    Request::Start {
+                        cli: "claude".to_string(),
+                        init,
+                        name: Some("failed".to_string()),
+                        protected: false,
+                        deadline_ms: None,
+                        background: true,
+                        allow_large_init: false,
+                        git_checkpoint: None,
+                        secrets: Vec::new(),
+                        workdir_subpath: None,
+                        hooks_in_subpath: false,
+                        ephemeral: false,
+                        idle_timeout_seconds: None,
+                        init_from_session: None,
+                        after: None,
+                        observer_of: None,
+                        busy_alert_minutes: None,
+                        inject_rate_limit: None,
+                        no_inject_history: false,
+                    },
+                    &summ_common::Scope::namespace(Namespace::new("work").unwrap()),
+                    Some(1000),
+                )
+                .await
+                .unwrap(),
+        );
+        let failed_id = started["session_id"].as_str().unwrap().to_string();
+        for _ in 0..200 {
+            if sessions.read().await[&failed_id].status != SessionStatus::Starting {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(sessions.read().await[&failed_id].status, SessionStatus::Failed);
+
+        // "Restart": keep the in-memory state, drop the daemon side, recover from disk
+        let before = sessions.read().await.clone();
+        drop(handler);
+        drop(sessions);
+        let tmux = FakeTmux(
+            [(&protected, 101), (&paused, 102), (&idle, 103)]
+                .into_iter()
+                .map(|(s, pid)| (s.tmux_session.clone(), pid))
+                .collect(),
+        );
+        let recovered = recover_with(&config, tmux).await.unwrap();
+
+        // Recovery reports live sessions as running (hooks refine that later)
+        let mut expected = before.clone();
+        expected.get_mut(&idle.session_id).unwrap().status = SessionStatus::Running;
+
+        let mut seen_fields = std::collections::BTreeSet::new();
+        assert_eq!(
+            recovered.keys().collect::<std::collections::BTreeSet<_>>(),
+            expected.keys().collect()
+        );
+        for (id, session) in &expected {
+            let expected = serde_json::to_value(session).unwrap();
+            let actual = serde_json::to_value(&recovered[id]).unwrap();
+            assert_eq!(actual, expected, "session {} changed across the restart", session.name);
+            seen_fields.extend(expected.as_object().unwrap().keys().cloned());
+        }
+        assert_eq!(
+            seen_fields.iter().map(String::as_str).collect::<Vec<_>>(),
+            PERSISTED_FIELDS
+                .iter()
+                .copied()
+                .collect::<std::collections::BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            "update PERSISTED_FIELDS and the sessions above for new Session fields"
+        );
+    }
 }
+
@@ -2,18 +2,42 @@
 // Unix socket server for daemon IPC
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use summ_common::{DaemonConfig, Session};
+use std::time::{Duration, Instant};
+use summ_common::{
+    CliStatus, DaemonConfig, HookHealth, LifecycleEvent, Session, SessionStatus, StopReason, TimelineKind,
+};
 use tokio::net::UnixListener;
 use tokio::sync::RwLock;
-use tokio::task::JoinSet;
-use tracing::{error, info};
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{debug, error, info, warn};
 
+use crate::attention;
+use crate::busy;
+use crate::checkpoint;
+use crate::clock;
+use crate::disk;
+use crate::ephemeral;
+use crate::events;
+use crate::evict::{self, Archive};
+use crate::gc;
 use crate::handler::Handler;
-use crate::recovery;
-use crate::session::SessionExt;
+use crate::health;
+use crate::hooks;
+use crate::idle;
+use crate::lifecycle;
+use crate::pending;
+use crate::permissions;
+use crate::prompt;
+use crate::recovery::{self, RecoveryProgress};
+use crate::session::{self, SessionExt};
+use crate::storage;
+use crate::shutdown::{self, StartKind};
+use crate::task;
 use crate::tmux::TmuxManager;
+use crate::usage;
+use crate::warnings;
 
 /// Daemon server that listens on Unix socket and manages sessions
 pub struct Daemon {
@@ -21,6 +45,10 @@ pub struct Daemon {
     config: DaemonConfig,
     /// Map of session_id to Session
     sessions: Arc<RwLock<HashMap<String, Session>>>,
+    /// Progress of the startup recovery
+    recovery: Arc<RecoveryProgress>,
+    /// Ended sessions the monitor dropped from `sessions`
+    archive: Arc<Archive>,
 }
 
 impl Daemon {
@@ -29,6 +57,8 @@ impl Daemon {
         Self {
             config,
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            recovery: Arc::new(RecoveryProgress::new()),
+            archive: Arc::default(),
         }
     }
 
@@ -47,21 +77,13 @@ impl Daemon {
                 .context("Failed to remove old socket file")?;
         }
 
-        // Recover existing sessions
-        info!("Recovering existing sessions...");
-        let recovered = recovery::recover_sessions(&self.config)?;
-        let mut sessions = self.sessions.write().await;
-        *sessions = recovered;
-        drop(sessions);
-        info!(
-            "Daemon recovered {} sessions",
-            self.sessions.read().await.len()
-        );
-
         // Bind to Unix socket
         let listener = UnixListener::bind(&self.config.socket_path)
             .context("Failed to bind to socket")?;
 
+        // Keep directories, logs, and the socket private to this user
+        permissions::secure_daemon_dirs(&self.config);
+
         // Notify systemd that daemon is ready
         #[cfg(target_os = "linux")]
         {
@@ -77,19 +99,34 @@ impl Daemon {
             self.config.socket_path.display()
         );
 
-        // Spawn monitoring task
-        let sessions_clone = self.sessions.clone();
-        let config_clone = self.config.clone();
-        tokio::spawn(async move {
-            monitor_sessions(sessions_clone, config_clone).await;
-        });
+        // Recover existing sessions while already answering read-only requests;
+        // the monitor starts once recovery is done
+        tokio::spawn(recover(
+            self.config.clone(),
+            self.sessions.clone(),
+            self.recovery.clone(),
+            self.archive.clone(),
+        ));
+
+        // Watch free space on the sessions filesystem
+        tokio::spawn(disk::watch_disk(self.config.clone()));
+
+        // Probe the sessions directory for writes (reported by Healthz)
+        tokio::spawn(health::watch_sessions_dir(self.config.clone()));
 
         // Accept connections
-        let handler = Handler::new(self.sessions.clone(), Arc::new(self.config.clone()));
+        let handler = Handler::new(self.sessions.clone(), Arc::new(self.config.clone()))
+            .with_recovery(self.recovery.clone())
+            .with_archive(self.archive.clone());
         let mut join_set = JoinSet::new();
 
+        let mut shutdown_requested = std::pin::pin!(shutdown_signal());
         loop {
-            match listener.accept().await {
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = &mut shutdown_requested => break,
+            };
+            match accepted {
                 Ok((stream, _addr)) => {
                     let handler_clone = handler.clone();
                     join_set.spawn(async move {
@@ -112,53 +149,421 @@ impl Daemon {
                 }
             }
         }
+
+        self.shutdown().await;
+        Ok(())
+    }
+
+    /// Save all sessions, leave the clean-shutdown marker, and remove the socket
+    async fn shutdown(&self) {
+        info!("Shutting down...");
+        // Holding the write lock keeps in-flight requests from changing sessions mid-save
+        let sessions = self.sessions.write().await;
+        if let Err(e) = shutdown::persist_on_shutdown(&self.config, &sessions) {
+            error!("Failed to persist state on shutdown: {:#}", e);
+        }
+        if let Err(e) = std::fs::remove_file(&self.config.socket_path) {
+            warn!("Failed to remove socket file: {}", e);
+        }
+        info!("Daemon stopped cleanly ({} sessions saved)", sessions.len());
+    }
+}
+
+/// Startup recovery: load sessions into the map, re-verify them after an unclean
+/// shutdown, refresh outdated hook assets, then start the session monitor
+async fn recover(
+    config: DaemonConfig,
+    sessions: Arc<RwLock<HashMap<String, Session>>>,
+    progress: Arc<RecoveryProgress>,
+    archive: Arc<Archive>,
+) {
+    info!("Recovering existing sessions...");
+    if let Err(e) =
+        recovery::recover_sessions_into(&config, recovery::SystemTmux, &sessions, &progress).await
+    {
+        // Mutating requests stay refused: acting on a partial view could clobber sessions
+        error!("Session recovery failed: {:#}; restart the daemon once this is fixed", e);
+        return;
+    }
+
+    let mut recovered = sessions.write().await;
+
+    // Statuses are only as good as the last save: after a crash, check every session again
+    let start_kind = match shutdown::take_marker(&config) {
+        Some(marker) => {
+            info!(
+                "Previous daemon (v{}) shut down cleanly at {}",
+                marker.version, marker.timestamp
+            );
+            StartKind::Clean
+        }
+        None => {
+            info!("No clean-shutdown marker; re-verifying all sessions");
+            for session in recovered.values_mut() {
+                refresh_session(session);
+            }
+            StartKind::Unclean
+        }
+    };
+    shutdown::set_start_kind(start_kind);
+    info!("Daemon recovered {} sessions", recovered.len());
+
+    // Refresh hook assets left behind by an older daemon version
+    match hooks::sync_hooks(&config.data_dir(), &recovered, false) {
+        Ok(report) => {
+            if report.script_updated {
+                info!("Updated summ-hook script to the current version");
+            }
+            if !report.sessions_updated.is_empty() {
+                info!(
+                    "Refreshed hook settings for sessions: {}",
+                    report.sessions_updated.join(", ")
+                );
+            }
+        }
+        Err(e) => error!("Failed to sync hook assets: {}", e),
+    }
+    drop(recovered);
+    progress.finish();
+
+    // Remove leftovers of sessions that no longer exist, now that all are known
+    tokio::spawn(gc::watch(sessions.clone(), config.clone()));
+
+    // Spawn monitoring task, restarted by its supervisor if it dies or stalls
+    tokio::spawn(supervise_monitor(
+        move || tokio::spawn(monitor_sessions(sessions.clone(), config.clone(), archive.clone())),
+        MONITOR_INTERVAL,
+        MONITOR_INTERVAL * MONITOR_STALL_TICKS,
+    ));
+}
+
+/// Resolves on SIGTERM or SIGINT
+async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let (Ok(mut terminate), Ok(mut interrupt)) =
+        (signal(SignalKind::terminate()), signal(SignalKind::interrupt()))
+    else {
+        error!("Failed to install signal handlers; graceful shutdown is unavailable");
+        return std::future::pending().await;
+    };
+    tokio::select! {
+        _ = terminate.recv() => info!("Received SIGTERM"),
+        _ = interrupt.recv() => info!("Received SIGINT"),
+    }
+}
+
+/// Hook reports that only repeated a session's previous state and message.
+///
+/// These are skipped without persisting anything; reported in `summ daemon status`.
+pub static SUPPRESSED_STATUS_UPDATES: AtomicU64 = AtomicU64::new(0);
+
+/// Interval between session monitor ticks
+const MONITOR_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The monitor is restarted after this many intervals without a completed tick
+const MONITOR_STALL_TICKS: u32 = 6;
+
+/// Times the supervisor restarted the session monitor; reported in `summ daemon status`
+pub static MONITOR_RESTARTS: AtomicU64 = AtomicU64::new(0);
+
+/// Keep the session monitor running: every `check_every`, restart it if its task
+/// ended (e.g. a panic) or it has not completed a tick for `stall_after`
+async fn supervise_monitor<F>(mut spawn: F, check_every: Duration, stall_after: Duration)
+where
+    F: FnMut() -> JoinHandle<()>,
+{
+    let mut monitor = spawn();
+    let mut spawned_at = Instant::now();
+    let mut interval = tokio::time::interval(check_every);
+
+    loop {
+        interval.tick().await;
+
+        if monitor.is_finished() {
+            match (&mut monitor).await {
+                Err(e) if e.is_panic() => error!("Session monitor panicked; restarting it"),
+                _ => error!("Session monitor stopped; restarting it"),
+            }
+        } else if monitor_stalled(health::monitor_heartbeat_age(), spawned_at.elapsed(), stall_after) {
+            error!(
+                "Session monitor has not completed a tick in {}s; restarting it",
+                stall_after.as_secs()
+            );
+            monitor.abort();
+        } else {
+            continue;
+        }
+
+        MONITOR_RESTARTS.fetch_add(1, Ordering::Relaxed);
+        monitor = spawn();
+        spawned_at = Instant::now();
     }
 }
 
+/// Whether a monitor running for `running_for` with the given heartbeat age is stuck
+fn monitor_stalled(heartbeat_age: Option<u64>, running_for: Duration, stall_after: Duration) -> bool {
+    running_for > stall_after
+        && heartbeat_age.is_none_or(|age| Duration::from_secs(age) > stall_after)
+}
+
 /// Background task that monitors session status
 async fn monitor_sessions(
     sessions: Arc<RwLock<HashMap<String, Session>>>,
-    _config: DaemonConfig,
+    config: DaemonConfig,
+    archive: Arc<Archive>,
 ) {
-    let mut interval = tokio::time::interval(Duration::from_secs(5));
+    let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+    let (prompts, errors) = prompt::PromptMatcher::new(&config);
+    for error in errors {
+        warn!("{}", error);
+    }
+    let mut seen_reports = HashMap::new();
+    let mut seen_transcripts = HashMap::new();
+    let mut seen_output = HashMap::new();
 
     loop {
         interval.tick().await;
 
-        let mut sessions = sessions.write().await;
-        let mut has_changes = false;
-
-        for (id, session) in sessions.iter_mut() {
-            // Get effective status by checking tmux and CLI status
-            let new_status = session.get_effective_status();
+        let alerts = TmuxManager::window_states().unwrap_or_else(|e| {
+            warnings::warn("tmux_alerts", None, format!("Failed to read tmux window states: {:#}", e));
+            HashMap::new()
+        });
+        let (transcripts, checkpoints_due, needing_attention, starts_due, ended) = {
+            let mut sessions = sessions.write().await;
+            let mut has_changes = false;
+            let mut needing_attention = Vec::new();
 
-            if new_status != session.status {
-                info!(
-                    "Session {} status changed: {:?} -> {:?}",
-                    id, session.status, new_status
-                );
-                session.status = new_status.clone();
-                session.pid = if new_status == summ_common::SessionStatus::Stopped {
-                    None
-                } else {
-                    TmuxManager::get_pane_pid(&session.tmux_session).ok().flatten()
+            for session in sessions.values_mut() {
+                if is_repeated_report(&mut seen_reports, session) {
+                    SUPPRESSED_STATUS_UPDATES.fetch_add(1, Ordering::Relaxed);
+                    debug!("Session {} repeated its last hook report", session.session_id);
+                }
+                let previous = session.status.clone();
+                has_changes |= refresh_session(session);
+                if idle::is_expired(session) {
+                    idle::stop(session);
+                    has_changes = true;
+                }
+                let report = session.read_cli_status();
+                has_changes |= task::record(session, report.as_ref());
+                has_changes |= busy::record(session, &previous, report.as_ref());
+                if busy::is_overdue(session, &config) {
+                    busy::alert(session, &config);
+                }
+                let window = alerts.get(&session.tmux_session);
+                session.current_command = match session.status {
+                    SessionStatus::Stopped
+                    | SessionStatus::Starting
+                    | SessionStatus::Pending
+                    | SessionStatus::Failed => None,
+                    _ => window.and_then(|window| window.current_command.clone()),
                 };
-                session.save_metadata().ok();
-                has_changes = true;
+                let mut needs_attention = attention::record(session, window);
+                if matches!(session.status, SessionStatus::Running | SessionStatus::Idle) {
+                    needs_attention |= prompt::record(session, prompt::detect(session, &prompts));
+                }
+                if needs_attention {
+                    needing_attention.push(session.clone());
+                }
+                if !matches!(
+                    session.status,
+                    SessionStatus::Stopped
+                        | SessionStatus::Starting
+                        | SessionStatus::Pending
+                        | SessionStatus::Failed
+                ) {
+                    record_hook_health(session, &config);
+                    hooks::record_report(session, report.as_ref(), &config);
+                    events::record_output(session, &mut seen_output);
+                }
             }
+            seen_reports.retain(|id, _| sessions.contains_key(id));
+            seen_output.retain(|id, _| sessions.contains_key(id));
+            warnings::retain_sessions(|id| sessions.contains_key(id));
 
-            // Update activity timestamp for non-stopped sessions
-            if session.status != summ_common::SessionStatus::Stopped {
-                session.last_activity = chrono::Utc::now();
+            if has_changes {
+                info!("Session monitoring cycle completed with status updates");
+            }
+            // Ephemeral sessions go as soon as they end, whatever ended them
+            let ended = ephemeral::take_ended(&mut sessions);
+            // Long-ended ones only leave memory; their metadata stays on disk
+            let evicted = evict::evict(&mut sessions, &archive, &config);
+            clock::retain_sessions(|id| sessions.contains_key(id));
+            if evicted > 0 {
+                info!("Archived {} ended sessions out of memory", evicted);
             }
+            // Newly stopped sessions that asked for a checkpoint, whatever stopped them
+            let checkpoints_due: Vec<Session> = sessions
+                .values()
+                .filter(|session| checkpoint::is_due(session))
+                .cloned()
+                .collect();
+            (
+                usage::changed_transcripts(&sessions, &mut seen_transcripts),
+                checkpoints_due,
+                needing_attention,
+                pending::take_due(&mut sessions),
+                ended,
+            )
+        };
+
+        if !ended.is_empty() {
+            tokio::task::spawn_blocking(move || {
+                for session in &ended {
+                    ephemeral::remove(session);
+                }
+            });
         }
 
-        if has_changes {
-            info!("Session monitoring cycle completed with status updates");
+        for session in starts_due {
+            session::launch_in_background(session, sessions.clone(), Arc::new(config.clone()), None);
+        }
+        for session in checkpoints_due {
+            checkpoint::spawn(sessions.clone(), session);
+        }
+        for session in &needing_attention {
+            attention::notify(session, &config);
         }
+
+        record_usage(&sessions, transcripts, &config).await;
+        let listed = TmuxManager::list_summ_sessions();
+        if let Err(e) = &listed {
+            warnings::warn("tmux_list", None, format!("Monitor could not list tmux sessions: {:#}", e));
+        }
+        health::record_monitor_tick(listed.is_ok());
     }
 }
 
+/// Re-read changed transcripts off the runtime (without holding the sessions
+/// lock) and store their token totals
+async fn record_usage(
+    sessions: &RwLock<HashMap<String, Session>>,
+    transcripts: Vec<(String, std::path::PathBuf)>,
+    config: &DaemonConfig,
+) {
+    if transcripts.is_empty() {
+        return;
+    }
+    let parsed = tokio::task::spawn_blocking(move || {
+        transcripts
+            .into_iter()
+            .filter_map(|(id, path)| usage::read_transcript(&path).map(|usage| (id, usage)))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    let mut sessions = sessions.write().await;
+    for (id, transcript_usage) in parsed {
+        if let Some(session) = sessions.get_mut(&id) {
+            if usage::apply(session, &transcript_usage, config) {
+                debug!(
+                    "Session {} used {:?} input / {:?} output tokens",
+                    id, session.tokens_in, session.tokens_out
+                );
+                storage::save(session).ok();
+            }
+        }
+    }
+}
+
+/// Remember the session's latest hook report; returns true if it is a new
+/// report with the same state and message as the previous one
+fn is_repeated_report(seen: &mut HashMap<String, CliStatus>, session: &Session) -> bool {
+    let Some(report) = session.read_cli_status() else {
+        return false;
+    };
+    let repeated = seen.get(&session.session_id).is_some_and(|previous| {
+        previous.timestamp != report.timestamp
+            && previous.state == report.state
+            && previous.message == report.message
+    });
+    seen.insert(session.session_id.clone(), report);
+    repeated
+}
+
+/// Run the post-start hook check and persist a changed verdict
+fn record_hook_health(session: &mut Session, config: &DaemonConfig) {
+    let Some(health) = hooks::check_hook_health(session, config) else {
+        return;
+    };
+    if session.hook_health == Some(health) {
+        return;
+    }
+    if health != HookHealth::Ok {
+        warn!(
+            "Session {} hooks are not reporting ({:?}); run `summ daemon sync-hooks`",
+            session.session_id, health
+        );
+    }
+    session.hook_health = Some(health);
+    storage::save(session).ok();
+}
+
+/// Reconcile one session with its effective status; returns whether it changed
+fn refresh_session(session: &mut Session) -> bool {
+    // Get effective status by checking tmux and CLI status
+    let new_status = session.get_effective_status();
+    let changed = new_status != session.status;
+
+    if changed {
+        info!(
+            "Session {} status changed: {:?} -> {:?}",
+            session.session_id, session.status, new_status
+        );
+        let transition = format!("{} -> {}", session.status, new_status);
+        session.status = new_status.clone();
+        session.pid = if new_status == SessionStatus::Stopped {
+            session.paused = false;
+            session.needs_attention = false;
+            session.waiting_for_input = None;
+            if session.stop_reason.is_none() {
+                // Neither summ nor the CLI ended it, so someone killed the tmux session
+                let reason = session.reported_exit().unwrap_or_else(|| {
+                    warn!(
+                        "Session {} was killed outside summ (tmux session {} is gone)",
+                        session.session_id, session.tmux_session
+                    );
+                    StopReason::ExternalKill
+                });
+                session.stop_reason = Some(reason);
+            }
+            if let Some(reason) = &session.stop_reason {
+                let kind = match reason {
+                    StopReason::ExternalKill => TimelineKind::ExternalKill,
+                    _ => TimelineKind::Stopped,
+                };
+                events::record(session, kind, format!("{} ({})", transition, reason));
+            }
+            None
+        } else {
+            events::record(session, TimelineKind::Status, transition);
+            TmuxManager::get_pane_pid(&session.tmux_session).unwrap_or_else(|e| {
+                warnings::warn(
+                    "pane_pid",
+                    Some(&session.session_id),
+                    format!("Failed to read the pane PID of session {}: {:#}", session.session_id, e),
+                );
+                None
+            })
+        };
+        storage::save(session).ok();
+        let event = match new_status {
+            SessionStatus::Running => Some(LifecycleEvent::Running),
+            SessionStatus::Idle => Some(LifecycleEvent::Idle),
+            SessionStatus::Stopped => Some(LifecycleEvent::Stopped),
+            SessionStatus::Failed => Some(LifecycleEvent::Failed),
+            _ => None,
+        };
+        if let Some(event) = event {
+            lifecycle::fire(session, event);
+        }
+    }
+
+    changed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,16 +572,156 @@ mod tests {
     #[test]
     fn test_daemon_new() {
         let temp_dir = TempDir::new().unwrap();
-        let config = DaemonConfig {
-            sessions_dir: temp_dir.path().join("sessions"),
-            logs_dir: temp_dir.path().join("logs"),
-            socket_path: temp_dir.path().join("daemon.sock"),
-            cleanup_retention_hours: 24,
-            tmux_prefix: "summ-".to_string(),
-        };
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
 
         let daemon = Daemon::new(config.clone());
         // Daemon is created successfully
         assert_eq!(daemon.config.sessions_dir, config.sessions_dir);
     }
+
+    fn running_session(temp_dir: &TempDir) -> Session {
+        let workdir = temp_dir.path().join("test001");
+        std::fs::create_dir_all(workdir.join("runtime")).unwrap();
+        Session {
+            session_id: "test001".to_string(),
+            tmux_session: "summ-nonexistent-test001".to_string(),
+            workdir,
+            status: SessionStatus::Running,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_refresh_session_external_kill() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = running_session(&temp_dir);
+
+        assert!(refresh_session(&mut session));
+        assert_eq!(session.status, SessionStatus::Stopped);
+        assert_eq!(session.stop_reason, Some(StopReason::ExternalKill));
+        let journal = events::read(&session);
+        assert_eq!(journal.len(), 1);
+        assert_eq!(journal[0].kind, TimelineKind::ExternalKill);
+    }
+
+    #[test]
+    fn test_refresh_session_exit_code() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = running_session(&temp_dir);
+        std::fs::write(session.workdir.join("runtime").join(crate::tmux::EXIT_CODE_FILE), "3\n").unwrap();
+
+        assert!(refresh_session(&mut session));
+        assert_eq!(
+            session.stop_reason,
+            Some(StopReason::ProcessExited { code: Some(3) })
+        );
+        assert_eq!(events::read(&session)[0].kind, TimelineKind::Stopped);
+    }
+
+    #[test]
+    fn test_refresh_session_process_exited() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = running_session(&temp_dir);
+        std::fs::write(
+            session.workdir.join("runtime/status.json"),
+            serde_json::json!({
+                "state": "stopped",
+                "message": "Session ended: exit",
+                "timestamp": chrono::Utc::now(),
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        assert!(refresh_session(&mut session));
+        assert_eq!(
+            session.stop_reason,
+            Some(StopReason::ProcessExited { code: None })
+        );
+    }
+
+    fn write_report(session: &Session, state: &str, message: &str) {
+        std::fs::write(
+            session.workdir.join("runtime/status.json"),
+            serde_json::json!({
+                "state": state,
+                "message": message,
+                "timestamp": chrono::Utc::now(),
+            })
+            .to_string(),
+        )
+        .unwrap();
+        std::thread::sleep(Duration::from_millis(2));
+    }
+
+    #[test]
+    fn test_monitor_stalled() {
+        let stall_after = Duration::from_secs(30);
+        // A fresh monitor gets a grace period before its first tick
+        assert!(!monitor_stalled(None, Duration::from_secs(10), stall_after));
+        assert!(monitor_stalled(None, Duration::from_secs(31), stall_after));
+        assert!(!monitor_stalled(Some(5), Duration::from_secs(600), stall_after));
+        assert!(monitor_stalled(Some(31), Duration::from_secs(600), stall_after));
+    }
+
+    #[tokio::test]
+    async fn test_supervise_monitor_restarts_after_panic() {
+        let spawns = Arc::new(AtomicU64::new(0));
+        let restarts_before = MONITOR_RESTARTS.load(Ordering::Relaxed);
+
+        let counter = spawns.clone();
+        let spawn = move || {
+            let attempt = counter.fetch_add(1, Ordering::Relaxed);
+            tokio::spawn(async move {
+                if attempt < 2 {
+                    panic!("monitor tick failed");
+                }
+                std::future::pending::<()>().await
+            })
+        };
+        let supervisor = tokio::spawn(supervise_monitor(
+            spawn,
+            Duration::from_millis(10),
+            Duration::from_secs(3600),
+        ));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        supervisor.abort();
+
+        // Two panics, then a monitor that keeps running
+        assert_eq!(spawns.load(Ordering::Relaxed), 3);
+        assert!(MONITOR_RESTARTS.load(Ordering::Relaxed) >= restarts_before + 2);
+    }
+
+    #[test]
+    fn test_is_repeated_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let session = running_session(&temp_dir);
+        let mut seen = HashMap::new();
+
+        // No report yet, then a first report
+        assert!(!is_repeated_report(&mut seen, &session));
+        write_report(&session, "idle", "Subagent task completed");
+        assert!(!is_repeated_report(&mut seen, &session));
+
+        // Polling the same report again is not a repeat
+        assert!(!is_repeated_report(&mut seen, &session));
+
+        // A rewrite with the same state and message is
+        write_report(&session, "idle", "Subagent task completed");
+        assert!(is_repeated_report(&mut seen, &session));
+
+        // A genuine change never is
+        write_report(&session, "idle", "Task completed");
+        assert!(!is_repeated_report(&mut seen, &session));
+    }
+
+    #[test]
+    fn test_refresh_session_keeps_existing_reason() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = running_session(&temp_dir);
+        session.stop_reason = Some(StopReason::UserRequested { method: None });
+
+        refresh_session(&mut session);
+        assert_eq!(session.stop_reason, Some(StopReason::UserRequested { method: None }));
+    }
 }
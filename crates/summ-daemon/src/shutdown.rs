@@ -0,0 +1,128 @@
+// summ-daemon/src/shutdown.rs
+// Clean-shutdown marker, so the next start can tell a clean exit from a crash
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use summ_common::{DaemonConfig, Session, SessionCounts};
+use tracing::warn;
+
+use crate::session::SessionExt;
+use crate::storage;
+
+/// Contents of `last_shutdown.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShutdownMarker {
+    /// When the daemon shut down
+    pub timestamp: DateTime<Utc>,
+    /// Sessions per status at shutdown
+    pub counts: SessionCounts,
+    /// Version of the daemon that wrote the marker
+    pub version: String,
+}
+
+/// How the previous daemon exited, as seen at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartKind {
+    /// The previous daemon shut down gracefully and left a marker
+    Clean,
+    /// No marker (first start, crash, or kill -9): statuses were re-verified
+    Unclean,
+}
+
+/// Set once at startup, reported by DaemonStatus
+static START_KIND: OnceLock<StartKind> = OnceLock::new();
+
+/// How this daemon started, once recovery has decided
+pub fn start_kind() -> Option<StartKind> {
+    START_KIND.get().copied()
+}
+
+pub fn set_start_kind(kind: StartKind) {
+    START_KIND.set(kind).ok();
+}
+
+/// Save every session's metadata and write the clean-shutdown marker
+pub fn persist_on_shutdown(config: &DaemonConfig, sessions: &HashMap<String, Session>) -> Result<()> {
+    let now = Utc::now();
+    let mut counts = SessionCounts::default();
+    for session in sessions.values() {
+        storage::save(session).ok();
+        counts.add(&session.status, session.status_age().as_secs() as i64);
+    }
+
+    let marker = ShutdownMarker {
+        timestamp: now,
+        counts,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let path = config.last_shutdown_path();
+    std::fs::write(&path, serde_json::to_vec_pretty(&marker)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Read and remove the marker left by the previous daemon; `None` means the
+/// previous run did not shut down cleanly (or this is the first start)
+pub fn take_marker(config: &DaemonConfig) -> Option<ShutdownMarker> {
+    let path = config.last_shutdown_path();
+    let content = std::fs::read(&path).ok()?;
+    if let Err(e) = std::fs::remove_file(&path) {
+        warn!("Failed to remove {}: {}", path.display(), e);
+    }
+    match serde_json::from_slice(&content) {
+        Ok(marker) => Some(marker),
+        Err(e) => {
+            warn!("Ignoring unreadable {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use summ_common::SessionStatus;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_marker_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        config.ensure_directories().unwrap();
+
+        let mut sessions = HashMap::new();
+        for (id, status) in [("test001", SessionStatus::Idle), ("test002", SessionStatus::Stopped)] {
+            let workdir = config.sessions_dir.join(id);
+            std::fs::create_dir_all(&workdir).unwrap();
+            let session = Session {
+                session_id: id.to_string(),
+                workdir,
+                status,
+                ..Default::default()
+            };
+            sessions.insert(id.to_string(), session);
+        }
+
+        // No marker: unclean
+        assert_eq!(take_marker(&config), None);
+
+        persist_on_shutdown(&config, &sessions).unwrap();
+        let saved = Session::load_metadata(&config.sessions_dir.join("test001")).unwrap();
+        assert_eq!(saved.status, SessionStatus::Idle);
+
+        let marker = take_marker(&config).unwrap();
+        assert_eq!(marker.counts.idle, 1);
+        assert_eq!(marker.counts.stopped, 1);
+        assert_eq!(marker.counts.total, 2);
+        assert_eq!(marker.version, env!("CARGO_PKG_VERSION"));
+        // Read once: a second start without a new shutdown is unclean
+        assert!(!config.last_shutdown_path().exists());
+        assert_eq!(take_marker(&config), None);
+
+        std::fs::write(config.last_shutdown_path(), "{ not json").unwrap();
+        assert_eq!(take_marker(&config), None);
+        assert!(!config.last_shutdown_path().exists());
+    }
+}
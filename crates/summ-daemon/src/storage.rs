@@ -0,0 +1,163 @@
+// summ-daemon/src/storage.rs
+// Accounting of metadata writes: repeated failures put the daemon in a
+// storage-unavailable mode instead of silently dropping state changes
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use summ_common::{DaemonError, Session};
+use tracing::{error, info};
+
+use crate::session::SessionExt;
+use crate::warnings;
+
+/// Consecutive failed writes that make storage unavailable
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// Write failures since the last successful write, and whether they crossed
+/// the threshold
+pub struct StorageHealth {
+    consecutive_failures: AtomicU32,
+    unavailable: AtomicBool,
+}
+
+/// Storage state reported by DaemonStatus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StorageStatus {
+    pub available: bool,
+    pub consecutive_failures: u32,
+}
+
+impl StorageHealth {
+    const fn new() -> Self {
+        Self {
+            consecutive_failures: AtomicU32::new(0),
+            unavailable: AtomicBool::new(false),
+        }
+    }
+
+    /// Count a successful write; clears the unavailable mode
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        if self.unavailable.swap(false, Ordering::Relaxed) {
+            info!("Sessions directory is writable again; accepting state changes");
+        }
+    }
+
+    /// Count a failed write; enters the unavailable mode at the threshold
+    fn record_failure(&self, what: &str, session_id: Option<&str>, error: &dyn std::fmt::Display) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        warnings::warn(
+            "storage_write",
+            session_id,
+            format!("Failed to write {}: {} ({} consecutive failures)", what, error, failures),
+        );
+        if failures >= FAILURE_THRESHOLD && !self.unavailable.swap(true, Ordering::Relaxed) {
+            error!(
+                "Storage unavailable after {} consecutive write failures; \
+                 refusing state-changing requests until the sessions directory is writable",
+                failures
+            );
+        }
+    }
+
+    fn status(&self) -> StorageStatus {
+        StorageStatus {
+            available: !self.unavailable.load(Ordering::Relaxed),
+            consecutive_failures: self.consecutive_failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
+static STORAGE: StorageHealth = StorageHealth::new();
+
+/// Save a session's metadata, counting the outcome. Failures are logged here,
+/// so callers that cannot do anything about them may ignore the result.
+pub fn save(session: &Session) -> Result<()> {
+    let result = session.save_metadata();
+    match &result {
+        Ok(()) => STORAGE.record_success(),
+        Err(e) => STORAGE.record_failure(
+            &format!("metadata of session {}", session.session_id),
+            Some(&session.session_id),
+            e,
+        ),
+    }
+    result
+}
+
+/// Count the outcome of the periodic write probe of the sessions directory
+pub fn record_probe(result: &std::io::Result<()>) {
+    match result {
+        Ok(()) => STORAGE.record_success(),
+        Err(e) => STORAGE.record_failure("the sessions directory probe", None, e),
+    }
+}
+
+pub fn status() -> StorageStatus {
+    STORAGE.status()
+}
+
+/// Error for a state-changing request while storage is unavailable
+pub fn refusal() -> Option<DaemonError> {
+    refusal_for(status())
+}
+
+fn refusal_for(status: StorageStatus) -> Option<DaemonError> {
+    (!status.available).then(|| {
+        DaemonError::e016(format!(
+            "Storage unavailable: {} consecutive writes to the sessions directory failed; \
+             state changes are refused until it is writable again",
+            status.consecutive_failures
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use summ_common::ErrorCode;
+
+    #[test]
+    fn test_failures_cross_threshold_and_clear() {
+        let storage = StorageHealth::new();
+        let error = "Read-only file system";
+
+        for failures in 1..FAILURE_THRESHOLD {
+            storage.record_failure("meta.json", None, &error);
+            assert_eq!(
+                storage.status(),
+                StorageStatus { available: true, consecutive_failures: failures }
+            );
+        }
+        assert!(refusal_for(storage.status()).is_none());
+
+        storage.record_failure("meta.json", None, &error);
+        storage.record_failure("meta.json", None, &error);
+        let status = storage.status();
+        assert!(!status.available);
+        assert_eq!(status.consecutive_failures, FAILURE_THRESHOLD + 1);
+        let refusal = refusal_for(status).unwrap();
+        assert_eq!(refusal.code, ErrorCode::E016);
+        assert!(refusal.message.contains("4 consecutive writes"));
+
+        // One successful write (or probe) clears the mode
+        storage.record_success();
+        assert_eq!(
+            storage.status(),
+            StorageStatus { available: true, consecutive_failures: 0 }
+        );
+    }
+
+    #[test]
+    fn test_success_resets_the_count() {
+        let storage = StorageHealth::new();
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            storage.record_failure("meta.json", None, &"EIO");
+        }
+        storage.record_success();
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            storage.record_failure("meta.json", None, &"EIO");
+        }
+        assert!(storage.status().available);
+    }
+}
@@ -0,0 +1,143 @@
+// summ-daemon/src/task.rs
+// Derive the "current task" line shown for a session from its hook reports
+use summ_common::{CliState, CliStatus, Session};
+
+use crate::storage;
+
+/// Longest current task kept, in characters
+pub const MAX_TASK_CHARS: usize = 80;
+
+/// What a hook report means for the session's current task
+#[derive(Debug, PartialEq, Eq)]
+pub enum TaskUpdate {
+    /// The report names what the CLI is working on
+    Set(String),
+    /// The CLI finished its task
+    Clear,
+    /// The report says nothing about the task
+    Keep,
+}
+
+/// Apply the derivation rules to a hook report:
+/// - a busy report with a message (for Claude Code, the prompt submitted
+///   through `UserPromptSubmit`) sets the task to that message
+/// - an idle report clears it
+/// - a CLI waiting on a prompt is still on its task, and a stopped one keeps
+///   its last task for the post-mortem
+///
+/// The message is flattened to one line, with control characters dropped and
+/// whitespace collapsed, and cut to `MAX_TASK_CHARS`.
+pub fn derive(status: &CliStatus) -> TaskUpdate {
+    match status.state {
+        CliState::Idle => TaskUpdate::Clear,
+        CliState::Busy => match status.message.as_deref().map(one_line) {
+            Some(task) if !task.is_empty() => TaskUpdate::Set(truncate(&task)),
+            _ => TaskUpdate::Keep,
+        },
+        CliState::WaitingForInput | CliState::Stopped => TaskUpdate::Keep,
+    }
+}
+
+/// Update `current_task` from the session's latest hook report; returns true
+/// when it changed
+pub fn record(session: &mut Session, status: Option<&CliStatus>) -> bool {
+    let task = match status.map(derive) {
+        Some(TaskUpdate::Set(task)) => Some(task),
+        Some(TaskUpdate::Clear) => None,
+        Some(TaskUpdate::Keep) | None => return false,
+    };
+    if task == session.current_task {
+        return false;
+    }
+    session.current_task = task;
+    storage::save(session).ok();
+    true
+}
+
+/// `text` flattened to one line and cut to `MAX_TASK_CHARS`, the way
+/// current tasks are shown
+pub fn summarize(text: &str) -> String {
+    truncate(&one_line(text))
+}
+
+fn one_line(text: &str) -> String {
+    text.split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_TASK_CHARS {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(MAX_TASK_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn report(state: CliState, message: Option<&str>) -> CliStatus {
+        CliStatus {
+            state,
+            message: message.map(str::to_string),
+            event: None,
+            timestamp: Utc::now(),
+            transcript_path: None,
+        }
+    }
+
+    #[test]
+    fn test_derive_rules() {
+        assert_eq!(
+            derive(&report(CliState::Busy, Some("Fix the login form"))),
+            TaskUpdate::Set("Fix the login form".to_string())
+        );
+        assert_eq!(derive(&report(CliState::Busy, None)), TaskUpdate::Keep);
+        assert_eq!(derive(&report(CliState::Busy, Some(" \n\t "))), TaskUpdate::Keep);
+        assert_eq!(derive(&report(CliState::Idle, Some("Task completed"))), TaskUpdate::Clear);
+        assert_eq!(
+            derive(&report(CliState::WaitingForInput, Some("Claude needs your permission"))),
+            TaskUpdate::Keep
+        );
+        assert_eq!(derive(&report(CliState::Stopped, Some("Session ended"))), TaskUpdate::Keep);
+    }
+
+    #[test]
+    fn test_derive_flattens_and_truncates() {
+        assert_eq!(
+            derive(&report(CliState::Busy, Some("Run the tests\n\n  then\x1b[0m fix\tfailures\u{7}"))),
+            TaskUpdate::Set("Run the tests then [0m fix failures".to_string())
+        );
+
+        let long = "word ".repeat(40);
+        let TaskUpdate::Set(task) = derive(&report(CliState::Busy, Some(&long))) else {
+            panic!("a long prompt still sets the task");
+        };
+        assert_eq!(task.chars().count(), MAX_TASK_CHARS);
+        assert!(task.ends_with('…'));
+    }
+
+    #[test]
+    fn test_record_keeps_task_while_waiting() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut session = Session {
+            workdir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        assert!(record(&mut session, Some(&report(CliState::Busy, Some("Refactor the parser")))));
+        assert_eq!(session.current_task.as_deref(), Some("Refactor the parser"));
+        assert!(!record(&mut session, Some(&report(CliState::Busy, Some("Refactor the parser")))));
+        assert!(!record(&mut session, Some(&report(CliState::WaitingForInput, None))));
+        assert!(!record(&mut session, None));
+        assert_eq!(session.current_task.as_deref(), Some("Refactor the parser"));
+
+        assert!(record(&mut session, Some(&report(CliState::Idle, Some("Task completed")))));
+        assert_eq!(session.current_task, None);
+    }
+}
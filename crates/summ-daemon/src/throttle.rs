@@ -0,0 +1,167 @@
+// summ-daemon/src/throttle.rs
+// Per-session limit on injections per minute, so runaway automation cannot
+// flood a CLI with prompts
+use chrono::Utc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use summ_common::{DaemonConfig, LifecycleEvent, Session, TimelineKind};
+use tracing::warn;
+
+use crate::attention;
+use crate::events;
+use crate::lifecycle;
+use crate::storage;
+
+/// The sliding window the limit applies to
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// When each session's injections in the last minute were accepted
+#[derive(Debug, Default)]
+pub struct InjectLimiter {
+    accepted: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl InjectLimiter {
+    /// Count an injection into `session_id` unless it already took `limit`
+    /// in the last minute; a refusal says how long until one of those leaves
+    /// the window. Refused injections are not counted.
+    pub fn admit(&self, session_id: &str, limit: Option<u32>, now: Instant) -> Result<(), Duration> {
+        let mut accepted = self.accepted.lock().unwrap_or_else(|e| e.into_inner());
+        accepted.retain(|_, times| {
+            expire(times, now);
+            !times.is_empty()
+        });
+        let times = accepted.entry(session_id.to_string()).or_default();
+        if let (Some(limit), Some(oldest)) = (limit, times.front()) {
+            if times.len() >= limit as usize {
+                return Err(WINDOW.saturating_sub(now.duration_since(*oldest)));
+            }
+        }
+        times.push_back(now);
+        Ok(())
+    }
+
+    /// Give back the injection admitted at `at` when it could not be sent,
+    /// so failures do not use up the limit
+    pub fn release(&self, session_id: &str, at: Instant) {
+        let mut accepted = self.accepted.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(times) = accepted.get_mut(session_id) {
+            if let Some(position) = times.iter().rposition(|time| *time == at) {
+                times.remove(position);
+            }
+        }
+    }
+
+    /// Injections into `session_id` accepted in the last minute
+    pub fn rate(&self, session_id: &str, now: Instant) -> usize {
+        let accepted = self.accepted.lock().unwrap_or_else(|e| e.into_inner());
+        accepted
+            .get(session_id)
+            .map_or(0, |times| times.iter().filter(|at| now.duration_since(**at) < WINDOW).count())
+    }
+}
+
+fn expire(times: &mut VecDeque<Instant>, now: Instant) {
+    while times.front().is_some_and(|at| now.duration_since(*at) >= WINDOW) {
+        times.pop_front();
+    }
+}
+
+/// Injections per minute the session accepts: its own setting, else the
+/// config's; `None` when it has no limit
+pub fn limit(session: &Session, config: &DaemonConfig) -> Option<u32> {
+    Some(session.inject_rate_limit.unwrap_or(config.inject_rate_limit)).filter(|limit| *limit > 0)
+}
+
+/// Whole seconds to wait before retrying, at least one
+pub fn retry_after_seconds(wait: Duration) -> u64 {
+    (wait.as_millis().div_ceil(1000) as u64).max(1)
+}
+
+/// Alert the first time a session refuses an injection: journal it, run
+/// `attention_command` and the `throttled` lifecycle hooks
+pub fn alert(session: &mut Session, config: &DaemonConfig, limit: u32) {
+    if session.throttled_at.is_some() {
+        return;
+    }
+    warn!(
+        "Session {} got more than {} injections in a minute; refusing the rest",
+        session.session_id, limit
+    );
+    session.throttled_at = Some(Utc::now());
+    events::record(session, TimelineKind::Throttled, format!("over {} injections/min", limit));
+    storage::save(session).ok();
+    attention::notify_throttled(session, config, limit);
+    lifecycle::fire(session, LifecycleEvent::Throttled);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_admit_slides_the_window() {
+        let limiter = InjectLimiter::default();
+        let start = Instant::now();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+
+        for second in 0..3 {
+            assert_eq!(limiter.admit("s1", Some(3), at(second)), Ok(()));
+        }
+        assert_eq!(limiter.admit("s1", Some(3), at(10)), Err(Duration::from_secs(50)));
+        // Other sessions have their own window
+        assert_eq!(limiter.admit("s2", Some(3), at(10)), Ok(()));
+        assert_eq!(limiter.rate("s1", at(10)), 3);
+
+        // The first injection leaves the window a minute after it was accepted
+        assert_eq!(limiter.admit("s1", Some(3), at(60)), Ok(()));
+        assert_eq!(limiter.admit("s1", Some(3), at(60)), Err(Duration::from_secs(1)));
+        assert_eq!(limiter.rate("s1", at(60)), 3);
+
+        // No limit: everything is admitted, and still counted
+        for _ in 0..10 {
+            assert_eq!(limiter.admit("s3", None, at(0)), Ok(()));
+        }
+        assert_eq!(limiter.rate("s3", at(30)), 10);
+        assert_eq!(limiter.rate("s3", at(61)), 0);
+
+        // A released injection frees its place in the window
+        limiter.release("s1", at(60));
+        assert_eq!(limiter.rate("s1", at(60)), 2);
+        assert_eq!(limiter.admit("s1", Some(3), at(60)), Ok(()));
+        limiter.release("unknown", at(60));
+    }
+
+    #[test]
+    fn test_limit_and_first_alert() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = DaemonConfig::with_base_dir(temp_dir.path());
+        let workdir = temp_dir.path().join("thr001");
+        crate::init::create_session_structure(&workdir).unwrap();
+        let mut session = Session {
+            session_id: "thr001".to_string(),
+            tmux_session: "summ-thr001".to_string(),
+            workdir,
+            ..Default::default()
+        };
+
+        assert_eq!(limit(&session, &config), Some(30));
+        session.inject_rate_limit = Some(0);
+        assert_eq!(limit(&session, &config), None);
+        session.inject_rate_limit = Some(5);
+        assert_eq!(limit(&session, &config), Some(5));
+        assert_eq!(retry_after_seconds(Duration::from_millis(200)), 1);
+        assert_eq!(retry_after_seconds(Duration::from_millis(12_300)), 13);
+
+        alert(&mut session, &config, 5);
+        let first = session.throttled_at.unwrap();
+        alert(&mut session, &config, 5);
+        assert_eq!(session.throttled_at, Some(first));
+        let journal = events::read(&session);
+        assert_eq!(journal.len(), 1);
+        assert_eq!(journal[0].kind, TimelineKind::Throttled);
+        assert_eq!(journal[0].detail, "over 5 injections/min");
+    }
+}
@@ -1,10 +1,54 @@
 // summ-daemon/src/tmux.rs
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::process::Command;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use summ_common::DaemonError;
 
-const MIN_TMUX_VERSION: (u32, u32) = (3, 0);
 const SUMM_SESSION_PREFIX: &str = "summ-";
+/// tmux user option set on every session the daemon creates, so it can tell
+/// its own sessions from a user's that happen to share the name
+const OWNER_OPTION: &str = "@summ_owned";
+/// tmux user option holding the summ session ID of a daemon-created session
+const SESSION_ID_OPTION: &str = "@summ_session_id";
+/// tmux user option holding the PID of the daemon that created (or adopted) it
+const DAEMON_PID_OPTION: &str = "@summ_daemon_pid";
+/// Fields read by `list_summ_sessions`: the session ID option first, which
+/// is empty on sessions the daemon did not mark
+const LIST_FORMAT: &str = "#{@summ_session_id} #{session_name}";
+/// File in a session's runtime directory where the pane's shell writes the
+/// CLI's exit status once it exits on its own. A killed tmux session takes
+/// the shell with it, so the file is never written then.
+pub const EXIT_CODE_FILE: &str = "exit_code";
+/// Most history lines a single pane capture may request
+pub const MAX_CAPTURE_LINES: i32 = 50_000;
+/// Per-window fields read by `window_states`, space-separated: tmux prints
+/// tabs as `_` when the daemon runs without a UTF-8 locale. The session name
+/// (which may contain spaces) sits between fields that cannot.
+const WINDOWS_FORMAT: &str = "#{window_active} #{pane_current_command} #{session_name} \
+     #{window_activity} #{window_activity_flag} #{window_bell_flag}";
+
+/// Activity, alert flags and foreground command of a tmux session's windows.
+/// A field is `None` when the running tmux does not know its format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowState {
+    /// Latest output in any window, in Unix seconds
+    pub activity: Option<i64>,
+    /// A window produced output since its flags were last cleared
+    pub activity_flag: Option<bool>,
+    /// A window rang the bell since its flags were last cleared
+    pub bell_flag: Option<bool>,
+    /// Foreground command of the active window's active pane (e.g. `node`)
+    pub current_command: Option<String>,
+}
+
+/// A live tmux session created by the daemon, or named like one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummTmuxSession {
+    pub name: String,
+    /// `@summ_session_id`; None on sessions the daemon did not mark
+    pub session_id: Option<String>,
+}
 
 pub struct TmuxManager;
 
@@ -17,70 +61,88 @@ impl TmuxManager {
             .context("tmux not found. Please install tmux 3.0 or later")?;
 
         let version_str = String::from_utf8_lossy(&output.stdout);
-        Self::parse_version(&version_str)?;
+        summ_common::tmux::check_tmux_version(&version_str)?;
 
         Ok(())
     }
 
-    fn parse_version(version_str: &str) -> Result<(u32, u32)> {
-        let parts: Vec<&str> = version_str
-            .trim()
-            .split_whitespace()
-            .collect();
-
-        if parts.len() < 2 {
-            anyhow::bail!("Invalid tmux version output: {}", version_str);
+    /// Create a detached session for summ session `session_id`, marked with
+    /// the identity options and running `command` with `SUMM_SESSION_ID` and
+    /// `SUMM_RUNTIME_DIR` exported, after sourcing and deleting `secrets_file`
+    /// if given. Fails with `E005` naming the session when one of that name
+    /// already exists.
+    #[tracing::instrument(skip_all, fields(session = %session_name))]
+    pub fn create_session(
+        session_name: &str,
+        workdir: &Path,
+        command: &str,
+        session_id: &str,
+        runtime_dir: &Path,
+        secrets_file: Option<&Path>,
+    ) -> Result<()> {
+        if Self::session_exists(session_name) {
+            return Err(DaemonError::e005(format!(
+                "tmux session {} already exists and was not created for this session; not reusing it",
+                session_name
+            ))
+            .into());
         }
-
-        let version_part = parts[1];
-        let version_numbers: Vec<&str> = version_part
-            .split('.')
-            .take(2)
-            .collect();
-
-        if version_numbers.len() < 2 {
-            anyhow::bail!("Invalid tmux version format: {}", version_part);
+        let status = Command::new("tmux")
+            .args(new_session_args(session_name, workdir, command, session_id, runtime_dir, secrets_file)?)
+            .status()
+            .context("Failed to create tmux session")?;
+        if !status.success() {
+            anyhow::bail!("tmux new-session failed with status: {}", status);
         }
+        Ok(())
+    }
 
-        let major: u32 = version_numbers[0]
-            .chars()
-            .take_while(|c| c.is_ascii_digit())
-            .collect::<String>()
-            .parse()
-            .unwrap_or(0);
-
-        let minor: u32 = version_numbers[1]
-            .chars()
-            .take_while(|c| c.is_ascii_digit())
-            .collect::<String>()
-            .parse()
-            .unwrap_or(0);
-
-        if (major, minor) < MIN_TMUX_VERSION {
-            anyhow::bail!(
-                "tmux version {}.{} is below minimum required {}.{}",
-                major, minor, MIN_TMUX_VERSION.0, MIN_TMUX_VERSION.1
-            );
+    /// Turn on `monitor-activity` and `monitor-bell` so tmux flags the session's
+    /// window when it produces output or rings the bell
+    pub fn enable_monitoring(session_name: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(["set-option", "-w", "-t", session_name, "monitor-activity", "on"])
+            .args([";", "set-option", "-w", "-t", session_name, "monitor-bell", "on"])
+            .status()
+            .context("Failed to enable activity monitoring")?;
+        if !status.success() {
+            anyhow::bail!("tmux set-option monitor-activity/monitor-bell failed");
         }
-
-        Ok((major, minor))
+        Ok(())
     }
 
-    pub fn create_session(session_name: &str, workdir: &Path, command: &str) -> Result<()> {
-        let workdir_str = workdir.to_str().context("Workdir contains invalid UTF-8")?;
+    /// Clear the bell and activity flags of the session's windows
+    /// (`kill-session -C` only clears alerts; it leaves the session running)
+    pub fn clear_alerts(session_name: &str) -> Result<()> {
         let status = Command::new("tmux")
-            .args(["new-session", "-d", "-s", session_name, "-c", workdir_str, command])
+            .args(["kill-session", "-C", "-t", session_name])
             .status()
-            .context("Failed to create tmux session")?;
+            .context("Failed to clear tmux alerts")?;
         if !status.success() {
-            anyhow::bail!("tmux new-session failed with status: {}", status);
+            anyhow::bail!("tmux kill-session -C failed");
         }
         Ok(())
     }
 
+    /// Activity, alert flags and foreground command of every summ session,
+    /// keyed by tmux session name, from a single `list-windows -a`; empty
+    /// without a tmux server
+    pub fn window_states() -> Result<HashMap<String, WindowState>> {
+        let output = Command::new("tmux")
+            .args(["list-windows", "-a", "-F", WINDOWS_FORMAT])
+            .output()
+            .context("Failed to list tmux windows")?;
+        if !output.status.success() {
+            return Ok(HashMap::new());
+        }
+        Ok(parse_window_states(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Whether a session of exactly this name exists (`-t` alone would also
+    /// match a longer name starting with it)
     pub fn session_exists(session_name: &str) -> bool {
         Command::new("tmux")
-            .args(["has-session", "-t", session_name])
+            .args(["has-session", "-t", &format!("={}", session_name)])
             .status()
             .map(|s| s.success())
             .unwrap_or(false)
@@ -98,6 +160,7 @@ impl TmuxManager {
         Ok(pid_str.trim().parse().ok())
     }
 
+    #[tracing::instrument(skip_all, fields(session = %session_name))]
     pub fn send_keys(session_name: &str, message: &str, press_enter: bool) -> Result<()> {
         let mut args = vec!["send-keys", "-t", session_name, message];
         if press_enter {
@@ -111,8 +174,89 @@ impl TmuxManager {
         Ok(())
     }
 
+    /// Send text verbatim (tmux `send-keys -l`), without interpreting key names
+    #[tracing::instrument(skip_all, fields(session = %session_name))]
+    pub fn send_literal(session_name: &str, text: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(["send-keys", "-t", session_name, "-l", text])
+            .status()
+            .context("Failed to send literal text to tmux session")?;
+        if !status.success() {
+            anyhow::bail!("tmux send-keys -l failed");
+        }
+        Ok(())
+    }
+
+    /// Send tmux key names (e.g. `Enter`, `C-c`) to the session
+    #[tracing::instrument(skip_all, fields(session = %session_name))]
+    pub fn send_key_names(session_name: &str, keys: &[&str]) -> Result<()> {
+        let mut args = vec!["send-keys", "-t", session_name];
+        args.extend_from_slice(keys);
+        let status = Command::new("tmux").args(&args).status()
+            .context("Failed to send keys to tmux session")?;
+        if !status.success() {
+            anyhow::bail!("tmux send-keys failed");
+        }
+        Ok(())
+    }
+
+    /// Read a format of exactly this session; empty when it does not exist
+    fn session_format(session_name: &str, format: &str) -> String {
+        Command::new("tmux")
+            .args(["display-message", "-p", "-t", &format!("={}:", session_name), format])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Whether the session carries the marker the daemon sets on sessions it creates
+    pub fn is_owned(session_name: &str) -> bool {
+        Self::session_format(session_name, &format!("#{{{}}}", OWNER_OPTION)) == "1"
+    }
+
+    /// Directory the session was started in
+    pub fn session_path(session_name: &str) -> Option<PathBuf> {
+        Some(Self::session_format(session_name, "#{session_path}"))
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// Set the identity options on a session started before the daemon set
+    /// them, claiming it for summ session `session_id`
+    pub fn adopt(session_name: &str, session_id: &str) -> Result<()> {
+        let status = Command::new("tmux")
+            .args(identity_args(session_name, session_id))
+            .status()
+            .context("Failed to mark tmux session")?;
+        if !status.success() {
+            anyhow::bail!("tmux set-option {} failed", SESSION_ID_OPTION);
+        }
+        Ok(())
+    }
+
+    /// Refuse to touch a session the daemon did not create, even when its
+    /// metadata names it
+    pub fn ensure_owned(session_name: &str) -> Result<()> {
+        if Self::is_owned(session_name) {
+            return Ok(());
+        }
+        if Self::session_exists(session_name) {
+            anyhow::bail!(
+                "tmux session {} was not created by summ (no {} marker); leaving it alone",
+                session_name,
+                OWNER_OPTION
+            );
+        }
+        anyhow::bail!("tmux session {} no longer exists", session_name)
+    }
+
+    /// Kill a session the daemon created; others are refused by `ensure_owned`
+    #[tracing::instrument(skip_all, fields(session = %session_name))]
     pub fn kill_session(session_name: &str) -> Result<()> {
-        let status = Command::new("tmux").args(["kill-session", "-t", session_name]).status()
+        Self::ensure_owned(session_name)?;
+        let status = Command::new("tmux").args(["kill-session", "-t", &format!("={}", session_name)]).status()
             .context("Failed to kill tmux session")?;
         if !status.success() {
             anyhow::bail!("tmux kill-session failed");
@@ -120,18 +264,20 @@ impl TmuxManager {
         Ok(())
     }
 
-    pub fn list_summ_sessions() -> Result<Vec<String>> {
+    /// Sessions carrying `@summ_session_id`, plus unmarked ones with the
+    /// `summ-` prefix (started before the option existed, or a user's)
+    pub fn list_summ_sessions() -> Result<Vec<SummTmuxSession>> {
         let output = Command::new("tmux")
-            .args(["list-sessions", "-F", "#{session_name}"])
+            .args(["list-sessions", "-F", LIST_FORMAT])
             .output()
             .context("Failed to list tmux sessions")?;
         if !output.status.success() {
             return Ok(vec![]);
         }
-        Ok(String::from_utf8_lossy(&output.stdout)
-            .lines().filter(|n| n.starts_with(SUMM_SESSION_PREFIX)).map(|s| s.to_string()).collect())
+        Ok(parse_summ_sessions(&String::from_utf8_lossy(&output.stdout)))
     }
 
+    #[tracing::instrument(skip_all, fields(session = %session_name))]
     pub fn enable_logging(session_name: &str, log_path: &Path) -> Result<()> {
         let log_path_str = log_path.to_str().context("Log path contains invalid UTF-8")?;
         let status = Command::new("tmux")
@@ -144,46 +290,234 @@ impl TmuxManager {
         Ok(())
     }
 
+    /// Capture the last `lines` of pane history, clamped to `MAX_CAPTURE_LINES`
+    #[tracing::instrument(skip_all, fields(session = %session_name))]
     pub fn capture_pane(session_name: &str, lines: i32) -> Result<String> {
+        let start = -lines.clamp(0, MAX_CAPTURE_LINES);
         let output = Command::new("tmux")
-            .args(["capture-pane", "-t", session_name, "-p", "-S", &(-lines).to_string()])
+            .args(["capture-pane", "-t", session_name, "-p", "-S", &start.to_string()])
             .output()
             .context("Failed to capture pane")?;
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 }
 
+/// `set-option` commands, separated by `;`, marking `session_name` as the
+/// daemon's, for summ session `session_id`
+fn identity_args(session_name: &str, session_id: &str) -> Vec<String> {
+    let pid = std::process::id().to_string();
+    let mut args = Vec::new();
+    for (option, value) in [(OWNER_OPTION, "1"), (SESSION_ID_OPTION, session_id), (DAEMON_PID_OPTION, &pid)] {
+        if !args.is_empty() {
+            args.push(";".to_string());
+        }
+        args.extend(["set-option", "-t", session_name, option, value].map(str::to_string));
+    }
+    args
+}
+
+/// Arguments creating a session and marking it in one tmux command, so no
+/// window exists where it is unmarked. The environment is exported by the
+/// shell tmux runs the command in (`new-session -e` needs tmux 3.2), and set
+/// on the session for windows opened later. Secrets are read from a file
+/// instead, so their values never appear in any process's arguments; only
+/// the first window gets them.
+fn new_session_args(
+    session_name: &str,
+    workdir: &Path,
+    command: &str,
+    session_id: &str,
+    runtime_dir: &Path,
+    secrets_file: Option<&Path>,
+) -> Result<Vec<String>> {
+    let workdir = workdir.to_str().context("Workdir contains invalid UTF-8")?;
+    let runtime_dir = runtime_dir.to_str().context("Runtime directory contains invalid UTF-8")?;
+    let secrets = match secrets_file {
+        Some(path) => {
+            let path = shell_quote(path.to_str().context("Secrets file path contains invalid UTF-8")?);
+            format!(". {}; rm -f {}; ", path, path)
+        }
+        None => String::new(),
+    };
+    // The newline ends a trailing comment in `command` before the exit status is written
+    let command = format!(
+        "{}export SUMM_SESSION_ID={} SUMM_RUNTIME_DIR={}; {}\necho $? > {}/{}",
+        secrets,
+        shell_quote(session_id),
+        shell_quote(runtime_dir),
+        command,
+        shell_quote(runtime_dir),
+        EXIT_CODE_FILE
+    );
+    let mut args: Vec<String> = ["new-session", "-d", "-s", session_name, "-c", workdir, &command]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    args.push(";".to_string());
+    args.extend(identity_args(session_name, session_id));
+    for (name, value) in [("SUMM_SESSION_ID", session_id), ("SUMM_RUNTIME_DIR", runtime_dir)] {
+        args.extend([";", "set-environment", "-t", session_name, name, value].map(str::to_string));
+    }
+    Ok(args)
+}
+
+/// Quote a value for `sh`
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Parse `LIST_FORMAT` lines into the sessions `list_summ_sessions` reports
+fn parse_summ_sessions(output: &str) -> Vec<SummTmuxSession> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (session_id, name) = line.split_once(' ')?;
+            let session_id = Some(session_id.to_string()).filter(|id| !id.is_empty());
+            (session_id.is_some() || name.starts_with(SUMM_SESSION_PREFIX))
+                .then(|| SummTmuxSession { name: name.to_string(), session_id })
+        })
+        .collect()
+}
+
+/// Parse `WINDOWS_FORMAT` lines, merging the windows of each summ session.
+/// Formats tmux does not know expand to nothing and are left unset. The
+/// fields are split from the right, so session names may contain spaces.
+fn parse_window_states(output: &str) -> HashMap<String, WindowState> {
+    let flag = |field: Option<&str>| match field {
+        Some("1") => Some(true),
+        Some("0") => Some(false),
+        _ => None,
+    };
+    let mut alerts: HashMap<String, WindowState> = HashMap::new();
+    for line in output.lines() {
+        let mut leading = line.splitn(3, ' ');
+        let active = leading.next() == Some("1");
+        let command = leading.next().filter(|command| !command.is_empty());
+        let mut fields = leading.next().unwrap_or_default().rsplitn(4, ' ');
+        let bell_flag = flag(fields.next());
+        let activity_flag = flag(fields.next());
+        let activity = fields.next().and_then(|field| field.parse().ok());
+        let Some(name) = fields.next().filter(|name| name.starts_with(SUMM_SESSION_PREFIX)) else {
+            continue;
+        };
+
+        let merged = alerts.entry(name.to_string()).or_default();
+        merged.activity = merged.activity.max(activity);
+        merged.activity_flag = merged.activity_flag.max(activity_flag);
+        merged.bell_flag = merged.bell_flag.max(bell_flag);
+        if active {
+            merged.current_command = command.map(str::to_string);
+        }
+    }
+    alerts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_version() {
-        let result = TmuxManager::parse_version("tmux 3.3a");
-        assert!(result.is_ok());
-        let (major, minor) = result.unwrap();
-        assert_eq!(major, 3);
-        assert_eq!(minor, 3);
+    fn test_tmux_session_name_generation() {
+        let session_id = "session_001";
+        let tmux_name = format!("summ-{}", session_id);
+        assert_eq!(tmux_name, "summ-session_001");
+    }
+
+    #[test]
+    fn test_parse_window_states() {
+        let output = "0 vim summ-a 1760000000 0 1\n\
+                      1 pytest summ-a 1760000100 1 0\n\
+                      1 claude summ-b 1760000050 0 0\n\
+                      1 node summ-my notes 1760000060 0 1\n\
+                      1 bash work 1760000200 1 1\n";
+        let alerts = parse_window_states(output);
+        assert_eq!(alerts.len(), 3);
+        assert_eq!(
+            alerts["summ-a"],
+            WindowState {
+                activity: Some(1_760_000_100),
+                activity_flag: Some(true),
+                bell_flag: Some(true),
+                current_command: Some("pytest".to_string()),
+            }
+        );
+        assert_eq!(alerts["summ-b"].bell_flag, Some(false));
+        assert_eq!(alerts["summ-my notes"].bell_flag, Some(true));
+        assert_eq!(alerts["summ-my notes"].current_command.as_deref(), Some("node"));
     }
 
     #[test]
-    fn test_parse_version_old() {
-        // Versions below 3.0 should fail
-        let result = TmuxManager::parse_version("tmux 2.9");
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("below minimum required"));
+    fn test_parse_window_states_unknown_formats() {
+        // An older tmux expands unknown formats to empty strings
+        let alerts = parse_window_states("1  summ-a   \n1  summ-b 1760000000  \n");
+        assert_eq!(alerts["summ-a"], WindowState::default());
+        assert_eq!(alerts["summ-b"].activity, Some(1_760_000_000));
+        assert_eq!(alerts["summ-b"].bell_flag, None);
     }
 
     #[test]
-    fn test_parse_version_invalid() {
-        let result = TmuxManager::parse_version("invalid");
-        assert!(result.is_err());
+    fn test_new_session_sets_identity_and_environment() {
+        let args = new_session_args(
+            "summ-session_ab12cd34",
+            Path::new("/s/session_ab12cd34/workspace"),
+            "claude --verbose",
+            "session_ab12cd34",
+            Path::new("/s/it's/runtime"),
+            None,
+        )
+        .unwrap();
+        let commands: Vec<&[String]> = args.split(|arg| arg == ";").collect();
+        assert_eq!(&commands[0][..6], ["new-session", "-d", "-s", "summ-session_ab12cd34", "-c", "/s/session_ab12cd34/workspace"]);
+        assert_eq!(
+            commands[0][6],
+            "export SUMM_SESSION_ID='session_ab12cd34' SUMM_RUNTIME_DIR='/s/it'\\''s/runtime'; claude --verbose\n\
+             echo $? > '/s/it'\\''s/runtime'/exit_code"
+        );
+        let pid = std::process::id().to_string();
+        for (option, value) in [("@summ_owned", "1"), ("@summ_session_id", "session_ab12cd34"), ("@summ_daemon_pid", &pid)] {
+            assert!(
+                commands.contains(&&["set-option", "-t", "summ-session_ab12cd34", option, value].map(String::from)[..]),
+                "{} not set in {:?}",
+                option,
+                args
+            );
+        }
+        assert!(commands.iter().any(|command| command[0] == "set-environment" && command[3] == "SUMM_RUNTIME_DIR"));
+
+        // Secrets come from the file, sourced before anything else runs
+        let args = new_session_args(
+            "summ-session_ab12cd34",
+            Path::new("/s/session_ab12cd34/workspace"),
+            "claude",
+            "session_ab12cd34",
+            Path::new("/s/session_ab12cd34/runtime"),
+            Some(Path::new("/s/session_ab12cd34/runtime/secrets.env")),
+        )
+        .unwrap();
+        assert_eq!(
+            args[6],
+            ". '/s/session_ab12cd34/runtime/secrets.env'; rm -f '/s/session_ab12cd34/runtime/secrets.env'; \
+             export SUMM_SESSION_ID='session_ab12cd34' SUMM_RUNTIME_DIR='/s/session_ab12cd34/runtime'; claude\n\
+             echo $? > '/s/session_ab12cd34/runtime'/exit_code"
+        );
+        assert!(!args.iter().any(|arg| arg.contains("SECRET")));
     }
 
     #[test]
-    fn test_tmux_session_name_generation() {
-        let session_id = "session_001";
-        let tmux_name = format!("summ-{}", session_id);
-        assert_eq!(tmux_name, "summ-session_001");
+    fn test_parse_summ_sessions() {
+        let sessions = parse_summ_sessions(
+            "session_ab12cd34 summ-session_ab12cd34\n summ-legacy\n work\nsession_x my project\n",
+        );
+        assert_eq!(
+            sessions,
+            vec![
+                SummTmuxSession {
+                    name: "summ-session_ab12cd34".to_string(),
+                    session_id: Some("session_ab12cd34".to_string()),
+                },
+                SummTmuxSession { name: "summ-legacy".to_string(), session_id: None },
+                SummTmuxSession { name: "my project".to_string(), session_id: Some("session_x".to_string()) },
+            ]
+        );
     }
 }
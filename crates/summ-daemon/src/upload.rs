@@ -0,0 +1,239 @@
+// summ-daemon/src/upload.rs
+// Files sent into a session's workspace (`summ send-file`), written chunk by chunk
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use summ_common::DaemonError;
+
+/// Directory of a session's runtime directory holding uploads in progress
+pub const UPLOADS_DIR: &str = "uploads";
+
+/// One `Request::PutFile` chunk, its destination already validated with
+/// `validate_workspace_file`
+#[derive(Debug)]
+pub struct Chunk<'a> {
+    pub dest: &'a Path,
+    pub data: &'a [u8],
+    pub offset: u64,
+    pub done: bool,
+    pub overwrite: bool,
+}
+
+/// Where an upload stands after a chunk
+#[derive(Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Absolute path of the file in the workspace
+    pub path: PathBuf,
+    /// Bytes written so far (the file's size once done)
+    pub bytes: u64,
+}
+
+/// Write a chunk of an upload into `runtime/uploads`, and move the file into
+/// `workspace` with its last chunk. A file is never seen half written in the
+/// workspace, and an upload abandoned halfway leaves nothing there.
+pub fn write_chunk(workspace: &Path, runtime: &Path, chunk: &Chunk) -> Result<Progress, DaemonError> {
+    let target = workspace.join(chunk.dest);
+    let shown = chunk.dest.display();
+    if (chunk.offset == 0 || chunk.done) && !chunk.overwrite && target.symlink_metadata().is_ok() {
+        return Err(DaemonError::e010(format!(
+            "{} already exists in the workspace; pass overwrite to replace it",
+            shown
+        )));
+    }
+    if target.is_dir() {
+        return Err(DaemonError::e010(format!("{} is a directory in the workspace", shown)));
+    }
+
+    let uploads = runtime.join(UPLOADS_DIR);
+    fs::create_dir_all(&uploads).map_err(|e| write_error(&uploads, e))?;
+    let part = uploads.join(part_name(chunk.dest));
+    let mut file = if chunk.offset == 0 {
+        File::create(&part).map_err(|e| write_error(&part, e))?
+    } else {
+        let written = fs::metadata(&part).map(|metadata| metadata.len()).map_err(|_| {
+            DaemonError::e010(format!(
+                "No upload of {} is in progress; start again at offset 0",
+                shown
+            ))
+        })?;
+        if written != chunk.offset {
+            return Err(DaemonError::e010(format!(
+                "Chunk at offset {} does not continue the upload of {} ({} bytes so far)",
+                chunk.offset, shown, written
+            )));
+        }
+        OpenOptions::new().append(true).open(&part).map_err(|e| write_error(&part, e))?
+    };
+    file.write_all(chunk.data).map_err(|e| write_error(&part, e))?;
+    let bytes = chunk.offset + chunk.data.len() as u64;
+    if !chunk.done {
+        return Ok(Progress { path: target, bytes });
+    }
+    file.sync_all().map_err(|e| write_error(&part, e))?;
+    drop(file);
+
+    let path = place(workspace, &target, chunk.dest).inspect_err(|_| {
+        let _ = fs::remove_file(&part);
+    })?;
+    fs::rename(&part, &path).map_err(|e| write_error(&path, e))?;
+    Ok(Progress { path, bytes })
+}
+
+/// Create the destination's directories and resolve them, refusing ones
+/// that symlinks take out of the workspace. The deepest part that exists is
+/// checked before anything is created, so a symlink never gets directories
+/// made outside the workspace.
+fn place(workspace: &Path, target: &Path, dest: &Path) -> Result<PathBuf, DaemonError> {
+    let (Some(parent), Some(file_name)) = (target.parent(), target.file_name()) else {
+        return Err(DaemonError::e010(format!("Invalid destination {}", dest.display())));
+    };
+    let root = fs::canonicalize(workspace).map_err(|e| write_error(workspace, e))?;
+    let inside = |path: &Path| -> Result<PathBuf, DaemonError> {
+        let resolved = fs::canonicalize(path).map_err(|e| write_error(path, e))?;
+        if !resolved.starts_with(&root) {
+            return Err(DaemonError::e010(format!(
+                "Destination {} leaves the workspace through a symlink",
+                dest.display()
+            )));
+        }
+        Ok(resolved)
+    };
+    // A dangling symlink counts as existing: resolving it fails rather than
+    // creating what it points to
+    let existing = parent
+        .ancestors()
+        .find(|ancestor| ancestor.symlink_metadata().is_ok())
+        .unwrap_or(workspace);
+    inside(existing)?;
+    fs::create_dir_all(parent).map_err(|e| write_error(parent, e))?;
+    Ok(inside(parent)?.join(file_name))
+}
+
+/// Name of the file an upload to `dest` is assembled in: a hash of its
+/// components, so no two destinations share one
+fn part_name(dest: &Path) -> String {
+    let mut hasher = Sha256::new();
+    for component in dest.components() {
+        hasher.update(component.as_os_str().as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}.part", hasher.finalize())
+}
+
+fn write_error(path: &Path, error: std::io::Error) -> DaemonError {
+    DaemonError::e016(format!("Failed to write {}: {}", path.display(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    struct Dirs {
+        _temp_dir: TempDir,
+        workspace: PathBuf,
+        runtime: PathBuf,
+    }
+
+    fn dirs() -> Dirs {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace = temp_dir.path().join("workspace");
+        let runtime = temp_dir.path().join("runtime");
+        fs::create_dir_all(&workspace).unwrap();
+        fs::create_dir_all(&runtime).unwrap();
+        Dirs {
+            _temp_dir: temp_dir,
+            workspace,
+            runtime,
+        }
+    }
+
+    fn chunk<'a>(dest: &'a str, data: &'a [u8], offset: u64, done: bool) -> Chunk<'a> {
+        Chunk {
+            dest: Path::new(dest),
+            data,
+            offset,
+            done,
+            overwrite: false,
+        }
+    }
+
+    #[test]
+    fn test_write_in_chunks() {
+        let dirs = dirs();
+        let progress = write_chunk(&dirs.workspace, &dirs.runtime, &chunk("docs/spec.md", b"# Spec\n", 0, false)).unwrap();
+        assert_eq!(progress.bytes, 7);
+        assert!(!dirs.workspace.join("docs").exists(), "nothing is placed before the last chunk");
+
+        // A chunk that does not continue the upload is refused
+        let error = write_chunk(&dirs.workspace, &dirs.runtime, &chunk("docs/spec.md", b"x", 3, true)).unwrap_err();
+        assert_eq!(error.code.code(), "E010");
+
+        let progress = write_chunk(&dirs.workspace, &dirs.runtime, &chunk("docs/spec.md", b"Body\n", 7, true)).unwrap();
+        assert_eq!(progress.bytes, 12);
+        assert_eq!(progress.path, fs::canonicalize(&dirs.workspace).unwrap().join("docs/spec.md"));
+        assert_eq!(fs::read_to_string(&progress.path).unwrap(), "# Spec\nBody\n");
+        assert_eq!(fs::read_dir(dirs.runtime.join(UPLOADS_DIR)).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_existing_file_needs_overwrite() {
+        let dirs = dirs();
+        fs::write(dirs.workspace.join("notes.txt"), "old").unwrap();
+        let error = write_chunk(&dirs.workspace, &dirs.runtime, &chunk("notes.txt", b"new", 0, true)).unwrap_err();
+        assert_eq!(error.message, "notes.txt already exists in the workspace; pass overwrite to replace it");
+
+        let overwrite = Chunk {
+            overwrite: true,
+            ..chunk("notes.txt", b"new", 0, true)
+        };
+        write_chunk(&dirs.workspace, &dirs.runtime, &overwrite).unwrap();
+        assert_eq!(fs::read_to_string(dirs.workspace.join("notes.txt")).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_symlinked_directory_outside_workspace_is_refused() {
+        let dirs = dirs();
+        let outside = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dirs.workspace.join("out")).unwrap();
+
+        let error = write_chunk(&dirs.workspace, &dirs.runtime, &chunk("out/secret.txt", b"x", 0, true)).unwrap_err();
+        assert_eq!(error.code.code(), "E010");
+        assert!(!outside.path().join("secret.txt").exists());
+        assert_eq!(fs::read_dir(dirs.runtime.join(UPLOADS_DIR)).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_symlinked_directory_is_checked_before_creating_directories() {
+        let dirs = dirs();
+        let outside = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dirs.workspace.join("link")).unwrap();
+
+        let error =
+            write_chunk(&dirs.workspace, &dirs.runtime, &chunk("link/new/dir/file.txt", b"x", 0, true)).unwrap_err();
+        assert_eq!(error.code.code(), "E010");
+        assert_eq!(fs::read_dir(outside.path()).unwrap().count(), 0, "nothing is created outside the workspace");
+
+        // Directories under a symlink that stays inside are still created
+        fs::create_dir(dirs.workspace.join("real")).unwrap();
+        std::os::unix::fs::symlink(dirs.workspace.join("real"), dirs.workspace.join("alias")).unwrap();
+        write_chunk(&dirs.workspace, &dirs.runtime, &chunk("alias/new/file.txt", b"x", 0, true)).unwrap();
+        assert!(dirs.workspace.join("real/new/file.txt").is_file());
+    }
+
+    #[test]
+    fn test_uploads_to_similar_paths_do_not_share_a_part_file() {
+        assert_ne!(part_name(Path::new("a/b")), part_name(Path::new("a%b")));
+
+        let dirs = dirs();
+        write_chunk(&dirs.workspace, &dirs.runtime, &chunk("a/b", b"first ", 0, false)).unwrap();
+        // Another upload starting between two chunks leaves the first alone
+        write_chunk(&dirs.workspace, &dirs.runtime, &chunk("a%b", b"other", 0, false)).unwrap();
+        write_chunk(&dirs.workspace, &dirs.runtime, &chunk("a/b", b"second", 6, true)).unwrap();
+        write_chunk(&dirs.workspace, &dirs.runtime, &chunk("a%b", b" file", 5, true)).unwrap();
+        assert_eq!(fs::read_to_string(dirs.workspace.join("a/b")).unwrap(), "first second");
+        assert_eq!(fs::read_to_string(dirs.workspace.join("a%b")).unwrap(), "other file");
+    }
+}
@@ -0,0 +1,288 @@
+// summ-daemon/src/usage.rs
+// Token counts and cost estimates from CLI conversation transcripts (Claude Code JSONL)
+//
+// Strictly best effort: unreadable transcripts and entries that do not look like
+// usage records are skipped, and a session without a transcript keeps null fields.
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use summ_common::{DaemonConfig, ModelPrice, Session};
+
+use crate::session::SessionExt;
+
+/// Tokens used by one model (the `usage` object of an assistant message)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct TokenCounts {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+}
+
+impl TokenCounts {
+    fn add(&mut self, other: &TokenCounts) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+    }
+
+    /// All input tokens, cached or not
+    fn total_input(&self) -> u64 {
+        self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens
+    }
+
+    /// Cost in USD at `price`
+    fn cost(&self, price: &ModelPrice) -> f64 {
+        let per_token = |per_mtok: f64| per_mtok / 1_000_000.0;
+        self.input_tokens as f64 * per_token(price.input_per_mtok)
+            + self.output_tokens as f64 * per_token(price.output_per_mtok)
+            + self.cache_creation_input_tokens as f64
+                * per_token(price.cache_write_per_mtok.unwrap_or(price.input_per_mtok))
+            + self.cache_read_input_tokens as f64
+                * per_token(price.cache_read_per_mtok.unwrap_or(price.input_per_mtok))
+    }
+}
+
+/// Token usage summed over a transcript, per model
+#[derive(Debug, Default, PartialEq)]
+pub struct TranscriptUsage {
+    pub by_model: HashMap<String, TokenCounts>,
+}
+
+/// The parts of a transcript line we read; everything else is ignored
+#[derive(Deserialize)]
+struct Entry {
+    message: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    id: Option<String>,
+    model: Option<String>,
+    usage: Option<TokenCounts>,
+}
+
+impl TranscriptUsage {
+    /// Input tokens over all models, including cache reads and writes
+    pub fn tokens_in(&self) -> u64 {
+        self.by_model.values().map(TokenCounts::total_input).sum()
+    }
+
+    /// Output tokens over all models
+    pub fn tokens_out(&self) -> u64 {
+        self.by_model.values().map(|counts| counts.output_tokens).sum()
+    }
+
+    /// Estimated cost in USD, or `None` when a model that used tokens has no price
+    pub fn estimated_cost(&self, config: &DaemonConfig) -> Option<f64> {
+        if config.model_prices.is_empty() {
+            return None;
+        }
+        self.by_model
+            .iter()
+            .filter(|(_, counts)| **counts != TokenCounts::default())
+            .map(|(model, counts)| config.model_price(model).map(|price| counts.cost(price)))
+            .sum()
+    }
+}
+
+/// Sum the usage records in a transcript, skipping lines it does not understand
+pub fn parse_transcript(reader: impl BufRead) -> TranscriptUsage {
+    // A response is logged once per content block, each line repeating its usage,
+    // so keep the last record per message id
+    let mut messages: HashMap<String, (String, TokenCounts)> = HashMap::new();
+    for (line_number, line) in reader.lines().map_while(Result::ok).enumerate() {
+        let Ok(Entry { message: Some(message) }) = serde_json::from_str::<Entry>(&line) else {
+            continue;
+        };
+        let Some(counts) = message.usage else {
+            continue;
+        };
+        let model = message.model.unwrap_or_else(|| "unknown".to_string());
+        let key = message.id.unwrap_or_else(|| format!("line {}", line_number));
+        messages.insert(key, (model, counts));
+    }
+
+    let mut usage = TranscriptUsage::default();
+    for (model, counts) in messages.into_values() {
+        usage.by_model.entry(model).or_default().add(&counts);
+    }
+    usage
+}
+
+/// Read and sum a transcript file (blocking)
+pub fn read_transcript(path: &Path) -> Option<TranscriptUsage> {
+    let file = File::open(path).ok()?;
+    Some(parse_transcript(BufReader::new(file)))
+}
+
+/// Size and modification time of a transcript, to skip re-reading unchanged files
+pub type TranscriptSignature = (u64, SystemTime);
+
+fn transcript_signature(path: &Path) -> Option<TranscriptSignature> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+/// Transcripts reported by the sessions' hooks that changed since they were last
+/// seen, as `(session_id, path)`; `seen` is updated and pruned of gone sessions
+pub fn changed_transcripts(
+    sessions: &HashMap<String, Session>,
+    seen: &mut HashMap<String, TranscriptSignature>,
+) -> Vec<(String, PathBuf)> {
+    seen.retain(|id, _| sessions.contains_key(id));
+
+    let mut changed = Vec::new();
+    for session in sessions.values() {
+        let Some(path) = session.read_cli_status().and_then(|status| status.transcript_path)
+        else {
+            continue;
+        };
+        let Some(signature) = transcript_signature(&path) else {
+            continue;
+        };
+        if seen.insert(session.session_id.clone(), signature) != Some(signature) {
+            changed.push((session.session_id.clone(), path));
+        }
+    }
+    changed
+}
+
+/// Store transcript totals on a session; returns whether anything changed
+pub fn apply(session: &mut Session, usage: &TranscriptUsage, config: &DaemonConfig) -> bool {
+    let tokens_in = Some(usage.tokens_in());
+    let tokens_out = Some(usage.tokens_out());
+    let estimated_cost = usage.estimated_cost(config);
+    if session.tokens_in == tokens_in
+        && session.tokens_out == tokens_out
+        && session.estimated_cost == estimated_cost
+    {
+        return false;
+    }
+    session.tokens_in = tokens_in;
+    session.tokens_out = tokens_out;
+    session.estimated_cost = estimated_cost;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const TRANSCRIPT: &str = r#"{"type":"summary","summary":"Fix tests","leafUuid":"x"}
+{"type":"user","message":{"role":"user","content":"fix the tests"}}
+{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4-5-20250929","usage":{"input_tokens":10,"cache_creation_input_tokens":1000,"cache_read_input_tokens":0,"output_tokens":5}}}
+{"type":"assistant","message":{"id":"msg_1","model":"claude-sonnet-4-5-20250929","usage":{"input_tokens":10,"cache_creation_input_tokens":1000,"cache_read_input_tokens":0,"output_tokens":200}}}
+not json at all
+{"type":"assistant","message":{"id":"msg_2","model":"claude-haiku-4-5","usage":{"input_tokens":50,"output_tokens":20,"service_tier":"standard"}}}
+{"type":"assistant","message":{"id":"msg_3","model":"<synthetic>","usage":{"input_tokens":0,"output_tokens":0}}}
+{"type":"assistant","message":"unexpected shape"}
+{"type":"assistant","message":{"id":"msg_4","model":"claude-sonnet-4-5-20250929","usage":{"input_tokens":"many"}}}
+"#;
+
+    fn config_with_prices(prices: &str) -> DaemonConfig {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        std::fs::write(&path, prices).unwrap();
+        DaemonConfig::load_from(&path).unwrap()
+    }
+
+    #[test]
+    fn test_parse_transcript_dedupes_and_skips_unknown_lines() {
+        let usage = parse_transcript(TRANSCRIPT.as_bytes());
+
+        assert_eq!(usage.by_model.len(), 3);
+        let sonnet = usage.by_model["claude-sonnet-4-5-20250929"];
+        assert_eq!(sonnet.input_tokens, 10);
+        assert_eq!(sonnet.cache_creation_input_tokens, 1000);
+        assert_eq!(sonnet.output_tokens, 200);
+        assert_eq!(usage.tokens_in(), 10 + 1000 + 50);
+        assert_eq!(usage.tokens_out(), 200 + 20);
+        assert_eq!(parse_transcript(&b""[..]), TranscriptUsage::default());
+    }
+
+    #[test]
+    fn test_estimated_cost() {
+        let usage = parse_transcript(TRANSCRIPT.as_bytes());
+
+        // No prices configured: no estimate
+        assert_eq!(usage.estimated_cost(&DaemonConfig::default()), None);
+
+        // A model without a price makes the estimate unknown
+        let sonnet_only = config_with_prices(
+            r#"{"model_prices": {"claude-sonnet-4": {"input_per_mtok": 3.0, "output_per_mtok": 15.0}}}"#,
+        );
+        assert_eq!(usage.estimated_cost(&sonnet_only), None);
+
+        let both = config_with_prices(
+            r#"{"model_prices": {
+                "claude-sonnet-4": {"input_per_mtok": 3.0, "output_per_mtok": 15.0, "cache_write_per_mtok": 3.75},
+                "claude-haiku": {"input_per_mtok": 1.0, "output_per_mtok": 5.0}
+            }}"#,
+        );
+        let expected = (10.0 * 3.0 + 1000.0 * 3.75 + 200.0 * 15.0 + 50.0 * 1.0 + 20.0 * 5.0)
+            / 1_000_000.0;
+        let cost = usage.estimated_cost(&both).unwrap();
+        assert!((cost - expected).abs() < 1e-12, "{} != {}", cost, expected);
+    }
+
+    #[test]
+    fn test_changed_transcripts_and_apply() {
+        let temp_dir = TempDir::new().unwrap();
+        let workdir = temp_dir.path().join("test001");
+        std::fs::create_dir_all(workdir.join("runtime")).unwrap();
+        let transcript = temp_dir.path().join("transcript.jsonl");
+        std::fs::write(&transcript, TRANSCRIPT).unwrap();
+
+        let mut session = Session {
+            session_id: "test001".to_string(),
+            workdir: workdir.clone(),
+            ..Default::default()
+        };
+        let mut sessions = HashMap::from([("test001".to_string(), session.clone())]);
+        let mut seen = HashMap::new();
+
+        // No transcript reported yet
+        assert!(changed_transcripts(&sessions, &mut seen).is_empty());
+
+        std::fs::write(
+            workdir.join("runtime/status.json"),
+            serde_json::json!({
+                "state": "idle",
+                "timestamp": "2026-10-16T12:00:00Z",
+                "transcript_path": transcript,
+            })
+            .to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            changed_transcripts(&sessions, &mut seen),
+            vec![("test001".to_string(), transcript.clone())]
+        );
+        // Unchanged since the last look
+        assert!(changed_transcripts(&sessions, &mut seen).is_empty());
+
+        let usage = read_transcript(&transcript).unwrap();
+        let config = DaemonConfig::default();
+        assert!(apply(&mut session, &usage, &config));
+        assert_eq!(session.tokens_in, Some(1060));
+        assert_eq!(session.tokens_out, Some(220));
+        assert_eq!(session.estimated_cost, None);
+        assert!(!apply(&mut session, &usage, &config));
+
+        // Sessions that are gone are forgotten
+        sessions.clear();
+        changed_transcripts(&sessions, &mut seen);
+        assert!(seen.is_empty());
+        assert!(read_transcript(&temp_dir.path().join("missing.jsonl")).is_none());
+    }
+}
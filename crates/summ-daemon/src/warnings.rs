@@ -0,0 +1,177 @@
+// summ-daemon/src/warnings.rs
+// Deduplicated warnings for the monitor loop and other periodic tasks
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// How often the same (kind, session) warning is logged while it keeps failing
+pub const REPEAT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Latest periodic error seen for a session, reported by Status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LastError {
+    pub kind: String,
+    pub message: String,
+    pub at: DateTime<Utc>,
+    /// Times this kind failed in a row, including this one
+    pub count: u64,
+}
+
+/// When a warning was last logged, and how many repeats were dropped since
+struct Logged {
+    at: Instant,
+    suppressed: u64,
+}
+
+/// A monitor loop ticking every 5s would otherwise log the same failure 60
+/// times in 5 minutes. Each (kind, session) pair is logged once per
+/// `REPEAT_INTERVAL`; the next line it logs carries the count it dropped.
+#[derive(Default)]
+pub struct Deduplicator {
+    logged: Mutex<HashMap<(String, String), Logged>>,
+    last_errors: Mutex<HashMap<String, LastError>>,
+    suppressed: AtomicU64,
+}
+
+impl Deduplicator {
+    /// Count one occurrence; returns the repeats suppressed since the last
+    /// logged one when this occurrence should be logged
+    fn check(&self, kind: &str, scope: &str, now: Instant) -> Option<u64> {
+        let mut logged = self.logged.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match logged.get_mut(&(kind.to_string(), scope.to_string())) {
+            Some(entry) if now.saturating_duration_since(entry.at) < REPEAT_INTERVAL => {
+                entry.suppressed += 1;
+                self.suppressed.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            Some(entry) => {
+                let suppressed = entry.suppressed;
+                *entry = Logged { at: now, suppressed: 0 };
+                Some(suppressed)
+            }
+            None => {
+                logged.insert((kind.to_string(), scope.to_string()), Logged { at: now, suppressed: 0 });
+                Some(0)
+            }
+        }
+    }
+
+    fn record_last_error(&self, session_id: &str, kind: &str, message: String) {
+        let mut last_errors = self.last_errors.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let count = match last_errors.get(session_id) {
+            Some(last) if last.kind == kind => last.count + 1,
+            _ => 1,
+        };
+        last_errors.insert(
+            session_id.to_string(),
+            LastError { kind: kind.to_string(), message, at: Utc::now(), count },
+        );
+    }
+
+    fn warn_at(&self, kind: &str, session_id: Option<&str>, message: &dyn Display, now: Instant) {
+        let message = message.to_string();
+        match self.check(kind, session_id.unwrap_or_default(), now) {
+            Some(0) => warn!("{}", message),
+            Some(suppressed) => warn!(
+                "{} (repeated {} more times in the last {} minutes)",
+                message,
+                suppressed,
+                REPEAT_INTERVAL.as_secs() / 60
+            ),
+            None => {}
+        }
+        if let Some(session_id) = session_id {
+            self.record_last_error(session_id, kind, message);
+        }
+    }
+
+    /// Drop the state of sessions `keep` rejects
+    fn retain(&self, keep: impl Fn(&str) -> bool) {
+        self.logged
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retain(|(_, scope), _| scope.is_empty() || keep(scope));
+        self.last_errors
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retain(|session_id, _| keep(session_id));
+    }
+}
+
+static WARNINGS: LazyLock<Deduplicator> = LazyLock::new(Deduplicator::default);
+
+/// Log a warning from a periodic task, at most once per `REPEAT_INTERVAL`
+/// for the same `kind` and session. Session errors are also kept as the
+/// session's `last_error`.
+pub fn warn(kind: &str, session_id: Option<&str>, message: impl Display) {
+    WARNINGS.warn_at(kind, session_id, &message, Instant::now());
+}
+
+/// The latest periodic error seen for a session
+pub fn last_error(session_id: &str) -> Option<LastError> {
+    WARNINGS
+        .last_errors
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(session_id)
+        .cloned()
+}
+
+/// Forget sessions the daemon no longer tracks
+pub fn retain_sessions(keep: impl Fn(&str) -> bool) {
+    WARNINGS.retain(keep);
+}
+
+/// Warnings dropped as repeats since the daemon started
+pub fn suppressed_total() -> u64 {
+    WARNINGS.suppressed.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeats_are_logged_once_per_interval() {
+        let warnings = Deduplicator::default();
+        let start = Instant::now();
+        assert_eq!(warnings.check("pane_pid", "s1", start), Some(0));
+        for tick in 1..60 {
+            assert_eq!(warnings.check("pane_pid", "s1", start + Duration::from_secs(tick * 5)), None);
+        }
+        // Other sessions and other kinds are logged on their own
+        assert_eq!(warnings.check("pane_pid", "s2", start), Some(0));
+        assert_eq!(warnings.check("tmux_alerts", "s1", start), Some(0));
+
+        assert_eq!(warnings.check("pane_pid", "s1", start + REPEAT_INTERVAL), Some(59));
+        assert_eq!(warnings.check("pane_pid", "s1", start + REPEAT_INTERVAL * 2), Some(0));
+        assert_eq!(warnings.suppressed.load(Ordering::Relaxed), 59);
+    }
+
+    #[test]
+    fn test_last_error_counts_repeats_of_a_kind() {
+        let warnings = Deduplicator::default();
+        let now = Instant::now();
+        warnings.warn_at("pane_pid", Some("s1"), &"no pane", now);
+        warnings.warn_at("pane_pid", Some("s1"), &"no pane", now);
+        let last = warnings.last_errors.lock().unwrap()["s1"].clone();
+        assert_eq!((last.kind.as_str(), last.count), ("pane_pid", 2));
+
+        warnings.warn_at("attention_command", Some("s1"), &"not found", now);
+        let last = warnings.last_errors.lock().unwrap()["s1"].clone();
+        assert_eq!((last.kind.as_str(), last.message.as_str(), last.count), ("attention_command", "not found", 1));
+
+        // Daemon-wide warnings have no session to attach to
+        warnings.warn_at("tmux_list", None, &"no server", now);
+        assert_eq!(warnings.last_errors.lock().unwrap().len(), 1);
+
+        warnings.retain(|session_id| session_id != "s1");
+        assert!(warnings.last_errors.lock().unwrap().is_empty());
+        assert_eq!(warnings.check("tmux_list", "", now), None);
+    }
+}
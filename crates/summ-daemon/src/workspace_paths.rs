@@ -0,0 +1,281 @@
+// summ-daemon/src/workspace_paths.rs
+// Check init source entries against what the workspace's filesystem can
+// hold: paths differing only by case, and names or paths that are too long
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Component, Path, PathBuf};
+use summ_common::{DaemonError, InitPathIssue, InitPathPolicy, InitPathProblem};
+
+/// Entries listed in the `E004` of a `fail` policy before the rest are counted
+const MAX_LISTED_ISSUES: usize = 20;
+
+/// Longest file name and path the destination's filesystem takes, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathLimits {
+    pub name_max: usize,
+    pub path_max: usize,
+}
+
+impl Default for PathLimits {
+    fn default() -> Self {
+        Self { name_max: 255, path_max: 4096 }
+    }
+}
+
+impl PathLimits {
+    /// The limits of the filesystem `dir` is on, from pathconf(3); the usual
+    /// Linux ones where it does not say
+    pub fn of(dir: &Path) -> Self {
+        let default = Self::default();
+        let Ok(path) = CString::new(dir.as_os_str().as_bytes()) else {
+            return default;
+        };
+        let limit = |name, fallback| {
+            // SAFETY: `path` is a valid NUL-terminated string
+            let value = unsafe { libc::pathconf(path.as_ptr(), name) };
+            if value > 0 { value as usize } else { fallback }
+        };
+        Self {
+            name_max: limit(libc::_PC_NAME_MAX, default.name_max),
+            path_max: limit(libc::_PC_PATH_MAX, default.path_max),
+        }
+    }
+}
+
+/// Where an entry goes
+#[derive(Debug, PartialEq, Eq)]
+pub enum Placement {
+    /// Write it at this path, relative to the destination
+    Write(PathBuf),
+    /// Leave it out
+    Skip,
+}
+
+/// Places the entries of one init source under a destination, in the order
+/// they are written, applying the policy to those that do not fit
+pub struct PathChecker {
+    policy: InitPathPolicy,
+    limits: PathLimits,
+    /// Bytes of the destination path and the separator after it
+    root_len: usize,
+    /// Every path written so far (and its parents), by its lowercase form
+    seen: HashMap<String, PathBuf>,
+    /// Source paths written under another name
+    renamed: HashMap<PathBuf, PathBuf>,
+    /// Source paths left out, with everything under them
+    skipped: HashSet<PathBuf>,
+    issues: Vec<InitPathIssue>,
+}
+
+impl PathChecker {
+    pub fn new(destination: &Path, policy: InitPathPolicy) -> Self {
+        // The destination exists by the time entries are placed, but maybe
+        // not yet: its parent is on the same filesystem
+        let probe = destination.ancestors().find(|dir| dir.exists()).unwrap_or(destination);
+        Self::with_limits(destination, policy, PathLimits::of(probe))
+    }
+
+    pub fn with_limits(destination: &Path, policy: InitPathPolicy, limits: PathLimits) -> Self {
+        Self {
+            policy,
+            limits,
+            root_len: destination.as_os_str().len() + 1,
+            seen: HashMap::new(),
+            renamed: HashMap::new(),
+            skipped: HashSet::new(),
+            issues: Vec::new(),
+        }
+    }
+
+    /// Where the entry at `relative` (a path inside the source, already
+    /// checked not to leave it) goes. Under `fail`, nothing more is written
+    /// once an entry did not fit; the rest are still checked, so the error
+    /// can list them all.
+    pub fn place(&mut self, relative: &Path) -> Placement {
+        let mut source = PathBuf::new();
+        let mut written = PathBuf::new();
+        for component in relative.components() {
+            let Component::Normal(name) = component else {
+                continue;
+            };
+            source.push(name);
+            if self.skipped.contains(&source) {
+                return Placement::Skip;
+            }
+            if let Some(target) = self.renamed.get(&source) {
+                written = target.clone();
+                continue;
+            }
+            if name.len() > self.limits.name_max {
+                return self.skip(&source, InitPathProblem::TooLong, None);
+            }
+            let candidate = written.join(name);
+            let key = lowercase(&candidate);
+            match self.seen.get(&key) {
+                Some(existing) if *existing == candidate => written = candidate,
+                Some(existing) => {
+                    let existing = existing.display().to_string();
+                    if self.policy != InitPathPolicy::Rename {
+                        return self.skip(&source, InitPathProblem::CaseCollision, Some(existing));
+                    }
+                    let target = self.free_name(&candidate);
+                    self.seen.insert(lowercase(&target), target.clone());
+                    self.renamed.insert(source.clone(), target.clone());
+                    self.issues.push(InitPathIssue {
+                        entry: source.display().to_string(),
+                        problem: InitPathProblem::CaseCollision,
+                        collides_with: Some(existing),
+                        renamed_to: Some(target.display().to_string()),
+                    });
+                    written = target;
+                }
+                None => {
+                    self.seen.insert(key, candidate.clone());
+                    written = candidate;
+                }
+            }
+        }
+        if self.root_len + written.as_os_str().len() > self.limits.path_max {
+            return self.skip(&source, InitPathProblem::TooLong, None);
+        }
+        if self.policy == InitPathPolicy::Fail && !self.issues.is_empty() {
+            return Placement::Skip;
+        }
+        Placement::Write(written)
+    }
+
+    fn skip(&mut self, source: &Path, problem: InitPathProblem, collides_with: Option<String>) -> Placement {
+        self.skipped.insert(source.to_path_buf());
+        self.issues.push(InitPathIssue {
+            entry: source.display().to_string(),
+            problem,
+            collides_with,
+            renamed_to: None,
+        });
+        Placement::Skip
+    }
+
+    /// `README~2.md`, `README~3.md`, ... next to `path`: the first whose
+    /// lowercase form is not taken
+    fn free_name(&self, path: &Path) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = path
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+        (2..)
+            .map(|n| path.with_file_name(format!("{}~{}{}", stem, n, extension)))
+            .find(|candidate| !self.seen.contains_key(&lowercase(candidate)))
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+
+    /// The entries that did not fit. Under `fail`, having any is an `E004`
+    /// listing them.
+    pub fn finish(self, source: &Path) -> Result<Vec<InitPathIssue>, DaemonError> {
+        if self.policy != InitPathPolicy::Fail || self.issues.is_empty() {
+            return Ok(self.issues);
+        }
+        let mut listed: Vec<String> = self
+            .issues
+            .iter()
+            .take(MAX_LISTED_ISSUES)
+            .map(|issue| format!("  {}", issue))
+            .collect();
+        if self.issues.len() > MAX_LISTED_ISSUES {
+            listed.push(format!("  ... and {} more", self.issues.len() - MAX_LISTED_ISSUES));
+        }
+        Err(DaemonError::e004(format!(
+            "{} has {} entr{} the workspace cannot hold as they are (init_path_policy is `fail`; \
+             `warn-and-skip` or `rename` start anyway):\n{}",
+            source.display(),
+            self.issues.len(),
+            if self.issues.len() == 1 { "y" } else { "ies" },
+            listed.join("\n")
+        )))
+    }
+}
+
+fn lowercase(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker(policy: InitPathPolicy) -> PathChecker {
+        PathChecker::with_limits(Path::new("/work"), policy, PathLimits { name_max: 16, path_max: 40 })
+    }
+
+    fn place(checker: &mut PathChecker, path: &str) -> Option<String> {
+        match checker.place(Path::new(path)) {
+            Placement::Write(path) => Some(path.display().to_string()),
+            Placement::Skip => None,
+        }
+    }
+
+    #[test]
+    fn test_case_collisions_by_policy() {
+        let mut skip = checker(InitPathPolicy::WarnAndSkip);
+        assert_eq!(place(&mut skip, "README.md").as_deref(), Some("README.md"));
+        assert_eq!(place(&mut skip, "src/main.rs").as_deref(), Some("src/main.rs"));
+        assert_eq!(place(&mut skip, "./src/lib.rs").as_deref(), Some("src/lib.rs"));
+        assert_eq!(place(&mut skip, "readme.md"), None);
+        // A directory differing by case takes everything under it along
+        assert_eq!(place(&mut skip, "Src/util.rs"), None);
+        assert_eq!(place(&mut skip, "Src/other.rs"), None);
+        let issues = skip.finish(Path::new("repo.zip")).unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].to_string(), "readme.md differs only by case from README.md");
+        assert_eq!(issues[1].to_string(), "Src differs only by case from src");
+
+        let mut rename = checker(InitPathPolicy::Rename);
+        assert_eq!(place(&mut rename, "README.md").as_deref(), Some("README.md"));
+        assert_eq!(place(&mut rename, "readme.md").as_deref(), Some("readme~2.md"));
+        assert_eq!(place(&mut rename, "ReadMe.md").as_deref(), Some("ReadMe~3.md"));
+        assert_eq!(place(&mut rename, "src/a.rs").as_deref(), Some("src/a.rs"));
+        assert_eq!(place(&mut rename, "SRC/b.rs").as_deref(), Some("SRC~2/b.rs"));
+        assert_eq!(place(&mut rename, "SRC/c.rs").as_deref(), Some("SRC~2/c.rs"));
+        let issues = rename.finish(Path::new("repo.zip")).unwrap();
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[2].to_string(), "SRC differs only by case from src (written as SRC~2)");
+    }
+
+    #[test]
+    fn test_too_long_entries() {
+        let mut rename = checker(InitPathPolicy::Rename);
+        assert_eq!(place(&mut rename, "a-name-of-17-byte").as_deref(), None);
+        assert_eq!(place(&mut rename, "deep/deeper/deepest/x.txt").as_deref(), Some("deep/deeper/deepest/x.txt"));
+        // 6 bytes of `/work/` and 35 of the path are over 40
+        assert_eq!(place(&mut rename, "deep/deeper/deepest/longer-name.txt"), None);
+        let issues = rename.finish(Path::new("repo")).unwrap();
+        assert_eq!(issues.iter().map(|issue| issue.problem).collect::<Vec<_>>(), [InitPathProblem::TooLong; 2]);
+        assert_eq!(issues[0].renamed_to, None);
+    }
+
+    #[test]
+    fn test_fail_stops_writing_and_lists_every_entry() {
+        let mut fail = checker(InitPathPolicy::Fail);
+        assert!(place(&mut fail, "README.md").is_some());
+        assert_eq!(place(&mut fail, "readme.md"), None);
+        // Nothing is written after the first problem, but later ones count
+        assert_eq!(place(&mut fail, "fine.txt"), None);
+        assert_eq!(place(&mut fail, "a-name-of-17-byte"), None);
+        let error = fail.finish(Path::new("/tmp/repo.tar.gz")).unwrap_err();
+        assert_eq!(error.code.code(), "E004");
+        assert!(error.message.starts_with("/tmp/repo.tar.gz has 2 entries"), "{}", error.message);
+        assert!(error.message.contains("\n  readme.md differs only by case from README.md"), "{}", error.message);
+        assert!(error.message.contains("\n  a-name-of-17-byte is too long"), "{}", error.message);
+
+        let mut clean = checker(InitPathPolicy::Fail);
+        assert!(place(&mut clean, "README.md").is_some());
+        assert_eq!(clean.finish(Path::new("repo")).unwrap(), []);
+    }
+
+    #[test]
+    fn test_limits_of_a_real_directory() {
+        let limits = PathLimits::of(Path::new("/"));
+        assert!(limits.name_max >= 14 && limits.path_max >= 256, "{:?}", limits);
+    }
+}
@@ -0,0 +1,201 @@
+// summ-e2e/src/lib.rs
+// End-to-end harness: the real summ-daemon and summ binaries in a scratch environment
+//
+// Each `E2eEnv` gets its own HOME (so ~/.summ-daemon, the socket and the logs are
+// private) and its own tmux server (TMUX_TMPDIR), and tears both down on drop,
+// including when the test panics.
+
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// How long to wait for the daemon socket to come up
+const DAEMON_START_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A running summ-daemon with an isolated HOME and tmux server
+pub struct E2eEnv {
+    home: TempDir,
+    summ: PathBuf,
+    daemon: Child,
+}
+
+impl E2eEnv {
+    /// Start a daemon for one test, or `None` (with a note on stderr) when
+    /// tmux is not installed so the test can skip
+    pub fn start() -> Option<Self> {
+        Self::launch(None)
+    }
+
+    /// Like `start`, with `config` written to the daemon's config.json first
+    pub fn start_with_config(config: &Value) -> Option<Self> {
+        Self::launch(Some(config))
+    }
+
+    fn launch(config: Option<&Value>) -> Option<Self> {
+        if !tmux_available() {
+            eprintln!("skipping e2e test: tmux is not available");
+            return None;
+        }
+
+        let bin_dir = binary_dir();
+        let summ = bin_dir.join("summ");
+        let daemon_bin = bin_dir.join("summ-daemon");
+        for bin in [&summ, &daemon_bin] {
+            assert!(
+                bin.exists(),
+                "{} not found; run `cargo build --workspace` before the e2e tests",
+                bin.display()
+            );
+        }
+
+        let home = TempDir::new().expect("create scratch HOME");
+        fs::create_dir_all(home.path().join("tmux")).expect("create tmux dir");
+        let daemon_log = fs::File::create(home.path().join("daemon.log")).expect("daemon log");
+        if let Some(config) = config {
+            let base = home.path().join(".summ-daemon");
+            fs::create_dir_all(&base).expect("create daemon dir");
+            fs::write(base.join("config.json"), config.to_string()).expect("write config.json");
+        }
+
+        let mut command = Command::new(&daemon_bin);
+        isolate(&mut command, home.path());
+        let daemon = command
+            .stdout(Stdio::from(daemon_log.try_clone().expect("daemon log")))
+            .stderr(Stdio::from(daemon_log))
+            .spawn()
+            .expect("spawn summ-daemon");
+
+        let env = Self { home, summ, daemon };
+        env.wait_until("daemon socket", DAEMON_START_TIMEOUT, || {
+            env.socket_path().exists() && env.summ(&["daemon", "status"]).status.success()
+        });
+        Some(env)
+    }
+
+    /// Scratch HOME directory of this environment
+    pub fn home(&self) -> &Path {
+        self.home.path()
+    }
+
+    /// The daemon's socket
+    pub fn socket_path(&self) -> PathBuf {
+        self.home().join(".summ-daemon/daemon.sock")
+    }
+
+    /// Terminal log the daemon writes for a session
+    pub fn session_log_path(&self, session_id: &str) -> PathBuf {
+        self.home()
+            .join(".summ-daemon/logs")
+            .join(format!("{}.log", session_id))
+    }
+
+    /// Run `summ` with `args` in this environment
+    pub fn summ(&self, args: &[&str]) -> Output {
+        let mut command = Command::new(&self.summ);
+        isolate(&mut command, self.home());
+        command
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .expect("run summ")
+    }
+
+    /// Run `summ` with `input` piped to its stdin
+    pub fn summ_with_input(&self, args: &[&str], input: &str) -> Output {
+        let mut command = Command::new(&self.summ);
+        isolate(&mut command, self.home());
+        let mut child = command
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("run summ");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .expect("write summ stdin");
+        child.wait_with_output().expect("run summ")
+    }
+
+    /// Run `summ` and return its stdout, panicking with its stderr on failure
+    pub fn summ_ok(&self, args: &[&str]) -> String {
+        let output = self.summ(args);
+        assert!(
+            output.status.success(),
+            "summ {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    /// Run `summ` and parse its stdout as JSON
+    pub fn summ_json(&self, args: &[&str]) -> Value {
+        let stdout = self.summ_ok(args);
+        serde_json::from_str(&stdout)
+            .unwrap_or_else(|e| panic!("summ {:?} printed invalid JSON ({}): {}", args, e, stdout))
+    }
+
+    /// Run `tmux` against this environment's private server
+    pub fn tmux(&self, args: &[&str]) -> Output {
+        let mut command = Command::new("tmux");
+        isolate(&mut command, self.home());
+        command.args(args).output().expect("run tmux")
+    }
+
+    /// Poll `condition` every 100ms, panicking after `timeout`
+    pub fn wait_until(&self, what: &str, timeout: Duration, mut condition: impl FnMut() -> bool) {
+        let deadline = Instant::now() + timeout;
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out waiting for {}", what);
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+impl Drop for E2eEnv {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+        let _ = self.tmux(&["kill-server"]);
+
+        if std::thread::panicking() {
+            if let Ok(log) = fs::read_to_string(self.home().join("daemon.log")) {
+                eprintln!("--- summ-daemon output ---\n{}", log);
+            }
+        }
+    }
+}
+
+/// Point a child process at the scratch HOME and private tmux server
+fn isolate(command: &mut Command, home: &Path) {
+    command
+        .env("HOME", home)
+        .env("TMUX_TMPDIR", home.join("tmux"))
+        .env_remove("TMUX")
+        .env_remove("TMUX_PANE")
+        .env_remove("RUST_LOG");
+}
+
+/// Whether a tmux binary can be run
+fn tmux_available() -> bool {
+    Command::new("tmux")
+        .arg("-V")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Cargo's output directory for this profile (`target/debug`), next to `deps/`
+/// where the test executables live
+fn binary_dir() -> PathBuf {
+    let exe = std::env::current_exe().expect("current test executable");
+    let deps = exe.parent().expect("test executable directory");
+    deps.parent().unwrap_or(deps).to_path_buf()
+}
@@ -0,0 +1,303 @@
+// End-to-end session lifecycle through the summ CLI and a real daemon
+//
+// Run with: cargo build --workspace && cargo test -p summ-e2e --features e2e
+#![cfg(feature = "e2e")]
+
+use std::fs;
+use std::time::Duration;
+use summ_e2e::E2eEnv;
+
+#[test]
+fn test_session_lifecycle() {
+    let Some(env) = E2eEnv::start() else {
+        return;
+    };
+
+    let init_dir = env.home().join("init");
+    fs::create_dir_all(&init_dir).unwrap();
+    fs::write(init_dir.join("README.md"), "e2e workspace\n").unwrap();
+
+    // Start
+    let started = env.summ_json(&[
+        "start",
+        "--cli",
+        "bash",
+        "--init",
+        init_dir.to_str().unwrap(),
+        "--name",
+        "e2e",
+    ]);
+    let session_id = started["session_id"].as_str().unwrap().to_string();
+    assert_eq!(started["status"], "running");
+
+    // Status reports the workspace copied from the init dir
+    let status = env.summ_json(&["status", &session_id]);
+    assert_eq!(status["name"], "e2e");
+    assert_eq!(status["status"], "running");
+    let workspace = status["workspace_path"].as_str().unwrap();
+    assert!(std::path::Path::new(workspace).join("README.md").exists());
+    assert_eq!(status["init_manifest"]["kind"], "directory");
+    assert_eq!(status["init_manifest"]["file_count"], 1);
+    let runtime = status["runtime_path"].as_str().unwrap();
+    assert!(std::path::Path::new(runtime).join("init_manifest.json").exists());
+
+    // List
+    let list = env.summ_ok(&["list"]);
+    assert!(list.contains(&session_id), "list output: {}", list);
+
+    // Inject a command and see its output in the pane and the session log
+    env.summ_ok(&["inject", &session_id, "--message", "echo e2e-$((40 + 2)) id=$SUMM_SESSION_ID"]);
+    let tmux_session = format!("summ-{}", session_id);
+    env.wait_until("injected command output", Duration::from_secs(10), || {
+        let pane = env.tmux(&["capture-pane", "-p", "-t", &tmux_session]);
+        String::from_utf8_lossy(&pane.stdout).contains(&format!("e2e-42 id={}", session_id))
+    });
+    env.wait_until("session log", Duration::from_secs(10), || {
+        fs::read_to_string(env.session_log_path(&session_id))
+            .is_ok_and(|log| log.contains("e2e-42"))
+    });
+
+    // The monitor reports the pane's foreground command
+    env.wait_until("current command", Duration::from_secs(15), || {
+        env.summ_json(&["status", &session_id])["current_command"] == "bash"
+    });
+
+    // Stop
+    env.summ_ok(&["stop", &session_id, "--yes"]);
+    let status = env.summ_json(&["status", &session_id]);
+    assert_eq!(status["status"], "stopped");
+    assert!(status["current_command"].is_null());
+    assert_eq!(status["stop_reason"]["kind"], "user_requested");
+    assert_eq!(status["stop_reason"]["method"], "kill_session");
+    assert!(!env.tmux(&["has-session", "-t", &tmux_session]).status.success());
+}
+
+#[test]
+fn test_stop_with_quit_command() {
+    let Some(env) = E2eEnv::start_with_config(&serde_json::json!({
+        "quit_commands": { "bash": "exit" },
+        "stop_grace_seconds": 5
+    })) else {
+        return;
+    };
+
+    let init_dir = env.home().join("init");
+    fs::create_dir_all(&init_dir).unwrap();
+    let started = env.summ_json(&["start", "--cli", "bash", "--init", init_dir.to_str().unwrap()]);
+    let session_id = started["session_id"].as_str().unwrap().to_string();
+
+    // bash exits on its own after `exit`, so the stop never escalates
+    let stopped = env.summ_json(&["stop", &session_id, "--yes"]);
+    assert_eq!(stopped["stop_method"], "quit_command");
+    let status = env.summ_json(&["status", &session_id]);
+    assert_eq!(status["stop_reason"]["kind"], "user_requested");
+    assert_eq!(status["stop_reason"]["method"], "quit_command");
+}
+
+#[test]
+fn test_foreign_tmux_session_is_left_alone() {
+    let Some(env) = E2eEnv::start() else {
+        return;
+    };
+
+    let init_dir = env.home().join("init");
+    fs::create_dir_all(&init_dir).unwrap();
+    let started = env.summ_json(&["start", "--cli", "bash", "--init", init_dir.to_str().unwrap()]);
+    let session_id = started["session_id"].as_str().unwrap().to_string();
+    let tmux_session = format!("summ-{}", session_id);
+    let marker = env.tmux(&["show-options", "-t", &tmux_session, "-v", "@summ_owned"]);
+    assert_eq!(String::from_utf8_lossy(&marker.stdout).trim(), "1");
+    let option = env.tmux(&["show-options", "-t", &tmux_session, "-v", "@summ_session_id"]);
+    assert_eq!(String::from_utf8_lossy(&option.stdout).trim(), session_id);
+
+    // A user's session takes over the name
+    assert!(env.tmux(&["kill-session", "-t", &tmux_session]).status.success());
+    assert!(env.tmux(&["new-session", "-d", "-s", &tmux_session, "sleep 300"]).status.success());
+
+    let output = env.summ(&["inject", &session_id, "--message", "echo hi"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not created by summ"));
+
+    env.summ_ok(&["stop", &session_id, "--yes"]);
+    assert!(env.tmux(&["has-session", "-t", &tmux_session]).status.success());
+    assert_eq!(env.summ_json(&["status", &session_id])["status"], "stopped");
+}
+
+#[test]
+fn test_background_start_and_wait() {
+    let Some(env) = E2eEnv::start() else {
+        return;
+    };
+
+    let init_dir = env.home().join("init");
+    fs::create_dir_all(&init_dir).unwrap();
+
+    let stdout = env.summ_ok(&[
+        "start",
+        "--cli",
+        "bash",
+        "--init",
+        init_dir.to_str().unwrap(),
+        "--background",
+    ]);
+    let session_id = stdout.trim();
+    assert!(!session_id.is_empty());
+
+    let status = env.summ_json(&["wait", session_id, "--for", "running", "--timeout", "30"]);
+    assert_eq!(status["status"], "running");
+
+    // Fan out: wait for a second session together with the first
+    let other = env.summ_ok(&["start", "--cli", "bash", "--init", init_dir.to_str().unwrap(), "--background"]);
+    let other = other.trim();
+    let waited = env.summ_json(&["wait", session_id, other, "--all", "--for", "running", "--timeout", "30s"]);
+    assert_eq!(waited["outcome"], "satisfied");
+    assert_eq!(waited["satisfied"].as_array().unwrap().len(), 2);
+
+    env.summ_ok(&["stop", session_id, "--yes"]);
+    let status = env.summ_json(&["wait", session_id, "--for", "stopped", "--timeout", "30"]);
+    assert_eq!(status["status"], "stopped");
+
+    // One of two stopped: partial (exit 2); neither idle nor failed in time: timeout (exit 3)
+    let output = env.summ(&["wait", session_id, other, "--for", "stopped", "--timeout", "1"]);
+    assert_eq!(output.status.code(), Some(2), "{}", String::from_utf8_lossy(&output.stderr));
+    let output = env.summ(&["wait", other, session_id, "--any", "--for", "failed", "--timeout", "1"]);
+    assert_eq!(output.status.code(), Some(1), "{}", String::from_utf8_lossy(&output.stderr));
+    let output = env.summ(&["wait", other, "--for", "idle,failed", "--timeout", "1"]);
+    assert_eq!(output.status.code(), Some(3), "{}", String::from_utf8_lossy(&output.stderr));
+    env.summ_ok(&["stop", other, "--yes"]);
+}
+
+#[test]
+fn test_start_after_another_session() {
+    let Some(env) = E2eEnv::start() else {
+        return;
+    };
+
+    let init_dir = env.home().join("init");
+    fs::create_dir_all(&init_dir).unwrap();
+    let started = env.summ_json(&["start", "--cli", "bash", "--init", init_dir.to_str().unwrap()]);
+    let first = started["session_id"].as_str().unwrap().to_string();
+    let workspace = std::path::PathBuf::from(env.summ_json(&["status", &first])["workspace_path"].as_str().unwrap());
+    fs::write(workspace.join("result.txt"), "from the first session\n").unwrap();
+
+    // Held until the first session stops, then started from its workspace
+    let after = format!("{}:stopped", first);
+    let stdout = env.summ_ok(&["start", "--cli", "bash", "--after", &after, "--init-from-session", &first]);
+    let second = stdout.trim().to_string();
+    let cancelled = env.summ_ok(&["start", "--cli", "bash", "--after", &after, "--init-from-session", &first]);
+    let cancelled = cancelled.trim().to_string();
+    let pending = env.summ_ok(&["list", "--pending"]);
+    assert!(pending.contains(&second) && pending.contains(&format!("after {}", after)), "{}", pending);
+    assert_eq!(pending.lines().count(), 2, "{}", pending);
+
+    let stopped = env.summ_json(&["stop", &cancelled, "--yes"]);
+    assert_eq!(stopped["cancelled"], true);
+    env.summ_ok(&["stop", &first, "--yes"]);
+    let status = env.summ_json(&["wait", &second, "--for", "running", "--timeout", "30"]);
+    let copied = std::path::Path::new(status["workspace_path"].as_str().unwrap()).join("result.txt");
+    assert_eq!(fs::read_to_string(copied).unwrap(), "from the first session\n");
+    assert_eq!(env.summ_json(&["status", &cancelled])["status"], "stopped");
+
+    // A trigger that can no longer fire is refused
+    let output = env.summ(&["start", "--cli", "bash", "--init", init_dir.to_str().unwrap(), "--after", &first]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("E010") && stderr.contains("will not become idle"), "{}", stderr);
+    env.summ_ok(&["stop", &second, "--yes"]);
+}
+
+#[test]
+fn test_missing_init_source_is_rejected() {
+    let Some(env) = E2eEnv::start() else {
+        return;
+    };
+
+    let missing = env.home().join("does-not-exist");
+    let output = env.summ(&["start", "--cli", "bash", "--init", missing.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("E001"));
+
+    // Nothing was left behind
+    let list = env.summ_json(&["list", "--counts-only"]);
+    assert_eq!(list["total"], 0);
+}
+
+#[test]
+fn test_secret_reaches_session_by_name() {
+    let Some(env) = E2eEnv::start() else {
+        return;
+    };
+
+    let output = env.summ_with_input(&["secret", "set", "E2E_TOKEN"], "tok-$(40+2) 'quoted'\n");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(env.summ_ok(&["secret", "list"]), "E2E_TOKEN\n");
+
+    // An unknown name fails before a session is allocated
+    let init_dir = env.home().join("init");
+    fs::create_dir_all(&init_dir).unwrap();
+    let init = init_dir.to_str().unwrap();
+    let output = env.summ(&["start", "--cli", "bash", "--init", init, "--secret", "E2E_MISSING"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("E010") && stderr.contains("E2E_MISSING"), "{}", stderr);
+    assert_eq!(env.summ_json(&["list", "--counts-only"])["total"], 0);
+
+    // The value reaches the pane, but not the metadata or the runtime directory
+    let started = env.summ_json(&["start", "--cli", "bash", "--init", init, "--secret", "E2E_TOKEN"]);
+    let session_id = started["session_id"].as_str().unwrap().to_string();
+    assert_eq!(started["secrets"], serde_json::json!(["E2E_TOKEN"]));
+    env.summ_ok(&["inject", &session_id, "--message", "echo \"secret=[$E2E_TOKEN]\""]);
+    let tmux_session = format!("summ-{}", session_id);
+    env.wait_until("secret in the pane", Duration::from_secs(10), || {
+        let pane = env.tmux(&["capture-pane", "-p", "-t", &tmux_session]);
+        String::from_utf8_lossy(&pane.stdout).contains("secret=[tok-$(40+2) 'quoted']")
+    });
+    let status = env.summ_json(&["status", &session_id]);
+    let runtime = std::path::Path::new(status["runtime_path"].as_str().unwrap());
+    assert!(!runtime.join("secrets.env").exists());
+    let meta = fs::read_to_string(runtime.parent().unwrap().join("meta.json")).unwrap();
+    assert!(meta.contains("E2E_TOKEN") && !meta.contains("tok-"), "{}", meta);
+
+    env.summ_ok(&["secret", "rm", "E2E_TOKEN"]);
+    assert!(env.summ_ok(&["secret", "list"]).starts_with("No secrets stored"));
+    env.summ_ok(&["stop", &session_id, "--yes"]);
+}
+
+#[test]
+fn test_bell_waits_for_answer() {
+    let Some(env) = E2eEnv::start() else {
+        return;
+    };
+
+    let init_dir = env.home().join("init");
+    fs::create_dir_all(&init_dir).unwrap();
+    let started = env.summ_json(&["start", "--cli", "bash", "--init", init_dir.to_str().unwrap()]);
+    let session_id = started["session_id"].as_str().unwrap().to_string();
+
+    // Nothing to answer yet
+    let output = env.summ(&["answer", &session_id, "yes"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("E017"));
+
+    // Ring the bell once the inject has returned (injecting clears the alerts)
+    env.summ_ok(&["inject", &session_id, "--message", "sleep 1; printf '\\a'"]);
+    env.wait_until("waiting_for_input", Duration::from_secs(20), || {
+        let status = env.summ_json(&["status", &session_id]);
+        status["needs_attention"] == true && status["waiting_for_input"]["source"] == "bell"
+    });
+    let list = env.summ_ok(&["list"]);
+    assert!(list.contains("WAITING FOR INPUT"), "list output: {}", list);
+
+    // A text answer is typed and submitted, and clears the flags
+    env.summ_ok(&["answer", &session_id, "echo answered-$((40 + 2))"]);
+    let tmux_session = format!("summ-{}", session_id);
+    env.wait_until("answer output", Duration::from_secs(10), || {
+        let pane = env.tmux(&["capture-pane", "-p", "-t", &tmux_session]);
+        String::from_utf8_lossy(&pane.stdout).contains("answered-42")
+    });
+    let status = env.summ_json(&["status", &session_id]);
+    assert_eq!(status["needs_attention"], false);
+    assert!(status["waiting_for_input"].is_null());
+
+    env.summ_ok(&["answer", &session_id, "--option", "2", "--force"]);
+    env.summ_ok(&["stop", &session_id, "--yes"]);
+}